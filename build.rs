@@ -0,0 +1,32 @@
+//! Build script: records the git commit the binary is built from as
+//! `HS_BENCHMARK_BUILD_COMMIT`, so reports can name the exact kernels that produced them.
+//! The name stays outside the `HSBENCH_*` options, since `cargo run` also sets it in the
+//! benchmark's environment. Builds outside a git checkout (e.g. from a crates.io package)
+//! leave the variable unset.
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn main() {
+    // Re-run when HEAD moves to another commit or the working tree's index changes
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    if let Some(commit) = git(&["rev-parse", "--short=12", "HEAD"]).filter(|c| !c.is_empty()) {
+        // Uncommitted changes to tracked files mean the kernels may differ from the commit
+        let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+            .is_some_and(|status| !status.is_empty());
+        let suffix = if dirty { "-dirty" } else { "" };
+        println!(
+            "cargo:rustc-env=HS_BENCHMARK_BUILD_COMMIT={}{}",
+            commit, suffix
+        );
+    }
+}
@@ -0,0 +1,221 @@
+/// CPU affinity (core pinning) for benchmark threads
+/// Pinning keeps the scheduler from migrating threads between cores during a measurement,
+/// which matters most on hybrid CPUs where P-cores and E-cores run at different speeds.
+/// Supported on Linux (`sched_setaffinity`) and Windows (`SetThreadAffinityMask`); on
+/// other platforms pinning fails with `ErrorKind::Unsupported` and threads run unpinned.
+use std::io;
+
+/// Whether this platform supports pinning threads to cores
+pub const PINNING_SUPPORTED: bool = cfg!(any(target_os = "linux", windows));
+
+/// Logical cores this process is allowed to run on, in ascending order
+pub fn available_cores() -> Vec<usize> {
+    allowed_cores().unwrap_or_else(|| {
+        let count = std::thread::available_parallelism().map_or(1, |n| n.get());
+        (0..count).collect()
+    })
+}
+
+/// Core assigned to worker `index`; workers wrap around `cores`, and an empty list
+/// means the worker is not pinned
+pub fn core_for(cores: &[usize], index: usize) -> Option<usize> {
+    if cores.is_empty() {
+        None
+    } else {
+        Some(cores[index % cores.len()])
+    }
+}
+
+/// Pin the calling worker thread to `core` (from `core_for`), if any
+/// For threads that exit after the measurement; failures leave the thread unpinned
+pub fn pin_worker(core: Option<usize>) {
+    if let Some(core) = core {
+        let _ = set_affinity(core);
+    }
+}
+
+/// Pins the calling thread to the first core of a list and restores its previous
+/// affinity when dropped
+pub struct PinGuard {
+    previous: Option<PreviousAffinity>,
+}
+
+impl PinGuard {
+    /// Pin the calling thread to `cores[0]`; does nothing for an empty list
+    pub fn pin(cores: &[usize]) -> Self {
+        Self {
+            previous: core_for(cores, 0).and_then(|core| set_affinity(core).ok()),
+        }
+    }
+}
+
+impl Drop for PinGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            restore_affinity(previous);
+        }
+    }
+}
+
+/// Pin the calling thread to `core`
+pub fn pin_current_thread(core: usize) -> io::Result<()> {
+    set_affinity(core).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+type PreviousAffinity = libc::cpu_set_t;
+
+#[cfg(target_os = "linux")]
+fn allowed_cores() -> Option<Vec<usize>> {
+    let set = current_affinity().ok()?;
+    let cores: Vec<usize> = (0..libc::CPU_SETSIZE as usize)
+        .filter(|&core| unsafe { libc::CPU_ISSET(core, &set) })
+        .collect();
+    (!cores.is_empty()).then_some(cores)
+}
+
+#[cfg(target_os = "linux")]
+fn current_affinity() -> io::Result<libc::cpu_set_t> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        // pid 0 is the calling thread
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply_affinity(set: &libc::cpu_set_t) -> io::Result<()> {
+    unsafe {
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the previous affinity so that it can be restored
+#[cfg(target_os = "linux")]
+fn set_affinity(core: usize) -> io::Result<PreviousAffinity> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("core {} is out of range", core),
+        ));
+    }
+    let previous = current_affinity()?;
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        apply_affinity(&set)?;
+    }
+    Ok(previous)
+}
+
+#[cfg(target_os = "linux")]
+fn restore_affinity(previous: PreviousAffinity) {
+    let _ = apply_affinity(&previous);
+}
+
+#[cfg(windows)]
+type PreviousAffinity = usize;
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetCurrentThread() -> *mut std::ffi::c_void;
+    fn SetThreadAffinityMask(thread: *mut std::ffi::c_void, mask: usize) -> usize;
+}
+
+#[cfg(windows)]
+fn allowed_cores() -> Option<Vec<usize>> {
+    // Without processor groups only the first 64 logical cores are addressable
+    None
+}
+
+#[cfg(windows)]
+fn set_affinity(core: usize) -> io::Result<PreviousAffinity> {
+    if core >= usize::BITS as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("core {} is out of range", core),
+        ));
+    }
+    let previous = unsafe { SetThreadAffinityMask(GetCurrentThread(), 1usize << core) };
+    if previous == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(previous)
+}
+
+#[cfg(windows)]
+fn restore_affinity(previous: PreviousAffinity) {
+    unsafe {
+        SetThreadAffinityMask(GetCurrentThread(), previous);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+type PreviousAffinity = ();
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn allowed_cores() -> Option<Vec<usize>> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn set_affinity(_core: usize) -> io::Result<PreviousAffinity> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "thread pinning is not supported on this platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn restore_affinity(_previous: PreviousAffinity) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_for_wraps_around() {
+        let cores = [0, 2, 4];
+        assert_eq!(core_for(&cores, 0), Some(0));
+        assert_eq!(core_for(&cores, 2), Some(4));
+        assert_eq!(core_for(&cores, 4), Some(2));
+        assert_eq!(core_for(&[], 3), None);
+    }
+
+    #[test]
+    fn test_available_cores() {
+        let cores = available_cores();
+        assert!(!cores.is_empty());
+        assert!(cores.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_pin_guard_restores_affinity() {
+        let before = available_cores();
+        let first = before[0];
+        std::thread::spawn(move || {
+            {
+                let _guard = PinGuard::pin(&[first]);
+                // Only Linux reports the allowed cores of the calling thread
+                if cfg!(target_os = "linux") {
+                    assert_eq!(available_cores(), vec![first]);
+                }
+            }
+            assert_eq!(available_cores(), before);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pin_out_of_range_core_fails() {
+        assert!(pin_current_thread(usize::MAX).is_err());
+    }
+}
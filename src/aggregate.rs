@@ -0,0 +1,306 @@
+/// Results aggregation across hosts
+/// Combines the JSON reports of several machines, e.g. a lab or a fleet collected with
+/// `--upload`, into one ranking by composite score and one per metric. Only the latest report
+/// of each host is used, and the coefficient of variation across hosts shows how much the
+/// machines differ on each metric
+use crate::compare;
+use crate::report::SuiteReport;
+use crate::scoring::Scores;
+use crate::stats::Statistics;
+use chrono::DateTime;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Latest report of one host
+#[derive(Debug, Clone, Serialize)]
+pub struct HostSummary {
+    pub hostname: String,
+    pub timestamp: String,
+    pub config_fingerprint: Option<String>,
+    /// Mean composite scores over the report's runs
+    pub scores: Scores,
+    /// Mean of every metric with at least one run, keyed by metric name
+    pub metrics: IndexMap<String, f64>,
+}
+
+impl HostSummary {
+    fn from_report(report: &SuiteReport) -> Self {
+        let metrics = report
+            .results
+            .values()
+            .flatten()
+            .filter_map(|(key, series)| Some((key.clone(), series.statistics.as_ref()?.mean)))
+            .collect();
+        HostSummary {
+            hostname: report.metadata.hostname.clone(),
+            timestamp: report.metadata.timestamp.clone(),
+            config_fingerprint: report.config_fingerprint.clone(),
+            scores: report.scores,
+            metrics,
+        }
+    }
+}
+
+/// Mean of one metric on one host
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedHost {
+    pub hostname: String,
+    pub value: f64,
+}
+
+/// Hosts ranked on one metric
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricRanking {
+    pub metric: String,
+    pub lower_is_better: bool,
+    /// Hosts that reported the metric, best first
+    pub ranking: Vec<RankedHost>,
+    /// Coefficient of variation of the host means in percent; None with fewer than two hosts
+    pub cv_percent: Option<f64>,
+}
+
+impl MetricRanking {
+    /// 1-based rank of `hostname`; None when the host did not report the metric
+    pub fn rank_of(&self, hostname: &str) -> Option<usize> {
+        self.ranking
+            .iter()
+            .position(|host| host.hostname == hostname)
+            .map(|index| index + 1)
+    }
+}
+
+/// Combined results of several hosts
+#[derive(Debug, Clone, Serialize)]
+pub struct Aggregate {
+    /// Hosts by overall score, best first; hosts without an overall score come last
+    pub hosts: Vec<HostSummary>,
+    /// Every metric reported by at least one host, in the order they first appear, except
+    /// the run conditions (see `compare::is_condition`)
+    pub metrics: Vec<MetricRanking>,
+    /// Coefficient of variation of the overall scores in percent
+    pub score_cv_percent: Option<f64>,
+    /// Reports left out because a later report of the same host was found
+    pub superseded: usize,
+}
+
+impl Aggregate {
+    /// Distinct config fingerprints among the hosts; more than one means the hosts did not
+    /// all measure the same work
+    pub fn fingerprints(&self) -> Vec<&str> {
+        let mut fingerprints: Vec<&str> = self
+            .hosts
+            .iter()
+            .filter_map(|host| host.config_fingerprint.as_deref())
+            .collect();
+        fingerprints.sort_unstable();
+        fingerprints.dedup();
+        fingerprints
+    }
+}
+
+/// Reports found in a directory
+#[derive(Debug)]
+pub struct LoadedReports {
+    /// Reports in file name order
+    pub reports: Vec<SuiteReport>,
+    /// JSON files that could not be loaded as reports, with the error
+    pub skipped: Vec<(PathBuf, io::Error)>,
+}
+
+/// Load every `.json` file in `dir` that is a benchmark report, in file name order
+pub fn load_reports(dir: &Path) -> io::Result<LoadedReports> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut loaded = LoadedReports {
+        reports: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for path in paths {
+        match SuiteReport::load(&path) {
+            Ok(report) => loaded.reports.push(report),
+            Err(e) => loaded.skipped.push((path, e)),
+        }
+    }
+    Ok(loaded)
+}
+
+/// Rank the hosts of `reports` by composite score and on every metric, using the latest
+/// report of each host
+pub fn aggregate(reports: &[SuiteReport]) -> Aggregate {
+    let mut latest: IndexMap<&str, &SuiteReport> = IndexMap::new();
+    for report in reports {
+        let entry = latest.entry(&report.metadata.hostname).or_insert(report);
+        if is_later(&report.metadata.timestamp, &entry.metadata.timestamp) {
+            *entry = report;
+        }
+    }
+    let superseded = reports.len() - latest.len();
+
+    let mut hosts: Vec<HostSummary> = latest
+        .values()
+        .map(|report| HostSummary::from_report(report))
+        .collect();
+    // Stable sort: hosts with equal (or no) scores keep the order of their files
+    hosts.sort_by(|a, b| descending(a.scores.overall, b.scores.overall));
+
+    let mut names: Vec<&str> = Vec::new();
+    for host in &hosts {
+        // Run conditions such as clock speeds do not rank hosts
+        for metric in host.metrics.keys() {
+            if !compare::is_condition(metric) && !names.contains(&metric.as_str()) {
+                names.push(metric);
+            }
+        }
+    }
+    let metrics = names
+        .into_iter()
+        .map(|metric| {
+            let lower_is_better = compare::lower_is_better(metric);
+            let mut ranking: Vec<RankedHost> = hosts
+                .iter()
+                .filter_map(|host| {
+                    Some(RankedHost {
+                        hostname: host.hostname.clone(),
+                        value: *host.metrics.get(metric)?,
+                    })
+                })
+                .collect();
+            ranking.sort_by(|a, b| {
+                let order = b.value.total_cmp(&a.value);
+                if lower_is_better {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            let values: Vec<f64> = ranking.iter().map(|host| host.value).collect();
+            MetricRanking {
+                metric: metric.to_string(),
+                lower_is_better,
+                ranking,
+                cv_percent: cv_percent(&values),
+            }
+        })
+        .collect();
+
+    let overall: Vec<f64> = hosts
+        .iter()
+        .filter_map(|host| host.scores.overall)
+        .collect();
+    Aggregate {
+        score_cv_percent: cv_percent(&overall),
+        hosts,
+        metrics,
+        superseded,
+    }
+}
+
+/// Whether RFC 3339 timestamp `a` is later than `b`; timestamps that do not parse are
+/// compared as text
+fn is_later(a: &str, b: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(a),
+        DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+/// Order of two optional scores, highest first and missing scores last
+fn descending(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn cv_percent(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    Statistics::from_values(values).map(|stats| stats.coefficient_of_variation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MetricSeries;
+
+    fn report(hostname: &str, timestamp: &str, primes: f64, latency: f64) -> SuiteReport {
+        let json = serde_json::json!({
+            "metadata": {"timestamp": timestamp, "hostname": hostname},
+            "system_info": {"cpu_brand": "CPU", "cpu_physical_cores": 4, "cpu_logical_cores": 8,
+                "total_memory_mb": 1024, "os_name": "OS", "os_version": "1", "hostname": hostname},
+            "config_fingerprint": "0123456789abcdef",
+            "scores": {"overall": primes / 100.0},
+            "results": {}
+        });
+        let mut report: SuiteReport = serde_json::from_value(json).unwrap();
+        report.results.entry("cpu".to_string()).or_default().insert(
+            "cpu_primes_per_sec".to_string(),
+            MetricSeries::from_values(vec![primes]),
+        );
+        report
+            .results
+            .entry("network".to_string())
+            .or_default()
+            .insert(
+                "network_latency_p99_us".to_string(),
+                MetricSeries::from_values(vec![latency]),
+            );
+        report
+    }
+
+    #[test]
+    fn test_aggregate_ranks_latest_report_of_each_host() {
+        let reports = vec![
+            report("a", "2026-01-02T00:00:00+00:00", 100.0, 30.0),
+            report("b", "2026-01-01T00:00:00+00:00", 300.0, 10.0),
+            // Earlier than the first report of "a", although in a later file
+            report("a", "2026-01-01T12:00:00+01:00", 500.0, 1.0),
+            report("c", "2026-01-01T00:00:00+00:00", 200.0, 20.0),
+        ];
+        let aggregate = aggregate(&reports);
+        assert_eq!(aggregate.superseded, 1);
+        let hosts: Vec<&str> = aggregate
+            .hosts
+            .iter()
+            .map(|h| h.hostname.as_str())
+            .collect();
+        assert_eq!(hosts, vec!["b", "c", "a"]);
+
+        let primes = &aggregate.metrics[0];
+        assert_eq!(primes.metric, "cpu_primes_per_sec");
+        assert_eq!(primes.rank_of("b"), Some(1));
+        assert_eq!(primes.rank_of("a"), Some(3));
+        // Population spread of 300, 200, and 100, as in `Statistics`
+        assert!((primes.cv_percent.unwrap() - 40.82).abs() < 0.01);
+
+        // Lower latencies rank first
+        let latency = &aggregate.metrics[1];
+        assert!(latency.lower_is_better);
+        assert_eq!(latency.ranking[0].hostname, "b");
+        assert_eq!(latency.ranking[0].value, 10.0);
+        assert_eq!(latency.rank_of("a"), Some(3));
+    }
+
+    #[test]
+    fn test_single_host_has_no_spread() {
+        let aggregate = aggregate(&[report("a", "2026-01-01T00:00:00+00:00", 100.0, 10.0)]);
+        assert_eq!(aggregate.hosts.len(), 1);
+        assert_eq!(aggregate.score_cv_percent, None);
+        assert_eq!(aggregate.metrics[0].cv_percent, None);
+        assert_eq!(aggregate.fingerprints().len(), 1);
+    }
+}
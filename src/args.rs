@@ -1,24 +1,185 @@
 /// Command-line argument parsing module
+use crate::config::{ConfigFile, ConfigOptions, ENV_PREFIX};
+use crate::render::ColorMode;
+use hs_benchmark_suite::disk::{
+    DiskMode, DiskProfile, Precondition, DEFAULT_PRECONDITION_MULTIPLE,
+};
+use hs_benchmark_suite::history::DEFAULT_HISTORY_FILE;
+use hs_benchmark_suite::load::DEFAULT_LOAD_THRESHOLD_PERCENT;
+use hs_benchmark_suite::memory::AccessPattern;
+use hs_benchmark_suite::memory::DEFAULT_MEMORY_THREADS;
+use hs_benchmark_suite::network::DEFAULT_NETWORK_PORT;
+use hs_benchmark_suite::report::CsvFormat;
+use hs_benchmark_suite::stress::DEFAULT_STRESS_DURATION;
+use hs_benchmark_suite::suite::DEFAULT_AUTO_SCALE_SECS;
+use hs_benchmark_suite::timing::DEFAULT_WARMUP_RUNS;
+use hs_benchmark_suite::units::UnitSystem;
+use hs_benchmark_suite::{Phase, Schedule};
+use std::path::Path;
+use std::time::Duration;
+
+/// Top-level action selected on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Run the benchmark suite (default)
+    Run,
+    /// Compare two previously written JSON reports
+    Compare { baseline: String, candidate: String },
+    /// Print per-metric trends from a history file
+    History { path: String },
+    /// Rank the hosts of the JSON reports in a directory
+    Aggregate { dir: String },
+    /// List the selectable benchmarks and their metrics
+    List,
+    /// Run the CPU, memory, and disk stress loads together for `duration`
+    Stress { duration: Duration },
+}
+
 pub struct BenchmarkArgs {
+    pub command: Command,
     pub scale: f64,
+    /// Per-subsystem scales layered on top of `scale`; None uses `scale`
+    pub cpu_scale: Option<f64>,
+    pub mem_scale: Option<f64>,
+    pub disk_scale: Option<f64>,
+    /// Target seconds per kernel pass for calibrating the scales; None disables calibration
+    pub auto_scale: Option<f64>,
+    pub min_time: Option<f64>,
+    pub warmup_runs: usize,
+    /// Seed mixed into the random inputs of every workload; None uses the built-in seeds
+    pub seed: Option<u64>,
+    /// Check the CPU kernels' output against references after timing them
+    pub verify: bool,
     pub count: usize,
+    /// Seconds to idle before every benchmark phase but the first
+    pub cooldown: f64,
+    /// Run the CPU, memory, and disk phases concurrently, or isolate every phase
+    pub schedule: Schedule,
+    /// Seconds each benchmark step may run; None derives the limit from the scale
+    pub timeout: Option<f64>,
     pub threads: usize,
+    pub mem_threads: usize,
+    pub mem_scaling: bool,
+    pub mem_numa: bool,
+    pub mem_patterns: Vec<AccessPattern>,
+    pub huge_pages: bool,
+    pub mem_prefault: bool,
+    pub core_latency: bool,
+    pub pin_cores: bool,
+    pub core_list: Option<Vec<usize>>,
     pub block_size: usize,
+    pub disk_path: Option<String>,
+    pub disk_mode: DiskMode,
+    /// Dataset written before the sequential disk passes; None writes the test file alone
+    pub disk_precondition: Option<Precondition>,
+    /// Repeat the sequential disk passes through the OS cache for comparison
+    pub disk_cache_compare: bool,
+    /// Mixed disk workloads to run after the standard disk passes
+    pub disk_profiles: Vec<DiskProfile>,
+    /// Percent of the CPU other processes may use before a run is contaminated
+    pub load_threshold: f64,
+    pub exclude_contaminated: bool,
+    /// Refuse to run on battery power instead of only warning
+    pub require_ac: bool,
     pub csv: bool,
+    pub csv_format: CsvFormat,
     pub json: bool,
+    /// Compare reports even when their config fingerprints differ
+    pub force: bool,
+    pub html: bool,
+    pub markdown: bool,
+    /// File the Mandelbrot image of the CPU benchmark is written to, as a PPM
+    pub mandelbrot_image: Option<String>,
+    /// Append the run's results to the history file
+    pub history: bool,
+    /// Re-run the suite every interval until interrupted
+    pub watch: Option<Duration>,
+    /// Number of reports of each format to keep; older `output_*` files are deleted
+    pub keep_reports: Option<usize>,
+    /// Directory the reports are written to; None writes them to the working directory
+    pub output_dir: Option<String>,
+    /// HTTP endpoint every run's JSON report is POSTed to
+    pub upload: Option<String>,
+    /// Print the resolved plan with its estimated duration instead of running the suite
+    pub dry_run: bool,
+    pub quiet: bool,
+    /// When to color the console output
+    pub color: ColorMode,
+    /// Prefixes of byte rates in the console and the CSV, HTML, and Markdown reports
+    pub units: UnitSystem,
     pub board_game: bool,
+    pub network_server: bool,
+    pub network_port: u16,
+    pub network_target: Option<String>,
+    /// Configuration file the options were loaded from, and the profile selected in it
+    pub config: Option<String>,
+    pub profile: Option<String>,
+    /// Benchmarks selected by the configuration file; None runs every default benchmark
+    pub benchmarks: Option<Vec<Phase>>,
+    /// Kernels selected by name in the configuration file; empty runs every kernel
+    pub kernels: Vec<String>,
 }
 
 impl Default for BenchmarkArgs {
     fn default() -> Self {
         Self {
+            command: Command::Run,
             scale: 1.0,
+            cpu_scale: None,
+            mem_scale: None,
+            disk_scale: None,
+            auto_scale: None,
+            min_time: None,
+            warmup_runs: DEFAULT_WARMUP_RUNS,
+            seed: None,
+            verify: false,
             count: 3,
+            cooldown: 0.0,
+            schedule: Schedule::Serial,
+            timeout: None,
             threads: 4,
+            mem_threads: DEFAULT_MEMORY_THREADS,
+            mem_scaling: false,
+            mem_numa: false,
+            mem_patterns: Vec::new(),
+            huge_pages: false,
+            mem_prefault: false,
+            core_latency: false,
+            pin_cores: false,
+            core_list: None,
             block_size: 512 * 1024, // 512 KB default
+            disk_path: None,
+            disk_mode: DiskMode::Direct,
+            disk_precondition: None,
+            disk_cache_compare: false,
+            disk_profiles: Vec::new(),
+            load_threshold: DEFAULT_LOAD_THRESHOLD_PERCENT,
+            exclude_contaminated: false,
+            require_ac: false,
             csv: false,
+            csv_format: CsvFormat::Wide,
             json: false,
+            force: false,
+            html: false,
+            markdown: false,
+            mandelbrot_image: None,
+            history: false,
+            watch: None,
+            keep_reports: None,
+            output_dir: None,
+            upload: None,
+            dry_run: false,
+            quiet: false,
+            color: ColorMode::Auto,
+            units: UnitSystem::Si,
             board_game: false,
+            network_server: false,
+            network_port: DEFAULT_NETWORK_PORT,
+            network_target: None,
+            config: None,
+            profile: None,
+            benchmarks: None,
+            kernels: Vec::new(),
         }
     }
 }
@@ -30,8 +191,101 @@ impl BenchmarkArgs {
         let cli_args: Vec<String> = std::env::args().collect();
 
         let mut i = 1;
+        if cli_args.get(1).map(String::as_str) == Some("compare") {
+            if cli_args.len() < 4 {
+                eprintln!("Error: compare requires two JSON report files");
+                eprintln!("Usage: benchmark compare <BASELINE.json> <CANDIDATE.json>");
+                std::process::exit(2);
+            }
+            args.command = Command::Compare {
+                baseline: cli_args[2].clone(),
+                candidate: cli_args[3].clone(),
+            };
+            i = 4;
+        } else if cli_args.get(1).map(String::as_str) == Some("history") {
+            i = 2;
+            let path = match cli_args.get(2) {
+                Some(path) if !path.starts_with('-') => {
+                    i = 3;
+                    path.clone()
+                }
+                _ => DEFAULT_HISTORY_FILE.to_string(),
+            };
+            args.command = Command::History { path };
+        } else if cli_args.get(1).map(String::as_str) == Some("aggregate") {
+            i = 2;
+            let dir = match cli_args.get(2) {
+                Some(dir) if !dir.starts_with('-') => {
+                    i = 3;
+                    dir.clone()
+                }
+                _ => ".".to_string(),
+            };
+            args.command = Command::Aggregate { dir };
+        } else if cli_args.get(1).map(String::as_str) == Some("list") {
+            args.command = Command::List;
+            i = 2;
+        } else if cli_args.get(1).map(String::as_str) == Some("stress") {
+            i = 2;
+            let duration = match cli_args.get(2) {
+                Some(duration) if !duration.starts_with('-') => {
+                    i = 3;
+                    parse_interval(duration).unwrap_or_else(|| {
+                        eprintln!(
+                            "Error: invalid stress duration '{}', expected e.g. 600, 30m, or 2h",
+                            duration
+                        );
+                        std::process::exit(2);
+                    })
+                }
+                _ => DEFAULT_STRESS_DURATION,
+            };
+            args.command = Command::Stress { duration };
+        }
+
+        // Options from the configuration file are applied first so that the command line
+        // overrides them
+        let env_option = |option: &str| std::env::var(format!("{}{}", ENV_PREFIX, option)).ok();
+        args.config = option_value(&cli_args, "--config").or_else(|| env_option("CONFIG"));
+        args.profile = option_value(&cli_args, "--profile").or_else(|| env_option("PROFILE"));
+        match &args.config {
+            Some(path) => {
+                let profile = args.profile.clone();
+                let loaded = ConfigFile::load(Path::new(path))
+                    .map_err(|e| e.to_string())
+                    .and_then(|config| config.apply(profile.as_deref(), &mut args));
+                if let Err(e) = loaded {
+                    eprintln!("Error: invalid --config: {}", e);
+                    std::process::exit(2);
+                }
+            }
+            None if args.profile.is_some() => {
+                eprintln!("Error: --profile requires --config");
+                std::process::exit(2);
+            }
+            None => {}
+        }
+
+        // HSBENCH_* environment variables are layered between the file and the command line
+        let env = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        if let Err(e) = ConfigOptions::from_env(env).and_then(|options| options.apply(&mut args)) {
+            eprintln!("Error: invalid environment variable {}", e);
+            std::process::exit(2);
+        }
+
         while i < cli_args.len() {
             match cli_args[i].as_str() {
+                "--config" | "--profile" => {
+                    // Already applied above
+                    if i + 1 < cli_args.len() {
+                        i += 2;
+                    } else {
+                        eprintln!("Error: {} requires a value", cli_args[i]);
+                        i += 1;
+                    }
+                }
                 "--scale" => {
                     if i + 1 < cli_args.len() {
                         args.scale = cli_args[i + 1].parse().unwrap_or(1.0);
@@ -41,6 +295,119 @@ impl BenchmarkArgs {
                         i += 1;
                     }
                 }
+                "--cpu-scale" => {
+                    if i + 1 < cli_args.len() {
+                        args.cpu_scale = cli_args[i + 1].parse().ok();
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --cpu-scale requires a value");
+                        i += 1;
+                    }
+                }
+                "--mem-scale" => {
+                    if i + 1 < cli_args.len() {
+                        args.mem_scale = cli_args[i + 1].parse().ok();
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --mem-scale requires a value");
+                        i += 1;
+                    }
+                }
+                "--disk-scale" => {
+                    if i + 1 < cli_args.len() {
+                        args.disk_scale = cli_args[i + 1].parse().ok();
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --disk-scale requires a value");
+                        i += 1;
+                    }
+                }
+                "--auto-scale" => {
+                    // The target duration is optional
+                    match cli_args.get(i + 1).and_then(|value| value.parse().ok()) {
+                        Some(seconds) => {
+                            args.auto_scale = Some(seconds);
+                            i += 2;
+                        }
+                        None => {
+                            args.auto_scale = Some(DEFAULT_AUTO_SCALE_SECS);
+                            i += 1;
+                        }
+                    }
+                }
+                "--min-time" => {
+                    if i + 1 < cli_args.len() {
+                        args.min_time = Some(cli_args[i + 1].parse().unwrap_or(0.0));
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --min-time requires a value");
+                        i += 1;
+                    }
+                }
+                "--warmup-runs" => {
+                    if i + 1 < cli_args.len() {
+                        args.warmup_runs = cli_args[i + 1].parse().unwrap_or(DEFAULT_WARMUP_RUNS);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --warmup-runs requires a value");
+                        i += 1;
+                    }
+                }
+                "--no-warmup" => {
+                    args.warmup_runs = 0;
+                    i += 1;
+                }
+                "--seed" => {
+                    if i + 1 < cli_args.len() {
+                        match cli_args[i + 1].parse() {
+                            Ok(seed) => args.seed = Some(seed),
+                            Err(_) => eprintln!(
+                                "Error: invalid --seed '{}', expected a non-negative integer",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --seed requires a value");
+                        i += 1;
+                    }
+                }
+                "--verify" => {
+                    args.verify = true;
+                    i += 1;
+                }
+                "--cooldown" => {
+                    if i + 1 < cli_args.len() {
+                        args.cooldown = cli_args[i + 1].parse().unwrap_or(0.0);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --cooldown requires a value");
+                        i += 1;
+                    }
+                }
+                "--parallel-suites" => {
+                    args.schedule = Schedule::Parallel;
+                    i += 1;
+                }
+                "--isolate" => {
+                    args.schedule = Schedule::Isolated;
+                    i += 1;
+                }
+                "--timeout" => {
+                    if i + 1 < cli_args.len() {
+                        match cli_args[i + 1].parse() {
+                            Ok(seconds) => args.timeout = Some(seconds),
+                            Err(_) => eprintln!(
+                                "Error: invalid --timeout '{}', expected a number of seconds",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --timeout requires a value");
+                        i += 1;
+                    }
+                }
                 "--count" => {
                     if i + 1 < cli_args.len() {
                         args.count = cli_args[i + 1].parse().unwrap_or(1);
@@ -59,6 +426,73 @@ impl BenchmarkArgs {
                         i += 1;
                     }
                 }
+                "--mem-threads" => {
+                    if i + 1 < cli_args.len() {
+                        args.mem_threads =
+                            cli_args[i + 1].parse().unwrap_or(DEFAULT_MEMORY_THREADS);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --mem-threads requires a value");
+                        i += 1;
+                    }
+                }
+                "--mem-scaling" => {
+                    args.mem_scaling = true;
+                    i += 1;
+                }
+                "--mem-numa" => {
+                    args.mem_numa = true;
+                    i += 1;
+                }
+                "--mem-patterns" => {
+                    if i + 1 < cli_args.len() {
+                        match parse_access_patterns(&cli_args[i + 1]) {
+                            Some(patterns) => args.mem_patterns = patterns,
+                            None => eprintln!(
+                                "Error: invalid --mem-patterns '{}', expected e.g. stride4096,random",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --mem-patterns requires a value");
+                        i += 1;
+                    }
+                }
+                "--huge-pages" => {
+                    args.huge_pages = true;
+                    i += 1;
+                }
+                "--mem-prefault" => {
+                    args.mem_prefault = true;
+                    i += 1;
+                }
+                "--core-latency" => {
+                    args.core_latency = true;
+                    i += 1;
+                }
+                "--pin-cores" => {
+                    args.pin_cores = true;
+                    i += 1;
+                }
+                "--core-list" => {
+                    if i + 1 < cli_args.len() {
+                        match parse_core_list(&cli_args[i + 1]) {
+                            Some(cores) => {
+                                args.core_list = Some(cores);
+                                args.pin_cores = true;
+                            }
+                            None => eprintln!(
+                                "Error: invalid --core-list '{}', expected e.g. 0,2,4",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --core-list requires a value");
+                        i += 1;
+                    }
+                }
                 "--block-size" => {
                     if i + 1 < cli_args.len() {
                         args.block_size = cli_args[i + 1].parse().unwrap_or(512 * 1024);
@@ -68,14 +502,252 @@ impl BenchmarkArgs {
                         i += 1;
                     }
                 }
+                "--disk-path" => {
+                    if i + 1 < cli_args.len() {
+                        args.disk_path = Some(cli_args[i + 1].clone());
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --disk-path requires a value");
+                        i += 1;
+                    }
+                }
+                "--disk-mode" => {
+                    if i + 1 < cli_args.len() {
+                        match DiskMode::from_name(&cli_args[i + 1]) {
+                            Some(mode) => args.disk_mode = mode,
+                            None => eprintln!(
+                                "Error: invalid --disk-mode '{}', expected direct or mmap",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --disk-mode requires a value");
+                        i += 1;
+                    }
+                }
+                "--disk-precondition" => {
+                    // The dataset multiple is optional
+                    let precondition = args.disk_precondition.get_or_insert_default();
+                    match cli_args.get(i + 1).and_then(|value| value.parse().ok()) {
+                        Some(multiple) => {
+                            precondition.multiple = multiple;
+                            i += 2;
+                        }
+                        None => i += 1,
+                    }
+                }
+                "--disk-precondition-passes" => {
+                    if i + 1 < cli_args.len() {
+                        args.disk_precondition.get_or_insert_default().passes =
+                            cli_args[i + 1].parse().unwrap_or(1);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --disk-precondition-passes requires a value");
+                        i += 1;
+                    }
+                }
+                "--disk-cache-compare" => {
+                    args.disk_cache_compare = true;
+                    i += 1;
+                }
+                "--disk-profile" => {
+                    if i + 1 < cli_args.len() {
+                        match parse_disk_profiles(&cli_args[i + 1]) {
+                            Some(profiles) => args.disk_profiles = profiles,
+                            None => eprintln!(
+                                "Error: invalid --disk-profile '{}', expected e.g. oltp,backup",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --disk-profile requires a value");
+                        i += 1;
+                    }
+                }
+                "--load-threshold" => {
+                    if i + 1 < cli_args.len() {
+                        args.load_threshold = cli_args[i + 1]
+                            .parse()
+                            .unwrap_or(DEFAULT_LOAD_THRESHOLD_PERCENT);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --load-threshold requires a value");
+                        i += 1;
+                    }
+                }
+                "--exclude-contaminated" => {
+                    args.exclude_contaminated = true;
+                    i += 1;
+                }
+                "--require-ac" => {
+                    args.require_ac = true;
+                    i += 1;
+                }
+                "--network-server" => {
+                    args.network_server = true;
+                    i += 1;
+                }
+                "--network-port" => {
+                    if i + 1 < cli_args.len() {
+                        args.network_port = cli_args[i + 1].parse().unwrap_or(DEFAULT_NETWORK_PORT);
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --network-port requires a value");
+                        i += 1;
+                    }
+                }
+                "--network-target" => {
+                    if i + 1 < cli_args.len() {
+                        args.network_target = Some(cli_args[i + 1].clone());
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --network-target requires a value");
+                        i += 1;
+                    }
+                }
                 "--csv" => {
                     args.csv = true;
                     i += 1;
                 }
+                "--csv-format" => {
+                    if i + 1 < cli_args.len() {
+                        match CsvFormat::from_name(&cli_args[i + 1]) {
+                            Some(format) => {
+                                args.csv_format = format;
+                                args.csv = true;
+                            }
+                            None => eprintln!(
+                                "Error: invalid --csv-format '{}', expected wide or long",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --csv-format requires a value");
+                        i += 1;
+                    }
+                }
                 "--json" => {
                     args.json = true;
                     i += 1;
                 }
+                "--force" => {
+                    args.force = true;
+                    i += 1;
+                }
+                "--list" => {
+                    args.command = Command::List;
+                    i += 1;
+                }
+                "--html" => {
+                    args.html = true;
+                    i += 1;
+                }
+                "--markdown" => {
+                    args.markdown = true;
+                    i += 1;
+                }
+                "--history" => {
+                    args.history = true;
+                    i += 1;
+                }
+                "--watch" => {
+                    if i + 1 < cli_args.len() {
+                        match parse_interval(&cli_args[i + 1]) {
+                            Some(interval) => args.watch = Some(interval),
+                            None => eprintln!(
+                                "Error: invalid --watch interval '{}', expected e.g. 90, 30m or 1h",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --watch requires a value");
+                        i += 1;
+                    }
+                }
+                "--keep" => {
+                    if i + 1 < cli_args.len() {
+                        match cli_args[i + 1].parse() {
+                            Ok(keep) if keep > 0 => args.keep_reports = Some(keep),
+                            _ => eprintln!(
+                                "Error: invalid --keep '{}', expected a positive number",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --keep requires a value");
+                        i += 1;
+                    }
+                }
+                "--mandelbrot-image" => {
+                    if i + 1 < cli_args.len() {
+                        args.mandelbrot_image = Some(cli_args[i + 1].clone());
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --mandelbrot-image requires a value");
+                        i += 1;
+                    }
+                }
+                "--output-dir" => {
+                    if i + 1 < cli_args.len() {
+                        args.output_dir = Some(cli_args[i + 1].clone());
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --output-dir requires a value");
+                        i += 1;
+                    }
+                }
+                "--upload" => {
+                    if i + 1 < cli_args.len() {
+                        args.upload = Some(cli_args[i + 1].clone());
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --upload requires a URL");
+                        i += 1;
+                    }
+                }
+                "--dry-run" => {
+                    args.dry_run = true;
+                    i += 1;
+                }
+                "--quiet" | "-q" => {
+                    args.quiet = true;
+                    i += 1;
+                }
+                "--color" => {
+                    if i + 1 < cli_args.len() {
+                        match ColorMode::from_name(&cli_args[i + 1]) {
+                            Some(mode) => args.color = mode,
+                            None => eprintln!(
+                                "Error: invalid --color '{}', expected auto, always, or never",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --color requires a value");
+                        i += 1;
+                    }
+                }
+                "--units" => {
+                    if i + 1 < cli_args.len() {
+                        match UnitSystem::from_name(&cli_args[i + 1]) {
+                            Some(units) => args.units = units,
+                            None => eprintln!(
+                                "Error: invalid --units '{}', expected si or binary",
+                                cli_args[i + 1]
+                            ),
+                        }
+                        i += 2;
+                    } else {
+                        eprintln!("Error: --units requires a value");
+                        i += 1;
+                    }
+                }
                 "--board-game" => {
                     args.board_game = true;
                     i += 1;
@@ -97,21 +769,91 @@ impl BenchmarkArgs {
             args.scale = 1.0;
         }
 
+        for (name, scale) in [
+            ("cpu-scale", &mut args.cpu_scale),
+            ("mem-scale", &mut args.mem_scale),
+            ("disk-scale", &mut args.disk_scale),
+            ("auto-scale", &mut args.auto_scale),
+        ] {
+            if scale.is_some_and(|scale| scale <= 0.0 || !scale.is_finite()) {
+                eprintln!("Warning: {} must be positive, using --scale", name);
+                *scale = None;
+            }
+        }
+
+        if args
+            .min_time
+            .is_some_and(|seconds| seconds <= 0.0 || !seconds.is_finite())
+        {
+            eprintln!("Warning: min-time must be positive, using fixed-size workloads");
+            args.min_time = None;
+        }
+
         if args.count == 0 {
             eprintln!("Warning: count must be at least 1, setting to 1");
             args.count = 1;
         }
 
+        if args.cooldown < 0.0 || !args.cooldown.is_finite() {
+            eprintln!("Warning: cooldown must be a non-negative number of seconds, setting to 0");
+            args.cooldown = 0.0;
+        }
+
+        if args
+            .timeout
+            .is_some_and(|seconds| seconds < 0.0 || !seconds.is_finite())
+        {
+            eprintln!(
+                "Warning: timeout must be a non-negative number of seconds, using the default"
+            );
+            args.timeout = None;
+        }
+
         if args.threads == 0 {
             eprintln!("Warning: threads must be at least 1, setting to 4");
             args.threads = 4;
         }
 
+        if args.mem_threads == 0 {
+            eprintln!(
+                "Warning: mem-threads must be at least 1, setting to {}",
+                DEFAULT_MEMORY_THREADS
+            );
+            args.mem_threads = DEFAULT_MEMORY_THREADS;
+        }
+
         if args.block_size == 0 {
             eprintln!("Warning: block-size must be at least 1, setting to 512 KB");
             args.block_size = 512 * 1024;
         }
 
+        if let Some(precondition) = &mut args.disk_precondition {
+            if precondition.multiple < 1.0 || !precondition.multiple.is_finite() {
+                eprintln!(
+                    "Warning: disk-precondition must be at least 1, setting to {}",
+                    DEFAULT_PRECONDITION_MULTIPLE
+                );
+                precondition.multiple = DEFAULT_PRECONDITION_MULTIPLE;
+            }
+            if precondition.passes == 0 {
+                eprintln!("Warning: disk-precondition-passes must be at least 1, setting to 1");
+                precondition.passes = 1;
+            }
+        }
+
+        if args.load_threshold < 0.0 || !args.load_threshold.is_finite() {
+            eprintln!(
+                "Warning: load-threshold must not be negative, setting to {}",
+                DEFAULT_LOAD_THRESHOLD_PERCENT
+            );
+            args.load_threshold = DEFAULT_LOAD_THRESHOLD_PERCENT;
+        }
+
+        // Watch mode exists to build up the history for trend analysis
+        if args.watch.is_some() {
+            args.history = true;
+        }
+
         args
     }
 
@@ -120,34 +862,277 @@ impl BenchmarkArgs {
         println!();
         println!("USAGE:");
         println!("    benchmark [OPTIONS]");
+        println!("    benchmark compare <BASELINE.json> <CANDIDATE.json> [--force]");
+        println!("    benchmark history [FILE]");
+        println!("    benchmark aggregate [DIR] [--json]");
+        println!("    benchmark list [--json]");
+        println!("    benchmark stress [DURATION]");
+        println!();
+        println!("COMMANDS:");
+        println!("    compare            Compare two JSON reports and flag significant changes");
+        println!("                        (changes significant at the 95% level, Welch's t-test)");
+        println!("                        Refuses reports whose config fingerprints differ,");
+        println!("                        i.e. that measured different work, unless --force");
+        println!("    history            Show per-metric trends from a --history file (default:");
+        println!("                        benchmark_history.jsonl) and flag regressions");
+        println!("    aggregate          Rank the hosts of the JSON reports in DIR (default: the");
+        println!("                        working directory) by score and on every metric, with");
+        println!("                        the CV across hosts; the latest report of each host is");
+        println!("                        used (as JSON with --json)");
+        println!(
+            "    list, --list       List the benchmarks and kernels that can be selected, with"
+        );
+        println!("                        their metrics and units (as JSON with --json)");
+        println!("    stress             Load the CPU (every core), memory (--mem-threads), and");
+        println!("                        disk (--disk-path) together for DURATION (default:");
+        println!("                        10m), check every result, and print a PASS/FAIL");
+        println!("                        stability verdict; --scale sizes the working sets");
         println!();
         println!("OPTIONS:");
+        println!("    --config <FILE>    Load options from a TOML configuration file");
+        println!("                        Options given on the command line take precedence");
+        println!("                        Every option of the file can also be set with an");
+        println!("                        HSBENCH_<OPTION> environment variable, e.g.");
+        println!("                        HSBENCH_SCALE=0.5 or HSBENCH_BENCHMARKS=cpu,memory,");
+        println!("                        which overrides the file but not the command line");
+        println!(
+            "    --profile <NAME>   Also apply the [profiles.NAME] table of the --config file"
+        );
         println!("    --scale <VALUE>    Scale factor for benchmark intensity (default: 1.0)");
         println!("                        Higher values increase test duration and memory usage");
+        println!("    --cpu-scale <VALUE>, --mem-scale <VALUE>, --disk-scale <VALUE>");
+        println!("                        Scale of one benchmark, overriding --scale for it");
+        println!("    --auto-scale [SECONDS]");
+        println!("                        Calibrate the CPU, memory, and disk scales so a kernel");
+        println!("                        pass takes about SECONDS on this machine (default: 3)");
+        println!("    --min-time <SECONDS>");
+        println!("                        Repeat each CPU, memory, and disk kernel until it has");
+        println!(
+            "                        run for SECONDS and report the rate over all repetitions"
+        );
+        println!("    --warmup-runs <NUM> Untimed warmup passes before the CPU, memory, disk, and");
+        println!("                        network kernels (default: 1); raise for CPUs that ramp");
+        println!("                        their clocks slowly");
+        println!(
+            "    --no-warmup        Skip warmup, e.g. for cold-cache studies (--warmup-runs 0)"
+        );
+        println!("    --seed <NUM>       Seed the random inputs of every workload (matrices,");
+        println!("                        Mandelbrot samples, disk offsets, ...); the same seed");
+        println!("                        runs the same workload on every machine (default:");
+        println!("                        built-in seeds)");
+        println!("    --verify           Check the output of the matrix, FFT, and prime kernels");
+        println!("                        against references after timing them");
         println!("    --count <NUM>      Number of times to run benchmarks (default: 3)");
         println!("                        Results from multiple runs are averaged");
+        println!("    --cooldown <SECONDS>");
+        println!("                        Idle before every phase but the first so thermals");
+        println!("                        recover between phases and runs (default: 0)");
+        println!("    --parallel-suites  Run the CPU, memory, and disk benchmarks at the same");
+        println!("                        time as a combined stress load");
+        println!("    --isolate          Run the benchmarks strictly one at a time with an idle");
+        println!("                        gap of at least 10s before each (or --cooldown)");
+        println!("    --timeout <SECONDS>");
+        println!("                        Cut short any benchmark step running longer and mark");
+        println!("                        it as timed out; 0 disables the watchdog (default:");
+        println!("                        600s per unit of scale, at least 120s)");
         println!("    --thread <NUM>     Number of threads for parallel benchmark (default: 4)");
         println!("                        Controls multithreaded matrix multiplication");
+        println!("    --mem-threads <NUM> Number of threads for the memory benchmark (default: 8)");
+        println!("    --mem-scaling      Also measure memory bandwidth with 1, 2, 4, ... threads");
+        println!("    --mem-numa         Also compare local and remote NUMA node memory");
+        println!("                        up to --mem-threads to show where the bus saturates");
+        println!("    --mem-patterns <LIST>");
+        println!("                        Also measure read bandwidth with these access patterns:");
+        println!("                        sequential, backward, stride64, stride256, stride4096,");
+        println!("                        random (comma-separated)");
+        println!("    --huge-pages       Also measure memory bandwidth with buffers on huge pages");
+        println!("    --mem-prefault     Fault memory buffers in before timing the write and read");
+        println!("                        passes, so they measure resident bandwidth");
+        println!(
+            "    --core-latency     Also measure the cache-line latency between every two cores"
+        );
+        println!(
+            "    --pin-cores        Pin CPU, memory, and concurrency benchmark threads to cores"
+        );
+        println!("                        Avoids migrations between P-cores and E-cores");
+        println!("    --core-list <LIST> Cores to pin to, e.g. 0,2,4 (implies --pin-cores)");
+        println!("                        Worker N runs on the Nth listed core, wrapping around");
         println!("    --block-size <SIZE> Disk benchmark block size in bytes (default: 524288)");
         println!("                        Use 131072 for 128 KB, 1048576 for 1 MB, etc.");
+        println!("    --disk-path <DIR>  Directory for the disk benchmark (default: current dir)");
+        println!("                        Selects the filesystem/device that is measured");
+        println!("    --disk-mode <MODE> Disk access mode: direct (default) or mmap, which copies");
+        println!("                        through a memory-mapped file and measures page faults");
+        println!("    --disk-precondition [MULTIPLE]");
+        println!("                        Write a dataset of MULTIPLE times the test file size");
+        println!("                        (default: 4) first, so that SSDs are measured in their");
+        println!("                        steady state; reports burst and steady-state writes");
+        println!("    --disk-precondition-passes <N>");
+        println!(
+            "                        Run at least N write passes after the dataset and report"
+        );
+        println!("                        each (default: 1; implies --disk-precondition)");
+        println!("    --disk-cache-compare");
+        println!("                        Also run the sequential disk passes through the OS");
+        println!("                        cache and report the speedup over uncached I/O");
+        println!("    --disk-profile <LIST>");
+        println!("                        Also run mixed disk workloads, e.g. oltp,backup:");
+        println!("                        oltp (random 8 KB, 70% reads), streaming (sequential");
+        println!("                        1 MB reads), backup (sequential 1 MB writes)");
+        println!("    --load-threshold <PERCENT>");
+        println!("                        Mark runs as contaminated when other processes use more");
+        println!("                        than PERCENT of the total CPU capacity (default: 10)");
+        println!("    --exclude-contaminated");
+        println!(
+            "                        Leave contaminated runs out of the results and statistics"
+        );
+        println!("    --require-ac       Refuse to run on battery power (default: warn only)");
+        println!(
+            "    --network-server   Run as a network benchmark server instead of benchmarking"
+        );
+        println!("    --network-port <PORT> Port for --network-server (default: 9797)");
+        println!("    --network-target <HOST:PORT>");
+        println!("                        Benchmark the network against a remote --network-server");
+        println!("                        instead of loopback");
         println!("    --csv              Output results to output.csv file");
+        println!("    --csv-format <wide|long>");
+        println!("                        CSV layout (implies --csv): wide has a row per metric");
+        println!("                        and a column per run (default); long has a row per");
+        println!("                        metric and run (metric,unit,run,value) and writes the");
+        println!("                        statistics to a separate output_*_stats.csv");
         println!("    --json             Output results to output.json file with full statistics");
+        println!("    --html             Output a self-contained HTML report with charts");
+        println!(
+            "    --markdown         Output a Markdown report for pasting into issues and wikis"
+        );
+        println!("    --mandelbrot-image <FILE>");
+        println!("                        Write the Mandelbrot set the CPU benchmark computes to");
+        println!("                        FILE as a PPM image, to check the measured work");
+        println!("    --history          Append the results to benchmark_history.jsonl");
+        println!(
+            "    --watch <INTERVAL> Re-run the suite every INTERVAL until interrupted, e.g. 90,"
+        );
+        println!("                        30s, 10m, 1h or 1d (implies --history)");
+        println!("    --keep <NUM>       After writing reports, delete all but the NUM newest");
+        println!("                        output_* files of each format");
+        println!(
+            "    --output-dir <DIR> Write the reports to DIR instead of the working directory"
+        );
+        println!("    --upload <URL>     POST the JSON report of every run to URL (http:// only),");
+        println!("                        retrying failures with backoff; the token in");
+        println!("                        HSBENCH_UPLOAD_TOKEN is sent as a bearer token, and");
+        println!("                        only to localhost, as plain HTTP does not encrypt it");
+        println!("    --dry-run          Print the resolved scales, sizes, paths and an estimated");
+        println!("                        duration from quick calibration passes, then exit");
+        println!("    --quiet, -q        Hide the progress bar and ETA shown while benchmarks run");
+        println!(
+            "    --color <WHEN>     Color the output, including the green/red change of every"
+        );
+        println!("                        metric from the previous run: auto (default, when");
+        println!("                        stdout is a terminal and NO_COLOR is unset), always,");
+        println!("                        or never");
+        println!("    --units <si|binary>");
+        println!("                        Show byte rates as MB/s and GB/s (si, default) or as");
+        println!("                        MiB/s and GiB/s (binary); JSON reports keep the units");
+        println!("                        the rates were measured in");
         println!("    --help, -h         Print this help message");
         println!();
         println!("EXAMPLES:");
         println!("    benchmark                    # Run with default settings");
         println!("    benchmark --scale 2.0        # Run with 2x intensity");
+        println!("    benchmark --disk-scale 0.1   # Keep the other benchmarks at full scale");
+        println!("    benchmark --auto-scale 2     # Size the workloads for this machine");
         println!("    benchmark --count 3          # Run 3 times and show average");
+        println!("    benchmark --min-time 2       # Run every kernel for at least 2 seconds");
+        println!("    benchmark --no-warmup        # Measure with cold caches");
+        println!("    benchmark --seed 42          # Run another, equally reproducible workload");
+        println!(
+            "    benchmark --verify           # Also check that the kernels compute correctly"
+        );
+        println!("    benchmark --cooldown 30      # Let a laptop cool down between phases");
         println!("    benchmark --thread 8         # Run parallel test with 8 threads");
+        println!("    benchmark --mem-scaling --mem-threads 16");
+        println!("                                  # Memory bandwidth curve from 1 to 16 threads");
+        println!("    benchmark --pin-cores        # Pin benchmark threads to all available cores");
+        println!("    benchmark --core-list 0,2,4  # Pin benchmark threads to cores 0, 2 and 4");
         println!("    benchmark --block-size 131072 # Use 128 KB blocks for disk benchmark");
         println!("    benchmark --scale 0.5 --count 5 --thread 2 --block-size 1048576");
         println!("                                  # Combined options with 1 MB blocks");
+        println!("    benchmark --disk-path /mnt/nvme # Benchmark the disk behind /mnt/nvme");
+        println!("    benchmark --disk-mode mmap   # Measure memory-mapped file I/O");
+        println!("    benchmark --disk-profile oltp # Add a database-like random 8 KB workload");
+        println!("    benchmark --disk-cache-compare");
+        println!("                                  # Disk speedup from the OS cache");
+        println!("    benchmark --disk-precondition 8 --disk-precondition-passes 3");
+        println!("                                  # SSD steady-state writes after an 8x dataset");
+        println!("    benchmark --count 10 --quiet # Run without the progress bar");
+        println!("    benchmark --scale 8 --dry-run # Check sizes and duration before a long run");
+        println!("    benchmark --config bench.toml --profile nightly");
+        println!("                                  # Run the team's nightly profile");
+        println!("    benchmark compare before.json after.json");
+        println!("                                  # Show improvements and regressions");
+        println!("    benchmark --history          # Record this run in the history file");
+        println!("    benchmark history            # Show trends of the recorded runs");
+        println!("    benchmark aggregate fleet/   # Rank the machines of a lab or fleet");
+        println!("    benchmark list --json        # Discover benchmarks and metrics");
+        println!("    benchmark --watch 1h --json --keep 24");
+        println!("                                  # Monitor hourly, keeping a day of reports");
+        println!("    benchmark --network-server   # Serve network benchmarks on port 9797");
+        println!("    benchmark --network-target 192.168.1.10:9797");
+        println!("                                  # Benchmark the network against that server");
     }
 }
 
+/// Value following the last occurrence of `flag`, if any
+fn option_value(cli_args: &[String], flag: &str) -> Option<String> {
+    let index = cli_args.iter().rposition(|arg| arg == flag)?;
+    cli_args.get(index + 1).cloned()
+}
+
+/// Parse an interval such as "90", "30s", "10m", "1h" or "1d"; a bare number is in seconds
+/// Returns None for malformed or zero intervals
+pub fn parse_interval(interval: &str) -> Option<Duration> {
+    let interval = interval.trim();
+    let (number, unit_seconds) = match interval.char_indices().last()? {
+        (i, 's') => (&interval[..i], 1),
+        (i, 'm') => (&interval[..i], 60),
+        (i, 'h') => (&interval[..i], 60 * 60),
+        (i, 'd') => (&interval[..i], 24 * 60 * 60),
+        _ => (interval, 1),
+    };
+    let seconds: u64 = number.trim().parse().ok()?;
+    (seconds > 0).then(|| Duration::from_secs(seconds * unit_seconds))
+}
+
+/// Parse a comma-separated list of core numbers such as "0,2,4"
+/// Returns None if the list is empty or any entry is not a number
+fn parse_core_list(list: &str) -> Option<Vec<usize>> {
+    list.split(',')
+        .map(|core| core.trim().parse().ok())
+        .collect::<Option<Vec<usize>>>()
+        .filter(|cores| !cores.is_empty())
+}
+
+/// Parse a comma-separated list of access pattern names such as "stride256,random"
+/// Returns None if any entry is not a known pattern
+fn parse_access_patterns(list: &str) -> Option<Vec<AccessPattern>> {
+    list.split(',')
+        .map(|name| AccessPattern::from_name(name.trim()))
+        .collect()
+}
+
+/// Parse a comma-separated list of disk profile names such as "oltp,backup"
+/// Returns None if any entry is not a known profile
+fn parse_disk_profiles(list: &str) -> Option<Vec<DiskProfile>> {
+    list.split(',')
+        .map(|name| DiskProfile::from_name(name.trim()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hs_benchmark_suite::SuiteConfig;
 
     #[test]
     fn test_default_args() {
@@ -161,6 +1146,46 @@ mod tests {
         assert!(!args.board_game);
     }
 
+    #[test]
+    fn test_defaults_match_the_library_configuration() {
+        let args = BenchmarkArgs::default();
+        let config = SuiteConfig::default();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.scale, config.scale);
+        assert_eq!(args.cpu_scale, config.cpu_scale);
+        assert_eq!(args.mem_scale, config.memory_scale);
+        assert_eq!(args.disk_scale, config.disk_scale);
+        assert_eq!(args.auto_scale, config.auto_scale);
+        assert_eq!(args.min_time, config.min_time);
+        assert_eq!(args.warmup_runs, config.warmup_runs);
+        assert_eq!(args.seed, config.seed);
+        assert_eq!(args.count, config.runs);
+        assert_eq!(args.threads, config.threads);
+        assert_eq!(args.mem_threads, config.memory_threads);
+        assert_eq!(args.mem_scaling, config.memory_scaling);
+        assert_eq!(args.mem_numa, config.memory_numa);
+        assert_eq!(args.mem_patterns, config.memory_patterns);
+        assert_eq!(args.huge_pages, config.memory_huge_pages);
+        assert_eq!(args.mem_prefault, config.memory_prefault);
+        assert_eq!(args.core_latency, config.core_latency);
+        assert_eq!(args.pin_cores, config.pin_cores);
+        assert_eq!(args.core_list.unwrap_or_default(), config.core_list);
+        assert_eq!(args.block_size, config.block_size);
+        assert_eq!(args.disk_path.is_none(), config.disk_path.is_none());
+        assert_eq!(args.disk_mode, config.disk_mode);
+        assert_eq!(args.disk_precondition, config.disk_precondition);
+        assert_eq!(args.disk_cache_compare, config.disk_cache_compare);
+        assert_eq!(args.disk_profiles, config.disk_profiles);
+        assert_eq!(args.cooldown, config.cooldown);
+        assert_eq!(args.schedule, config.schedule);
+        assert_eq!(args.timeout, config.timeout);
+        assert_eq!(args.verify, config.verify);
+        assert_eq!(args.kernels, config.kernels);
+        assert_eq!(args.network_target, config.network_target);
+        assert_eq!(args.load_threshold, config.load_threshold_percent);
+        assert_eq!(args.exclude_contaminated, config.exclude_contaminated);
+    }
+
     #[test]
     fn test_args_validation_negative_scale() {
         // Note: This test documents current behavior
@@ -173,6 +1198,7 @@ mod tests {
             csv: false,
             json: false,
             board_game: false,
+            ..BenchmarkArgs::default()
         };
         // Should be valid after constructor, but parse() validates
         assert_eq!(args.scale, -1.0);
@@ -188,6 +1214,7 @@ mod tests {
             csv: false,
             json: false,
             board_game: false,
+            ..BenchmarkArgs::default()
         };
         assert_eq!(args.count, 0);
     }
@@ -202,6 +1229,7 @@ mod tests {
             csv: true,
             json: true,
             board_game: true,
+            ..BenchmarkArgs::default()
         };
         assert_eq!(args.scale, 2.5);
         assert_eq!(args.count, 10);
@@ -232,6 +1260,7 @@ mod tests {
             csv: false,
             json: false,
             board_game: false,
+            ..BenchmarkArgs::default()
         };
         assert_eq!(args.block_size, 128 * 1024);
     }
@@ -246,7 +1275,67 @@ mod tests {
             csv: false,
             json: false,
             board_game: false,
+            ..BenchmarkArgs::default()
         };
         assert_eq!(args.block_size, 1024 * 1024);
     }
+
+    #[test]
+    fn test_option_value() {
+        let cli_args: Vec<String> = ["benchmark", "--config", "a.toml", "--config", "b.toml"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            option_value(&cli_args, "--config").as_deref(),
+            Some("b.toml")
+        );
+        assert!(option_value(&cli_args, "--profile").is_none());
+        assert!(option_value(&cli_args[..2], "--config").is_none());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_interval("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_interval("10m"), Some(Duration::from_secs(600)));
+        assert_eq!(parse_interval("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_interval("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_interval("0"), None);
+        assert_eq!(parse_interval("1.5h"), None);
+        assert_eq!(parse_interval("h"), None);
+        assert_eq!(parse_interval(""), None);
+    }
+
+    #[test]
+    fn test_parse_core_list() {
+        assert_eq!(parse_core_list("0,2,4"), Some(vec![0, 2, 4]));
+        assert_eq!(parse_core_list(" 3 , 1"), Some(vec![3, 1]));
+        assert_eq!(parse_core_list("0,x"), None);
+        assert_eq!(parse_core_list(""), None);
+    }
+
+    #[test]
+    fn test_parse_access_patterns() {
+        assert_eq!(
+            parse_access_patterns("Backward, stride4096,random"),
+            Some(vec![
+                AccessPattern::Backward,
+                AccessPattern::Stride4096,
+                AccessPattern::Random
+            ])
+        );
+        assert_eq!(parse_access_patterns("stride128"), None);
+        assert_eq!(parse_access_patterns(""), None);
+    }
+
+    #[test]
+    fn test_parse_disk_profiles() {
+        assert_eq!(
+            parse_disk_profiles("oltp, Backup"),
+            Some(vec![DiskProfile::Oltp, DiskProfile::Backup])
+        );
+        assert_eq!(parse_disk_profiles("oltp,random"), None);
+        assert_eq!(parse_disk_profiles(""), None);
+    }
 }
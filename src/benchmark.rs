@@ -0,0 +1,190 @@
+/// Benchmark kernels and their registry
+/// Every kernel implements `Benchmark` and is listed in `registry()`, so the runner,
+/// kernel selection by name, and listings all iterate the same table instead of naming
+/// each kernel by hand. Adding a kernel means implementing the trait and adding it to the
+/// registry; its metrics are then recorded, timed, and charged energy and counters like
+/// every other kernel's.
+use crate::cpu;
+use crate::error::BenchError;
+use crate::pool::ThreadPool;
+use crate::suite::Phase;
+use crate::timing::KernelTiming;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+
+/// What a kernel needs besides its scale
+pub struct BenchContext<'a> {
+    /// Repeat the kernel until this many seconds have passed (0 runs the workload once)
+    pub min_time: f64,
+    /// Workers shared by the parallel kernels of a phase
+    pub pool: &'a ThreadPool,
+}
+
+/// Name and unit of one rate a kernel reports
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricSpec {
+    /// Key of the rate in the result's `timings`, e.g. "sieve_mibs"
+    pub name: &'static str,
+    /// Unit of the rate, e.g. MB/s
+    pub unit: Unit,
+}
+
+/// One measured rate of a kernel run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metric {
+    pub name: &'static str,
+    pub unit: Unit,
+    /// Raw work and elapsed time behind the rate
+    pub timing: KernelTiming,
+}
+
+impl Metric {
+    /// The rate, in `unit`
+    pub fn value(&self) -> f64 {
+        self.timing.rate()
+    }
+}
+
+/// Outcome of checking a kernel's output against a reference (`--verify`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Verification {
+    /// Registry name of the kernel, e.g. "matrix_blocked"
+    pub kernel: String,
+    pub passed: bool,
+    /// What was checked, or how the output differed from the reference
+    pub detail: String,
+}
+
+/// A benchmark kernel
+pub trait Benchmark: Sync {
+    /// Unique name used to select the kernel, e.g. "parallel_fft"
+    fn name(&self) -> &'static str;
+
+    /// Phase whose results hold the kernel's metrics
+    fn phase(&self) -> Phase;
+
+    /// Short description shown in listings and progress, e.g. "FFT (MT)"
+    fn description(&self) -> &'static str;
+
+    /// The rates `run` returns, in order; `run` may leave out trailing ones at small scales
+    fn metrics(&self) -> &'static [MetricSpec];
+
+    /// Run a small untimed workload to stabilize caches and branch predictors
+    fn warmup(&self, _scale: f64, _ctx: &BenchContext) {}
+
+    /// Run the timed kernel; returns one metric per entry of `metrics()`, or
+    /// `BenchError::InvalidResult` when the kernel's own output check fails
+    fn run(&self, scale: f64, ctx: &BenchContext) -> Result<Vec<Metric>, BenchError>;
+
+    /// Check the kernel's output at `scale` against an independent reference, untimed
+    /// Returns: what was checked, or how the output differed; None for kernels without a check
+    fn verify(&self, _scale: f64, _ctx: &BenchContext) -> Option<Result<String, String>> {
+        None
+    }
+
+    /// `verify` as a recorded outcome; None for kernels without a check
+    fn verification(&self, scale: f64, ctx: &BenchContext) -> Option<Verification> {
+        let outcome = self.verify(scale, ctx)?;
+        Some(Verification {
+            kernel: self.name().to_string(),
+            passed: outcome.is_ok(),
+            detail: outcome.unwrap_or_else(|e| e),
+        })
+    }
+}
+
+/// Every kernel, in execution order
+pub fn registry() -> impl Iterator<Item = &'static dyn Benchmark> {
+    cpu::KERNELS
+        .iter()
+        .map(|kernel| kernel as &'static dyn Benchmark)
+}
+
+/// Registered kernel with the given name, ignoring case
+pub fn find(name: &str) -> Option<&'static dyn Benchmark> {
+    registry().find(|benchmark| benchmark.name().eq_ignore_ascii_case(name))
+}
+
+/// Registered kernels of `phase`, in execution order
+pub fn phase_kernels(phase: Phase) -> impl Iterator<Item = &'static dyn Benchmark> {
+    registry().filter(move |benchmark| benchmark.phase() == phase)
+}
+
+/// What `--list` shows: the selectable benchmark names and every registered kernel
+#[derive(Debug, Clone, Serialize)]
+pub struct Listing {
+    /// Phases that can be selected as a whole, e.g. "cpu"
+    pub phases: Vec<String>,
+    pub kernels: Vec<KernelInfo>,
+}
+
+/// One registered kernel in a `Listing`
+#[derive(Debug, Clone, Serialize)]
+pub struct KernelInfo {
+    pub name: &'static str,
+    /// Name of the kernel's phase, e.g. "cpu"
+    pub phase: String,
+    pub description: &'static str,
+    pub metrics: &'static [MetricSpec],
+}
+
+/// Every selectable benchmark, for discovery by users and wrappers
+pub fn listing() -> Listing {
+    Listing {
+        phases: Phase::ALL
+            .iter()
+            .map(|phase| phase.name().to_lowercase())
+            .collect(),
+        kernels: registry()
+            .map(|benchmark| KernelInfo {
+                name: benchmark.name(),
+                phase: benchmark.phase().name().to_lowercase(),
+                description: benchmark.description(),
+                metrics: benchmark.metrics(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        let mut names = HashSet::new();
+        let mut metrics = HashSet::new();
+        for benchmark in registry() {
+            assert!(names.insert(benchmark.name()), "{}", benchmark.name());
+            assert!(Phase::from_name(benchmark.name()).is_none());
+            assert!(!benchmark.metrics().is_empty());
+            for metric in benchmark.metrics() {
+                assert!(metrics.insert(metric.name), "{}", metric.name);
+            }
+        }
+        assert_eq!(find("Parallel_FFT").unwrap().name(), "parallel_fft");
+        assert!(find("tape").is_none());
+        assert_eq!(phase_kernels(Phase::Cpu).count(), cpu::KERNELS.len());
+    }
+
+    #[test]
+    fn test_listing_json() {
+        let listing = listing();
+        assert_eq!(listing.phases.len(), Phase::ALL.len());
+        assert!(listing
+            .phases
+            .iter()
+            .all(|name| Phase::from_name(name).is_some()));
+        let json = serde_json::to_value(&listing).unwrap();
+        let sieve = json["kernels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|kernel| kernel["name"] == "sieve")
+            .unwrap();
+        assert_eq!(sieve["phase"], "cpu");
+        assert_eq!(sieve["metrics"][1]["name"], "sieve_mibs");
+        assert_eq!(sieve["metrics"][1]["unit"], "MiB/s");
+    }
+}
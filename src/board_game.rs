@@ -1,3 +1,4 @@
+use hs_benchmark_suite::rng::{self, SimpleRng};
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -104,7 +105,11 @@ pub fn run_board_game() {
         if game_num == 1 || game_num % 2000 == 0 {
             println!("--- Game {} ---", game_num);
             let mut board = Board::new();
-            let seed = seed_from_time(game_num as u64);
+            // --seed replays the same games; otherwise every run shows different ones
+            let seed = match rng::seed() {
+                Some(_) => rng::workload_seed(game_num as u64),
+                None => seed_from_time(game_num as u64),
+            };
             play_perfect_game(&mut board, Some(seed));
             board.display();
             println!();
@@ -146,7 +151,7 @@ fn play_perfect_game(board: &mut Board, seed: Option<u64>) {
     // Randomize the opening move
     let mut player = Cell::X;
     let avail = board.available_moves();
-    if let Some(&pos) = avail.get((rng.next() as usize) % avail.len()) {
+    if let Some(&pos) = avail.get((rng.next_u64() as usize) % avail.len()) {
         board.cells[pos] = player;
         player = opponent(player);
     }
@@ -267,26 +272,6 @@ fn minimax(board: &Board, player: Cell, cache: &mut HashMap<u32, i8>) -> i8 {
     best
 }
 
-// Minimal RNG for reproducible pseudo-random first move
-struct SimpleRng {
-    state: u64,
-}
-
-impl SimpleRng {
-    fn new(seed: u64) -> Self {
-        SimpleRng { state: seed | 1 } // force odd seed to avoid degenerate cycles
-    }
-
-    fn next(&mut self) -> u64 {
-        // 64-bit LCG parameters (Numerical Recipes style)
-        self.state = self
-            .state
-            .wrapping_mul(6364136223846793005)
-            .wrapping_add(1442695040888963407);
-        self.state
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,28 +366,6 @@ mod tests {
         assert!(board2.winner().is_none());
     }
 
-    #[test]
-    fn test_simplerng_deterministic() {
-        let mut rng1 = SimpleRng::new(42);
-        let mut rng2 = SimpleRng::new(42);
-
-        // Same seed should produce same sequence
-        for _ in 0..10 {
-            assert_eq!(rng1.next(), rng2.next());
-        }
-    }
-
-    #[test]
-    fn test_simplerng_different_seeds() {
-        let mut rng1 = SimpleRng::new(1);
-        let mut rng2 = SimpleRng::new(2);
-
-        // Different seeds should produce different sequences
-        let val1 = rng1.next();
-        let val2 = rng2.next();
-        assert_ne!(val1, val2);
-    }
-
     #[test]
     fn test_board_available_moves() {
         let mut board = Board::new();
@@ -0,0 +1,441 @@
+/// Baseline comparison of two JSON reports
+/// Computes per-metric deltas and flags changes that are statistically significant at the
+/// 95% level (Welch's t-test), so run-to-run noise is not reported as a change
+use crate::report::{MetricSeries, SuiteReport};
+use crate::stats::t_critical_95;
+use serde_json::Value;
+use std::io;
+use std::path::Path;
+
+/// Changes smaller than this are treated as noise when the reports contain single
+/// runs and no variance estimate is available
+const SINGLE_RUN_THRESHOLD_PERCENT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Improvement,
+    Regression,
+    Unchanged,
+}
+
+impl Verdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Improvement => "improved",
+            Verdict::Regression => "REGRESSED",
+            Verdict::Unchanged => "~",
+        }
+    }
+}
+
+/// Mean, spread, and run count of one metric in one report
+#[derive(Debug, Clone, Copy)]
+pub struct MetricSummary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub runs: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricComparison {
+    pub category: String,
+    pub metric: String,
+    pub baseline: MetricSummary,
+    pub candidate: MetricSummary,
+    pub delta: f64,
+    pub percent_change: f64,
+    /// 95% confidence interval of `delta`; None when either report has a single run or
+    /// neither varies
+    pub delta_ci95: Option<(f64, f64)>,
+    pub lower_is_better: bool,
+    pub verdict: Verdict,
+}
+
+pub fn load_report(path: &Path) -> io::Result<SuiteReport> {
+    SuiteReport::load(path)
+}
+
+/// Compare every metric present in both reports, in baseline order
+pub fn compare_reports(baseline: &SuiteReport, candidate: &SuiteReport) -> Vec<MetricComparison> {
+    let mut comparisons = Vec::new();
+
+    for (category, metrics) in &baseline.results {
+        for (metric, baseline_series) in metrics {
+            let candidate_series = candidate.results.get(category).and_then(|c| c.get(metric));
+            let (Some(base), Some(cand)) = (
+                metric_summary(baseline_series),
+                candidate_series.and_then(metric_summary),
+            ) else {
+                continue;
+            };
+
+            comparisons.push(compare_metric(category, metric, base, cand));
+        }
+    }
+
+    comparisons
+}
+
+/// Differences between the run manifests of two reports whose config fingerprints differ
+/// Returns: None when the fingerprints match or either report predates them; otherwise the
+/// manifest fields that differ, empty when a report has no manifest to compare
+pub fn fingerprint_mismatch(
+    baseline: &SuiteReport,
+    candidate: &SuiteReport,
+) -> Option<Vec<String>> {
+    let (Some(base), Some(other)) = (&baseline.config_fingerprint, &candidate.config_fingerprint)
+    else {
+        return None;
+    };
+    if base == other {
+        return None;
+    }
+    Some(match (&baseline.manifest, &candidate.manifest) {
+        (Some(base), Some(other)) => base.differences(other),
+        _ => Vec::new(),
+    })
+}
+
+/// Configuration keys whose values differ between the two reports
+pub fn configuration_differences(baseline: &SuiteReport, candidate: &SuiteReport) -> Vec<String> {
+    let mut differences = Vec::new();
+    let base = serde_json::to_value(&baseline.configuration).unwrap_or(Value::Null);
+    let other = serde_json::to_value(&candidate.configuration).unwrap_or(Value::Null);
+    if let Some(base) = base.as_object() {
+        for (key, value) in base {
+            let other = other.get(key).unwrap_or(&Value::Null);
+            if other != value {
+                differences.push(format!("{}: {} vs {}", key, value, other));
+            }
+        }
+    }
+    differences
+}
+
+/// Host, time, and build of a report, e.g. "host (2026-01-25T14:30:22+01:00, 0.3.2 commit
+/// 1a2b3c4d5e6f)"; reports of older versions name no build
+pub fn report_origin(report: &SuiteReport) -> String {
+    let metadata = &report.metadata;
+    let build = match (&metadata.suite_version, &metadata.git_commit) {
+        (version, _) if version.is_empty() => String::new(),
+        (version, Some(commit)) => format!(", {} commit {}", version, commit),
+        (version, None) => format!(", {}", version),
+    };
+    format!("{} ({}{})", metadata.hostname, metadata.timestamp, build)
+}
+
+fn metric_summary(series: &MetricSeries) -> Option<MetricSummary> {
+    let statistics = series.statistics.as_ref()?;
+    Some(MetricSummary {
+        mean: statistics.mean,
+        std_dev: statistics.std_dev,
+        runs: series.runs.len().max(1),
+    })
+}
+
+/// Whether smaller values of a metric are better: latencies, reported in microseconds or
+/// nanoseconds, NUMA penalty ratios, and the energy and power drawn by a run (work per watt
+/// stays higher-is-better)
+pub fn lower_is_better(metric: &str) -> bool {
+    ["_us", "_ns", "_penalty", "_joules", "_watts"]
+        .iter()
+        .any(|suffix| metric.ends_with(suffix))
+}
+
+/// Whether a metric describes the conditions of a run rather than its performance: clock
+/// speeds in MHz and temperatures, which are neither better nor worse when higher
+pub fn is_condition(metric: &str) -> bool {
+    metric.ends_with("_mhz") || metric.ends_with("_temperature_c")
+}
+
+fn compare_metric(
+    category: &str,
+    metric: &str,
+    baseline: MetricSummary,
+    candidate: MetricSummary,
+) -> MetricComparison {
+    let delta = candidate.mean - baseline.mean;
+    let percent_change = if baseline.mean.abs() > f64::EPSILON {
+        delta / baseline.mean * 100.0
+    } else {
+        0.0
+    };
+
+    let lower_is_better = lower_is_better(metric);
+
+    let delta_ci95 = difference_ci95(&baseline, &candidate);
+    // Significant when the interval excludes zero; without run variance a fixed
+    // percentage is used instead
+    let significant = match delta_ci95 {
+        Some((low, high)) => low > 0.0 || high < 0.0,
+        None => percent_change.abs() >= SINGLE_RUN_THRESHOLD_PERCENT,
+    };
+    // Conditions are shown with their change but never flagged
+    let verdict = if significant && !is_condition(metric) {
+        if (delta > 0.0) != lower_is_better {
+            Verdict::Improvement
+        } else {
+            Verdict::Regression
+        }
+    } else {
+        Verdict::Unchanged
+    };
+
+    MetricComparison {
+        category: category.to_string(),
+        metric: metric.to_string(),
+        baseline,
+        candidate,
+        delta,
+        percent_change,
+        delta_ci95,
+        lower_is_better,
+        verdict,
+    }
+}
+
+/// 95% confidence interval of `candidate.mean - baseline.mean` from Welch's t-test, which
+/// does not assume equal variances or run counts
+/// None when either report has a single run or neither varies
+fn difference_ci95(baseline: &MetricSummary, candidate: &MetricSummary) -> Option<(f64, f64)> {
+    if baseline.runs < 2 || candidate.runs < 2 {
+        return None;
+    }
+    // Squared standard errors of both means, from the sample variances
+    let squared_error = |summary: &MetricSummary| {
+        let n = summary.runs as f64;
+        summary.std_dev.powi(2) * n / (n - 1.0) / n
+    };
+    let (base_error, cand_error) = (squared_error(baseline), squared_error(candidate));
+    let standard_error = (base_error + cand_error).sqrt();
+    if standard_error <= f64::EPSILON {
+        return None;
+    }
+
+    // Welch-Satterthwaite degrees of freedom
+    let df = (base_error + cand_error).powi(2)
+        / (base_error.powi(2) / (baseline.runs - 1) as f64
+            + cand_error.powi(2) / (candidate.runs - 1) as f64);
+    let margin = t_critical_95(df) * standard_error;
+    let delta = candidate.mean - baseline.mean;
+    Some((delta - margin, delta + margin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suite::{SuiteConfig, SuiteResults};
+    use crate::sysinfo_capture::SystemInfo;
+    use std::fs;
+
+    fn report(primes: &[f64], latency: &[f64], threads: usize) -> SuiteReport {
+        let mut report = SuiteReport::new(
+            &SuiteResults {
+                config: SuiteConfig {
+                    threads,
+                    ..SuiteConfig::default()
+                },
+                cpu: vec![],
+                memory: vec![],
+                concurrency: vec![],
+                disk: vec![],
+                network: vec![],
+                gpu: vec![],
+                disk_target: None,
+                background_load: vec![],
+                cooldowns: vec![],
+                timed_out: vec![],
+                interrupted: false,
+            },
+            &SystemInfo {
+                cpu_brand: "CPU".to_string(),
+                cpu_physical_cores: 4,
+                cpu_logical_cores: 8,
+                total_memory_mb: 1024,
+                os_name: "OS".to_string(),
+                os_version: "1".to_string(),
+                hostname: "host".to_string(),
+                gpu_adapter: None,
+                aes_acceleration: None,
+                simd_features: Vec::new(),
+                simd_level: String::new(),
+                cpu_frequency: Default::default(),
+                cpu_topology: Default::default(),
+                numa_nodes: Vec::new(),
+                power: Default::default(),
+            },
+        );
+        report.results.clear();
+        report.results.entry("cpu".to_string()).or_default().insert(
+            "cpu_primes_per_sec".to_string(),
+            MetricSeries::from_values(primes.to_vec()),
+        );
+        report
+            .results
+            .entry("network".to_string())
+            .or_default()
+            .insert(
+                "network_latency_p50_us".to_string(),
+                MetricSeries::from_values(latency.to_vec()),
+            );
+        report
+    }
+
+    #[test]
+    fn test_compare_detects_improvement_and_regression() {
+        let baseline = report(&[100.0, 101.0, 99.0], &[10.0, 10.5, 9.5], 4);
+        let candidate = report(&[120.0, 121.0, 119.0], &[20.0, 20.5, 19.5], 4);
+        let comparisons = compare_reports(&baseline, &candidate);
+
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].metric, "cpu_primes_per_sec");
+        assert_eq!(comparisons[0].verdict, Verdict::Improvement);
+        assert!((comparisons[0].percent_change - 20.0).abs() < 0.01);
+        // Higher latency is worse
+        assert!(comparisons[1].lower_is_better);
+        assert_eq!(comparisons[1].verdict, Verdict::Regression);
+    }
+
+    #[test]
+    fn test_lower_is_better_metrics() {
+        assert!(lower_is_better("disk_fsync_latency_p99_us"));
+        assert!(lower_is_better("cpu_branch_mispredict_ns"));
+        assert!(lower_is_better("memory_numa_latency_penalty"));
+        assert!(!lower_is_better("memory_numa_local_read_gbs"));
+        assert!(lower_is_better("cpu_energy_joules"));
+        assert!(lower_is_better("cpu_average_watts"));
+        assert!(!lower_is_better("cpu_primes_per_sec_per_watt"));
+    }
+
+    #[test]
+    fn test_compare_flags_higher_energy_as_regression() {
+        let mut baseline = report(&[100.0, 101.0, 99.0], &[10.0, 10.5, 9.5], 4);
+        let mut candidate = baseline.clone();
+        for (report, joules) in [(&mut baseline, 50.0), (&mut candidate, 80.0)] {
+            report.results["cpu"].insert(
+                "cpu_energy_joules".to_string(),
+                MetricSeries::from_values(vec![joules, joules + 1.0]),
+            );
+        }
+        let comparisons = compare_reports(&baseline, &candidate);
+
+        let energy = &comparisons[1];
+        assert_eq!(energy.metric, "cpu_energy_joules");
+        assert!(energy.lower_is_better);
+        assert_eq!(energy.verdict, Verdict::Regression);
+    }
+
+    #[test]
+    fn test_delta_confidence_interval() {
+        // Sample variance 1 in both reports: standard error sqrt(2/3), df = 4
+        let baseline = report(&[99.0, 100.0, 101.0], &[10.0, 10.0, 10.0], 4);
+        let candidate = report(&[102.0, 103.0, 104.0], &[10.0, 10.0, 10.0], 4);
+        let comparisons = compare_reports(&baseline, &candidate);
+
+        let (low, high) = comparisons[0].delta_ci95.unwrap();
+        let margin = 2.776 * (2.0f64 / 3.0).sqrt();
+        assert!((low - (3.0 - margin)).abs() < 1e-9, "{}", low);
+        assert!((high - (3.0 + margin)).abs() < 1e-9, "{}", high);
+        assert_eq!(comparisons[0].verdict, Verdict::Improvement);
+        // Identical runs have no interval
+        assert!(comparisons[1].delta_ci95.is_none());
+    }
+
+    #[test]
+    fn test_compare_does_not_flag_condition_metrics() {
+        let mut baseline = report(&[100.0, 101.0, 99.0], &[10.0, 10.5, 9.5], 4);
+        let mut candidate = baseline.clone();
+        for (report, temperature) in [(&mut baseline, 60.0), (&mut candidate, 90.0)] {
+            report.results["cpu"].insert(
+                "cpu_max_temperature_c".to_string(),
+                MetricSeries::from_values(vec![temperature, temperature + 1.0]),
+            );
+        }
+        let comparisons = compare_reports(&baseline, &candidate);
+
+        let temperature = &comparisons[1];
+        assert_eq!(temperature.metric, "cpu_max_temperature_c");
+        assert!((temperature.delta - 30.0).abs() < 1e-9);
+        assert_eq!(temperature.verdict, Verdict::Unchanged);
+        assert!(is_condition("cpu_sustained_frequency_mhz"));
+        assert!(!is_condition("cpu_primes_per_sec"));
+    }
+
+    #[test]
+    fn test_compare_ignores_changes_within_noise() {
+        let baseline = report(&[100.0, 130.0, 70.0], &[10.0, 10.0, 10.0], 4);
+        let candidate = report(&[105.0, 135.0, 75.0], &[10.0, 10.0, 10.0], 4);
+        let comparisons = compare_reports(&baseline, &candidate);
+        assert_eq!(comparisons[0].verdict, Verdict::Unchanged);
+        assert_eq!(comparisons[1].verdict, Verdict::Unchanged);
+    }
+
+    #[test]
+    fn test_compare_single_run_uses_threshold() {
+        let baseline = report(&[100.0], &[10.0], 4);
+        let candidate = report(&[103.0], &[12.0], 4);
+        let comparisons = compare_reports(&baseline, &candidate);
+        assert_eq!(comparisons[0].verdict, Verdict::Unchanged);
+        assert_eq!(comparisons[1].verdict, Verdict::Regression);
+    }
+
+    #[test]
+    fn test_compare_skips_metrics_missing_from_candidate() {
+        let baseline = report(&[100.0, 101.0], &[10.0, 11.0], 4);
+        let mut candidate = report(&[100.0, 101.0], &[10.0, 11.0], 4);
+        candidate.results["network"].clear();
+        let comparisons = compare_reports(&baseline, &candidate);
+        assert_eq!(comparisons.len(), 1);
+    }
+
+    #[test]
+    fn test_configuration_differences() {
+        let baseline = report(&[100.0], &[10.0], 4);
+        let candidate = report(&[100.0], &[10.0], 8);
+        let differences = configuration_differences(&baseline, &candidate);
+        assert_eq!(differences, vec!["threads: 4 vs 8".to_string()]);
+    }
+
+    #[test]
+    fn test_fingerprint_mismatch() {
+        let baseline = report(&[100.0], &[10.0], 4);
+        assert!(baseline.config_fingerprint.is_some());
+        assert_eq!(fingerprint_mismatch(&baseline, &baseline), None);
+
+        let candidate = report(&[100.0], &[10.0], 8);
+        assert_eq!(
+            fingerprint_mismatch(&baseline, &candidate),
+            Some(vec!["threads: 4 vs 8".to_string()])
+        );
+
+        // Reports written before fingerprints existed are compared as before
+        let mut legacy = candidate.clone();
+        legacy.config_fingerprint = None;
+        legacy.manifest = None;
+        assert_eq!(fingerprint_mismatch(&baseline, &legacy), None);
+    }
+
+    #[test]
+    fn test_load_report_roundtrip() {
+        let path = std::env::temp_dir().join("hsbench_compare_test.json");
+        let original = report(&[1.0, 2.0], &[10.0, 11.0], 4);
+        fs::write(&path, serde_json::to_string_pretty(&original).unwrap()).unwrap();
+        let loaded = load_report(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.metadata.hostname, "host");
+        let comparisons = compare_reports(&loaded, &loaded);
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].baseline.runs, 2);
+        assert_eq!(comparisons[0].verdict, Verdict::Unchanged);
+    }
+
+    #[test]
+    fn test_load_report_rejects_missing_results() {
+        let path = std::env::temp_dir().join("hsbench_compare_invalid.json");
+        fs::write(&path, r#"{"metadata":{}}"#).unwrap();
+        let loaded = load_report(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.is_err());
+    }
+}
@@ -0,0 +1,533 @@
+/// Thread synchronization benchmark
+/// Measures Mutex lock/unlock rates without contention (one Mutex per thread) and with
+/// contention (one Mutex shared by all threads), shared atomic increments, and mpsc channel
+/// messages sent by every thread to a single consumer, for 1, 2, 4, ... threads
+/// Every rate is the total across all threads of a point
+/// Also measures OS-level latencies: creating and joining a thread, and a context switch
+/// between two threads handing a turn back and forth through a Condvar
+/// False sharing is measured by incrementing per-thread counters that share one cache line
+/// and counters padded onto lines of their own
+/// On request, pinned pairs of threads bounce a cache line between every two cores to
+/// produce a core-to-core latency matrix
+use crate::affinity;
+use crate::error::BenchError;
+use crate::memory::scaling_thread_counts;
+use crate::pool::ThreadPool;
+use crate::progress::Progress;
+use crate::timing::{self, KernelTiming, KernelTimings};
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Barrier, Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+
+const BASE_MUTEX_OPS: u64 = 2_000_000; // Lock/unlock pairs per thread per pass
+const BASE_ATOMIC_OPS: u64 = 5_000_000; // Increments per thread per pass
+const BASE_CHANNEL_MESSAGES: u64 = 1_000_000; // Messages per producer per pass
+const CHANNEL_CAPACITY: usize = 1024; // Bounded, so producers cannot run ahead of the consumer
+const BASE_THREAD_SPAWNS: u64 = 2_000; // Threads created and joined per pass
+const BASE_PING_PONGS: u64 = 100_000; // Round trips (two context switches each) per pass
+const BASE_COUNTER_INCREMENTS: u64 = 10_000_000; // Per thread per false-sharing pass
+const BASE_CORE_ROUND_TRIPS: u64 = 20_000; // Cache-line round trips per core pair
+const CORE_WARMUP_ROUND_TRIPS: u64 = 100;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConcurrencyResult {
+    pub points: Vec<ConcurrencyPoint>,
+    /// Time to create and join one thread that does no work
+    #[serde(default)]
+    pub thread_spawn_us: f64,
+    /// One-way handoff between two threads: wake the other thread and block
+    #[serde(default)]
+    pub context_switch_us: f64,
+    /// Private counters on a shared cache line vs on lines of their own
+    #[serde(default)]
+    pub false_sharing: FalseSharingResult,
+    /// One-way cache-line latency between every two cores; None unless requested
+    #[serde(default)]
+    pub core_latency: Option<CoreLatencyMatrix>,
+    /// Raw work and elapsed time behind each value above, keyed by field name; point
+    /// entries are keyed `points.<threads>.<field>` and false-sharing entries
+    /// `false_sharing.<field>`
+    /// Latencies are `secs_per_unit` of their timing, the rates `rate`
+    #[serde(default)]
+    pub timings: KernelTimings,
+}
+
+/// Synchronization rates measured with one thread count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyPoint {
+    pub threads: usize,
+    pub mutex_uncontended_ops_per_sec: f64,
+    pub mutex_contended_ops_per_sec: f64,
+    pub atomic_ops_per_sec: f64,
+    pub channel_messages_per_sec: f64,
+}
+
+/// Increments per second, summed over all threads, of counters that each thread owns
+/// alone, once packed next to each other and once padded to separate cache lines
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FalseSharingResult {
+    pub threads: usize,
+    pub adjacent_ops_per_sec: f64,
+    pub padded_ops_per_sec: f64,
+}
+
+impl FalseSharingResult {
+    /// How many times faster the padded counters are; 0 when nothing was measured
+    pub fn penalty(&self) -> f64 {
+        if self.adjacent_ops_per_sec > 0.0 {
+            self.padded_ops_per_sec / self.adjacent_ops_per_sec
+        } else {
+            0.0
+        }
+    }
+}
+
+/// One-way latency of handing a cache line from one core to another
+/// `latency_ns[i][j]` is the latency between `cores[i]` and `cores[j]`; the diagonal is 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreLatencyMatrix {
+    pub cores: Vec<usize>,
+    pub latency_ns: Vec<Vec<f64>>,
+}
+
+impl CoreLatencyMatrix {
+    /// Latencies between distinct cores, each pair once
+    pub fn pairs(&self) -> impl Iterator<Item = f64> + '_ {
+        self.latency_ns
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().skip(i + 1).copied())
+    }
+
+    /// Minimum, mean, and maximum latency between distinct cores
+    pub fn summary(&self) -> Option<(f64, f64, f64)> {
+        let pairs: Vec<f64> = self.pairs().collect();
+        if pairs.is_empty() {
+            return None;
+        }
+        let min = pairs.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = pairs.iter().copied().fold(0.0, f64::max);
+        Some((min, pairs.iter().sum::<f64>() / pairs.len() as f64, max))
+    }
+}
+
+/// Run the concurrency benchmark for 1, 2, 4, ... up to `max_threads` threads
+pub fn run_concurrency_benchmark_scaled(
+    scale: f64,
+    max_threads: usize,
+) -> Result<ConcurrencyResult, BenchError> {
+    run_concurrency_benchmark_with_progress(scale, max_threads, 0.0, &[], &mut ())
+}
+
+/// Run the concurrency benchmark for 1, 2, 4, ... up to `max_threads` threads, announcing
+/// each primitive to `progress`
+/// Every measurement is repeated until at least `min_time` seconds have passed
+/// (0 runs the scaled workload once)
+/// With a non-empty `cores` list, worker thread `i` is pinned to `cores[i % cores.len()]`
+pub fn run_concurrency_benchmark_with_progress(
+    scale: f64,
+    max_threads: usize,
+    min_time: f64,
+    cores: &[usize],
+    progress: &mut dyn Progress,
+) -> Result<ConcurrencyResult, BenchError> {
+    let mutex_ops = scaled_count(BASE_MUTEX_OPS, scale);
+    let atomic_ops = scaled_count(BASE_ATOMIC_OPS, scale);
+    let messages = scaled_count(BASE_CHANNEL_MESSAGES, scale);
+
+    let pools: Vec<ThreadPool> = scaling_thread_counts(max_threads)
+        .into_iter()
+        .map(|threads| ThreadPool::new(threads, cores))
+        .collect();
+    let sweep = |measure: &dyn Fn(&ThreadPool) -> KernelTiming| -> Vec<KernelTiming> {
+        pools.iter().map(measure).collect()
+    };
+
+    progress.step("mutex (uncontended)");
+    let uncontended = sweep(&|pool| mutex_uncontended_rate(pool, mutex_ops, min_time));
+    progress.step("mutex (contended)");
+    let contended = sweep(&|pool| mutex_contended_rate(pool, mutex_ops, min_time));
+    progress.step("atomic increment");
+    let atomic = sweep(&|pool| atomic_rate(pool, atomic_ops, min_time));
+    progress.step("channel");
+    let channel = sweep(&|pool| channel_rate(pool, messages, min_time));
+
+    progress.step("false sharing");
+    // With the most threads, where the cache line has the most owners to bounce between
+    let widest = pools.last().expect("at least one thread count");
+    let increments = scaled_count(BASE_COUNTER_INCREMENTS, scale);
+    let adjacent = adjacent_counter_rate(widest, increments, min_time);
+    let padded = padded_counter_rate(widest, increments, min_time);
+
+    progress.step("thread spawn");
+    let thread_spawn = thread_spawn_latency(scaled_count(BASE_THREAD_SPAWNS, scale), min_time);
+    progress.step("context switch");
+    let context_switch =
+        context_switch_latency(scaled_count(BASE_PING_PONGS, scale), min_time, cores);
+
+    let mut timings = KernelTimings::new();
+    let points = pools
+        .iter()
+        .enumerate()
+        .map(|(i, pool)| {
+            let point = [
+                ("mutex_uncontended_ops_per_sec", uncontended[i]),
+                ("mutex_contended_ops_per_sec", contended[i]),
+                ("atomic_ops_per_sec", atomic[i]),
+                ("channel_messages_per_sec", channel[i]),
+            ];
+            for (field, timing) in point {
+                timings.insert(format!("points.{}.{}", pool.threads(), field), timing);
+            }
+            ConcurrencyPoint {
+                threads: pool.threads(),
+                mutex_uncontended_ops_per_sec: uncontended[i].rate(),
+                mutex_contended_ops_per_sec: contended[i].rate(),
+                atomic_ops_per_sec: atomic[i].rate(),
+                channel_messages_per_sec: channel[i].rate(),
+            }
+        })
+        .collect();
+    timings.insert("false_sharing.adjacent_ops_per_sec".to_string(), adjacent);
+    timings.insert("false_sharing.padded_ops_per_sec".to_string(), padded);
+    timings.insert("thread_spawn_us".to_string(), thread_spawn);
+    timings.insert("context_switch_us".to_string(), context_switch);
+    timing::check_timings("concurrency", &timings)?;
+    Ok(ConcurrencyResult {
+        points,
+        thread_spawn_us: thread_spawn.secs_per_unit() * 1e6,
+        context_switch_us: context_switch.secs_per_unit() * 1e6,
+        core_latency: None,
+        false_sharing: FalseSharingResult {
+            threads: widest.threads(),
+            adjacent_ops_per_sec: adjacent.rate(),
+            padded_ops_per_sec: padded.rate(),
+        },
+        timings,
+    })
+}
+
+fn scaled_count(base: u64, scale: f64) -> u64 {
+    ((base as f64 * scale) as u64).max(1)
+}
+
+/// Time `job(worker_index)` on every worker of `pool` until at least `min_time` seconds
+/// are measured
+/// Returns: operations, given `ops` operations per worker and pass, per second
+fn pool_rate(
+    pool: &ThreadPool,
+    ops: u64,
+    min_time: f64,
+    job: impl Fn(usize) + Sync,
+) -> KernelTiming {
+    let (passes, elapsed) = timing::repeat_passes(min_time, || {
+        let start = Instant::now();
+        pool.broadcast(&job);
+        start.elapsed().as_secs_f64()
+    });
+    KernelTiming::new((ops * pool.threads() as u64) as f64, passes, elapsed)
+}
+
+/// Lock, increment, and unlock `mutex` `ops` times
+fn lock_increment(mutex: &Mutex<u64>, ops: u64) {
+    for _ in 0..ops {
+        *black_box(mutex).lock().unwrap() += 1;
+    }
+}
+
+/// Every thread locks its own Mutex, so the lock is never contended
+fn mutex_uncontended_rate(pool: &ThreadPool, ops: u64, min_time: f64) -> KernelTiming {
+    pool_rate(pool, ops, min_time, |_| {
+        let mutex = Mutex::new(0u64);
+        lock_increment(&mutex, ops);
+        black_box(mutex.into_inner().unwrap());
+    })
+}
+
+/// Every thread locks the same Mutex
+fn mutex_contended_rate(pool: &ThreadPool, ops: u64, min_time: f64) -> KernelTiming {
+    let mutex = Mutex::new(0u64);
+    pool_rate(pool, ops, min_time, |_| lock_increment(&mutex, ops))
+}
+
+/// Every thread increments the same atomic counter
+fn atomic_rate(pool: &ThreadPool, ops: u64, min_time: f64) -> KernelTiming {
+    let counter = AtomicU64::new(0);
+    pool_rate(pool, ops, min_time, |_| {
+        for _ in 0..ops {
+            black_box(&counter).fetch_add(1, Ordering::Relaxed);
+        }
+    })
+}
+
+/// Counter on a cache line of its own; 128 bytes also keeps the adjacent-line prefetcher,
+/// which fetches lines in pairs, from coupling neighbours
+#[repr(align(128))]
+#[derive(Default)]
+struct PaddedCounter(AtomicU64);
+
+/// Increment `counter` `ops` times with a plain load and store, as an ordinary `u64` field
+/// would be; the atomic only keeps the compiler from folding the loop
+fn increment(counter: &AtomicU64, ops: u64) {
+    for _ in 0..ops {
+        let counter = black_box(counter);
+        counter.store(counter.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+    }
+}
+
+/// Every worker increments its own counter; the counters are packed into one array, so
+/// up to eight share a cache line
+fn adjacent_counter_rate(pool: &ThreadPool, ops: u64, min_time: f64) -> KernelTiming {
+    let counters: Vec<AtomicU64> = (0..pool.threads()).map(|_| AtomicU64::new(0)).collect();
+    pool_rate(pool, ops, min_time, |worker| {
+        increment(&counters[worker], ops)
+    })
+}
+
+/// Every worker increments its own counter on a cache line of its own
+fn padded_counter_rate(pool: &ThreadPool, ops: u64, min_time: f64) -> KernelTiming {
+    let counters: Vec<PaddedCounter> = (0..pool.threads()).map(|_| Default::default()).collect();
+    pool_rate(pool, ops, min_time, |worker| {
+        increment(&counters[worker].0, ops)
+    })
+}
+
+/// Every worker sends `messages` messages through one bounded channel to a consumer thread
+fn channel_rate(pool: &ThreadPool, messages: u64, min_time: f64) -> KernelTiming {
+    let expected = messages * pool.threads() as u64;
+    let (passes, elapsed) = timing::repeat_passes(min_time, || {
+        let (received, seconds) = channel_pass(pool, messages);
+        assert_eq!(received, expected, "channel lost messages");
+        seconds
+    });
+    KernelTiming::new(expected as f64, passes, elapsed)
+}
+
+/// One timed channel pass; the consumer is spawned before the clock starts
+/// Returns: (messages received, elapsed seconds)
+fn channel_pass(pool: &ThreadPool, messages: u64) -> (u64, f64) {
+    let (sender, receiver) = mpsc::sync_channel::<u64>(CHANNEL_CAPACITY);
+    thread::scope(|scope| {
+        let consumer = scope.spawn(move || receiver.iter().map(black_box).count() as u64);
+        let start = Instant::now();
+        pool.broadcast(|_| {
+            let sender = sender.clone();
+            for message in 0..messages {
+                sender.send(message).unwrap();
+            }
+        });
+        // The consumer finishes once the last sender is gone
+        drop(sender);
+        let received = consumer.join().expect("channel consumer panicked");
+        (received, start.elapsed().as_secs_f64())
+    })
+}
+
+/// Threads spawned and joined, doing no work; the latency is the time per thread
+fn thread_spawn_latency(spawns: u64, min_time: f64) -> KernelTiming {
+    let (passes, elapsed) = timing::repeat_passes(min_time, || {
+        let start = Instant::now();
+        for i in 0..spawns {
+            thread::spawn(move || black_box(i))
+                .join()
+                .expect("spawned thread panicked");
+        }
+        start.elapsed().as_secs_f64()
+    });
+    KernelTiming::new(spawns as f64, passes, elapsed)
+}
+
+/// Measure the cache-line latency between every two of `cores` (all available cores for
+/// an empty list) with a pair of threads pinned to them
+/// Returns None with fewer than two cores or when threads cannot be pinned
+pub fn run_core_latency_benchmark(scale: f64, cores: &[usize]) -> Option<CoreLatencyMatrix> {
+    let cores = if cores.is_empty() {
+        affinity::available_cores()
+    } else {
+        cores.to_vec()
+    };
+    if cores.len() < 2 || !affinity::PINNING_SUPPORTED {
+        return None;
+    }
+    let round_trips = scaled_count(BASE_CORE_ROUND_TRIPS, scale);
+
+    let mut latency_ns = vec![vec![0.0; cores.len()]; cores.len()];
+    for i in 0..cores.len() {
+        for j in i + 1..cores.len() {
+            // The line travels the same path both ways, so one measurement fills both cells
+            let latency = core_pair_latency(cores[i], cores[j], round_trips)?;
+            latency_ns[i][j] = latency;
+            latency_ns[j][i] = latency;
+        }
+    }
+    Some(CoreLatencyMatrix { cores, latency_ns })
+}
+
+/// Bounce one cache line between a thread on `first` and a thread on `second`
+/// The initiator writes an odd sequence number and spins until the responder answers with
+/// the next even one, so every round trip moves the line to the other core and back
+/// Returns: one-way latency in ns, or None if either thread could not be pinned
+fn core_pair_latency(first: usize, second: usize, round_trips: u64) -> Option<f64> {
+    let line = PaddedCounter::default();
+    let pinned = AtomicU64::new(0);
+    let ready = Barrier::new(2);
+    let total = CORE_WARMUP_ROUND_TRIPS + round_trips;
+
+    // Both threads wait for each other, so neither spins alone when the other is not pinned
+    let pin = |core: usize| {
+        let ok = affinity::pin_current_thread(core).is_ok();
+        pinned.fetch_add(ok as u64, Ordering::SeqCst);
+        ready.wait();
+        pinned.load(Ordering::SeqCst) == 2
+    };
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            if !pin(second) {
+                return;
+            }
+            for i in 0..total {
+                while line.0.load(Ordering::Acquire) != 2 * i + 1 {
+                    std::hint::spin_loop();
+                }
+                line.0.store(2 * i + 2, Ordering::Release);
+            }
+        });
+        // The initiator gets a thread of its own too, so the caller's affinity is untouched
+        let initiator = scope.spawn(|| {
+            if !pin(first) {
+                return None;
+            }
+            let mut start = Instant::now();
+            for i in 0..total {
+                if i == CORE_WARMUP_ROUND_TRIPS {
+                    start = Instant::now();
+                }
+                line.0.store(2 * i + 1, Ordering::Release);
+                while line.0.load(Ordering::Acquire) != 2 * i + 2 {
+                    std::hint::spin_loop();
+                }
+            }
+            Some(start.elapsed().as_secs_f64() / (2 * round_trips) as f64 * 1e9)
+        });
+        initiator.join().expect("core latency thread panicked")
+    })
+}
+
+/// Context switches between two threads passing a turn back and forth; the latency is the
+/// time per switch
+/// With a non-empty `cores` list both threads are pinned to `cores[0]`, so every handoff is
+/// a switch on one core; unpinned, the threads may run on two cores and the result is the
+/// cross-core wakeup latency instead
+fn context_switch_latency(round_trips: u64, min_time: f64, cores: &[usize]) -> KernelTiming {
+    let _pin = affinity::PinGuard::pin(cores);
+    let (passes, elapsed) = timing::repeat_passes(min_time, || ping_pong_pass(round_trips, cores));
+    // Every round trip switches to the partner and back
+    KernelTiming::new((2 * round_trips) as f64, passes, elapsed)
+}
+
+/// One timed ping-pong pass; the partner thread is spawned before the clock starts
+/// Returns: elapsed seconds
+fn ping_pong_pass(round_trips: u64, cores: &[usize]) -> f64 {
+    // true while it is the partner's turn
+    let partner_turn = Mutex::new(false);
+    let changed = Condvar::new();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            affinity::pin_worker(affinity::core_for(cores, 0));
+            for _ in 0..round_trips {
+                let guard = partner_turn.lock().unwrap();
+                let mut turn = changed.wait_while(guard, |turn| !*turn).unwrap();
+                *turn = false;
+                changed.notify_one();
+            }
+        });
+        let start = Instant::now();
+        for _ in 0..round_trips {
+            // Hand the turn over, then wait until the partner hands it back
+            let mut turn = partner_turn.lock().unwrap();
+            *turn = true;
+            changed.notify_one();
+            drop(changed.wait_while(turn, |turn| *turn).unwrap());
+        }
+        start.elapsed().as_secs_f64()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrency_sweep() {
+        // Use lightweight scale for CI/testing
+        let result =
+            run_concurrency_benchmark_scaled(0.01, 4).expect("concurrency benchmark failed");
+        let threads: Vec<usize> = result.points.iter().map(|p| p.threads).collect();
+        assert_eq!(threads, vec![1, 2, 4]);
+        for point in &result.points {
+            assert!(point.mutex_uncontended_ops_per_sec > 0.0);
+            assert!(point.mutex_contended_ops_per_sec > 0.0);
+            assert!(point.atomic_ops_per_sec > 0.0);
+            assert!(point.channel_messages_per_sec > 0.0);
+        }
+        assert!(result.thread_spawn_us > 0.0);
+        assert!(result.context_switch_us > 0.0);
+        assert_eq!(result.false_sharing.threads, 4);
+        assert!(result.false_sharing.adjacent_ops_per_sec > 0.0);
+        assert!(result.false_sharing.padded_ops_per_sec > 0.0);
+        assert!(result.false_sharing.penalty() > 0.0);
+    }
+
+    #[test]
+    fn test_counters_count_every_increment() {
+        let counter = AtomicU64::new(5);
+        increment(&counter, 1000);
+        assert_eq!(counter.load(Ordering::Relaxed), 1005);
+        assert_eq!(std::mem::align_of::<PaddedCounter>(), 128);
+        assert_eq!(FalseSharingResult::default().penalty(), 0.0);
+    }
+
+    #[test]
+    fn test_core_latency_matrix() {
+        let matrix = CoreLatencyMatrix {
+            cores: vec![0, 1, 2],
+            latency_ns: vec![
+                vec![0.0, 40.0, 100.0],
+                vec![40.0, 0.0, 70.0],
+                vec![100.0, 70.0, 0.0],
+            ],
+        };
+        assert_eq!(matrix.pairs().collect::<Vec<_>>(), vec![40.0, 100.0, 70.0]);
+        assert_eq!(matrix.summary(), Some((40.0, 70.0, 100.0)));
+        // A single core has no pairs to measure
+        let core = affinity::available_cores()[0];
+        assert!(run_core_latency_benchmark(0.01, &[core]).is_none());
+    }
+
+    #[test]
+    fn test_channel_pass_delivers_every_message() {
+        let pool = ThreadPool::new(3, &[]);
+        let (received, seconds) = channel_pass(&pool, 5_000);
+        assert_eq!(received, 15_000);
+        assert!(seconds > 0.0);
+    }
+
+    #[test]
+    fn test_ping_pong_pass_completes() {
+        // Every round trip must be answered, or the pass would never return
+        assert!(ping_pong_pass(1_000, &[]) > 0.0);
+        assert!(context_switch_latency(100, 0.0, &[]).secs_per_unit() > 0.0);
+    }
+
+    #[test]
+    fn test_contended_mutex_counts_every_increment() {
+        let pool = ThreadPool::new(4, &[]);
+        let mutex = Mutex::new(0u64);
+        pool.broadcast(|_| lock_increment(&mutex, 1_000));
+        assert_eq!(mutex.into_inner().unwrap(), 4_000);
+    }
+}
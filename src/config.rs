@@ -0,0 +1,542 @@
+/// Benchmark configuration files and environment variables
+/// A TOML file sets command-line options by name; options at the top level apply to every
+/// run, and a `[profiles.<name>]` table selected with `--profile <name>` overrides them
+/// `HSBENCH_<OPTION>` environment variables set the same options over the file, and options
+/// given on the command line take precedence over both
+use crate::args::{parse_interval, BenchmarkArgs};
+use crate::render::ColorMode;
+use hs_benchmark_suite::benchmark;
+use hs_benchmark_suite::disk::{DiskMode, DiskProfile};
+use hs_benchmark_suite::memory::AccessPattern;
+use hs_benchmark_suite::report::CsvFormat;
+use hs_benchmark_suite::units::UnitSystem;
+use hs_benchmark_suite::{Phase, Schedule};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Options that can be set in a configuration file; unset options keep their current value
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigOptions {
+    pub scale: Option<f64>,
+    pub cpu_scale: Option<f64>,
+    pub mem_scale: Option<f64>,
+    pub disk_scale: Option<f64>,
+    /// Target seconds per kernel pass, as with `--auto-scale SECONDS`
+    pub auto_scale: Option<f64>,
+    pub min_time: Option<f64>,
+    pub warmup_runs: Option<usize>,
+    pub seed: Option<u64>,
+    pub verify: Option<bool>,
+    pub count: Option<usize>,
+    pub cooldown: Option<f64>,
+    /// `"serial"`, `"parallel"` (as with `--parallel-suites`), or `"isolated"` (as with
+    /// `--isolate`)
+    pub schedule: Option<String>,
+    /// Seconds each benchmark step may run, as with `--timeout`
+    pub timeout: Option<f64>,
+    pub threads: Option<usize>,
+    pub mem_threads: Option<usize>,
+    pub mem_scaling: Option<bool>,
+    pub mem_numa: Option<bool>,
+    pub mem_patterns: Option<Vec<String>>,
+    pub huge_pages: Option<bool>,
+    pub mem_prefault: Option<bool>,
+    pub core_latency: Option<bool>,
+    pub pin_cores: Option<bool>,
+    pub core_list: Option<Vec<usize>>,
+    pub block_size: Option<usize>,
+    pub disk_path: Option<String>,
+    /// Disk access mode, `"direct"` or `"mmap"`
+    pub disk_mode: Option<String>,
+    /// Size of the dataset written before the sequential disk passes, as a multiple of the
+    /// test file size
+    pub disk_precondition: Option<f64>,
+    /// Write passes to run at least after the dataset; implies `disk_precondition`
+    pub disk_precondition_passes: Option<usize>,
+    pub disk_cache_compare: Option<bool>,
+    /// Mixed disk workloads by name, e.g. `["oltp", "backup"]`
+    pub disk_profile: Option<Vec<String>>,
+    pub network_target: Option<String>,
+    pub load_threshold: Option<f64>,
+    pub exclude_contaminated: Option<bool>,
+    pub require_ac: Option<bool>,
+    /// Benchmarks to run by name, e.g. `["cpu", "memory"]`; unlisted benchmarks are skipped
+    /// Kernel names such as `"fft"` run only the listed kernels of their benchmark
+    pub benchmarks: Option<Vec<String>>,
+    pub csv: Option<bool>,
+    /// CSV layout, `"wide"` or `"long"`
+    pub csv_format: Option<String>,
+    pub json: Option<bool>,
+    pub html: Option<bool>,
+    pub markdown: Option<bool>,
+    pub history: Option<bool>,
+    /// Interval for watch mode, e.g. `"1h"`
+    pub watch: Option<String>,
+    pub keep: Option<usize>,
+    /// PPM file the Mandelbrot image is written to
+    pub mandelbrot_image: Option<String>,
+    /// Directory the reports are written to
+    pub output_dir: Option<String>,
+    /// HTTP endpoint the JSON reports are POSTed to, as with `--upload`
+    pub upload: Option<String>,
+    pub quiet: Option<bool>,
+    /// `"auto"`, `"always"`, or `"never"`
+    pub color: Option<String>,
+    /// `"si"` or `"binary"`
+    pub units: Option<String>,
+}
+
+impl ConfigOptions {
+    /// Overwrite every option of `args` that is set here
+    pub fn apply(&self, args: &mut BenchmarkArgs) -> Result<(), String> {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+
+        set(&mut args.scale, &self.scale);
+        for (scale, value) in [
+            (&mut args.cpu_scale, self.cpu_scale),
+            (&mut args.mem_scale, self.mem_scale),
+            (&mut args.disk_scale, self.disk_scale),
+            (&mut args.auto_scale, self.auto_scale),
+        ] {
+            if value.is_some() {
+                *scale = value;
+            }
+        }
+        if self.min_time.is_some() {
+            args.min_time = self.min_time;
+        }
+        set(&mut args.warmup_runs, &self.warmup_runs);
+        if self.seed.is_some() {
+            args.seed = self.seed;
+        }
+        set(&mut args.verify, &self.verify);
+        set(&mut args.count, &self.count);
+        set(&mut args.cooldown, &self.cooldown);
+        if let Some(schedule) = &self.schedule {
+            args.schedule = Schedule::from_name(schedule)
+                .ok_or_else(|| format!("unknown schedule '{}'", schedule))?;
+        }
+        if self.timeout.is_some() {
+            args.timeout = self.timeout;
+        }
+        set(&mut args.threads, &self.threads);
+        set(&mut args.mem_threads, &self.mem_threads);
+        set(&mut args.mem_scaling, &self.mem_scaling);
+        set(&mut args.mem_numa, &self.mem_numa);
+        set(&mut args.huge_pages, &self.huge_pages);
+        set(&mut args.mem_prefault, &self.mem_prefault);
+        set(&mut args.core_latency, &self.core_latency);
+        set(&mut args.pin_cores, &self.pin_cores);
+        if let Some(cores) = &self.core_list {
+            if cores.is_empty() {
+                return Err("core_list must name at least one core".to_string());
+            }
+            args.core_list = Some(cores.clone());
+            args.pin_cores = true;
+        }
+        set(&mut args.block_size, &self.block_size);
+        if self.disk_path.is_some() {
+            args.disk_path = self.disk_path.clone();
+        }
+        if let Some(mode) = &self.disk_mode {
+            args.disk_mode =
+                DiskMode::from_name(mode).ok_or_else(|| format!("unknown disk mode '{}'", mode))?;
+        }
+        if let Some(multiple) = self.disk_precondition {
+            if multiple < 1.0 || !multiple.is_finite() {
+                return Err("disk_precondition must be at least 1".to_string());
+            }
+            args.disk_precondition.get_or_insert_default().multiple = multiple;
+        }
+        if let Some(passes) = self.disk_precondition_passes {
+            if passes == 0 {
+                return Err("disk_precondition_passes must be at least 1".to_string());
+            }
+            args.disk_precondition.get_or_insert_default().passes = passes;
+        }
+        set(&mut args.disk_cache_compare, &self.disk_cache_compare);
+        if let Some(names) = &self.mem_patterns {
+            args.mem_patterns = names
+                .iter()
+                .map(|name| {
+                    AccessPattern::from_name(name)
+                        .ok_or_else(|| format!("unknown memory access pattern '{}'", name))
+                })
+                .collect::<Result<Vec<AccessPattern>, String>>()?;
+        }
+        if let Some(names) = &self.disk_profile {
+            args.disk_profiles = names
+                .iter()
+                .map(|name| {
+                    DiskProfile::from_name(name)
+                        .ok_or_else(|| format!("unknown disk profile '{}'", name))
+                })
+                .collect::<Result<Vec<DiskProfile>, String>>()?;
+        }
+        if self.network_target.is_some() {
+            args.network_target = self.network_target.clone();
+        }
+        set(&mut args.load_threshold, &self.load_threshold);
+        set(&mut args.exclude_contaminated, &self.exclude_contaminated);
+        set(&mut args.require_ac, &self.require_ac);
+        if let Some(names) = &self.benchmarks {
+            let mut phases = Vec::new();
+            let mut kernels = Vec::new();
+            for name in names {
+                let phase = match (Phase::from_name(name), benchmark::find(name)) {
+                    (Some(phase), _) => phase,
+                    (None, Some(kernel)) => {
+                        kernels.push(kernel.name().to_string());
+                        kernel.phase()
+                    }
+                    (None, None) => return Err(format!("unknown benchmark '{}'", name)),
+                };
+                if !phases.contains(&phase) {
+                    phases.push(phase);
+                }
+            }
+            args.benchmarks = Some(phases);
+            args.kernels = kernels;
+        }
+        set(&mut args.csv, &self.csv);
+        if let Some(format) = &self.csv_format {
+            args.csv_format = CsvFormat::from_name(format)
+                .ok_or_else(|| format!("unknown CSV format '{}'", format))?;
+        }
+        set(&mut args.json, &self.json);
+        set(&mut args.html, &self.html);
+        set(&mut args.markdown, &self.markdown);
+        set(&mut args.history, &self.history);
+        if let Some(interval) = &self.watch {
+            let interval = parse_interval(interval)
+                .ok_or_else(|| format!("invalid watch interval '{}'", interval))?;
+            args.watch = Some(interval);
+        }
+        if let Some(keep) = self.keep {
+            if keep == 0 {
+                return Err("keep must be at least 1".to_string());
+            }
+            args.keep_reports = Some(keep);
+        }
+        if self.upload.is_some() {
+            args.upload = self.upload.clone();
+        }
+        if self.output_dir.is_some() {
+            args.output_dir = self.output_dir.clone();
+        }
+        if self.mandelbrot_image.is_some() {
+            args.mandelbrot_image = self.mandelbrot_image.clone();
+        }
+        set(&mut args.quiet, &self.quiet);
+        if let Some(mode) = &self.color {
+            args.color = ColorMode::from_name(mode)
+                .ok_or_else(|| format!("unknown color mode '{}'", mode))?;
+        }
+        if let Some(units) = &self.units {
+            args.units = UnitSystem::from_name(units)
+                .ok_or_else(|| format!("unknown unit system '{}'", units))?;
+        }
+        Ok(())
+    }
+}
+
+/// Prefix of the environment variables that set options, e.g. `HSBENCH_SCALE=0.5`
+pub const ENV_PREFIX: &str = "HSBENCH_";
+
+/// Options whose environment variable holds a comma-separated list
+const ENV_LIST_OPTIONS: [&str; 4] = ["benchmarks", "mem_patterns", "disk_profile", "core_list"];
+
+impl ConfigOptions {
+    /// Options set by the `HSBENCH_<OPTION>` variables among `vars`, with the option named as
+    /// in configuration files (`HSBENCH_DISK_PATH` sets `disk_path`)
+    /// Values are numbers, booleans (also `1`/`0`, `yes`/`no`, `on`/`off`), or text as the
+    /// option requires, and lists are comma-separated, e.g. `HSBENCH_BENCHMARKS=cpu,memory`
+    /// `HSBENCH_CONFIG` and `HSBENCH_PROFILE` select the configuration file instead, and
+    /// `HSBENCH_UPLOAD_TOKEN` holds the upload token, so they are skipped here
+    pub fn from_env(vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, String> {
+        let mut table = toml::Table::new();
+        for (name, value) in vars {
+            let Some(option) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let option = option.to_ascii_lowercase();
+            if option == "config" || option == "profile" || option == "upload_token" {
+                continue;
+            }
+            let value = env_value(&option, &value).map_err(|e| format!("{}: {}", name, e))?;
+            table.insert(option, value);
+        }
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| e.to_string())
+    }
+}
+
+/// The first reading of an environment variable's `text` that `option` accepts
+fn env_value(option: &str, text: &str) -> Result<toml::Value, String> {
+    let mut candidates = Vec::new();
+    if ENV_LIST_OPTIONS.contains(&option) {
+        let items = text
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| toml_value(item).unwrap_or_else(|| toml::Value::String(item.to_string())))
+            .collect();
+        candidates.push(toml::Value::Array(items));
+    } else {
+        candidates.extend(toml_value(text));
+        let flag = match text.to_ascii_lowercase().as_str() {
+            "1" | "yes" | "on" => Some(true),
+            "0" | "no" | "off" => Some(false),
+            _ => None,
+        };
+        candidates.extend(flag.map(toml::Value::Boolean));
+        candidates.push(toml::Value::String(text.to_string()));
+    }
+
+    let mut error = String::new();
+    for candidate in candidates {
+        let table = toml::Table::from_iter([(option.to_string(), candidate.clone())]);
+        match toml::Value::Table(table).try_into::<ConfigOptions>() {
+            Ok(_) => return Ok(candidate),
+            Err(e) => error = e.to_string(),
+        }
+    }
+    // Drop the "in `option`" line the deserializer appends
+    Err(error.lines().next().unwrap_or_default().to_string())
+}
+
+/// `text` as a TOML number or boolean; None for anything else
+fn toml_value(text: &str) -> Option<toml::Value> {
+    let mut table: toml::Table = format!("value = {}", text).parse().ok()?;
+    table.remove("value").filter(|value| {
+        matches!(
+            value,
+            toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_)
+        )
+    })
+}
+
+/// Parsed configuration file: top-level options plus named profiles
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    pub defaults: ConfigOptions,
+    pub profiles: BTreeMap<String, ConfigOptions>,
+}
+
+impl ConfigFile {
+    /// Read and parse a TOML configuration file
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        Self::parse(&text).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let profiles = match table.remove("profiles") {
+            Some(profiles) => profiles
+                .try_into()
+                .map_err(|e: toml::de::Error| format!("profiles: {}", e))?,
+            None => BTreeMap::new(),
+        };
+        let defaults = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| e.to_string())?;
+        Ok(Self { defaults, profiles })
+    }
+
+    /// Apply the top-level options and then those of `profile`, if given, to `args`
+    pub fn apply(&self, profile: Option<&str>, args: &mut BenchmarkArgs) -> Result<(), String> {
+        self.defaults.apply(args)?;
+        if let Some(name) = profile {
+            let options = self.profiles.get(name).ok_or_else(|| {
+                let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                format!(
+                    "unknown profile '{}' (available: {})",
+                    name,
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                )
+            })?;
+            options
+                .apply(args)
+                .map_err(|e| format!("profile '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hs_benchmark_suite::disk::{Precondition, DEFAULT_PRECONDITION_MULTIPLE};
+
+    const SAMPLE: &str = r#"
+        scale = 0.5
+        json = true
+        disk_path = "/tmp"
+
+        [profiles.quick]
+        scale = 0.1
+        disk_scale = 0.01
+        count = 1
+        benchmarks = ["cpu", "memory"]
+
+        [profiles.nightly]
+        count = 10
+        watch = "1d"
+        keep = 7
+        min_time = 2.0
+        seed = 42
+        core_list = [0, 2]
+        disk_mode = "mmap"
+        disk_precondition_passes = 3
+        disk_cache_compare = true
+        schedule = "isolated"
+        disk_profile = ["oltp", "streaming"]
+        html = true
+        color = "never"
+        units = "binary"
+    "#;
+
+    #[test]
+    fn test_top_level_options_apply_without_profile() {
+        let config = ConfigFile::parse(SAMPLE).unwrap();
+        let mut args = BenchmarkArgs::default();
+        config.apply(None, &mut args).unwrap();
+        assert_eq!(args.scale, 0.5);
+        assert_eq!(args.count, 3);
+        assert!(args.json);
+        assert_eq!(args.disk_path.as_deref(), Some("/tmp"));
+        assert!(args.benchmarks.is_none());
+    }
+
+    #[test]
+    fn test_benchmarks_select_kernels_by_name() {
+        let config =
+            ConfigFile::parse("benchmarks = [\"FFT\", \"memory\", \"parallel_fft\"]").unwrap();
+        let mut args = BenchmarkArgs::default();
+        config.apply(None, &mut args).unwrap();
+        assert_eq!(args.benchmarks, Some(vec![Phase::Cpu, Phase::Memory]));
+        assert_eq!(args.kernels, vec!["fft", "parallel_fft"]);
+    }
+
+    #[test]
+    fn test_profile_overrides_top_level_options() {
+        let config = ConfigFile::parse(SAMPLE).unwrap();
+
+        let mut args = BenchmarkArgs::default();
+        config.apply(Some("quick"), &mut args).unwrap();
+        assert_eq!(args.scale, 0.1);
+        assert_eq!(args.disk_scale, Some(0.01));
+        assert!(args.cpu_scale.is_none());
+        assert_eq!(args.count, 1);
+        assert!(args.json);
+        assert_eq!(args.benchmarks, Some(vec![Phase::Cpu, Phase::Memory]));
+
+        let mut args = BenchmarkArgs::default();
+        config.apply(Some("nightly"), &mut args).unwrap();
+        assert_eq!(args.scale, 0.5);
+        assert_eq!(args.min_time, Some(2.0));
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.core_list, Some(vec![0, 2]));
+        assert!(args.pin_cores && args.html);
+        assert_eq!(args.watch, Some(std::time::Duration::from_secs(86_400)));
+        assert_eq!(args.keep_reports, Some(7));
+        assert_eq!(args.disk_mode, DiskMode::Mmap);
+        assert_eq!(
+            args.disk_precondition,
+            Some(Precondition {
+                multiple: DEFAULT_PRECONDITION_MULTIPLE,
+                passes: 3,
+            })
+        );
+        assert!(args.disk_cache_compare);
+        assert_eq!(args.schedule, Schedule::Isolated);
+        assert_eq!(args.color, ColorMode::Never);
+        assert_eq!(args.units, UnitSystem::Binary);
+        assert_eq!(
+            args.disk_profiles,
+            vec![DiskProfile::Oltp, DiskProfile::Streaming]
+        );
+    }
+
+    #[test]
+    fn test_environment_variables_set_options() {
+        let vars = [
+            ("HSBENCH_SCALE", "0.25"),
+            ("HSBENCH_COUNT", "1"),
+            ("HSBENCH_JSON", "1"),
+            ("HSBENCH_QUIET", "yes"),
+            ("HSBENCH_WATCH", "90"),
+            ("HSBENCH_OUTPUT_DIR", "/results"),
+            ("HSBENCH_BENCHMARKS", "cpu, memory"),
+            ("HSBENCH_CORE_LIST", "0,2"),
+            ("HSBENCH_CONFIG", "ci.toml"),
+            ("HSBENCH_UPLOAD", "http://results.lab/runs"),
+            ("HSBENCH_UPLOAD_TOKEN", "secret"),
+            ("PATH", "/usr/bin"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let mut args = BenchmarkArgs::default();
+        ConfigOptions::from_env(vars)
+            .unwrap()
+            .apply(&mut args)
+            .unwrap();
+        assert_eq!(args.scale, 0.25);
+        assert_eq!(args.count, 1);
+        assert!(args.json && args.quiet);
+        assert_eq!(args.watch, Some(std::time::Duration::from_secs(90)));
+        assert_eq!(args.output_dir.as_deref(), Some("/results"));
+        assert_eq!(args.upload.as_deref(), Some("http://results.lab/runs"));
+        assert_eq!(args.benchmarks, Some(vec![Phase::Cpu, Phase::Memory]));
+        assert_eq!(args.core_list, Some(vec![0, 2]));
+
+        let env = |name: &str, value: &str| [(name.to_string(), value.to_string())];
+        let error = ConfigOptions::from_env(env("HSBENCH_COUNT", "many")).unwrap_err();
+        assert!(error.starts_with("HSBENCH_COUNT"), "{}", error);
+        assert!(ConfigOptions::from_env(env("HSBENCH_SCAEL", "2")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_configs_are_rejected() {
+        let config = ConfigFile::parse(SAMPLE).unwrap();
+        let error = config
+            .apply(Some("weekly"), &mut BenchmarkArgs::default())
+            .unwrap_err();
+        assert!(error.contains("nightly, quick"), "{}", error);
+
+        assert!(ConfigFile::parse("scael = 2.0").is_err());
+        assert!(ConfigFile::parse("[profiles.quick]\ncount = \"one\"").is_err());
+
+        let config = ConfigFile::parse("benchmarks = [\"tape\"]").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+        let config = ConfigFile::parse("disk_profile = [\"tape\"]").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+        let config = ConfigFile::parse("disk_mode = \"tape\"").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+        let config = ConfigFile::parse("units = \"iec\"").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+        let config = ConfigFile::parse("color = \"sometimes\"").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+        let config = ConfigFile::parse("watch = \"soon\"").unwrap();
+        assert!(config.apply(None, &mut BenchmarkArgs::default()).is_err());
+    }
+}
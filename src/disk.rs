@@ -1,8 +1,22 @@
 /// Disk Benchmark Module
 /// Tests disk I/O performance through read/write operations
-/// Uses direct I/O where possible to bypass OS cache and measure true disk throughput
+/// Uses direct I/O where possible to bypass OS cache and measure true disk throughput,
+/// or memory-mapped files to measure page-fault-driven I/O
+use crate::error::BenchError;
+use crate::interrupt;
+use crate::progress::Progress;
+use crate::rng::{self, SimpleRng};
+use crate::stats::{Histogram, HistogramSummary, Statistics};
+use crate::stress;
+use crate::sysinfo_capture::{self, StorageKind};
+use crate::timing::{self, KernelTiming, KernelTimings, DEFAULT_WARMUP_RUNS, WARMUP_SCALE};
+use crate::units;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 
 #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
 use std::os::fd::AsRawFd;
@@ -14,303 +28,2004 @@ use std::os::windows::io::AsRawHandle;
 use std::fs::File;
 
 const BASE_FILE_SIZE: usize = 50_000_000; // 50 MB
-const DEFAULT_BLOCK_SIZE: usize = 512 * 1024; // 512 KB - modest default for sequential I/O
+pub const DEFAULT_BLOCK_SIZE: usize = 512 * 1024; // 512 KB - modest default for sequential I/O
 const ALIGNMENT: usize = 4096; // Align buffers for O_DIRECT when available
-const TEST_DIR: &str = ".bench_temp";
-const TEST_FILE: &str = ".bench_temp/test_file.bin";
+const MAX_SECTOR_SIZE: usize = 64 * 1024; // Larger reported sectors are ignored
+#[cfg(target_os = "macos")]
+const PURGE_COMMAND: &str = "/usr/sbin/purge"; // Evicts the whole file cache; needs root
+const TEST_DIR_PREFIX: &str = ".bench_temp_"; // Followed by <pid>_<sequence>, inside the benchmark directory
+const TEST_FILE_NAME: &str = "test_file.bin";
+const PROBE_FILE_PREFIX: &str = ".bench_probe_"; // Followed by <pid>, written by the preflight permission check
+const WARMUP_FILE_NAME: &str = "warmup_file.bin";
+const FSYNC_FILE_NAME: &str = "fsync_file.bin";
+const BASE_FSYNC_OPS: usize = 1000; // Small appends, each followed by sync_data
+const FSYNC_RECORD_SIZE: usize = 512; // Typical size of a small database commit record
+const SWEEP_FILE_SIZE: usize = 8 * 1024 * 1024; // 8 MB per block size in the sweep
+const METADATA_DIR_NAME: &str = "metadata";
+const BASE_METADATA_FILES: usize = 2000; // Empty files created, stat-ed, renamed, and deleted
+const SMALL_FILES_DIR_NAME: &str = "small_files";
+const BASE_SMALL_FILES: usize = 1000; // Files per pass written without a sync
+const BASE_SYNCED_SMALL_FILES: usize = 200; // Files per pass synced before closing
+const SMALL_FILE_MIN_SIZE: usize = 4 * 1024;
+const SMALL_FILE_MAX_SIZE: usize = 64 * 1024;
+const SMALL_FILES_SEED: u64 = 0x5A11_F11E;
+const PROFILE_FILE_NAME: &str = "profile_file.bin";
+const PRECONDITION_FILE_NAME: &str = "precondition_file.bin";
+/// Default size of the preconditioning dataset, as a multiple of the test file size
+pub const DEFAULT_PRECONDITION_MULTIPLE: f64 = 4.0;
+const CURVE_INTERVAL_SECS: f64 = 1.0; // Writing time per sample of the write throughput curve
+const PROFILE_FILE_SIZE: usize = 16 * 1024 * 1024; // 16 MB working file per mixed workload
+const PROFILE_MIN_OPS: usize = 64; // Enough operations for the large-block profiles' percentiles
+const PROFILE_SEED: u64 = 0x5EED_D15C;
+/// Block sizes measured by the block-size sweep: 4 KB, 64 KB, 1 MB
+pub const SWEEP_BLOCK_SIZES: [usize; 3] = [4 * 1024, 64 * 1024, 1024 * 1024];
+
+/// Whether the memory-mapped disk mode is implemented on this platform
+pub const MMAP_SUPPORTED: bool = cfg!(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "macos"
+));
+
+/// How the sequential write and read passes access the test file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskMode {
+    /// `write`/`read` calls with direct I/O, bypassing the OS cache (default)
+    #[default]
+    Direct,
+    /// Copy into and out of a shared memory mapping of the file; every block's first
+    /// touch page-faults, the way mmap-heavy databases and runtimes access storage
+    Mmap,
+}
+
+impl DiskMode {
+    pub const ALL: [DiskMode; 2] = [DiskMode::Direct, DiskMode::Mmap];
+
+    /// Mode with the given name, ignoring case (e.g. "direct" or "mmap")
+    pub fn from_name(name: &str) -> Option<DiskMode> {
+        DiskMode::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiskMode::Direct => "direct",
+            DiskMode::Mmap => "mmap",
+        }
+    }
+}
+
+/// Dataset written before the sequential passes so that they measure an SSD's steady state
+/// rather than its fast write cache, which a fresh file on an empty drive lands in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Precondition {
+    /// Size of the dataset, as a multiple of the test file size (at least 1)
+    pub multiple: f64,
+    /// Sequential write passes to run at least after the dataset, so that a drive still
+    /// settling shows in the throughput of each
+    pub passes: usize,
+}
+
+impl Default for Precondition {
+    fn default() -> Self {
+        Precondition {
+            multiple: DEFAULT_PRECONDITION_MULTIPLE,
+            passes: 1,
+        }
+    }
+}
+
+impl Precondition {
+    /// Description for configuration tables, e.g. "4x the test file, 3 write passes"
+    pub fn summary(&self) -> String {
+        format!(
+            "{}x the test file, {} write pass{}",
+            self.multiple,
+            self.passes,
+            if self.passes == 1 { "" } else { "es" }
+        )
+    }
+
+    /// Dataset size for a `file_size` byte test file, in whole `alignment` units
+    fn dataset_size(&self, file_size: usize, alignment: usize) -> usize {
+        ((file_size as f64 * self.multiple.max(1.0)) as usize / alignment).max(1) * alignment
+    }
+}
+
+/// How the sequential passes actually reached the test file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IoMode {
+    /// Bypassing the OS cache
+    Direct,
+    /// Through the OS cache: mappings always are, and direct I/O falls back to it on
+    /// filesystems that reject or ignore it (tmpfs, some network and FUSE mounts)
+    Buffered,
+}
+
+impl IoMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IoMode::Direct => "direct",
+            IoMode::Buffered => "buffered",
+        }
+    }
+}
+
+/// Mixed read/write workload modeled on a common fio job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskProfile {
+    /// Database page traffic: random 8 KB operations, 70% reads
+    Oltp,
+    /// Media playback: sequential 1 MB reads
+    Streaming,
+    /// Archive creation: sequential 1 MB writes
+    Backup,
+}
+
+impl DiskProfile {
+    pub const ALL: [DiskProfile; 3] = [
+        DiskProfile::Oltp,
+        DiskProfile::Streaming,
+        DiskProfile::Backup,
+    ];
+
+    /// Profile with the given name, ignoring case (e.g. "oltp" or "Backup")
+    pub fn from_name(name: &str) -> Option<DiskProfile> {
+        DiskProfile::ALL
+            .into_iter()
+            .find(|profile| profile.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Name used on the command line and in metric keys
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiskProfile::Oltp => "oltp",
+            DiskProfile::Streaming => "streaming",
+            DiskProfile::Backup => "backup",
+        }
+    }
+
+    /// Human-readable name used in console output and report labels
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiskProfile::Oltp => "OLTP",
+            DiskProfile::Streaming => "Streaming",
+            DiskProfile::Backup => "Backup",
+        }
+    }
 
+    /// Share of the operations that are reads, in percent
+    pub fn read_percent(&self) -> u32 {
+        match self {
+            DiskProfile::Oltp => 70,
+            DiskProfile::Streaming => 100,
+            DiskProfile::Backup => 0,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        match self {
+            DiskProfile::Oltp => 8 * 1024,
+            DiskProfile::Streaming | DiskProfile::Backup => 1024 * 1024,
+        }
+    }
+
+    /// Whether operations go to random block-aligned offsets instead of walking the file
+    pub fn random(&self) -> bool {
+        matches!(self, DiskProfile::Oltp)
+    }
+}
+
+/// Aligned to `ALIGNMENT`, or to the largest power of two dividing `size` (up to
+/// `MAX_SECTOR_SIZE`) when larger, so a block of whole sectors is aligned to the sector size
 fn alloc_aligned(size: usize) -> (Vec<u8>, usize) {
+    let alignment =
+        (1 << size.trailing_zeros().min(MAX_SECTOR_SIZE.trailing_zeros())).max(ALIGNMENT);
     // Allocate slightly larger buffer and return an aligned slice offset
-    let buffer = vec![0u8; size + ALIGNMENT];
+    let buffer = vec![0u8; size + alignment];
     let ptr = buffer.as_ptr() as usize;
-    let offset = (ALIGNMENT - (ptr % ALIGNMENT)) % ALIGNMENT;
+    let offset = (alignment - (ptr % alignment)) % alignment;
     (buffer, offset)
 }
 
+/// Unit the sizes of direct I/O transfers on the volume holding `dir` are rounded to:
+/// its sector size, and at least `ALIGNMENT`
+/// Windows fails unbuffered transfers that are not whole sectors; Linux's logical block
+/// sizes (512 B or 4 KB) divide `ALIGNMENT`
+fn direct_io_alignment(dir: &Path) -> usize {
+    sector_size(dir)
+        .filter(|size| size.is_power_of_two() && *size <= MAX_SECTOR_SIZE)
+        .map_or(ALIGNMENT, |size| size.max(ALIGNMENT))
+}
+
+/// Sector size of the volume holding `dir`
+#[cfg(windows)]
+fn sector_size(dir: &Path) -> Option<usize> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetVolumePathNameW(file_name: *const u16, volume_path: *mut u16, length: u32) -> i32;
+        fn GetDiskFreeSpaceW(
+            root: *const u16,
+            sectors_per_cluster: *mut u32,
+            bytes_per_sector: *mut u32,
+            free_clusters: *mut u32,
+            total_clusters: *mut u32,
+        ) -> i32;
+    }
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut root = [0u16; 1024];
+    let (mut sectors_per_cluster, mut bytes_per_sector) = (0u32, 0u32);
+    let (mut free_clusters, mut total_clusters) = (0u32, 0u32);
+    // SAFETY: `path` is NUL-terminated, `root` holds `root.len()` characters, and the OS
+    // NUL-terminates the volume path it writes there
+    let found = unsafe {
+        GetVolumePathNameW(path.as_ptr(), root.as_mut_ptr(), root.len() as u32) != 0
+            && GetDiskFreeSpaceW(
+                root.as_ptr(),
+                &mut sectors_per_cluster,
+                &mut bytes_per_sector,
+                &mut free_clusters,
+                &mut total_clusters,
+            ) != 0
+    };
+    (found && bytes_per_sector > 0).then_some(bytes_per_sector as usize)
+}
+
+#[cfg(not(windows))]
+fn sector_size(_dir: &Path) -> Option<usize> {
+    None
+}
+
+/// Returns whether the OS accepted the request
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-fn drop_os_cache(fd: std::os::fd::RawFd) {
+fn drop_os_cache(fd: std::os::fd::RawFd) -> bool {
     // Best-effort: tell kernel the data is not needed in page cache
-    unsafe {
-        let _ = libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_DONTNEED);
-    }
+    unsafe { libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_DONTNEED) == 0 }
 }
 
 #[cfg(target_os = "macos")]
-fn drop_os_cache(fd: std::os::fd::RawFd) {
-    // macOS: disable caching on this descriptor
+fn drop_os_cache(fd: std::os::fd::RawFd) -> bool {
+    // macOS: disable caching on this descriptor, and read-ahead, which would otherwise
+    // fetch the next blocks before they are timed
     unsafe {
-        let _ = libc::fcntl(fd, libc::F_NOCACHE, 1);
+        libc::fcntl(fd, libc::F_NOCACHE, 1) != -1 && libc::fcntl(fd, libc::F_RDAHEAD, 0) != -1
     }
 }
 
 #[cfg(windows)]
-fn drop_os_cache(_handle: std::os::windows::io::RawHandle) {
+fn drop_os_cache(_handle: std::os::windows::io::RawHandle) -> bool {
     // Windows flags already request no buffering; nothing extra to do here
+    true
+}
+
+/// Whether `purge` can evict the OS file cache before every read pass: it needs root
+#[cfg(target_os = "macos")]
+fn purge_supported() -> bool {
+    (unsafe { libc::geteuid() } == 0) && Path::new(PURGE_COMMAND).exists()
+}
+
+/// Evict every file from the OS cache; returns whether it succeeded
+#[cfg(target_os = "macos")]
+fn purge_os_cache() -> bool {
+    std::process::Command::new(PURGE_COMMAND)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn purge_supported() -> bool {
+    false
 }
 
+#[cfg(not(target_os = "macos"))]
+fn purge_os_cache() -> bool {
+    false
+}
+
+/// Whether the read passes bypassed the OS cache: macOS caches every file, so it takes
+/// `F_NOCACHE` on the descriptor (`io_mode` direct) or a cache `purged` before every pass
+/// None where it is not checked
+#[cfg(target_os = "macos")]
+fn cache_bypassed(io_mode: IoMode, purged: bool) -> Option<bool> {
+    Some(io_mode == IoMode::Direct || purged)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn cache_bypassed(_io_mode: IoMode, _purged: bool) -> Option<bool> {
+    None
+}
+
+/// Set the flags that request direct I/O (`direct`) and writes that reach the device
+/// before returning (`sync`) on `options`
+fn set_io_flags(options: &mut fs::OpenOptions, direct: bool, sync: bool) {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        let direct = if direct { libc::O_DIRECT } else { 0 };
+        options.custom_flags(direct | if sync { libc::O_SYNC } else { 0 });
+    }
+
+    #[cfg(target_os = "freebsd")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        let _ = sync;
+        options.custom_flags(if direct { libc::O_DIRECT } else { 0 });
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        let direct = if direct { 0x20000000 } else { 0 }; // FILE_FLAG_NO_BUFFERING
+        options.custom_flags(direct | if sync { 0x80000000 } else { 0 }); // FILE_FLAG_WRITE_THROUGH
+    }
+
+    // macOS: no open flag; `F_NOCACHE` is set on the descriptor instead
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd", windows)))]
+    let _ = (options, direct, sync);
+}
+
+/// Open `path` with `options` and direct I/O, falling back to buffered I/O where the
+/// filesystem rejects the flags (tmpfs on older kernels, some network and FUSE mounts)
+/// Returns the file and whether its I/O actually bypasses the OS cache, which also catches
+/// filesystems that accept the flags but drop them
+fn open_direct(
+    options: &mut fs::OpenOptions,
+    path: &Path,
+    sync: bool,
+) -> io::Result<(fs::File, bool)> {
+    set_io_flags(options, true, sync);
+    let (file, requested) = match options.open(path) {
+        Ok(file) => (file, true),
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            set_io_flags(options, false, sync);
+            (options.open(path)?, false)
+        }
+        Err(e) => return Err(e),
+    };
+    let bypassed = bypasses_cache(&file);
+    Ok((file, requested && bypassed))
+}
+
+/// Drop `file` from the OS cache and return whether its descriptor bypasses the cache
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn bypasses_cache(file: &fs::File) -> bool {
+    drop_os_cache(file.as_raw_fd());
+    let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+    flags != -1 && flags & libc::O_DIRECT != 0
+}
+
+#[cfg(target_os = "macos")]
+fn bypasses_cache(file: &fs::File) -> bool {
+    drop_os_cache(file.as_raw_fd())
+}
+
+/// Windows fails the open rather than ignoring `FILE_FLAG_NO_BUFFERING`
+#[cfg(windows)]
+fn bypasses_cache(file: &fs::File) -> bool {
+    drop_os_cache(file.as_raw_handle())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "macos",
+    windows
+)))]
+fn bypasses_cache(_file: &fs::File) -> bool {
+    false
+}
+
+/// I/O the sequential passes of `mode` over the existing file at `path` got: direct where
+/// the filesystem honors it, buffered otherwise and for mappings
+fn effective_io_mode(path: &Path, mode: DiskMode) -> IoMode {
+    let direct = mode == DiskMode::Direct
+        && open_direct(fs::OpenOptions::new().read(true), path, false)
+            .is_ok_and(|(_, direct)| direct);
+    if direct {
+        IoMode::Direct
+    } else {
+        IoMode::Buffered
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskResult {
+    /// Access mode of the sequential passes and the block-size sweep
+    #[serde(default)]
+    pub mode: DiskMode,
+    /// Sequential throughputs, in MiB/s
     pub write_throughput: f64,
     pub read_throughput: f64,
     pub combined_throughput: f64,
+    /// I/O the sequential passes actually got; buffered in direct mode means the
+    /// filesystem did not honor direct I/O. None in reports written before it was recorded
+    #[serde(default)]
+    pub io_mode: Option<IoMode>,
+    /// Whether the sequential reads bypassed the OS cache, i.e. measured the disk rather
+    /// than memory; None where it is not checked
+    #[serde(default)]
+    pub cache_bypassed: Option<bool>,
+    /// Throughput per block size, in `SWEEP_BLOCK_SIZES` order; sizes below the volume's
+    /// direct I/O alignment are left out
+    #[serde(default)]
+    pub block_size_sweep: Vec<BlockSizeThroughput>,
+    /// Small appends each made durable with `sync_data`, per second
+    #[serde(default)]
+    pub fsync_ops_per_sec: f64,
+    #[serde(default)]
+    pub fsync_latency_p50_us: f64,
+    #[serde(default)]
+    pub fsync_latency_p95_us: f64,
+    #[serde(default)]
+    pub fsync_latency_p99_us: f64,
+    /// Completion time of every block written and read by the main sequential passes
+    /// (each write is synchronous, so it includes reaching the device)
+    #[serde(default)]
+    pub write_latency: HistogramSummary,
+    #[serde(default)]
+    pub read_latency: HistogramSummary,
+    /// Write throughput every second of the preconditioning dataset, if any, and the
+    /// sequential write passes, showing write-cache exhaustion and thermal throttling that
+    /// the averages hide; memory-mapped writes show the copy rate until the final msync
+    #[serde(default)]
+    pub write_curve: Vec<ThroughputSample>,
+    /// Burst and steady-state write throughput; None unless `--disk-precondition` was given
+    #[serde(default)]
+    pub precondition: Option<PreconditionResult>,
+    /// The sequential passes repeated through the OS cache; None unless
+    /// `--disk-cache-compare` was given
+    #[serde(default)]
+    pub cached: Option<CachedResult>,
+    /// Mixed workloads selected with `--disk-profile`, in the order they ran
+    #[serde(default)]
+    pub profiles: Vec<DiskProfileResult>,
+    /// Filesystem metadata operation rates; all zero in reports written before it was measured
+    #[serde(default)]
+    pub metadata: MetadataResult,
+    /// Small-file write rates; all zero in reports written before it was measured
+    #[serde(default)]
+    pub small_files: SmallFilesResult,
+    /// Raw work and elapsed time behind each rate above, keyed by field name; sweep entries
+    /// are keyed `block_size_sweep.<block size>.<field>`, preconditioning entries
+    /// `precondition.<field>`, profile entries `profiles.<profile>.<field>`, and metadata and
+    /// small-file entries `metadata.<field>` and `small_files.<field>`
+    #[serde(default)]
+    pub timings: KernelTimings,
 }
 
-#[allow(dead_code)]
-pub fn run_disk_benchmark() -> DiskResult {
-    run_disk_benchmark_scaled(1.0)
+/// Sequential throughput measured with one block size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockSizeThroughput {
+    pub block_size: usize,
+    pub write_throughput: f64,
+    pub read_throughput: f64,
 }
 
-pub fn run_disk_benchmark_scaled(scale: f64) -> DiskResult {
-    run_disk_benchmark_scaled_with_block_size(scale, DEFAULT_BLOCK_SIZE)
+/// Rates of the filesystem metadata operations, measured on thousands of empty files
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataResult {
+    pub create_ops_per_sec: f64,
+    pub stat_ops_per_sec: f64,
+    pub rename_ops_per_sec: f64,
+    pub delete_ops_per_sec: f64,
 }
 
-pub fn run_disk_benchmark_scaled_with_block_size(scale: f64, block_size: usize) -> DiskResult {
-    // Warmup phase: small file to prime disk cache
-    warmup_disk_with_block_size(scale * 0.1, block_size);
+/// Rates of writing many 4-64 KB files, as package managers, checkouts, and builds do,
+/// with buffered writes and with every file synced before it is closed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmallFilesResult {
+    pub files_per_sec: f64,
+    /// Mebibytes written per second
+    pub throughput: f64,
+    pub synced_files_per_sec: f64,
+    pub synced_throughput: f64,
+}
 
-    // Actual benchmark with full file
-    let file_size = (BASE_FILE_SIZE as f64 * scale) as usize;
+/// Burst and steady-state write throughput of a preconditioned run, in MiB/s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreconditionResult {
+    /// Size of the dataset, as a multiple of the test file size
+    pub multiple: f64,
+    /// Throughput of the dataset's first test-file-sized segment, written while the
+    /// drive's write cache was still empty
+    pub burst_throughput: f64,
+    /// Throughput of every sequential write pass after the dataset, in order
+    pub pass_throughput: Vec<f64>,
+    /// Throughput of the last of those passes
+    pub steady_throughput: f64,
+}
 
-    // Create temporary directory
-    let _ = fs::create_dir(TEST_DIR);
+/// Sequential throughput with the passes going through the OS cache, in MiB/s, for
+/// comparison with the uncached passes of the same run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub write_throughput: f64,
+    pub read_throughput: f64,
+    pub combined_throughput: f64,
+}
 
-    let (mut data_buf, data_offset) = alloc_aligned(block_size);
-    let data_slice = &mut data_buf[data_offset..data_offset + block_size];
-    data_slice.fill(0xAB);
+impl CachedResult {
+    /// How many times faster the cached passes are than the uncached ones, combined
+    pub fn speedup(&self, uncached: &DiskResult) -> f64 {
+        self.combined_throughput / uncached.combined_throughput
+    }
 
-    // Write benchmark with direct I/O (bypassing OS cache)
-    let write_start = std::time::Instant::now();
-    {
-        let mut options = std::fs::OpenOptions::new();
-        options.write(true).create(true).truncate(true);
+    /// How many times faster reads from the cache are than reads from the disk
+    pub fn read_speedup(&self, uncached: &DiskResult) -> f64 {
+        self.read_throughput / uncached.read_throughput
+    }
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT | libc::O_SYNC);
+/// Write throughput over one interval of the write passes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputSample {
+    /// Seconds spent writing when the interval ended, counted across passes
+    pub elapsed_secs: f64,
+    /// Mebibytes written per second during the interval
+    pub throughput: f64,
+}
+
+/// Collects a `ThroughputSample` every `CURVE_INTERVAL_SECS` of writing; passes are laid
+/// end to end, leaving out the time between them, and each ends with a shorter interval
+#[derive(Debug, Default)]
+struct ThroughputCurve {
+    samples: Vec<ThroughputSample>,
+    /// Writing time of the finished passes
+    offset: f64,
+    /// Start of the open interval, relative to the start of the pass, and bytes written in it
+    interval_start: f64,
+    interval_bytes: usize,
+}
+
+impl ThroughputCurve {
+    /// Count `bytes` the pass finished writing `pass_elapsed` seconds after it started
+    fn record(&mut self, pass_elapsed: f64, bytes: usize) {
+        self.interval_bytes += bytes;
+        if pass_elapsed - self.interval_start >= CURVE_INTERVAL_SECS {
+            self.close_interval(pass_elapsed);
         }
+    }
 
-        #[cfg(target_os = "freebsd")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
+    /// End the pass `pass_elapsed` seconds after it started, including its final sync
+    fn finish_pass(&mut self, pass_elapsed: f64) {
+        if self.interval_bytes > 0 {
+            self.close_interval(pass_elapsed);
         }
+        self.offset += pass_elapsed;
+        self.interval_start = 0.0;
+    }
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::fs::OpenOptionsExt;
-            options.custom_flags(0x20000000 | 0x80000000); // FILE_FLAG_NO_BUFFERING | FILE_FLAG_WRITE_THROUGH
+    fn close_interval(&mut self, pass_elapsed: f64) {
+        let secs = pass_elapsed - self.interval_start;
+        self.samples.push(ThroughputSample {
+            elapsed_secs: self.offset + pass_elapsed,
+            throughput: self.interval_bytes as f64 / units::MEBIBYTE / secs.max(1e-9),
+        });
+        self.interval_start = pass_elapsed;
+        self.interval_bytes = 0;
+    }
+}
+
+/// Throughput, IOPS, and per-operation latency of one mixed workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskProfileResult {
+    pub profile: DiskProfile,
+    /// Mebibytes read and written per second
+    pub throughput: f64,
+    /// Read and write operations per second
+    pub iops: f64,
+    pub read_latency: HistogramSummary,
+    pub write_latency: HistogramSummary,
+}
+
+/// Filesystem and device backing the directory the disk benchmark runs in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskTarget {
+    pub path: String,
+    pub mount_point: String,
+    pub filesystem: String,
+    pub device: String,
+    /// Size of the filesystem in bytes; 0 when unknown
+    #[serde(default)]
+    pub total_bytes: u64,
+    /// Bytes available to unprivileged users when the benchmark started; 0 when unknown
+    #[serde(default)]
+    pub free_bytes: u64,
+    /// Rotational disk or SSD, where the OS reports it
+    #[serde(default)]
+    pub kind: StorageKind,
+    /// Whether the filesystem trims the blocks of deleted files right away (the Linux
+    /// `discard` mount option); without it, an SSD only learns they are free from a periodic
+    /// `fstrim`, and rewriting the test file wears down its steady-state write throughput
+    /// None where it is not known
+    #[serde(default)]
+    pub discard: Option<bool>,
+}
+
+impl DiskTarget {
+    /// Describe the filesystem of `dir`; fields that cannot be determined are "Unknown"
+    /// The mount table is preferred over the disks listed by sysinfo, which leave out
+    /// overlay and network filesystems
+    pub fn describe(dir: &Path) -> Self {
+        let path = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let disk = sysinfo_capture::disk_for_path(&path);
+        let (mount_point, filesystem, device, discard) = mount_info(&path)
+            .or_else(|| {
+                disk.as_ref().map(|d| {
+                    let (mount_point, filesystem) = (d.mount_point.clone(), d.filesystem.clone());
+                    (mount_point, filesystem, d.name.clone(), None)
+                })
+            })
+            .unwrap_or_else(|| {
+                (
+                    "Unknown".to_string(),
+                    "Unknown".to_string(),
+                    "Unknown".to_string(),
+                    None,
+                )
+            });
+        let (total_bytes, free_bytes) = filesystem_space(&path)
+            .or_else(|| disk.as_ref().map(|d| (d.total_bytes, d.free_bytes)))
+            .unwrap_or((0, 0));
+        // sysinfo reports virtual filesystems such as tmpfs as HDDs on Linux, so sysfs is
+        // asked about the mounted device there
+        let kind = if cfg!(target_os = "linux") {
+            sysinfo_capture::block_device_kind(&device)
+        } else {
+            disk.map_or(StorageKind::Unknown, |d| d.kind)
+        };
+        DiskTarget {
+            path: path.display().to_string(),
+            mount_point,
+            filesystem,
+            device,
+            total_bytes,
+            free_bytes,
+            kind,
+            discard,
         }
+    }
 
-        // macOS: Use standard I/O, direct I/O not commonly available
-        #[cfg(target_os = "macos")]
-        {
-            // No special flags on macOS
+    /// One-line description, e.g. "/data (ext4 on /dev/nvme0n1p2, SSD, 120.5 of 500.0 GB free)"
+    pub fn summary(&self) -> String {
+        let mut details = vec![format!("{} on {}", self.filesystem, self.device)];
+        if self.kind != StorageKind::Unknown {
+            details.push(self.kind.name().to_string());
+        }
+        if self.discard == Some(true) {
+            details.push("discard".to_string());
+        }
+        if self.total_bytes > 0 {
+            let gb = |bytes: u64| bytes as f64 / 1e9;
+            details.push(format!(
+                "{:.1} of {:.1} GB free",
+                gb(self.free_bytes),
+                gb(self.total_bytes)
+            ));
+        }
+        format!("{} ({})", self.path, details.join(", "))
+    }
+}
+
+/// Longest matching entry of the mount table: (mount point, filesystem type, device,
+/// whether it is mounted with `discard`)
+#[cfg(target_os = "linux")]
+fn mount_info(path: &Path) -> Option<(String, String, String, Option<bool>)> {
+    // Fields are space-separated with spaces inside paths escaped as octal (\040)
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && i + 3 < bytes.len() {
+                let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).unwrap_or("");
+                if let Ok(code) = u8::from_str_radix(digits, 8) {
+                    out.push(code);
+                    i += 4;
+                    continue;
+                }
+            }
+            out.push(bytes[i]);
+            i += 1;
         }
+        String::from_utf8_lossy(&out).into_owned()
+    }
 
-        if let Ok(mut file) = options.open(TEST_FILE) {
-            #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
-            drop_os_cache(file.as_raw_fd());
+    let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape(fields.next()?);
+            let mount_point = unescape(fields.next()?);
+            let filesystem = fields.next()?.to_string();
+            let discard = fields.next()?.split(',').any(|option| option == "discard");
+            Some((mount_point, filesystem, device, Some(discard)))
+        })
+        .filter(|(mount_point, _, _, _)| path.starts_with(mount_point))
+        // Later entries shadow earlier ones mounted at the same point
+        .max_by_key(|(mount_point, _, _, _)| mount_point.len())
+}
 
-            #[cfg(windows)]
-            drop_os_cache(file.as_raw_handle());
+/// Mount of `path` (see the Linux version); `discard` is unknown
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+fn mount_info(path: &Path) -> Option<(String, String, String, Option<bool>)> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
 
-            // Write in sequential blocks
-            let mut bytes_written = 0;
-            while bytes_written < file_size {
-                let remaining = file_size - bytes_written;
-                let write_size = remaining.min(block_size);
-                let _ = file.write_all(&data_slice[..write_size]);
-                bytes_written += write_size;
-            }
-            let _ = file.sync_all();
-        } // File handle dropped here, ensuring flush
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
     }
-    let write_time = write_start.elapsed().as_secs_f64();
-    let write_throughput = (file_size as f64 / (1024.0 * 1024.0)) / write_time;
+    let field = |chars: &[libc::c_char]| {
+        unsafe { CStr::from_ptr(chars.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    Some((
+        field(&stat.f_mntonname),
+        field(&stat.f_fstypename),
+        field(&stat.f_mntfromname),
+        None,
+    ))
+}
 
-    // Read benchmark with direct I/O (bypassing OS cache)
-    let read_start = std::time::Instant::now();
-    let (mut buffer, buffer_offset) = alloc_aligned(block_size);
-    let buffer_slice = &mut buffer[buffer_offset..buffer_offset + block_size];
-    {
-        let mut options = std::fs::OpenOptions::new();
-        options.read(true);
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+fn mount_info(_path: &Path) -> Option<(String, String, String, Option<bool>)> {
+    None
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
-        }
+pub fn run_disk_benchmark() -> Result<DiskResult, BenchError> {
+    run_disk_benchmark_scaled(1.0)
+}
 
-        #[cfg(target_os = "freebsd")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
+pub fn run_disk_benchmark_scaled(scale: f64) -> Result<DiskResult, BenchError> {
+    run_disk_benchmark_scaled_with_block_size(scale, DEFAULT_BLOCK_SIZE, Path::new("."))
+}
+
+/// Run the disk benchmark with temporary files created under `dir`
+pub fn run_disk_benchmark_scaled_with_block_size(
+    scale: f64,
+    block_size: usize,
+    dir: &Path,
+) -> Result<DiskResult, BenchError> {
+    run_disk_benchmark_with_progress(
+        scale,
+        block_size,
+        dir,
+        DiskMode::Direct,
+        None,
+        &[],
+        0.0,
+        DEFAULT_WARMUP_RUNS,
+        &mut (),
+    )
+}
+
+/// Run the disk benchmark under `dir`, announcing each step to `progress`
+/// Every write and read pass and the fsync loop are repeated until at least `min_time`
+/// seconds have been measured (0 runs each scaled workload once)
+/// `mode` selects how the sequential passes and the sweep access the file; the fsync
+/// loop always uses buffered writes. Mmap falls back to direct I/O where unsupported
+/// With `precondition`, its dataset is written before the sequential passes, which then
+/// run at least `precondition.passes` write passes
+/// Each of `profiles` then runs as a mixed workload with direct I/O
+/// `warmup_runs` untimed write and read passes over a small file run first
+/// Fails if the `preflight` checks fail or a sequential pass cannot write or read its file
+#[allow(clippy::too_many_arguments)]
+pub fn run_disk_benchmark_with_progress(
+    scale: f64,
+    block_size: usize,
+    dir: &Path,
+    mode: DiskMode,
+    precondition: Option<Precondition>,
+    profiles: &[DiskProfile],
+    min_time: f64,
+    warmup_runs: usize,
+    progress: &mut dyn Progress,
+) -> Result<DiskResult, BenchError> {
+    let mode = if MMAP_SUPPORTED {
+        mode
+    } else {
+        DiskMode::Direct
+    };
+    preflight(dir, scale, precondition)?;
+
+    // Create a temporary directory no other run, in this or another process, writes to
+    let test_dir = unique_test_dir(dir);
+    let test_file = test_dir.join(TEST_FILE_NAME);
+    let _ = fs::create_dir(&test_dir);
+    // Direct I/O rejects transfers of partial sectors
+    let alignment = direct_io_alignment(dir);
+    let block_size = block_size.max(1).next_multiple_of(alignment);
+
+    // Warmup phase: small file to prime disk cache
+    if warmup_runs > 0 {
+        progress.step("warmup");
+    }
+    for _ in 0..warmup_runs {
+        warmup_disk_with_block_size(scale * WARMUP_SCALE, block_size, alignment, &test_dir, mode)
+            .map_err(|e| failed_pass(&test_dir, "warmup", e))?;
+    }
+
+    // Actual benchmark with full file
+    let file_size = test_file_size(scale, alignment);
+
+    let mebibytes = file_size as f64 / units::MEBIBYTE;
+    // The dataset stays in place until the sequential passes are done
+    let dataset_file = test_dir.join(PRECONDITION_FILE_NAME);
+    let mut write_curve = ThroughputCurve::default();
+    let burst = match precondition {
+        Some(precondition) => {
+            check_interrupted(&test_dir)?;
+            progress.step("precondition");
+            let dataset_size = precondition.dataset_size(file_size, alignment);
+            let burst_time = write_dataset(
+                &dataset_file,
+                dataset_size,
+                file_size,
+                block_size,
+                &mut write_curve,
+            )
+            .map_err(|e| failed_pass(&test_dir, "precondition", e))?;
+            Some(KernelTiming::new(mebibytes, 1, burst_time))
+        }
+        None => None,
+    };
+
+    check_interrupted(&test_dir)?;
+    progress.step("sequential write");
+    let mut write_latency = Histogram::new();
+    let mut write_pass_times = Vec::new();
+    let min_write_passes = precondition.map_or(1, |precondition| precondition.passes.max(1));
+    let (write_passes, write_time) =
+        timing::try_repeat_passes_min(min_write_passes as u64, min_time, || {
+            let pass_time = timed_write(
+                mode,
+                &test_file,
+                file_size,
+                block_size,
+                &mut write_latency,
+                &mut write_curve,
+            )?;
+            write_pass_times.push(pass_time);
+            Ok(pass_time)
+        })
+        .map_err(|e| failed_pass(&test_dir, "sequential write", e))?;
+    let write = KernelTiming::new(mebibytes, write_passes, write_time);
+
+    check_interrupted(&test_dir)?;
+    progress.step("sequential read");
+    let mut read_latency = Histogram::new();
+    // Purging runs outside the timed region, so it only keeps the cache out of the reads
+    let mut purged = purge_supported();
+    let (read_passes, read_time) = timing::try_repeat_passes(min_time, || {
+        if purged {
+            purged = purge_os_cache();
         }
+        timed_read(mode, &test_file, file_size, block_size, &mut read_latency)
+    })
+    .map_err(|e| failed_pass(&test_dir, "sequential read", e))?;
+    let read = KernelTiming::new(mebibytes, read_passes, read_time);
+    let io_mode = effective_io_mode(&test_file, mode);
+    let cache_bypassed = cache_bypassed(io_mode, purged);
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::fs::OpenOptionsExt;
-            options.custom_flags(0x20000000); // FILE_FLAG_NO_BUFFERING
+    // Calculate combined throughput
+    let combined = KernelTiming::new(
+        mebibytes,
+        write_passes + read_passes,
+        write_time + read_time,
+    );
+    let mut timings = KernelTimings::from([
+        ("write_throughput".to_string(), write),
+        ("read_throughput".to_string(), read),
+        ("combined_throughput".to_string(), combined),
+    ]);
+
+    let precondition = burst.map(|burst| {
+        let steady = KernelTiming::new(
+            mebibytes,
+            1,
+            write_pass_times.last().copied().unwrap_or(0.0),
+        );
+        timings.insert("precondition.burst_throughput".to_string(), burst);
+        timings.insert("precondition.steady_throughput".to_string(), steady);
+        PreconditionResult {
+            multiple: precondition.map_or(0.0, |precondition| precondition.multiple.max(1.0)),
+            burst_throughput: burst.rate(),
+            pass_throughput: write_pass_times
+                .iter()
+                .map(|&pass_time| KernelTiming::new(mebibytes, 1, pass_time).rate())
+                .collect(),
+            steady_throughput: steady.rate(),
         }
+    });
+
+    let _ = fs::remove_file(&test_file);
+    let _ = fs::remove_file(&dataset_file);
+
+    // Smaller file per block size so that 4 KB synchronous writes stay affordable
+    let sweep_file_size =
+        ((SWEEP_FILE_SIZE as f64 * scale) as usize / (1024 * 1024)).max(1) * 1024 * 1024;
+    check_interrupted(&test_dir)?;
+    progress.step("block-size sweep");
+    let block_size_sweep = sweep_block_sizes(alignment)
+        .map(|sweep_block_size| {
+            let mut latency = Histogram::new();
+            let (write_passes, write_time) = timing::try_repeat_passes(min_time, || {
+                timed_write(
+                    mode,
+                    &test_file,
+                    sweep_file_size,
+                    sweep_block_size,
+                    &mut latency,
+                    &mut ThroughputCurve::default(),
+                )
+            })?;
+            let (read_passes, read_time) = timing::try_repeat_passes(min_time, || {
+                timed_read(
+                    mode,
+                    &test_file,
+                    sweep_file_size,
+                    sweep_block_size,
+                    &mut latency,
+                )
+            })?;
+            let _ = fs::remove_file(&test_file);
+            let mebibytes = sweep_file_size as f64 / units::MEBIBYTE;
+            let write = KernelTiming::new(mebibytes, write_passes, write_time);
+            let read = KernelTiming::new(mebibytes, read_passes, read_time);
+            for (field, timing) in [("write_throughput", write), ("read_throughput", read)] {
+                timings.insert(
+                    format!("block_size_sweep.{}.{}", sweep_block_size, field),
+                    timing,
+                );
+            }
+            Ok(BlockSizeThroughput {
+                block_size: sweep_block_size,
+                write_throughput: write.rate(),
+                read_throughput: read.rate(),
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()
+        .map_err(|e| failed_pass(&test_dir, "block-size sweep", e))?;
+
+    check_interrupted(&test_dir)?;
+    progress.step("fsync");
+    let fsync = benchmark_fsync(&test_dir.join(FSYNC_FILE_NAME), scale, min_time);
+    timings.insert("fsync_ops_per_sec".to_string(), fsync.commits);
+
+    check_interrupted(&test_dir)?;
+    progress.step("metadata");
+    let (metadata, metadata_timings) =
+        benchmark_metadata(&test_dir.join(METADATA_DIR_NAME), scale, min_time);
+    timings.extend(metadata_timings);
+
+    check_interrupted(&test_dir)?;
+    progress.step("small files");
+    let (small_files, small_files_timings) =
+        benchmark_small_files(&test_dir.join(SMALL_FILES_DIR_NAME), scale, min_time);
+    timings.extend(small_files_timings);
+
+    let profile_file_size =
+        ((PROFILE_FILE_SIZE as f64 * scale) as usize / (1024 * 1024)).max(1) * 1024 * 1024;
+    let profiles = profiles
+        .iter()
+        .map(|&profile| {
+            check_interrupted(&test_dir)?;
+            let step = format!("{} profile", profile.name());
+            progress.step(&step);
+            let (result, iops, throughput) = benchmark_profile(
+                &test_dir.join(PROFILE_FILE_NAME),
+                profile,
+                profile_file_size,
+                min_time,
+            )
+            .map_err(|e| failed_pass(&test_dir, &step, e))?;
+            for (field, timing) in [("iops", iops), ("throughput", throughput)] {
+                timings.insert(format!("profiles.{}.{}", profile.name(), field), timing);
+            }
+            Ok(result)
+        })
+        .collect::<Result<Vec<_>, BenchError>>()?;
 
-        // macOS: Use standard I/O
-        #[cfg(target_os = "macos")]
-        {
-            // No special flags on macOS
-        }
-
-        if let Ok(mut file) = options.open(TEST_FILE) {
-            #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
-            drop_os_cache(file.as_raw_fd());
-
-            #[cfg(windows)]
-            drop_os_cache(file.as_raw_handle());
-
-            // Read in sequential blocks
-            let mut bytes_read = 0;
-            while bytes_read < file_size {
-                let remaining = file_size - bytes_read;
-                let read_size = remaining.min(block_size);
-                match file.read_exact(&mut buffer_slice[..read_size]) {
-                    Ok(()) => {
-                        bytes_read += read_size;
-                    }
-                    Err(_) => break,
-                }
+    // Cleanup
+    let _ = fs::remove_dir(&test_dir);
+
+    // The fsync, metadata, small-file, and profile loops stop at the first failed operation
+    timing::check_timings("disk", &timings)?;
+    Ok(DiskResult {
+        mode,
+        write_throughput: write.rate(),
+        read_throughput: read.rate(),
+        combined_throughput: combined.rate(),
+        io_mode: Some(io_mode),
+        cache_bypassed,
+        block_size_sweep,
+        fsync_ops_per_sec: fsync.commits.rate(),
+        fsync_latency_p50_us: fsync.p50_us,
+        fsync_latency_p95_us: fsync.p95_us,
+        fsync_latency_p99_us: fsync.p99_us,
+        write_latency: write_latency.summary(),
+        read_latency: read_latency.summary(),
+        write_curve: write_curve.samples,
+        precondition,
+        cached: None,
+        profiles,
+        metadata,
+        small_files,
+        timings,
+    })
+}
+
+/// Repeat the sequential write and read passes of `run_disk_benchmark_with_progress`
+/// through the OS cache: writes are left for the OS to flush and every read finds the
+/// file in memory, which shows how much a workload that fits in the cache gains from it
+/// Fails like the uncached passes when the file cannot be written or read
+pub fn run_cached_disk_benchmark(
+    scale: f64,
+    block_size: usize,
+    dir: &Path,
+    min_time: f64,
+) -> Result<CachedResult, BenchError> {
+    preflight(dir, scale, None)?;
+    let test_dir = unique_test_dir(dir);
+    let test_file = test_dir.join(TEST_FILE_NAME);
+    let _ = fs::create_dir(&test_dir);
+    // The same file and block sizes as the uncached passes
+    let alignment = direct_io_alignment(dir);
+    let block_size = block_size.max(1).next_multiple_of(alignment);
+    let file_size = test_file_size(scale, alignment);
+    let mebibytes = file_size as f64 / units::MEBIBYTE;
+
+    check_interrupted(&test_dir)?;
+    let (write_passes, write_time) = timing::try_repeat_passes(min_time, || {
+        timed_sequential_write(
+            &test_file,
+            file_size,
+            block_size,
+            false,
+            &mut Histogram::new(),
+            &mut ThroughputCurve::default(),
+        )
+    })
+    .map_err(|e| failed_pass(&test_dir, "cached write", e))?;
+    check_interrupted(&test_dir)?;
+    let (read_passes, read_time) = timing::try_repeat_passes(min_time, || {
+        timed_sequential_read(
+            &test_file,
+            file_size,
+            block_size,
+            false,
+            &mut Histogram::new(),
+        )
+    })
+    .map_err(|e| failed_pass(&test_dir, "cached read", e))?;
+    remove_test_files(&test_dir);
+
+    let write = KernelTiming::new(mebibytes, write_passes, write_time);
+    let read = KernelTiming::new(mebibytes, read_passes, read_time);
+    let combined = KernelTiming::new(
+        mebibytes,
+        write_passes + read_passes,
+        write_time + read_time,
+    );
+    Ok(CachedResult {
+        write_throughput: write.rate(),
+        read_throughput: read.rate(),
+        combined_throughput: combined.rate(),
+    })
+}
+
+/// Check that the disk benchmark can run in `dir` at `scale`: the directory must be writable
+/// and its filesystem must have room for `required_space(scale, precondition)` bytes
+/// (unchecked where the free space cannot be queried)
+pub fn preflight(
+    dir: &Path,
+    scale: f64,
+    precondition: Option<Precondition>,
+) -> Result<(), BenchError> {
+    if !dir.is_dir() {
+        return Err(BenchError::io(
+            dir.display().to_string(),
+            io::Error::new(io::ErrorKind::NotFound, "not a directory"),
+        ));
+    }
+
+    let probe = dir.join(format!("{}{}", PROBE_FILE_PREFIX, std::process::id()));
+    let written = fs::File::create(&probe).and_then(|mut file| file.write_all(&[0u8; ALIGNMENT]));
+    let _ = fs::remove_file(&probe);
+    written.map_err(|e| BenchError::io(format!("cannot write to {}", dir.display()), e))?;
+
+    let required = required_space(scale, precondition);
+    match available_space(dir) {
+        Some(available) if available < required => Err(BenchError::io(
+            dir.display().to_string(),
+            io::Error::new(
+                io::ErrorKind::StorageFull,
+                format!(
+                    "{} MB free, the disk benchmark needs {} MB at scale {}",
+                    available / (1024 * 1024),
+                    required.div_ceil(1024 * 1024),
+                    scale
+                ),
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Bytes the disk benchmark needs free: its largest working set at `scale` plus 10% headroom
+/// Only one of the test, sweep, profile, and small-file sets exists at a time; the
+/// `precondition` dataset exists alongside the test file
+pub fn required_space(scale: f64, precondition: Option<Precondition>) -> u64 {
+    let megabytes = |bytes: usize| ((bytes as f64 * scale) as usize / (1024 * 1024)).max(1);
+    let dataset_multiple = precondition.map_or(0.0, |precondition| precondition.multiple.max(1.0));
+    let largest = [
+        (BASE_FILE_SIZE as f64 * scale * (1.0 + dataset_multiple)) as usize,
+        megabytes(SWEEP_FILE_SIZE) * 1024 * 1024,
+        megabytes(PROFILE_FILE_SIZE) * 1024 * 1024,
+        ((BASE_SMALL_FILES as f64 * scale) as usize).clamp(1, BASE_SMALL_FILES)
+            * SMALL_FILE_MAX_SIZE,
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(0);
+    largest as u64 + largest as u64 / 10
+}
+
+/// Free bytes available to unprivileged users on the filesystem holding `dir`
+fn available_space(dir: &Path) -> Option<u64> {
+    filesystem_space(dir).map(|(_, available)| available)
+}
+
+/// Size of the filesystem holding `dir` and the bytes available to unprivileged users
+#[cfg(unix)]
+fn filesystem_space(dir: &Path) -> Option<(u64, u64)> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)] // The field types differ between platforms
+    Some((
+        stat.f_blocks as u64 * stat.f_frsize as u64,
+        stat.f_bavail as u64 * stat.f_frsize as u64,
+    ))
+}
+
+#[cfg(not(unix))]
+fn filesystem_space(_dir: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Numbers the temporary directories of the disk benchmark runs of this process
+static TEST_DIR_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory of one disk benchmark run in `dir`, named after the process ID so
+/// that concurrent instances (and concurrent runs within one process) never share files
+fn unique_test_dir(dir: &Path) -> PathBuf {
+    let sequence = TEST_DIR_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    dir.join(format!(
+        "{}{}_{}",
+        TEST_DIR_PREFIX,
+        std::process::id(),
+        sequence
+    ))
+}
+
+/// Process that created a disk benchmark temporary directory, from its name
+fn test_dir_pid(name: &str) -> Option<u32> {
+    name.strip_prefix(TEST_DIR_PREFIX)?
+        .split('_')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn process_exists(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).is_some()
+}
+
+/// Temporary directories of other disk benchmark runs found in a benchmark directory
+#[derive(Debug, Default)]
+pub struct TempDirScan {
+    /// Other running processes with a temporary directory there; their disk I/O competes
+    /// with this run's
+    pub active_pids: Vec<u32>,
+    /// Directories left behind by processes that no longer exist, which were removed
+    pub removed: Vec<PathBuf>,
+}
+
+/// Look for the temporary directories of other disk benchmark runs in `dir`
+/// Directories of processes that no longer exist are left over from crashed runs and are
+/// removed; the others belong to concurrently running instances. Directories of this
+/// process are ignored.
+pub fn scan_temp_dirs(dir: &Path) -> TempDirScan {
+    let mut scan = TempDirScan::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return scan;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str().and_then(test_dir_pid) else {
+            continue;
+        };
+        if pid == std::process::id() {
+            continue;
+        }
+        if process_exists(pid) {
+            if !scan.active_pids.contains(&pid) {
+                scan.active_pids.push(pid);
             }
-        } // File handle dropped here
+        } else if fs::remove_dir_all(entry.path()).is_ok() {
+            scan.removed.push(entry.path());
+        }
     }
-    let read_time = read_start.elapsed().as_secs_f64();
-    let read_throughput = (file_size as f64 / (1024.0 * 1024.0)) / read_time;
+    scan.active_pids.sort_unstable();
+    scan
+}
 
-    // Cleanup
-    let _ = fs::remove_file(TEST_FILE);
-    let _ = fs::remove_dir(TEST_DIR);
+/// Remove the files a failed pass may have left in `test_dir` and name the pass in `error`
+fn failed_pass(test_dir: &Path, pass: &str, error: io::Error) -> BenchError {
+    remove_test_files(test_dir);
+    BenchError::io(format!("disk {}", pass), error)
+}
 
-    // Calculate combined throughput
-    let total_time = write_time + read_time;
-    let combined_throughput = (file_size as f64 / (1024.0 * 1024.0) * 2.0) / total_time;
+/// Stop before the next step once the run is interrupted, leaving no test files behind
+fn check_interrupted(test_dir: &Path) -> Result<(), BenchError> {
+    if interrupt::interrupted() {
+        remove_test_files(test_dir);
+        return Err(BenchError::Interrupted);
+    }
+    Ok(())
+}
 
-    DiskResult {
-        write_throughput,
-        read_throughput,
-        combined_throughput,
+/// Remove the files the sequential passes leave between steps, and `test_dir` itself
+/// (the other steps clean up after themselves)
+fn remove_test_files(test_dir: &Path) {
+    let _ = fs::remove_file(test_dir.join(TEST_FILE_NAME));
+    let _ = fs::remove_file(test_dir.join(WARMUP_FILE_NAME));
+    let _ = fs::remove_file(test_dir.join(PRECONDITION_FILE_NAME));
+    let _ = fs::remove_dir(test_dir);
+}
+
+/// Benchmark filesystem metadata: create, stat, rename, and delete many empty files in `dir`,
+/// one operation type at a time, repeating the cycle until `min_time` seconds have passed
+/// Returns the rates and their raw timings, keyed `metadata.<field>`
+fn benchmark_metadata(dir: &Path, scale: f64, min_time: f64) -> (MetadataResult, KernelTimings) {
+    let files = ((BASE_METADATA_FILES as f64 * scale) as usize).max(100);
+    let names: Vec<PathBuf> = (0..files)
+        .map(|i| dir.join(format!("file_{:06}", i)))
+        .collect();
+    let renamed: Vec<PathBuf> = (0..files)
+        .map(|i| dir.join(format!("renamed_{:06}", i)))
+        .collect();
+
+    // Successful operations and elapsed seconds of each operation type
+    let mut totals = [(0u64, 0.0f64); 4];
+    let mut timed = |phase: usize, op: &dyn Fn(usize) -> std::io::Result<()>| {
+        let start = std::time::Instant::now();
+        let completed = (0..files).filter(|&i| op(i).is_ok()).count();
+        totals[phase].0 += completed as u64;
+        totals[phase].1 += start.elapsed().as_secs_f64();
+    };
+
+    let total_start = std::time::Instant::now();
+    if fs::create_dir_all(dir).is_ok() {
+        loop {
+            timed(0, &|i| fs::File::create(&names[i]).map(|_| ()));
+            timed(1, &|i| fs::metadata(&names[i]).map(|_| ()));
+            timed(2, &|i| fs::rename(&names[i], &renamed[i]));
+            timed(3, &|i| fs::remove_file(&renamed[i]));
+            if total_start.elapsed().as_secs_f64() >= min_time {
+                break;
+            }
+        }
+        let _ = fs::remove_dir(dir);
     }
+
+    let [create, stat, rename, delete] =
+        totals.map(|(ops, elapsed)| KernelTiming::new(1.0, ops, elapsed));
+    let result = MetadataResult {
+        create_ops_per_sec: create.rate(),
+        stat_ops_per_sec: stat.rate(),
+        rename_ops_per_sec: rename.rate(),
+        delete_ops_per_sec: delete.rate(),
+    };
+    let timings = KernelTimings::from([
+        ("metadata.create_ops_per_sec".to_string(), create),
+        ("metadata.stat_ops_per_sec".to_string(), stat),
+        ("metadata.rename_ops_per_sec".to_string(), rename),
+        ("metadata.delete_ops_per_sec".to_string(), delete),
+    ]);
+    (result, timings)
 }
 
-fn warmup_disk_with_block_size(scale: f64, block_size: usize) {
-    const WARMUP_FILE: &str = ".bench_temp/warmup_file.bin";
-    let file_size = (BASE_FILE_SIZE as f64 * scale) as usize;
+/// Commits and latency percentiles of the fsync benchmark
+#[derive(Default)]
+struct FsyncResult {
+    commits: KernelTiming,
+    p50_us: f64,
+    p95_us: f64,
+    p99_us: f64,
+}
 
-    // Create temporary directory
-    let _ = fs::create_dir(TEST_DIR);
+/// Benchmark commit latency: append a small record and `sync_data` it, many times
+/// Uses regular buffered I/O, like a database write-ahead log; keeps committing past
+/// the scaled count until `min_time` seconds have passed
+fn benchmark_fsync(path: &Path, scale: f64, min_time: f64) -> FsyncResult {
+    let ops = ((BASE_FSYNC_OPS as f64 * scale) as usize).max(10);
+    let record = [0xCDu8; FSYNC_RECORD_SIZE];
+    let mut latencies_us = Vec::with_capacity(ops);
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(path);
+    let total_start = std::time::Instant::now();
+    if let Ok(mut file) = file {
+        while latencies_us.len() < ops || total_start.elapsed().as_secs_f64() < min_time {
+            let start = std::time::Instant::now();
+            if file
+                .write_all(&record)
+                .and_then(|_| file.sync_data())
+                .is_err()
+            {
+                break;
+            }
+            latencies_us.push(timing::measured_since(start).as_secs_f64() * 1_000_000.0);
+        }
+    }
+    let total_time = total_start.elapsed().as_secs_f64();
+    let _ = fs::remove_file(path);
+
+    match Statistics::from_values(&latencies_us) {
+        Some(latency) => FsyncResult {
+            commits: KernelTiming::new(1.0, latencies_us.len() as u64, total_time),
+            p50_us: latency.p50,
+            p95_us: latency.p95,
+            p99_us: latency.p99,
+        },
+        None => FsyncResult::default(),
+    }
+}
 
-    let (mut data_buf, data_offset) = alloc_aligned(block_size);
-    let data_slice = &mut data_buf[data_offset..data_offset + block_size];
-    data_slice.fill(0xAB);
+/// Sequential write pass in the given mode; see `timed_sequential_write` and `timed_mmap_write`
+fn timed_write(
+    mode: DiskMode,
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+    curve: &mut ThroughputCurve,
+) -> io::Result<f64> {
+    match mode {
+        DiskMode::Direct => {
+            timed_sequential_write(path, file_size, block_size, true, latency, curve)
+        }
+        DiskMode::Mmap => timed_mmap_write(path, file_size, block_size, latency, curve),
+    }
+}
 
-    // Warmup write with direct I/O
-    {
-        let mut options = std::fs::OpenOptions::new();
-        options.write(true).create(true).truncate(true);
+/// Sequential read pass in the given mode; see `timed_sequential_read` and `timed_mmap_read`
+fn timed_read(
+    mode: DiskMode,
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+) -> io::Result<f64> {
+    match mode {
+        DiskMode::Direct => timed_sequential_read(path, file_size, block_size, true, latency),
+        DiskMode::Mmap => timed_mmap_read(path, file_size, block_size, latency),
+    }
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT | libc::O_SYNC);
+/// Benchmark small-file writes in `dir`: create and write files of 4-64 KB, first with
+/// buffered writes and then syncing every file, repeating each pass until `min_time`
+/// seconds have passed; files are deleted after every pass, outside the timed region
+/// Returns the rates and their raw timings, keyed `small_files.<field>`
+fn benchmark_small_files(
+    dir: &Path,
+    scale: f64,
+    min_time: f64,
+) -> (SmallFilesResult, KernelTimings) {
+    let mut rng = SimpleRng::new(rng::workload_seed(SMALL_FILES_SEED));
+    let sizes: Vec<usize> = (0..BASE_SMALL_FILES)
+        .map(|_| {
+            SMALL_FILE_MIN_SIZE
+                + (rng.next_u64() as usize) % (SMALL_FILE_MAX_SIZE - SMALL_FILE_MIN_SIZE + 1)
+        })
+        .collect();
+    let data = vec![0x5Au8; SMALL_FILE_MAX_SIZE];
+
+    // Timings of the files and mebibytes written by complete passes of `count` files
+    let write_files = |count: usize, sync: bool| -> (KernelTiming, KernelTiming) {
+        let count = count.clamp(1, sizes.len());
+        let names: Vec<PathBuf> = (0..count)
+            .map(|i| dir.join(format!("small_{:06}.bin", i)))
+            .collect();
+        let mebibytes = sizes[..count].iter().sum::<usize>() as f64 / units::MEBIBYTE;
+        let mut passes = 0;
+        let mut elapsed = 0.0;
+        while fs::create_dir_all(dir).is_ok() {
+            let start = std::time::Instant::now();
+            let complete = names.iter().zip(&sizes).all(|(name, &size)| {
+                fs::File::create(name)
+                    .and_then(|mut file| {
+                        file.write_all(&data[..size])?;
+                        if sync {
+                            file.sync_all()?;
+                        }
+                        Ok(())
+                    })
+                    .is_ok()
+            });
+            elapsed += start.elapsed().as_secs_f64();
+            let _ = fs::remove_dir_all(dir);
+            if !complete {
+                break;
+            }
+            passes += 1;
+            if elapsed >= min_time {
+                break;
+            }
         }
+        (
+            KernelTiming::new(count as f64, passes, elapsed),
+            KernelTiming::new(mebibytes, passes, elapsed),
+        )
+    };
+    let (files, throughput) = write_files((BASE_SMALL_FILES as f64 * scale) as usize, false);
+    let (synced_files, synced_throughput) =
+        write_files((BASE_SYNCED_SMALL_FILES as f64 * scale) as usize, true);
+
+    let result = SmallFilesResult {
+        files_per_sec: files.rate(),
+        throughput: throughput.rate(),
+        synced_files_per_sec: synced_files.rate(),
+        synced_throughput: synced_throughput.rate(),
+    };
+    let timings = KernelTimings::from([
+        ("small_files.files_per_sec".to_string(), files),
+        ("small_files.throughput".to_string(), throughput),
+        ("small_files.synced_files_per_sec".to_string(), synced_files),
+        (
+            "small_files.synced_throughput".to_string(),
+            synced_throughput,
+        ),
+    ]);
+    (result, timings)
+}
 
-        #[cfg(target_os = "freebsd")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
+/// Run `profile` against a `file_size` file at `path`, filled beforehand so that reads hit
+/// written blocks; issues at least one operation per block (and `PROFILE_MIN_OPS`) and keeps
+/// going until `min_time` seconds have passed
+/// Returns the result and the raw timings behind its IOPS and throughput, or the first
+/// failed operation's error
+fn benchmark_profile(
+    path: &Path,
+    profile: DiskProfile,
+    file_size: usize,
+    min_time: f64,
+) -> io::Result<(DiskProfileResult, KernelTiming, KernelTiming)> {
+    let block_size = profile.block_size();
+    let blocks = (file_size / block_size).max(1);
+    let target_ops = blocks.max(PROFILE_MIN_OPS);
+
+    let (mut data_buf, data_offset) = alloc_aligned(block_size);
+    let data_slice = &mut data_buf[data_offset..data_offset + block_size];
+    data_slice.fill(0xEF);
+    let mut rng = SimpleRng::new(rng::workload_seed(PROFILE_SEED));
+    let mut read_latency = Histogram::new();
+    let mut write_latency = Histogram::new();
+    let mut ops = 0u64;
+
+    let fill = timed_sequential_write(
+        path,
+        blocks * block_size,
+        DEFAULT_BLOCK_SIZE.min(block_size * blocks),
+        true,
+        &mut Histogram::new(),
+        &mut ThroughputCurve::default(),
+    );
+
+    let start = std::time::Instant::now();
+    let run = || -> io::Result<()> {
+        fill?;
+        let (file, _) = open_direct(fs::OpenOptions::new().read(true).write(true), path, true)?;
+
+        let mut next_block = 0;
+        while (ops as usize) < target_ops || start.elapsed().as_secs_f64() < min_time {
+            let block = if profile.random() {
+                (rng.next_u64() % blocks as u64) as usize
+            } else {
+                let block = next_block;
+                next_block = (next_block + 1) % blocks;
+                block
+            };
+            let offset = (block * block_size) as u64;
+            let is_read = rng.next_f64() * 100.0 < profile.read_percent() as f64;
+            let op_start = std::time::Instant::now();
+            if is_read {
+                read_block_at(&file, data_slice, offset)?;
+            } else {
+                write_block_at(&file, data_slice, offset)?;
+            }
+            let latency = if is_read {
+                &mut read_latency
+            } else {
+                &mut write_latency
+            };
+            latency.record(timing::measured_since(op_start));
+            ops += 1;
         }
+        Ok(())
+    };
+    let completed = run();
+    let elapsed = start.elapsed().as_secs_f64();
+    let _ = fs::remove_file(path);
+    completed?;
+
+    let iops = KernelTiming::new(1.0, ops, elapsed);
+    let throughput = KernelTiming::new(block_size as f64 / units::MEBIBYTE, ops, elapsed);
+    let result = DiskProfileResult {
+        profile,
+        throughput: throughput.rate(),
+        iops: iops.rate(),
+        read_latency: read_latency.summary(),
+        write_latency: write_latency.summary(),
+    };
+    Ok((result, iops, throughput))
+}
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::fs::OpenOptionsExt;
-            options.custom_flags(0x20000000 | 0x80000000);
+/// Block size of the stress test's disk passes
+pub(crate) const STRESS_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// One pass of the stress test's disk load: write `file_size` bytes of the pattern of
+/// `pass` to `path`, sync them, and read them back, with direct I/O where the filesystem
+/// supports it so the reads come from the disk rather than the page cache
+/// Returns: the number of blocks that read back differently
+pub(crate) fn stress_disk_pass(path: &Path, file_size: usize, pass: u64) -> io::Result<usize> {
+    // Every pass overwrites the whole file at the same size, so it is never truncated
+    let (file, _) = open_direct(
+        fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false),
+        path,
+        true,
+    )?;
+    let words = STRESS_BLOCK_SIZE / 8;
+    let (mut buffer, offset) = alloc_aligned(STRESS_BLOCK_SIZE);
+    let block = &mut buffer[offset..offset + STRESS_BLOCK_SIZE];
+    let fill = |block: &mut [u8], index: usize| {
+        for (word, bytes) in block.chunks_exact_mut(8).enumerate() {
+            let value = stress::pattern_word(pass, (index * words + word) as u64);
+            bytes.copy_from_slice(&value.to_le_bytes());
         }
+    };
 
-        #[cfg(target_os = "macos")]
-        {
-            // No special flags on macOS
+    let blocks = file_size / STRESS_BLOCK_SIZE;
+    for index in 0..blocks {
+        fill(block, index);
+        write_block_at(&file, block, (index * STRESS_BLOCK_SIZE) as u64)?;
+    }
+    file.sync_all()?;
+    #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+    drop_os_cache(file.as_raw_fd());
+
+    let mut expected = vec![0u8; STRESS_BLOCK_SIZE];
+    let mut mismatches = 0;
+    for index in 0..blocks {
+        read_block_at(&file, block, (index * STRESS_BLOCK_SIZE) as u64)?;
+        fill(&mut expected, index);
+        if *block != *expected {
+            mismatches += 1;
         }
+    }
+    Ok(mismatches)
+}
 
-        if let Ok(mut file) = options.open(WARMUP_FILE) {
-            #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
-            drop_os_cache(file.as_raw_fd());
+#[cfg(unix)]
+fn read_block_at(file: &fs::File, buffer: &mut [u8], offset: u64) -> std::io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.read_exact_at(buffer, offset)
+}
 
-            #[cfg(windows)]
-            drop_os_cache(file.as_raw_handle());
+#[cfg(unix)]
+fn write_block_at(file: &fs::File, data: &[u8], offset: u64) -> std::io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.write_all_at(data, offset)
+}
+
+/// `seek_read` may transfer less than asked, so loop like `read_exact_at`
+#[cfg(windows)]
+fn read_block_at(file: &fs::File, mut buffer: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buffer.is_empty() {
+        match file.seek_read(buffer, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => {
+                buffer = &mut buffer[read..];
+                offset += read as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
 
-            // Write in sequential blocks
-            let mut bytes_written = 0;
-            while bytes_written < file_size {
-                let remaining = file_size - bytes_written;
-                let write_size = remaining.min(block_size);
-                let _ = file.write_all(&data_slice[..write_size]);
-                bytes_written += write_size;
+/// `seek_write` may transfer less than asked, so loop like `write_all_at`
+#[cfg(windows)]
+fn write_block_at(file: &fs::File, mut data: &[u8], mut offset: u64) -> std::io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !data.is_empty() {
+        match file.seek_write(data, offset) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => {
+                data = &data[written..];
+                offset += written as u64;
             }
-            let _ = file.sync_all();
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Write the `dataset_size` byte preconditioning dataset to `path` in `block_size` chunks
+/// with direct I/O where the filesystem honors it, filling an SSD's write cache, and add
+/// its throughput over time to `curve`
+/// Returns: elapsed seconds of the first `burst_size` bytes, which the cache still absorbed
+fn write_dataset(
+    path: &Path,
+    dataset_size: usize,
+    burst_size: usize,
+    block_size: usize,
+    curve: &mut ThroughputCurve,
+) -> io::Result<f64> {
+    let (mut data_buf, data_offset) = alloc_aligned(block_size);
+    let data_slice = &mut data_buf[data_offset..data_offset + block_size];
+    data_slice.fill(0xCD);
+
+    let (mut file, _) = open_direct(
+        fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true),
+        path,
+        true,
+    )?;
+    let start = std::time::Instant::now();
+    let mut burst_time = None;
+    let mut bytes_written = 0;
+    while bytes_written < dataset_size {
+        let write_size = (dataset_size - bytes_written).min(block_size);
+        file.write_all(&data_slice[..write_size])?;
+        bytes_written += write_size;
+        let elapsed = start.elapsed().as_secs_f64();
+        curve.record(elapsed, write_size);
+        if burst_time.is_none() && bytes_written >= burst_size {
+            burst_time = Some(elapsed);
         }
     }
+    file.sync_all()?;
+    let elapsed = start.elapsed().as_secs_f64();
+    curve.finish_pass(elapsed);
+    Ok(burst_time.unwrap_or(elapsed))
+}
 
-    // Warmup read with direct I/O
-    let (mut _buffer, buffer_offset) = alloc_aligned(block_size);
-    let buffer_slice = &mut _buffer[buffer_offset..buffer_offset + block_size];
+/// Write `file_size` bytes to `path` in `block_size` chunks with direct I/O where the filesystem
+/// honors it (see `open_direct`),
+/// recording the completion time of every block in `latency` and the throughput over time
+/// in `curve`
+/// Without `direct`, the blocks go through the OS cache and are not synced, so the pass
+/// only fills the cache
+/// Returns: elapsed seconds, including the final sync
+fn timed_sequential_write(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    direct: bool,
+    latency: &mut Histogram,
+    curve: &mut ThroughputCurve,
+) -> io::Result<f64> {
+    let (mut data_buf, data_offset) = alloc_aligned(block_size);
+    let data_slice = &mut data_buf[data_offset..data_offset + block_size];
+    data_slice.fill(0xAB);
+
+    let write_start = std::time::Instant::now();
     {
-        let mut options = std::fs::OpenOptions::new();
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        let mut file = if direct {
+            open_direct(&mut options, path, true)?.0
+        } else {
+            options.open(path)?
+        };
+
+        // Write in sequential blocks
+        let mut bytes_written = 0;
+        while bytes_written < file_size {
+            let remaining = file_size - bytes_written;
+            let write_size = remaining.min(block_size);
+            let block_start = std::time::Instant::now();
+            file.write_all(&data_slice[..write_size])?;
+            latency.record(timing::measured_since(block_start));
+            curve.record(write_start.elapsed().as_secs_f64(), write_size);
+            bytes_written += write_size;
+        }
+        if direct {
+            file.sync_all()?;
+        }
+    } // File handle dropped here, ensuring flush
+    let elapsed = write_start.elapsed().as_secs_f64();
+    curve.finish_pass(elapsed);
+    Ok(elapsed)
+}
+
+/// Read `file_size` bytes from `path` in `block_size` chunks with direct I/O where the filesystem
+/// honors it (see `open_direct`),
+/// recording the completion time of every block in `latency`
+/// Without `direct`, the blocks are read through the OS cache
+/// Returns: elapsed seconds
+fn timed_sequential_read(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    direct: bool,
+    latency: &mut Histogram,
+) -> io::Result<f64> {
+    let read_start = std::time::Instant::now();
+    let (mut buffer, buffer_offset) = alloc_aligned(block_size);
+    let buffer_slice = &mut buffer[buffer_offset..buffer_offset + block_size];
+    {
+        let mut options = fs::OpenOptions::new();
         options.read(true);
+        let mut file = if direct {
+            open_direct(&mut options, path, false)?.0
+        } else {
+            options.open(path)?
+        };
+
+        // Read in sequential blocks
+        let mut bytes_read = 0;
+        while bytes_read < file_size {
+            let remaining = file_size - bytes_read;
+            let read_size = remaining.min(block_size);
+            let block_start = std::time::Instant::now();
+            file.read_exact(&mut buffer_slice[..read_size])?;
+            latency.record(timing::measured_since(block_start));
+            bytes_read += read_size;
+        }
+    } // File handle dropped here
+    Ok(read_start.elapsed().as_secs_f64())
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
+/// Shared memory mapping of the first `len` bytes of a file, unmapped on drop
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+struct MappedFile {
+    ptr: *mut u8,
+    len: usize,
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+impl MappedFile {
+    /// Map `len` (non-zero) bytes of `file`, read-write if `writable`
+    fn new(file: &fs::File, len: usize, writable: bool) -> io::Result<Self> {
+        let prot = if writable {
+            libc::PROT_READ | libc::PROT_WRITE
+        } else {
+            libc::PROT_READ
+        };
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                prot,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self {
+                ptr: ptr.cast(),
+                len,
+            })
         }
+    }
 
-        #[cfg(target_os = "freebsd")]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.custom_flags(libc::O_DIRECT);
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Only valid on a mapping created with `writable`
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Write dirty pages back to the file and wait for completion
+    fn sync(&self) -> io::Result<()> {
+        if unsafe { libc::msync(self.ptr.cast(), self.len, libc::MS_SYNC) } != 0 {
+            return Err(io::Error::last_os_error());
         }
+        Ok(())
+    }
+}
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::fs::OpenOptionsExt;
-            options.custom_flags(0x20000000);
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = libc::munmap(self.ptr.cast(), self.len);
         }
+    }
+}
 
-        #[cfg(target_os = "macos")]
-        {
-            // No special flags on macOS
-        }
-
-        if let Ok(mut file) = options.open(WARMUP_FILE) {
-            #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
-            drop_os_cache(file.as_raw_fd());
-
-            #[cfg(windows)]
-            drop_os_cache(file.as_raw_handle());
-
-            // Read in sequential blocks
-            let mut bytes_read = 0;
-            while bytes_read < file_size {
-                let remaining = file_size - bytes_read;
-                let read_size = remaining.min(block_size);
-                match file.read_exact(&mut buffer_slice[..read_size]) {
-                    Ok(()) => {
-                        bytes_read += read_size;
-                    }
-                    Err(_) => break,
-                }
-            }
+/// Write `file_size` bytes to `path` through a shared memory mapping, copying `block_size`
+/// bytes at a time and recording the time to fill every block in `latency` and the
+/// throughput over time in `curve`
+/// Each block's first touch page-faults, so the latencies are dominated by fault handling
+/// Returns: elapsed seconds, including the final msync
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+fn timed_mmap_write(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+    curve: &mut ThroughputCurve,
+) -> io::Result<f64> {
+    let data = vec![0xABu8; block_size];
+
+    let write_start = std::time::Instant::now();
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.set_len(file_size as u64)?;
+    if file_size > 0 {
+        let mut map = MappedFile::new(&file, file_size, true)?;
+        for block in map.as_mut_slice().chunks_mut(block_size) {
+            let block_start = std::time::Instant::now();
+            block.copy_from_slice(&data[..block.len()]);
+            latency.record(timing::measured_since(block_start));
+            curve.record(write_start.elapsed().as_secs_f64(), block.len());
+        }
+        map.sync()?;
+    }
+    let elapsed = write_start.elapsed().as_secs_f64();
+    curve.finish_pass(elapsed);
+    Ok(elapsed)
+}
+
+/// Read `file_size` bytes from `path` through a shared memory mapping after dropping it
+/// from the OS cache, copying `block_size` bytes at a time and recording the time to copy
+/// every block in `latency`
+/// Returns: elapsed seconds
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+fn timed_mmap_read(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+) -> io::Result<f64> {
+    let mut buffer = vec![0u8; block_size];
+
+    let read_start = std::time::Instant::now();
+    let file = fs::File::open(path)?;
+    drop_os_cache(file.as_raw_fd());
+
+    // Touching pages past the end of the file would raise SIGBUS instead of an error
+    if file.metadata()?.len() < file_size as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{} is shorter than {} bytes", path.display(), file_size),
+        ));
+    }
+    if file_size > 0 {
+        let map = MappedFile::new(&file, file_size, false)?;
+        for block in map.as_slice().chunks(block_size) {
+            let block_start = std::time::Instant::now();
+            buffer[..block.len()].copy_from_slice(block);
+            latency.record(timing::measured_since(block_start));
         }
+        std::hint::black_box(&buffer);
     }
+    Ok(read_start.elapsed().as_secs_f64())
+}
+
+/// Memory mapping is not implemented on this platform; runs the direct I/O write instead
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "macos")))]
+fn timed_mmap_write(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+    curve: &mut ThroughputCurve,
+) -> io::Result<f64> {
+    timed_sequential_write(path, file_size, block_size, true, latency, curve)
+}
+
+/// Memory mapping is not implemented on this platform; runs the direct I/O read instead
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "macos")))]
+fn timed_mmap_read(
+    path: &Path,
+    file_size: usize,
+    block_size: usize,
+    latency: &mut Histogram,
+) -> io::Result<f64> {
+    timed_sequential_read(path, file_size, block_size, true, latency)
+}
+
+fn warmup_disk_with_block_size(
+    scale: f64,
+    block_size: usize,
+    alignment: usize,
+    test_dir: &Path,
+    mode: DiskMode,
+) -> io::Result<()> {
+    let file_size = test_file_size(scale, alignment);
+
+    // Create temporary directory
+    let warmup_file = test_dir.join(WARMUP_FILE_NAME);
+    let _ = fs::create_dir(test_dir);
+
+    let mut latency = Histogram::new();
+    let passes = timed_write(
+        mode,
+        &warmup_file,
+        file_size,
+        block_size,
+        &mut latency,
+        &mut ThroughputCurve::default(),
+    )
+    .and_then(|_| timed_read(mode, &warmup_file, file_size, block_size, &mut latency));
 
     // Cleanup warmup file
-    let _ = fs::remove_file(WARMUP_FILE);
+    let _ = fs::remove_file(&warmup_file);
+    passes.map(|_| ())
+}
+
+/// Block sizes of `SWEEP_BLOCK_SIZES` that are whole `alignment` units, since direct I/O
+/// rejects transfers of partial sectors
+fn sweep_block_sizes(alignment: usize) -> impl Iterator<Item = usize> {
+    SWEEP_BLOCK_SIZES
+        .into_iter()
+        .filter(move |size| size % alignment == 0)
+}
+
+/// Size of the sequential test file at `scale`, rounded down to whole `alignment` units
+/// (see `direct_io_alignment`) so that the last, partial block stays aligned
+fn test_file_size(scale: f64, alignment: usize) -> usize {
+    ((BASE_FILE_SIZE as f64 * scale) as usize / alignment).max(1) * alignment
 }
 
 #[cfg(test)]
@@ -320,7 +2035,7 @@ mod tests {
     #[test]
     fn test_disk_benchmark() {
         // Use lightweight scale for CI/testing - 5 MB instead of 50 MB
-        let result = run_disk_benchmark_scaled(0.1);
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
         assert!(
             result.combined_throughput > 0.0,
             "Disk benchmark should return positive throughput"
@@ -328,9 +2043,130 @@ mod tests {
     }
 
     #[test]
-    fn test_disk_file_creation_and_cleanup() {
-        use std::path::Path;
+    fn test_preconditioned_run_reports_burst_and_steady_writes() {
+        let precondition = Precondition {
+            multiple: 2.0,
+            passes: 3,
+        };
+        let result = run_disk_benchmark_with_progress(
+            0.05,
+            DEFAULT_BLOCK_SIZE,
+            Path::new("."),
+            DiskMode::Direct,
+            Some(precondition),
+            &[],
+            0.0,
+            0,
+            &mut (),
+        )
+        .expect("Preconditioned disk benchmark failed");
+        let steady = result
+            .precondition
+            .expect("Preconditioning should be reported");
+        assert_eq!(steady.multiple, 2.0);
+        assert!(steady.burst_throughput > 0.0);
+        // Every write pass after the dataset is reported, the last as the steady state
+        assert_eq!(steady.pass_throughput.len(), 3);
+        assert_eq!(
+            steady.pass_throughput.last(),
+            Some(&steady.steady_throughput)
+        );
+        assert_eq!(result.timings["write_throughput"].rounds, 3);
+        // The curve covers the dataset and every write pass
+        assert!(result.write_curve.len() >= 4);
+        assert!(result.timings.contains_key("precondition.burst_throughput"));
+        assert_eq!(
+            precondition.dataset_size(test_file_size(0.05, ALIGNMENT), ALIGNMENT),
+            2 * test_file_size(0.05, ALIGNMENT)
+        );
+    }
 
+    #[test]
+    fn test_cached_passes_leave_no_files() {
+        let dir = Path::new(".bench_test_cached");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let cached = run_cached_disk_benchmark(0.05, DEFAULT_BLOCK_SIZE, dir, 0.0);
+        let leftovers = fs::read_dir(dir)
+            .map(|entries| entries.count())
+            .unwrap_or(0);
+        fs::remove_dir(dir).expect("Failed to remove benchmark directory");
+
+        let cached = cached.expect("Cached disk passes failed");
+        assert!(cached.write_throughput > 0.0 && cached.read_throughput > 0.0);
+        assert!(cached.combined_throughput > 0.0);
+        assert_eq!(
+            leftovers, 0,
+            "cached passes should remove their test directory"
+        );
+
+        // Speedups are cached over uncached throughput
+        let uncached: DiskResult = serde_json::from_str(&format!(
+            "{{\"write_throughput\": 1.0, \"read_throughput\": {}, \
+             \"combined_throughput\": {}}}",
+            cached.read_throughput / 4.0,
+            cached.combined_throughput / 2.0
+        ))
+        .unwrap();
+        assert!(uncached.cached.is_none());
+        assert!((cached.read_speedup(&uncached) - 4.0).abs() < 1e-9);
+        assert!((cached.speedup(&uncached) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_effective_io_mode_matches_the_open_file() {
+        let path = Path::new(".bench_test_io_mode.tmp");
+        fs::write(path, vec![0u8; ALIGNMENT]).expect("Failed to create test file");
+        // Mappings always go through the cache
+        assert_eq!(effective_io_mode(path, DiskMode::Mmap), IoMode::Buffered);
+        let (_, direct) = open_direct(fs::OpenOptions::new().read(true), path, false)
+            .expect("Open with or without direct I/O should succeed");
+        assert_eq!(
+            effective_io_mode(path, DiskMode::Direct) == IoMode::Direct,
+            direct
+        );
+        fs::remove_file(path).expect("Failed to remove test file");
+    }
+
+    #[test]
+    fn test_cache_bypass_is_only_reported_where_it_is_verified() {
+        if cfg!(target_os = "macos") {
+            assert_eq!(cache_bypassed(IoMode::Direct, false), Some(true));
+            assert_eq!(cache_bypassed(IoMode::Buffered, true), Some(true));
+            assert_eq!(cache_bypassed(IoMode::Buffered, false), Some(false));
+        } else {
+            assert_eq!(cache_bypassed(IoMode::Direct, false), None);
+        }
+    }
+
+    #[test]
+    fn test_disk_benchmark_records_the_effective_io_mode() {
+        let path = Path::new(".bench_test_run_io_mode.tmp");
+        fs::write(path, vec![0u8; ALIGNMENT]).expect("Failed to create test file");
+        let expected = effective_io_mode(path, DiskMode::Direct);
+        fs::remove_file(path).expect("Failed to remove test file");
+        let run = |mode| {
+            run_disk_benchmark_with_progress(
+                0.05,
+                DEFAULT_BLOCK_SIZE,
+                Path::new("."),
+                mode,
+                None,
+                &[],
+                0.0,
+                0,
+                &mut (),
+            )
+            .expect("Disk benchmark failed")
+        };
+        // Direct I/O where the filesystem accepts it, the buffered fallback elsewhere
+        assert_eq!(run(DiskMode::Direct).io_mode, Some(expected));
+        if MMAP_SUPPORTED {
+            assert_eq!(run(DiskMode::Mmap).io_mode, Some(IoMode::Buffered));
+        }
+    }
+
+    #[test]
+    fn test_disk_file_creation_and_cleanup() {
         let test_file = ".bench_test_cleanup.tmp";
 
         // Create test file
@@ -388,7 +2224,7 @@ mod tests {
     #[test]
     fn test_disk_benchmark_reasonable_throughput() {
         // Use lightweight scale for CI/testing
-        let result = run_disk_benchmark_scaled(0.1);
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
         // Throughput should be reasonable - at least 1 MB/s on most systems
         assert!(
             result.combined_throughput > 1.0,
@@ -400,25 +2236,52 @@ mod tests {
     #[test]
     fn test_disk_benchmark_scaled() {
         // Use lightweight scale for CI/testing
-        let result = run_disk_benchmark_scaled(0.1);
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
         assert!(result.write_throughput > 0.0);
         assert!(result.read_throughput > 0.0);
         assert!(result.combined_throughput > 0.0);
     }
 
+    #[test]
+    fn test_direct_io_sizes_are_sector_aligned() {
+        for alignment in [ALIGNMENT, 8192, MAX_SECTOR_SIZE] {
+            assert_eq!(test_file_size(0.1, alignment) % alignment, 0);
+            let (buffer, offset) = alloc_aligned(3 * alignment);
+            assert_eq!((buffer.as_ptr() as usize + offset) % alignment, 0);
+        }
+        // Block sizes that are no multiple of a sector are still aligned for O_DIRECT
+        let (buffer, offset) = alloc_aligned(1000);
+        assert_eq!((buffer.as_ptr() as usize + offset) % ALIGNMENT, 0);
+        assert_eq!(direct_io_alignment(Path::new(".")) % ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn test_sweep_skips_block_sizes_below_the_alignment() {
+        assert_eq!(
+            sweep_block_sizes(ALIGNMENT).collect::<Vec<_>>(),
+            SWEEP_BLOCK_SIZES.to_vec()
+        );
+        assert_eq!(
+            sweep_block_sizes(MAX_SECTOR_SIZE).collect::<Vec<_>>(),
+            vec![64 * 1024, 1024 * 1024]
+        );
+    }
+
     #[test]
     fn test_disk_warmup_no_panic() {
         // Ensure warmup doesn't panic and cleans up properly
-        warmup_disk_with_block_size(0.1, DEFAULT_BLOCK_SIZE);
+        let dir = Path::new(".bench_test_warmup");
+        warmup_disk_with_block_size(0.1, DEFAULT_BLOCK_SIZE, ALIGNMENT, dir, DiskMode::Direct)
+            .expect("Warmup failed");
         // Verify warmup file was cleaned up
-        use std::path::Path;
-        assert!(!Path::new(".bench_temp/warmup_file.bin").exists());
+        assert!(!dir.join(WARMUP_FILE_NAME).exists());
+        fs::remove_dir(dir).expect("Warmup directory should be empty after cleanup");
     }
 
     #[test]
     fn test_disk_combined_calculation() {
         // Use lightweight scale for CI/testing
-        let result = run_disk_benchmark_scaled(0.1);
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
         // Combined throughput should be reasonable
         assert!(result.combined_throughput > 0.0);
         // Combined should not exceed sum of read and write
@@ -428,7 +2291,7 @@ mod tests {
     #[test]
     fn test_disk_benchmark_default() {
         // Use lightweight scale for CI/testing
-        let result = run_disk_benchmark_scaled(0.1);
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
         assert!(result.write_throughput > 0.0);
         assert!(result.read_throughput > 0.0);
         assert!(result.combined_throughput > 0.0);
@@ -436,12 +2299,321 @@ mod tests {
 
     #[test]
     fn test_disk_cleanup_on_completion() {
-        use std::path::Path;
         // Use lightweight scale for CI/testing
-        run_disk_benchmark_scaled(0.1);
-        // Give filesystem time to complete cleanup
-        std::thread::sleep(std::time::Duration::from_millis(100));
+        let dir = Path::new(".bench_test_cleanup");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        run_disk_benchmark_scaled_with_block_size(0.1, DEFAULT_BLOCK_SIZE, dir)
+            .expect("Disk benchmark failed");
         // Verify test file and directory are cleaned up
-        assert!(!Path::new(TEST_FILE).exists());
+        let leftover: Vec<_> = fs::read_dir(dir)
+            .expect("Failed to list benchmark directory")
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        fs::remove_dir_all(dir).expect("Failed to remove benchmark directory");
+        assert!(leftover.is_empty(), "{:?}", leftover);
+    }
+
+    #[test]
+    fn test_disk_block_size_sweep() {
+        // Use lightweight scale for CI/testing
+        let result = run_disk_benchmark_scaled(0.1).expect("Disk benchmark failed");
+        let swept: Vec<usize> = result
+            .block_size_sweep
+            .iter()
+            .map(|s| s.block_size)
+            .collect();
+        assert_eq!(
+            swept,
+            sweep_block_sizes(direct_io_alignment(Path::new("."))).collect::<Vec<_>>()
+        );
+        for sweep in &result.block_size_sweep {
+            assert!(sweep.write_throughput > 0.0 && sweep.read_throughput > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_disk_profiles() {
+        let dir = Path::new(".bench_test_profiles");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let path = dir.join(PROFILE_FILE_NAME);
+
+        // 1 MB working file: 128 OLTP blocks, but only one 1 MB block for the others
+        let results: Vec<(DiskProfileResult, KernelTiming)> = DiskProfile::ALL
+            .iter()
+            .map(|&profile| {
+                let (result, iops, _) =
+                    benchmark_profile(&path, profile, 1024 * 1024, 0.0).expect("profile failed");
+                (result, iops)
+            })
+            .collect();
+        let leftover = path.exists();
+        fs::remove_dir(dir).expect("Failed to remove benchmark directory");
+
+        let ops: Vec<u64> = results.iter().map(|(_, iops)| iops.rounds).collect();
+        assert_eq!(
+            ops,
+            vec![128, PROFILE_MIN_OPS as u64, PROFILE_MIN_OPS as u64]
+        );
+        for (result, iops) in &results {
+            assert_eq!(
+                result.read_latency.samples + result.write_latency.samples,
+                iops.rounds
+            );
+            assert!(result.iops > 0.0 && result.throughput > 0.0);
+        }
+        let oltp = &results[0].0;
+        assert!(oltp.read_latency.samples > oltp.write_latency.samples);
+        assert!(oltp.write_latency.samples > 0);
+        assert_eq!(results[1].0.write_latency.samples, 0);
+        assert_eq!(results[2].0.read_latency.samples, 0);
+        assert!(!leftover, "profile test file should be removed");
+
+        assert_eq!(DiskProfile::from_name("OLTP"), Some(DiskProfile::Oltp));
+        assert_eq!(DiskProfile::from_name("random"), None);
+    }
+
+    #[test]
+    fn test_metadata_benchmark() {
+        let dir = Path::new(".bench_test_metadata");
+
+        // Use lightweight scale for CI/testing; at least 100 files per operation
+        let (result, timings) = benchmark_metadata(dir, 0.01, 0.0);
+
+        assert!(!dir.exists(), "metadata test directory should be removed");
+        assert!(result.create_ops_per_sec > 0.0);
+        assert!(result.stat_ops_per_sec > 0.0);
+        assert!(result.rename_ops_per_sec > 0.0);
+        assert!(result.delete_ops_per_sec > 0.0);
+        assert_eq!(timings.len(), 4);
+        assert!(timings.values().all(|timing| timing.rounds == 100));
+    }
+
+    #[test]
+    fn test_small_files_benchmark() {
+        let dir = Path::new(".bench_test_small_files");
+
+        // Use lightweight scale for CI/testing: 20 buffered and 4 synced files per pass
+        let (result, timings) = benchmark_small_files(dir, 0.02, 0.0);
+
+        assert!(!dir.exists(), "small-file test directory should be removed");
+        assert!(result.files_per_sec > 0.0 && result.synced_files_per_sec > 0.0);
+        assert!(result.throughput > 0.0 && result.synced_throughput > 0.0);
+        let files = timings["small_files.files_per_sec"];
+        let throughput = timings["small_files.throughput"];
+        assert_eq!((files.work, files.rounds), (20.0, 1));
+        // Every file is between 4 and 64 KB
+        let average_kb = throughput.work * 1024.0 / files.work;
+        assert!((4.0..=64.0).contains(&average_kb), "{}", average_kb);
+        assert_eq!(timings["small_files.synced_files_per_sec"].work, 4.0);
+    }
+
+    #[test]
+    fn test_temp_dir_scan() {
+        let dir = Path::new(".bench_test_scan");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let test_dir = unique_test_dir(dir);
+        assert_eq!(
+            test_dir_pid(&test_dir.file_name().unwrap().to_string_lossy()),
+            Some(std::process::id())
+        );
+        assert_eq!(test_dir_pid(".bench_temp"), None);
+
+        // No process has the largest PID, so its directory is left over from a crash
+        let stale = dir.join(format!("{}{}_0", TEST_DIR_PREFIX, u32::MAX));
+        fs::create_dir_all(stale.join(METADATA_DIR_NAME)).unwrap();
+        fs::create_dir(&test_dir).unwrap();
+        #[cfg(unix)]
+        let parent = std::os::unix::process::parent_id();
+        #[cfg(unix)]
+        fs::create_dir(dir.join(format!("{}{}_0", TEST_DIR_PREFIX, parent))).unwrap();
+
+        let scan = scan_temp_dirs(dir);
+        let own_dir_kept = test_dir.exists();
+        fs::remove_dir_all(dir).expect("Failed to remove benchmark directory");
+
+        assert_eq!(scan.removed, vec![stale]);
+        assert!(own_dir_kept);
+        #[cfg(unix)]
+        assert_eq!(scan.active_pids, vec![parent]);
+    }
+
+    #[test]
+    fn test_preflight() {
+        assert!(preflight(Path::new("."), 0.1, None).is_ok());
+        let probe = format!("{}{}", PROBE_FILE_PREFIX, std::process::id());
+        assert!(!Path::new(".").join(probe).exists());
+
+        let kind = |error: BenchError| match error {
+            BenchError::Io { source, .. } => source.kind(),
+            other => panic!("unexpected error: {}", other),
+        };
+        let missing = preflight(Path::new(".bench_test_missing_dir"), 0.1, None).unwrap_err();
+        assert_eq!(kind(missing), io::ErrorKind::NotFound);
+
+        // No filesystem here has room for an exabyte of test files
+        if available_space(Path::new(".")).is_some() {
+            let full = preflight(Path::new("."), 1e11, None).unwrap_err();
+            assert_eq!(kind(full), io::ErrorKind::StorageFull);
+        }
+        assert!(required_space(1.0, None) > BASE_FILE_SIZE as u64);
+        assert!(required_space(0.1, None) < required_space(1.0, None));
+        let precondition = Precondition {
+            multiple: 4.0,
+            passes: 1,
+        };
+        assert!(required_space(1.0, Some(precondition)) > 5 * BASE_FILE_SIZE as u64);
+    }
+
+    #[test]
+    fn test_failed_io_is_reported() {
+        let path = Path::new(".bench_test_missing_file.bin");
+        let mut latency = Histogram::new();
+        assert!(timed_sequential_read(path, 1024 * 1024, 64 * 1024, true, &mut latency).is_err());
+        assert!(timed_read(DiskMode::Mmap, path, 1024 * 1024, 64 * 1024, &mut latency).is_err());
+        assert_eq!(latency.samples(), 0);
+    }
+
+    #[test]
+    fn test_fsync_benchmark() {
+        let dir = Path::new(".bench_test_fsync");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let path = dir.join(FSYNC_FILE_NAME);
+
+        // Use lightweight scale for CI/testing
+        let result = benchmark_fsync(&path, 0.05, 0.0);
+        let leftover = path.exists();
+        fs::remove_dir(dir).expect("Failed to remove benchmark directory");
+
+        assert!(result.commits.rate() > 0.0);
+        assert!(result.commits.rounds >= 10);
+        assert!(result.p50_us > 0.0);
+        assert!(result.p50_us <= result.p95_us && result.p95_us <= result.p99_us);
+        assert!(!leftover, "fsync test file should be removed");
+    }
+
+    #[test]
+    fn test_timed_io_uses_requested_block_size() {
+        let dir = Path::new(".bench_test_timed_io");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let path = dir.join(TEST_FILE_NAME);
+
+        // 1 MB in 64 KB chunks should produce a complete 1 MB file
+        let mut write_latency = Histogram::new();
+        let mut curve = ThroughputCurve::default();
+        let write_time = timed_sequential_write(
+            &path,
+            1024 * 1024,
+            64 * 1024,
+            true,
+            &mut write_latency,
+            &mut curve,
+        )
+        .expect("Timed I/O failed");
+        let written = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let mut read_latency = Histogram::new();
+        let read_time =
+            timed_sequential_read(&path, 1024 * 1024, 64 * 1024, true, &mut read_latency)
+                .expect("Timed I/O failed");
+        let _ = fs::remove_file(&path);
+        fs::remove_dir(dir).expect("Failed to remove benchmark directory");
+
+        assert_eq!(written, 1024 * 1024);
+        assert!(write_time > 0.0 && read_time > 0.0);
+        // One completion per block
+        assert_eq!(write_latency.samples(), 16);
+        assert_eq!(read_latency.samples(), 16);
+        // The curve ends with the pass, its final sync included
+        let last = curve.samples.last().expect("Every pass adds a sample");
+        assert_eq!(last.elapsed_secs, write_time);
+    }
+
+    #[test]
+    fn test_throughput_curve_samples_every_interval_across_passes() {
+        let mebibyte = 1024 * 1024;
+        let mut curve = ThroughputCurve::default();
+        curve.record(0.5, mebibyte);
+        curve.record(1.0, mebibyte);
+        curve.record(1.5, mebibyte);
+        curve.finish_pass(2.0);
+        // The second pass continues where the first ended; its sync adds no bytes
+        curve.record(1.0, 2 * mebibyte);
+        curve.finish_pass(1.0);
+        let samples: Vec<(f64, f64)> = curve
+            .samples
+            .iter()
+            .map(|sample| (sample.elapsed_secs, sample.throughput))
+            .collect();
+        assert_eq!(samples, vec![(1.0, 2.0), (2.0, 1.0), (3.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_timed_mmap_io() {
+        let dir = Path::new(".bench_test_mmap_io");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+        let path = dir.join(TEST_FILE_NAME);
+
+        // 1 MB through the mapping in 64 KB chunks, the last block short
+        let size = 1024 * 1024 - 4096;
+        let mut write_latency = Histogram::new();
+        let write_time = timed_mmap_write(
+            &path,
+            size,
+            64 * 1024,
+            &mut write_latency,
+            &mut ThroughputCurve::default(),
+        )
+        .expect("Timed I/O failed");
+        let contents = fs::read(&path).unwrap_or_default();
+        let mut read_latency = Histogram::new();
+        let read_time =
+            timed_mmap_read(&path, size, 64 * 1024, &mut read_latency).expect("Timed I/O failed");
+        let _ = fs::remove_file(&path);
+        fs::remove_dir(dir).expect("Failed to remove benchmark directory");
+
+        assert_eq!(contents.len(), size);
+        assert!(contents.iter().all(|&byte| byte == 0xAB));
+        assert!(write_time > 0.0 && read_time > 0.0);
+        assert_eq!(write_latency.samples(), 16);
+        assert_eq!(read_latency.samples(), 16);
+    }
+
+    #[test]
+    fn test_disk_mode_names() {
+        assert_eq!(DiskMode::default(), DiskMode::Direct);
+        assert_eq!(DiskMode::from_name("MMAP"), Some(DiskMode::Mmap));
+        assert_eq!(DiskMode::from_name("direct"), Some(DiskMode::Direct));
+        assert_eq!(DiskMode::from_name("buffered"), None);
+        assert_eq!(serde_json::to_string(&DiskMode::Mmap).unwrap(), "\"mmap\"");
+    }
+
+    #[test]
+    fn test_disk_benchmark_custom_path() {
+        let dir = Path::new(".bench_test_custom_path");
+        fs::create_dir_all(dir).expect("Failed to create benchmark directory");
+
+        // Use lightweight scale for CI/testing
+        let result = run_disk_benchmark_scaled_with_block_size(0.1, DEFAULT_BLOCK_SIZE, dir)
+            .expect("Disk benchmark failed");
+        // Removing fails unless the temporary directory is gone as well
+        fs::remove_dir(dir).expect("Benchmark directory should be empty after cleanup");
+
+        assert!(result.combined_throughput > 0.0);
+    }
+
+    #[test]
+    fn test_disk_target_describe() {
+        let target = DiskTarget::describe(Path::new("."));
+        assert!(Path::new(&target.path).is_absolute());
+        assert!(target.free_bytes <= target.total_bytes);
+        assert!(target.summary().starts_with(&target.path));
+        #[cfg(target_os = "linux")]
+        {
+            assert_ne!(target.filesystem, "Unknown");
+            assert!(Path::new(&target.path).starts_with(&target.mount_point));
+            assert!(target.total_bytes > 0);
+            // Every mount table entry lists its options
+            assert!(target.discard.is_some());
+        }
     }
 }
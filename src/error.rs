@@ -0,0 +1,96 @@
+/// Benchmark error type
+/// Every benchmark entry point returns `Result<_, BenchError>` so that a failed subsystem is
+/// reported instead of contributing rates for work that never happened
+use std::fmt;
+use std::io;
+
+/// Why a benchmark could not produce a result
+#[derive(Debug)]
+pub enum BenchError {
+    /// An I/O operation failed; `context` names the step that issued it
+    Io { context: String, source: io::Error },
+    /// The benchmark cannot run on this platform or build, e.g. without a GPU
+    Unsupported(String),
+    /// The benchmark ran but produced no usable measurement, e.g. a kernel that did no work
+    InvalidResult(String),
+    /// The run was interrupted (Ctrl-C) before the benchmark finished
+    Interrupted,
+    /// The named step ran past the step timeout and did not return, so the benchmark
+    /// was abandoned
+    TimedOut(String),
+}
+
+impl BenchError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        BenchError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Whether the benchmark was skipped rather than failed
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, BenchError::Unsupported(_))
+    }
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BenchError::Io { context, source } if context.is_empty() => write!(f, "{}", source),
+            BenchError::Io { context, source } => write!(f, "{}: {}", context, source),
+            BenchError::Unsupported(message) => write!(f, "{}", message),
+            BenchError::InvalidResult(message) => write!(f, "invalid result: {}", message),
+            BenchError::Interrupted => write!(f, "interrupted"),
+            BenchError::TimedOut(step) => {
+                write!(f, "{} timed out and was abandoned", step)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BenchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BenchError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BenchError {
+    fn from(source: io::Error) -> Self {
+        BenchError::io(String::new(), source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_bench_error_display() {
+        let error = BenchError::io(
+            "disk sequential write",
+            io::Error::new(io::ErrorKind::StorageFull, "no space left on device"),
+        );
+        assert_eq!(
+            error.to_string(),
+            "disk sequential write: no space left on device"
+        );
+        assert!(error.source().is_some());
+
+        let error: BenchError = io::Error::other("connection refused").into();
+        assert_eq!(error.to_string(), "connection refused");
+
+        let error = BenchError::Unsupported("built without the `gpu` feature".to_string());
+        assert!(error.is_unsupported());
+        assert!(error.source().is_none());
+        assert!(!BenchError::InvalidResult("no rounds".to_string()).is_unsupported());
+        assert_eq!(
+            BenchError::TimedOut("sequential write".to_string()).to_string(),
+            "sequential write timed out and was abandoned"
+        );
+    }
+}
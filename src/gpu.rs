@@ -0,0 +1,422 @@
+/// GPU Compute Benchmark Module
+/// Runs a tiled f32 matrix multiplication and a buffer-copy bandwidth test as compute
+/// shaders through wgpu (Vulkan, Metal, DX12, or OpenGL, whichever the adapter supports)
+///
+/// The wgpu backend is only compiled with the optional `gpu` cargo feature. Without it,
+/// or when no adapter is available, the benchmark returns an error and is skipped.
+use crate::error::BenchError;
+use crate::progress::Progress;
+use crate::timing::KernelTimings;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gpu")]
+use std::io;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuResult {
+    pub adapter_name: String,
+    /// Graphics API used to talk to the adapter, e.g. "Vulkan" or "Metal"
+    pub backend: String,
+    pub matmul_gflops: f64,
+    /// Device memory bandwidth of a buffer copy shader, counting both read and write (10^9 bytes/sec)
+    pub bandwidth_gbs: f64,
+    /// Raw work and elapsed time behind the rates above, keyed by field name
+    #[serde(default)]
+    pub timings: KernelTimings,
+}
+
+pub fn run_gpu_benchmark() -> Result<GpuResult, BenchError> {
+    run_gpu_benchmark_scaled(1.0)
+}
+
+/// Run the GPU benchmark on the highest-performance adapter
+/// Returns `BenchError::Unsupported` when built without the `gpu` feature or when no
+/// usable adapter exists
+pub fn run_gpu_benchmark_scaled(scale: f64) -> Result<GpuResult, BenchError> {
+    run_gpu_benchmark_with_progress(scale, &mut ())
+}
+
+/// Run the GPU benchmark, announcing each step to `progress`
+pub fn run_gpu_benchmark_with_progress(
+    scale: f64,
+    progress: &mut dyn Progress,
+) -> Result<GpuResult, BenchError> {
+    #[cfg(feature = "gpu")]
+    {
+        let result = backend::run(scale, progress).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::Unsupported => {
+                BenchError::Unsupported(e.to_string())
+            }
+            _ => BenchError::io("gpu", e),
+        })?;
+        crate::timing::check_timings("gpu", &result.timings)?;
+        Ok(result)
+    }
+    #[cfg(not(feature = "gpu"))]
+    {
+        let _ = (scale, progress);
+        Err(BenchError::Unsupported(
+            "built without the `gpu` feature".to_string(),
+        ))
+    }
+}
+
+/// Name of the GPU adapter the benchmark would use, if any
+pub fn adapter_name() -> Option<String> {
+    #[cfg(feature = "gpu")]
+    {
+        backend::request_adapter().ok().map(|a| a.get_info().name)
+    }
+    #[cfg(not(feature = "gpu"))]
+    {
+        None
+    }
+}
+
+#[cfg(feature = "gpu")]
+mod backend {
+    use super::*;
+    use crate::timing::KernelTiming;
+    use std::time::Instant;
+
+    const BASE_MATRIX_SIZE: usize = 1024; // Multiple of the 16x16 shader tile
+    pub(super) const MATRIX_TILE: usize = 16;
+    const MATMUL_ITERATIONS: usize = 10;
+    const BASE_COPY_BUFFER_SIZE: usize = 64 * 1024 * 1024; // 64 MB source and destination
+    const COPY_ITERATIONS: usize = 20;
+
+    /// Matrix dimension for a scale factor, rounded down to a whole number of tiles
+    pub(super) fn matrix_size(scale: f64) -> usize {
+        let n = (BASE_MATRIX_SIZE as f64 * scale.sqrt()) as usize;
+        (n / MATRIX_TILE).max(1) * MATRIX_TILE
+    }
+
+    /// Copy buffer size for a scale factor: a whole number of 16-byte vectors, at least 1 MB
+    pub(super) fn copy_buffer_size(scale: f64) -> usize {
+        let size = ((BASE_COPY_BUFFER_SIZE as f64 * scale) as usize).max(1024 * 1024);
+        size / 16 * 16
+    }
+
+    const MATMUL_SHADER: &str = r#"
+const N: u32 = {N}u;
+const TILE: u32 = 16u;
+
+@group(0) @binding(0) var<storage, read> a: array<f32>;
+@group(0) @binding(1) var<storage, read> b: array<f32>;
+@group(0) @binding(2) var<storage, read_write> c: array<f32>;
+
+var<workgroup> tile_a: array<array<f32, 16>, 16>;
+var<workgroup> tile_b: array<array<f32, 16>, 16>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>,
+        @builtin(local_invocation_id) lid: vec3<u32>) {
+    let row = gid.y;
+    let col = gid.x;
+    var sum = 0.0;
+    for (var t = 0u; t < N; t += TILE) {
+        tile_a[lid.y][lid.x] = a[row * N + t + lid.x];
+        tile_b[lid.y][lid.x] = b[(t + lid.y) * N + col];
+        workgroupBarrier();
+        for (var k = 0u; k < TILE; k++) {
+            sum += tile_a[lid.y][k] * tile_b[k][lid.x];
+        }
+        workgroupBarrier();
+    }
+    c[row * N + col] = sum;
+}
+"#;
+
+    const COPY_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> src: array<vec4<f32>>;
+@group(0) @binding(1) var<storage, read_write> dst: array<vec4<f32>>;
+
+@compute @workgroup_size(256)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>,
+        @builtin(num_workgroups) groups: vec3<u32>) {
+    let index = gid.y * groups.x * 256u + gid.x;
+    if (index < arrayLength(&src)) {
+        dst[index] = src[index];
+    }
+}
+"#;
+
+    /// Workgroups per dimension allowed by every wgpu backend
+    const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+
+    fn gpu_error(message: impl std::fmt::Display) -> io::Error {
+        io::Error::other(message.to_string())
+    }
+
+    /// Highest-performance hardware adapter; software renderers such as llvmpipe are
+    /// rejected since they would only measure the CPU again
+    pub(super) fn request_adapter() -> io::Result<wgpu::Adapter> {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))?;
+
+        let info = adapter.get_info();
+        if info.device_type == wgpu::DeviceType::Cpu {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no hardware GPU (only the software adapter {})", info.name),
+            ));
+        }
+        Ok(adapter)
+    }
+
+    pub(super) fn run(scale: f64, progress: &mut dyn Progress) -> io::Result<GpuResult> {
+        progress.step("device setup");
+        let adapter = request_adapter()?;
+        let info = adapter.get_info();
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("hsbench"),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        }))
+        .map_err(gpu_error)?;
+
+        progress.step("matrix multiplication");
+        let matmul = benchmark_matmul(&device, &queue, matrix_size(scale))?;
+        let max_binding = device.limits().max_storage_buffer_binding_size as usize;
+        let copy_size = copy_buffer_size(scale).min(max_binding / 16 * 16);
+        progress.step("bandwidth");
+        let copy = benchmark_copy(&device, &queue, copy_size)?;
+
+        Ok(GpuResult {
+            adapter_name: info.name,
+            backend: format!("{:?}", info.backend),
+            matmul_gflops: matmul.rate(),
+            bandwidth_gbs: copy.rate(),
+            timings: KernelTimings::from([
+                ("matmul_gflops".to_string(), matmul),
+                ("bandwidth_gbs".to_string(), copy),
+            ]),
+        })
+    }
+
+    fn f32_bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    fn storage_buffer(device: &wgpu::Device, queue: &wgpu::Queue, data: &[u8]) -> wgpu::Buffer {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: data.len() as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&buffer, 0, data);
+        buffer
+    }
+
+    fn compute_pipeline(
+        device: &wgpu::Device,
+        source: &str,
+        buffers: &[&wgpu::Buffer],
+    ) -> (wgpu::ComputePipeline, wgpu::BindGroup) {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let entries: Vec<wgpu::BindGroupEntry> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+        (pipeline, bind_group)
+    }
+
+    /// Submit `iterations` dispatches and wait for the GPU to finish them
+    fn timed_dispatches(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        pipeline: &wgpu::ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        workgroups: (u32, u32),
+        iterations: usize,
+    ) -> io::Result<f64> {
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            for _ in 0..iterations {
+                pass.dispatch_workgroups(workgroups.0, workgroups.1, 1);
+            }
+        }
+        let start = Instant::now();
+        queue.submit([encoder.finish()]);
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(gpu_error)?;
+        Ok(start.elapsed().as_secs_f64())
+    }
+
+    /// Returns: GFLOP, per second
+    fn benchmark_matmul(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        n: usize,
+    ) -> io::Result<KernelTiming> {
+        let a = storage_buffer(device, queue, &f32_bytes(&vec![1.0; n * n]));
+        let b = storage_buffer(device, queue, &f32_bytes(&vec![2.0; n * n]));
+        let c = storage_buffer(device, queue, &vec![0u8; n * n * 4]);
+        let source = MATMUL_SHADER.replace("{N}", &n.to_string());
+        let (pipeline, bind_group) = compute_pipeline(device, &source, &[&a, &b, &c]);
+        let groups = (n / MATRIX_TILE) as u32;
+
+        // Warmup compiles the pipeline and wakes the GPU from its idle clocks
+        timed_dispatches(device, queue, &pipeline, &bind_group, (groups, groups), 1)?;
+        let elapsed = timed_dispatches(
+            device,
+            queue,
+            &pipeline,
+            &bind_group,
+            (groups, groups),
+            MATMUL_ITERATIONS,
+        )?;
+
+        // Every element of (1.0) x (2.0) is 2n
+        let first = read_f32(device, queue, &c)?;
+        if first != 2.0 * n as f32 {
+            return Err(gpu_error(format!(
+                "matrix multiplication produced {} instead of {}",
+                first,
+                2 * n
+            )));
+        }
+
+        let flops = 2.0 * (n as f64).powi(3);
+        Ok(KernelTiming::new(
+            flops / 1e9,
+            MATMUL_ITERATIONS as u64,
+            elapsed,
+        ))
+    }
+
+    /// Returns: GB read and written, per second
+    fn benchmark_copy(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: usize,
+    ) -> io::Result<KernelTiming> {
+        let src = storage_buffer(device, queue, &f32_bytes(&vec![1.5; size / 4]));
+        let dst = storage_buffer(device, queue, &vec![0u8; size]);
+        let (pipeline, bind_group) = compute_pipeline(device, COPY_SHADER, &[&src, &dst]);
+
+        // Spread the workgroups over two dimensions to stay within the per-dimension limit
+        let total_groups = (size / 16).div_ceil(256) as u32;
+        let x = total_groups.min(MAX_WORKGROUPS_PER_DIMENSION);
+        let workgroups = (x, total_groups.div_ceil(x));
+
+        timed_dispatches(device, queue, &pipeline, &bind_group, workgroups, 1)?;
+        let elapsed = timed_dispatches(
+            device,
+            queue,
+            &pipeline,
+            &bind_group,
+            workgroups,
+            COPY_ITERATIONS,
+        )?;
+
+        if read_f32(device, queue, &dst)? != 1.5 {
+            return Err(gpu_error("buffer copy produced wrong data"));
+        }
+
+        // Each copy reads and writes the whole buffer
+        let bytes = 2.0 * size as f64;
+        Ok(KernelTiming::new(
+            bytes / 1e9,
+            COPY_ITERATIONS as u64,
+            elapsed,
+        ))
+    }
+
+    /// Read back the first f32 of a storage buffer
+    fn read_f32(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        buffer: &wgpu::Buffer,
+    ) -> io::Result<f32> {
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, 4);
+        queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(gpu_error)?;
+        receiver.recv().map_err(gpu_error)?.map_err(gpu_error)?;
+
+        let data = slice.get_mapped_range().map_err(gpu_error)?;
+        Ok(f32::from_ne_bytes([data[0], data[1], data[2], data[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "gpu")]
+    use backend::{copy_buffer_size, matrix_size, MATRIX_TILE};
+
+    #[test]
+    #[cfg(feature = "gpu")]
+    fn test_matrix_size_is_whole_tiles() {
+        assert_eq!(matrix_size(1.0), 1024);
+        assert_eq!(matrix_size(0.1) % MATRIX_TILE, 0);
+        assert_eq!(matrix_size(0.0001), MATRIX_TILE);
+    }
+
+    #[test]
+    #[cfg(feature = "gpu")]
+    fn test_copy_buffer_size() {
+        assert_eq!(copy_buffer_size(1.0), 64 * 1024 * 1024);
+        assert_eq!(copy_buffer_size(0.001), 1024 * 1024);
+        assert_eq!(copy_buffer_size(0.3) % 16, 0);
+    }
+
+    #[test]
+    fn test_gpu_benchmark_skips_or_succeeds() {
+        // Use lightweight scale for CI/testing; most CI machines have no GPU
+        match run_gpu_benchmark_scaled(0.1) {
+            Ok(result) => {
+                assert!(!result.adapter_name.is_empty());
+                assert!(result.matmul_gflops > 0.0);
+                assert!(result.bandwidth_gbs > 0.0);
+            }
+            Err(e) => assert!(!e.to_string().is_empty()),
+        }
+    }
+}
@@ -0,0 +1,421 @@
+/// Result history
+/// Appends a summary of every recorded suite run to a local append-only JSONL file (one JSON
+/// object per line), keyed by hostname and a hash of the benchmark configuration, and
+/// reports per-metric trends and regressions over the entries recorded for each key: sudden
+/// changes in the latest entry, gradual drifts, and steps in the level of a metric
+use crate::compare::{self, Verdict};
+use crate::report::{self, SuiteReport};
+use crate::stats::{self, ChangePoint, LinearFit, Statistics};
+use crate::suite::SuiteConfig;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// History file used when no path is given
+pub const DEFAULT_HISTORY_FILE: &str = "benchmark_history.jsonl";
+
+/// Number of standard deviations of the earlier entries the latest value must move by
+/// to be flagged
+const TREND_SIGMAS: f64 = 2.0;
+
+/// Changes smaller than this are treated as noise when the earlier entries vary too little
+/// (or are too few) for a standard deviation to be meaningful
+const TREND_THRESHOLD_PERCENT: f64 = 5.0;
+
+/// Earlier entries needed before their spread is used as the noise estimate
+const MIN_TREND_ENTRIES: usize = 3;
+
+/// Entries needed before a line fitted through them is trusted to show a drift
+pub const MIN_DRIFT_ENTRIES: usize = 5;
+
+/// Share of the variance the fitted line must explain for a drift to be flagged; below it
+/// the values scatter more than they move
+const DRIFT_R_SQUARED: f64 = 0.6;
+
+/// Standard deviations a step in the level must span to be reported as a change point
+/// A straight ramp split in the middle spans sqrt(12) (about 3.5), so drifts stay below it
+const CHANGE_POINT_SCORE: f64 = 4.0;
+
+/// One recorded suite run: the mean of every reported metric over its runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub hostname: String,
+    /// Hash of `configuration`; only entries with equal hashes are comparable
+    pub config_hash: String,
+    pub configuration: SuiteConfig,
+    /// Mean value of every metric with at least one run, keyed by metric name
+    pub metrics: IndexMap<String, f64>,
+}
+
+impl HistoryEntry {
+    pub fn from_report(report: &SuiteReport) -> Self {
+        let metrics = report
+            .results
+            .values()
+            .flatten()
+            .filter_map(|(key, series)| {
+                let statistics = series.statistics.as_ref()?;
+                Some((key.clone(), statistics.mean))
+            })
+            .collect();
+        HistoryEntry {
+            timestamp: report.metadata.timestamp.clone(),
+            hostname: report.metadata.hostname.clone(),
+            config_hash: config_hash(&report.configuration),
+            configuration: report.configuration.clone(),
+            metrics,
+        }
+    }
+}
+
+/// Stable hash of a configuration: 64-bit FNV-1a of its JSON form, as 16 hex digits
+/// (std's `DefaultHasher` may change between Rust releases, which would split the history)
+pub fn config_hash(config: &SuiteConfig) -> String {
+    let json = serde_json::to_string(config).unwrap_or_default();
+    let hash = json.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Append `entry` as one line to the history file at `path`, creating it if needed
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Load every entry of a history file, in the order they were recorded, with the metric
+/// names of earlier versions replaced by the current ones
+pub fn load_history(path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut entry: HistoryEntry = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} line {}: {}", path.display(), index + 1, e),
+            )
+        })?;
+        entry.metrics = report::current_metric_names(entry.metrics);
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Entries grouped by hostname and configuration hash, in order of first appearance
+pub fn group_entries(entries: &[HistoryEntry]) -> IndexMap<(String, String), Vec<&HistoryEntry>> {
+    let mut groups: IndexMap<(String, String), Vec<&HistoryEntry>> = IndexMap::new();
+    for entry in entries {
+        groups
+            .entry((entry.hostname.clone(), entry.config_hash.clone()))
+            .or_default()
+            .push(entry);
+    }
+    groups
+}
+
+/// Values of one metric over a group of entries, and how the latest compares to the rest
+#[derive(Debug, Clone)]
+pub struct MetricTrend {
+    pub metric: String,
+    pub values: Vec<f64>,
+    /// Mean of the values before the latest one
+    pub previous_mean: f64,
+    pub latest: f64,
+    pub percent_change: f64,
+    pub lower_is_better: bool,
+    /// Sudden change of the latest value from the earlier ones
+    pub verdict: Verdict,
+    /// Line fitted through all values; None for a single value
+    pub fit: Option<LinearFit>,
+    /// Change of the fitted line from the first to the latest entry, in percent of its start
+    pub drift_percent: f64,
+    /// Gradual change over the entries, e.g. a disk slowing down as it fills; Unchanged for
+    /// fewer than `MIN_DRIFT_ENTRIES` entries and for metrics with a change point
+    pub drift: Verdict,
+    /// Step in the level of the metric, e.g. after a driver or firmware update
+    pub change_point: Option<ChangePoint>,
+}
+
+/// Trend of every metric recorded in the latest entry of `entries`, except the run
+/// conditions (see `compare::is_condition`), which do not trend better or worse
+pub fn metric_trends(entries: &[&HistoryEntry]) -> Vec<MetricTrend> {
+    let Some(latest_entry) = entries.last() else {
+        return Vec::new();
+    };
+    latest_entry
+        .metrics
+        .keys()
+        .filter(|metric| !compare::is_condition(metric))
+        .map(|metric| {
+            let values: Vec<f64> = entries
+                .iter()
+                .filter_map(|entry| entry.metrics.get(metric).copied())
+                .collect();
+            metric_trend(metric, values)
+        })
+        .collect()
+}
+
+fn metric_trend(metric: &str, values: Vec<f64>) -> MetricTrend {
+    let (latest, previous) = values
+        .split_last()
+        .expect("the latest entry has the metric");
+    let latest = *latest;
+    let statistics = Statistics::from_values(previous);
+    let previous_mean = statistics.as_ref().map_or(latest, |s| s.mean);
+    let percent_change = if previous_mean.abs() > f64::EPSILON {
+        (latest - previous_mean) / previous_mean * 100.0
+    } else {
+        0.0
+    };
+
+    let lower_is_better = compare::lower_is_better(metric);
+
+    let significant = match statistics {
+        Some(s) if previous.len() >= MIN_TREND_ENTRIES && s.std_dev > f64::EPSILON => {
+            (latest - s.mean).abs() > TREND_SIGMAS * s.std_dev
+                && percent_change.abs() >= TREND_THRESHOLD_PERCENT
+        }
+        Some(_) => percent_change.abs() >= TREND_THRESHOLD_PERCENT,
+        None => false,
+    };
+    let verdict = direction(significant, latest > previous_mean, lower_is_better);
+
+    let fit = stats::linear_fit(&values);
+    let drift_percent = fit.map_or(0.0, |fit| {
+        let start = fit.at(0.0);
+        if start.abs() > f64::EPSILON {
+            (fit.at((values.len() - 1) as f64) - start) / start.abs() * 100.0
+        } else {
+            0.0
+        }
+    });
+    let change_point = stats::change_point(&values).filter(|point| {
+        let step_percent = if point.mean_before.abs() > f64::EPSILON {
+            (point.mean_after - point.mean_before) / point.mean_before.abs() * 100.0
+        } else {
+            0.0
+        };
+        point.score >= CHANGE_POINT_SCORE && step_percent.abs() >= TREND_THRESHOLD_PERCENT
+    });
+
+    // A step in the level also fits a sloped line; it is reported as the change point only
+    let drifting = change_point.is_none()
+        && fit.is_some_and(|fit| {
+            values.len() >= MIN_DRIFT_ENTRIES
+                && fit.r_squared >= DRIFT_R_SQUARED
+                && drift_percent.abs() >= TREND_THRESHOLD_PERCENT
+        });
+    let drift = direction(drifting, drift_percent > 0.0, lower_is_better);
+
+    MetricTrend {
+        metric: metric.to_string(),
+        values,
+        previous_mean,
+        latest,
+        percent_change,
+        lower_is_better,
+        verdict,
+        fit,
+        drift_percent,
+        drift,
+        change_point,
+    }
+}
+
+/// Verdict of a change that is `significant` and goes up when `increased`
+fn direction(significant: bool, increased: bool, lower_is_better: bool) -> Verdict {
+    match significant {
+        false => Verdict::Unchanged,
+        true if increased != lower_is_better => Verdict::Improvement,
+        true => Verdict::Regression,
+    }
+}
+
+/// One character per value, from the lowest (▁) to the highest (█) value of the series
+pub fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&value| {
+            if max - min <= f64::EPSILON {
+                BARS[BARS.len() / 2]
+            } else {
+                BARS[(((value - min) / (max - min)) * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hostname: &str, threads: usize, primes: f64, latency: f64) -> HistoryEntry {
+        let configuration = SuiteConfig {
+            threads,
+            ..SuiteConfig::default()
+        };
+        HistoryEntry {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            hostname: hostname.to_string(),
+            config_hash: config_hash(&configuration),
+            configuration,
+            metrics: [
+                ("cpu_primes_per_sec".to_string(), primes),
+                ("network_latency_p99_us".to_string(), latency),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn test_config_hash_is_stable_and_config_dependent() {
+        let config = SuiteConfig::default();
+        assert_eq!(config_hash(&config), config_hash(&config.clone()));
+        assert_eq!(config_hash(&config).len(), 16);
+        let other = SuiteConfig {
+            threads: 16,
+            ..SuiteConfig::default()
+        };
+        assert_ne!(config_hash(&config), config_hash(&other));
+    }
+
+    #[test]
+    fn test_history_roundtrip_appends_lines() {
+        let path =
+            std::env::temp_dir().join(format!("hsbench_history_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        append_entry(&path, &entry("a", 4, 100.0, 10.0)).unwrap();
+        append_entry(&path, &entry("a", 4, 110.0, 9.0)).unwrap();
+
+        let entries = load_history(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].metrics["cpu_primes_per_sec"], 110.0);
+        assert_eq!(entries[0].config_hash, entries[1].config_hash);
+    }
+
+    #[test]
+    fn test_entries_are_grouped_by_host_and_config() {
+        let entries = vec![
+            entry("a", 4, 1.0, 1.0),
+            entry("b", 4, 1.0, 1.0),
+            entry("a", 8, 1.0, 1.0),
+            entry("a", 4, 1.0, 1.0),
+        ];
+        let groups = group_entries(&entries);
+        let sizes: Vec<usize> = groups.values().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn test_trends_flag_regressions_beyond_the_noise() {
+        let entries = vec![
+            entry("a", 4, 100.0, 10.0),
+            entry("a", 4, 102.0, 10.2),
+            entry("a", 4, 98.0, 9.8),
+            entry("a", 4, 80.0, 10.1),
+        ];
+        let group: Vec<&HistoryEntry> = entries.iter().collect();
+        let trends = metric_trends(&group);
+
+        let primes = &trends[0];
+        assert_eq!(primes.values, vec![100.0, 102.0, 98.0, 80.0]);
+        assert!((primes.previous_mean - 100.0).abs() < 1e-9);
+        assert_eq!(primes.verdict, Verdict::Regression);
+
+        // Within the spread of the earlier entries
+        let latency = &trends[1];
+        assert!(latency.lower_is_better);
+        assert_eq!(latency.verdict, Verdict::Unchanged);
+
+        // Lower latency is an improvement
+        let mut faster = entries.clone();
+        faster[3].metrics["network_latency_p99_us"] = 5.0;
+        let group: Vec<&HistoryEntry> = faster.iter().collect();
+        assert_eq!(metric_trends(&group)[1].verdict, Verdict::Improvement);
+
+        // A single entry has nothing to compare against
+        assert_eq!(metric_trends(&group[..1])[0].verdict, Verdict::Unchanged);
+
+        // Temperatures are conditions, not results
+        let mut hotter = entries.clone();
+        for (entry, temperature) in hotter.iter_mut().zip([60.0, 61.0, 59.0, 90.0]) {
+            entry
+                .metrics
+                .insert("cpu_max_temperature_c".to_string(), temperature);
+        }
+        let group: Vec<&HistoryEntry> = hotter.iter().collect();
+        assert_eq!(metric_trends(&group).len(), 2);
+    }
+
+    #[test]
+    fn test_trends_flag_gradual_drift_and_level_changes() {
+        // A disk filling up: throughput falls a little with every entry, never enough
+        // between two entries to be flagged as a sudden regression
+        let entries: Vec<HistoryEntry> = (0..12)
+            .map(|i| entry("a", 4, 1000.0 - 5.0 * i as f64 + [2.0, -2.0][i % 2], 10.0))
+            .collect();
+        let group: Vec<&HistoryEntry> = entries.iter().collect();
+        let primes = &metric_trends(&group)[0];
+        assert_eq!(primes.verdict, Verdict::Unchanged);
+        assert_eq!(primes.drift, Verdict::Regression);
+        assert!(primes.fit.unwrap().r_squared > 0.9);
+        assert!(
+            (primes.drift_percent - -5.5).abs() < 0.5,
+            "{}",
+            primes.drift_percent
+        );
+        assert!(primes.change_point.is_none());
+
+        // Rising latency is also a degradation; fewer entries are not enough for a drift
+        let latency = |values: &[f64]| {
+            let entries: Vec<HistoryEntry> = values
+                .iter()
+                .map(|&latency| entry("a", 4, 100.0, latency))
+                .collect();
+            let group: Vec<&HistoryEntry> = entries.iter().collect();
+            metric_trends(&group).remove(1)
+        };
+        assert_eq!(
+            latency(&[10.0, 11.0, 12.0, 13.0, 14.0]).drift,
+            Verdict::Regression
+        );
+        assert_eq!(latency(&[10.0, 11.0, 12.0, 13.0]).drift, Verdict::Unchanged);
+        // Scattered values do not drift
+        assert_eq!(
+            latency(&[10.0, 14.0, 9.0, 13.0, 10.0, 14.0]).drift,
+            Verdict::Unchanged
+        );
+
+        // A step in the level is reported where it happened
+        let step = latency(&[10.0, 10.1, 9.9, 10.0, 12.0, 12.1, 11.9, 12.0]);
+        let point = step.change_point.unwrap();
+        assert_eq!(point.index, 4);
+        assert_eq!(step.drift, Verdict::Unchanged);
+        assert!((point.mean_after - 12.0).abs() < 1e-9);
+        assert!(latency(&[10.0, 10.1, 9.9, 10.0, 10.1, 9.9])
+            .change_point
+            .is_none());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[1.0, 2.0, 3.0]), "▁▅█");
+        assert_eq!(sparkline(&[5.0, 5.0]), "▅▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
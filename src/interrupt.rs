@@ -0,0 +1,83 @@
+/// Graceful interruption of a benchmark run
+/// `install` replaces the default Ctrl-C (SIGINT) and SIGTERM action with a handler that
+/// only sets a flag. The suite checks the flag between phases and the disk benchmark
+/// between steps, so an interrupted run removes its temporary files and still returns the
+/// results measured so far. A second signal terminates the process immediately.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Longest time `sleep` waits before looking at the flag again
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Catch Ctrl-C and SIGTERM for the rest of the process; does nothing where unsupported
+pub fn install() {
+    #[cfg(any(unix, windows))]
+    // SAFETY: the handler only touches an atomic and calls async-signal-safe functions
+    unsafe {
+        let handler = handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+/// Whether the run was interrupted
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Interrupt the run as if Ctrl-C had been pressed, e.g. from another thread of an
+/// embedding application
+pub fn request() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Sleep for `duration`, returning early when the run is interrupted
+/// Returns: whether the full duration passed
+pub fn sleep(duration: Duration) -> bool {
+    sleep_unless(&INTERRUPTED, duration)
+}
+
+fn sleep_unless(flag: &AtomicBool, duration: Duration) -> bool {
+    let start = Instant::now();
+    loop {
+        if flag.load(Ordering::SeqCst) {
+            return false;
+        }
+        let remaining = duration.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return true;
+        }
+        std::thread::sleep(remaining.min(POLL_INTERVAL));
+    }
+}
+
+#[cfg(any(unix, windows))]
+extern "C" fn handle_signal(signal: libc::c_int) {
+    const MESSAGE: &[u8] =
+        b"\nInterrupted: finishing the current step (press Ctrl-C again to quit immediately)\n";
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    // SAFETY: write and signal are async-signal-safe; restoring the default action makes
+    // the next signal terminate the process
+    unsafe {
+        libc::write(2, MESSAGE.as_ptr().cast(), MESSAGE.len() as _);
+        libc::signal(signal, libc::SIG_DFL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep_stops_when_interrupted() {
+        let flag = AtomicBool::new(false);
+        assert!(sleep_unless(&flag, Duration::from_millis(10)));
+
+        flag.store(true, Ordering::SeqCst);
+        let start = Instant::now();
+        assert!(!sleep_unless(&flag, Duration::from_secs(60)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,67 @@
+//! HsBenchMarkSuite - Rust Performance Benchmark Suite (library)
+//!
+//! Exposes the CPU, memory, concurrency, disk, and network benchmarks together with the
+//! statistics and system information helpers so they can be embedded in other tools. The
+//! `benchmark` binary is a thin command-line wrapper over this crate.
+//!
+//! ```no_run
+//! use hs_benchmark_suite::BenchmarkSuite;
+//!
+//! let results = BenchmarkSuite::builder()
+//!     .scale(0.5)
+//!     .runs(3)
+//!     .threads(8)
+//!     .disk(false)
+//!     .build()
+//!     .run();
+//!
+//! for cpu in &results.cpu {
+//!     println!("{:.2} GFLOPS", cpu.matrix_mult_gflops);
+//! }
+//! ```
+//!
+//! DISCLAIMER:
+//! Results represent performance on specific synthetic algorithms and do NOT necessarily
+//! equate to actual system capability for real-world workloads.
+
+pub mod affinity;
+pub mod aggregate;
+pub mod benchmark;
+pub mod compare;
+pub mod concurrency;
+pub mod cpu;
+pub mod disk;
+pub mod error;
+pub mod gpu;
+pub mod history;
+pub mod interrupt;
+pub mod load;
+pub mod manifest;
+pub mod memory;
+pub mod network;
+pub mod pages;
+pub mod perf;
+pub mod pool;
+pub mod power;
+pub mod progress;
+pub mod report;
+pub mod report_html;
+pub mod report_markdown;
+pub mod rng;
+pub mod scoring;
+pub mod stats;
+pub mod stress;
+pub mod suite;
+pub mod sysinfo_capture;
+pub mod thermal;
+pub mod timing;
+pub mod units;
+pub mod upload;
+pub mod watchdog;
+
+pub use error::BenchError;
+pub use report::SuiteReport;
+pub use suite::{
+    BenchmarkSuite, BenchmarkSuiteBuilder, Cooldown, Phase, PhasePlan, Schedule, SuiteConfig,
+    SuiteObserver, SuitePlan, SuiteResults,
+};
@@ -0,0 +1,151 @@
+/// Background load detection
+/// Measures how much CPU other processes used just before and during each benchmark run;
+/// a run sharing the CPU with other work measures that contention rather than the system,
+/// so runs above the threshold are marked as contaminated
+/// The load average is recorded for context where the OS provides it (Unix)
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{
+    CpuRefreshKind, Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System,
+    MINIMUM_CPU_UPDATE_INTERVAL,
+};
+
+/// Default share of the total CPU capacity, in percent, that other processes may use
+/// before a run is contaminated
+pub const DEFAULT_LOAD_THRESHOLD_PERCENT: f64 = 10.0;
+
+/// Idle window measured before every run
+const BASELINE_WINDOW: Duration = Duration::from_millis(500);
+
+/// CPU used by other processes around one benchmark run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundLoad {
+    /// Share of the total CPU capacity used by other processes just before the run, in percent
+    pub before_percent: f64,
+    /// Share of the total CPU capacity used by other processes while the run was going
+    pub during_percent: f64,
+    /// One-minute load average before and after the run; None where it is not available
+    pub load_average_before: Option<f64>,
+    pub load_average_after: Option<f64>,
+    /// Other processes used more than the threshold before or during the run
+    pub contaminated: bool,
+    /// The run's results were left out of the results and statistics
+    #[serde(default)]
+    pub excluded: bool,
+}
+
+impl BackgroundLoad {
+    /// Highest share of the CPU used by other processes before or during the run
+    pub fn peak_percent(&self) -> f64 {
+        self.before_percent.max(self.during_percent)
+    }
+}
+
+/// Measures the CPU usage of other processes from just before a run until `finish()`
+pub struct LoadMonitor {
+    system: System,
+    pid: Option<Pid>,
+    /// CPU time of this process in milliseconds at the previous measurement, and when it was
+    /// taken
+    own_cpu_ms: u64,
+    measured_at: Instant,
+    before_percent: f64,
+    load_average_before: Option<f64>,
+}
+
+impl LoadMonitor {
+    /// Measure the background load over a short idle window, then start measuring the run
+    pub fn start() -> Self {
+        let mut monitor = Self {
+            system: System::new_with_specifics(
+                RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing().with_cpu_usage()),
+            ),
+            pid: sysinfo::get_current_pid().ok(),
+            own_cpu_ms: 0,
+            measured_at: Instant::now(),
+            before_percent: 0.0,
+            load_average_before: load_average(),
+        };
+        monitor.other_cpu_percent();
+        thread::sleep(BASELINE_WINDOW.max(MINIMUM_CPU_UPDATE_INTERVAL));
+        monitor.before_percent = monitor.other_cpu_percent();
+        monitor
+    }
+
+    /// Stop measuring; the run is contaminated when other processes used more than
+    /// `threshold_percent` of the CPU before or during it
+    pub fn finish(mut self, threshold_percent: f64) -> BackgroundLoad {
+        let mut load = BackgroundLoad {
+            before_percent: self.before_percent,
+            during_percent: self.other_cpu_percent(),
+            load_average_before: self.load_average_before,
+            load_average_after: load_average(),
+            contaminated: false,
+            excluded: false,
+        };
+        load.contaminated = load.peak_percent() > threshold_percent;
+        load
+    }
+
+    /// Share of the total CPU capacity used by other processes since the previous call
+    fn other_cpu_percent(&mut self) -> f64 {
+        self.system.refresh_cpu_usage();
+        // Computed from the accumulated CPU time, since the process usage reported by
+        // sysinfo stays 0 until the process has used at least one clock tick
+        let own_cpu_ms = match self.pid {
+            Some(pid) => {
+                self.system.refresh_processes_specifics(
+                    ProcessesToUpdate::Some(&[pid]),
+                    false,
+                    ProcessRefreshKind::nothing().with_cpu(),
+                );
+                self.system
+                    .process(pid)
+                    .map_or(0, |process| process.accumulated_cpu_time())
+            }
+            None => 0,
+        };
+        let capacity_ms =
+            self.measured_at.elapsed().as_secs_f64() * 1e3 * self.system.cpus().len().max(1) as f64;
+        let own_percent = if capacity_ms > 0.0 {
+            own_cpu_ms.saturating_sub(self.own_cpu_ms) as f64 / capacity_ms * 100.0
+        } else {
+            0.0
+        };
+        self.own_cpu_ms = own_cpu_ms;
+        self.measured_at = Instant::now();
+        other_share(self.system.global_cpu_usage() as f64, own_percent)
+    }
+}
+
+/// CPU share of other processes, from the system-wide share and the share of this process
+fn other_share(system_percent: f64, own_percent: f64) -> f64 {
+    (system_percent - own_percent).clamp(0.0, 100.0)
+}
+
+fn load_average() -> Option<f64> {
+    cfg!(unix).then(|| System::load_average().one)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_other_share_excludes_own_usage() {
+        assert_eq!(other_share(60.0, 45.0), 15.0);
+        // Sampling skew can make the own share exceed the system-wide share
+        assert_eq!(other_share(40.0, 45.0), 0.0);
+    }
+
+    #[test]
+    fn test_monitor_threshold() {
+        let monitor = LoadMonitor::start();
+        let load = monitor.finish(100.0);
+        assert!((0.0..=100.0).contains(&load.before_percent));
+        assert!((0.0..=100.0).contains(&load.during_percent));
+        assert!(!load.contaminated);
+        assert!(!load.excluded);
+    }
+}
@@ -15,29 +15,181 @@
 /// on these benchmarks for critical system purchasing, deployment, or performance guarantees.
 mod args;
 mod board_game;
-mod cpu;
-mod disk;
-mod memory;
-mod stats;
-mod sysinfo_capture;
+mod config;
+mod render;
 
-use args::BenchmarkArgs;
+use args::{BenchmarkArgs, Command};
 use chrono::Local;
-use cpu::CpuResult;
-use disk::DiskResult;
-use memory::MemoryResult;
-use stats::Statistics;
-use std::time::Instant;
-use sysinfo_capture::SystemInfo;
-
-struct BenchmarkResults {
-    cpu: Vec<CpuResult>,
-    memory: Vec<MemoryResult>,
-    disk: Vec<DiskResult>,
+use hs_benchmark_suite::affinity;
+use hs_benchmark_suite::aggregate;
+use hs_benchmark_suite::benchmark;
+use hs_benchmark_suite::compare;
+use hs_benchmark_suite::concurrency::ConcurrencyResult;
+use hs_benchmark_suite::cpu::{self, CpuResult};
+use hs_benchmark_suite::disk::{self, DiskMode, DiskResult};
+use hs_benchmark_suite::gpu::GpuResult;
+use hs_benchmark_suite::history::{self, HistoryEntry, DEFAULT_HISTORY_FILE};
+use hs_benchmark_suite::interrupt;
+use hs_benchmark_suite::load::BackgroundLoad;
+use hs_benchmark_suite::memory::MemoryResult;
+use hs_benchmark_suite::network::{self, NetworkResult};
+use hs_benchmark_suite::progress::{Progress, ProgressBar};
+use hs_benchmark_suite::report::{
+    rotate_reports, write_csv_report, write_json_report, write_long_csv_report, CsvFormat,
+    SuiteReport,
+};
+use hs_benchmark_suite::report_html::write_html_report;
+use hs_benchmark_suite::report_markdown::write_markdown_report;
+use hs_benchmark_suite::rng;
+use hs_benchmark_suite::scoring;
+use hs_benchmark_suite::stats::StreamingStats;
+use hs_benchmark_suite::stress::{self, StressConfig, StressLoad};
+use hs_benchmark_suite::sysinfo_capture::SystemInfo;
+use hs_benchmark_suite::upload;
+use hs_benchmark_suite::{BenchError, BenchmarkSuite, Phase, SuiteObserver, SuiteResults};
+use indexmap::IndexMap;
+use render::{Renderer, Table};
+use std::time::{Duration, Instant};
+
+/// Prints per-run results to the console as each benchmark phase completes,
+/// with a progress bar and ETA while a phase is running unless `--quiet` is given
+struct ConsoleObserver<'a> {
+    progress: Option<ProgressBar>,
+    renderer: &'a mut Renderer,
+}
+
+impl ConsoleObserver<'_> {
+    /// Erase the progress bar before printing results
+    fn clear_progress(&mut self) {
+        if let Some(progress) = &mut self.progress {
+            progress.clear();
+        }
+    }
+
+    /// Print the results of a phase and how long it took
+    fn print_results(&mut self, mut table: Table, duration: Duration) {
+        self.clear_progress();
+        table.text("Duration", format!("{:?}", duration));
+        self.renderer.print(&table);
+        println!();
+    }
+}
+
+impl SuiteObserver for ConsoleObserver<'_> {
+    fn run_started(&mut self, run: usize, _total_runs: usize) {
+        self.clear_progress();
+        println!("--- Run {} ---", run);
+        if let Some(progress) = &mut self.progress {
+            progress.run_started(run);
+        }
+    }
+
+    fn run_finished(&mut self, run: usize, load: &BackgroundLoad) {
+        if load.contaminated {
+            self.clear_progress();
+            let warning = format!(
+                "Run {} contaminated: other processes used {:.0}% of the CPU before and \
+                 {:.0}% during the run{}",
+                run,
+                load.before_percent,
+                load.during_percent,
+                if load.excluded {
+                    "; excluded from the results"
+                } else {
+                    ""
+                }
+            );
+            println!("{}\n", self.renderer.warning(&warning));
+        }
+    }
+
+    fn calibration_started(&mut self, phase: Phase) {
+        self.clear_progress();
+        println!("Calibrating {} Scale...", phase.name());
+    }
+
+    fn cooldown_started(&mut self, phase: Phase, duration: Duration) {
+        self.clear_progress();
+        println!(
+            "Cooling down for {:.0}s before the {} benchmark...",
+            duration.as_secs_f64(),
+            phase.name()
+        );
+    }
+
+    fn phase_started(&mut self, phase: Phase) {
+        self.clear_progress();
+        println!("Running {} Benchmark...", phase.name());
+        if let Some(progress) = &mut self.progress {
+            progress.phase_started(phase.name());
+        }
+    }
+
+    fn step_started(&mut self, _phase: Phase, step: &str) {
+        if let Some(progress) = &mut self.progress {
+            progress.step(step);
+        }
+    }
+
+    fn step_timed_out(&mut self, phase: Phase, step: &str, limit: Duration) {
+        self.clear_progress();
+        let warning = format!(
+            "{} {} exceeded the {}s timeout and was cut short",
+            phase.name(),
+            step,
+            limit.as_secs_f64()
+        );
+        println!("{}\n", self.renderer.warning(&warning));
+    }
+
+    fn cpu_finished(&mut self, cpu_result: &CpuResult, cpu_duration: Duration) {
+        self.print_results(render::cpu_table(cpu_result), cpu_duration);
+    }
+
+    fn memory_finished(&mut self, mem_result: &MemoryResult, mem_duration: Duration) {
+        self.print_results(render::memory_table(mem_result), mem_duration);
+    }
+
+    fn concurrency_finished(&mut self, result: &ConcurrencyResult, duration: Duration) {
+        self.print_results(render::concurrency_table(result), duration);
+    }
+
+    fn disk_finished(&mut self, disk_result: &DiskResult, disk_duration: Duration) {
+        self.print_results(render::disk_table(disk_result), disk_duration);
+        for (title, latency) in [
+            ("Write Latency", &disk_result.write_latency),
+            ("Read Latency", &disk_result.read_latency),
+        ] {
+            if let Some(text) = render::histogram_text(title, latency) {
+                println!("{}", text);
+            }
+        }
+    }
+
+    fn network_finished(&mut self, net_result: &NetworkResult, net_duration: Duration) {
+        self.print_results(render::network_table(net_result), net_duration);
+    }
+
+    fn gpu_finished(&mut self, gpu_result: &GpuResult, gpu_duration: Duration) {
+        self.print_results(render::gpu_table(gpu_result), gpu_duration);
+    }
+
+    fn phase_failed(&mut self, phase: Phase, error: &BenchError) {
+        self.clear_progress();
+        match error {
+            BenchError::Interrupted => eprintln!("{} benchmark interrupted\n", phase.name()),
+            BenchError::Unsupported(_) => {
+                eprintln!("{} benchmark skipped: {}\n", phase.name(), error)
+            }
+            _ => eprintln!("{} benchmark failed: {}\n", phase.name(), error),
+        }
+    }
 }
 
 fn main() {
     let cli_args = BenchmarkArgs::parse();
+    let mut renderer = Renderer::new(cli_args.color).with_units(cli_args.units);
+    rng::set_seed(cli_args.seed);
 
     // Easter egg: board_game
     if cli_args.board_game {
@@ -45,6 +197,119 @@ fn main() {
         return;
     }
 
+    if let Command::Compare {
+        baseline,
+        candidate,
+    } = &cli_args.command
+    {
+        run_compare(baseline, candidate, cli_args.force, &renderer);
+        return;
+    }
+
+    if let Command::History { path } = &cli_args.command {
+        run_history(path, &renderer);
+        return;
+    }
+
+    if let Command::Aggregate { dir } = &cli_args.command {
+        run_aggregate(dir, cli_args.json, &renderer);
+        return;
+    }
+
+    if cli_args.command == Command::List {
+        run_list(cli_args.json, &mut renderer);
+        return;
+    }
+
+    if let Command::Stress { duration } = cli_args.command {
+        run_stress(&cli_args, duration, &mut renderer);
+        return;
+    }
+
+    // Network server mode: serve remote --network-target clients until Ctrl-C
+    if cli_args.network_server {
+        let bind_addr = format!("0.0.0.0:{}", cli_args.network_port);
+        println!(
+            "Network benchmark server listening on {} (stop with Ctrl-C)",
+            bind_addr
+        );
+        interrupt::install();
+        if let Err(e) = network::run_network_server(&bind_addr) {
+            eprintln!("Error running network server: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // The disk benchmark silently measures nothing if it cannot create its files
+    if let Some(path) = &cli_args.disk_path {
+        if !std::path::Path::new(path).is_dir() {
+            eprintln!("Error: --disk-path {} is not a directory", path);
+            std::process::exit(2);
+        }
+    }
+    // Reports are written after the benchmarks, so a bad directory would lose the results
+    if let Some(dir) = &cli_args.output_dir {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            eprintln!("Error: cannot create --output-dir {}: {}", dir, e);
+            std::process::exit(2);
+        }
+    }
+    // Fail before the other benchmarks run, not after minutes of CPU and memory work
+    let runs_disk = cli_args
+        .benchmarks
+        .as_ref()
+        .is_none_or(|phases| phases.contains(&Phase::Disk));
+    if runs_disk {
+        let dir = std::path::Path::new(cli_args.disk_path.as_deref().unwrap_or("."));
+        let scan = disk::scan_temp_dirs(dir);
+        for removed in &scan.removed {
+            eprintln!(
+                "Removed {} left behind by a crashed benchmark",
+                removed.display()
+            );
+        }
+        if !scan.active_pids.is_empty() {
+            eprintln!(
+                "Warning: another benchmark (PID {:?}) is using {}; concurrent disk benchmarks disturb each other's results",
+                scan.active_pids,
+                dir.display()
+            );
+        }
+        let scale = cli_args.disk_scale.unwrap_or(cli_args.scale);
+        if let Err(e) = disk::preflight(dir, scale, cli_args.disk_precondition) {
+            eprintln!("Error: the disk benchmark cannot run: {}", e);
+            std::process::exit(2);
+        }
+    }
+
+    // Uploads happen after every run, so a rejected endpoint would lose all of them
+    if let Some(url) = &cli_args.upload {
+        let token = std::env::var(upload::TOKEN_ENV).ok();
+        if let Err(e) = upload::check_endpoint(url, token.as_deref()) {
+            eprintln!("Error: --upload {}: {}", url, e);
+            std::process::exit(2);
+        }
+    }
+
+    // Pinning to a core the process may not run on would fail silently in every worker
+    if let Some(cores) = &cli_args.core_list {
+        let available = affinity::available_cores();
+        if let Some(core) = cores.iter().find(|core| !available.contains(core)) {
+            eprintln!(
+                "Error: --core-list core {} is not available (available: {:?})",
+                core, available
+            );
+            std::process::exit(2);
+        }
+    }
+    if cli_args.pin_cores && !affinity::PINNING_SUPPORTED {
+        eprintln!("Warning: core pinning is not supported on this platform, threads run unpinned");
+    }
+    if cli_args.disk_mode == DiskMode::Mmap && !disk::MMAP_SUPPORTED {
+        eprintln!("Warning: --disk-mode mmap is not supported on this platform, using direct I/O");
+    }
+
     // Display disclaimer
     println!("DISCLAIMER: Benchmark Results vs Actual System Capability");
     println!("These results reflect runtime metrics for synthetic test scenarios");
@@ -54,669 +319,557 @@ fn main() {
 
     // Capture system information
     let system_info = SystemInfo::capture();
-    system_info.display();
+    println!("{}", renderer.heading("System Information"));
+    renderer.print(&render::system_info_table(&system_info));
+    println!();
+    if let Some(warning) = system_info.governor_warning() {
+        println!("{}\n", renderer.warning(&warning));
+    }
+    if let Some(warning) = system_info.battery_warning() {
+        if cli_args.require_ac {
+            eprintln!("Error: --require-ac is set: {}", warning);
+            std::process::exit(2);
+        }
+        println!("{}\n", renderer.warning(&warning));
+    }
 
-    println!("=== Benchmark Configuration ===");
-    println!("Scale: {}", cli_args.scale);
-    println!("Runs: {}", cli_args.count);
-    println!("Threads: {}\n", cli_args.threads);
+    println!("{}", renderer.heading("Benchmark Configuration"));
+    renderer.print(&render::config_table(&cli_args));
+    println!();
+
+    let mut builder = BenchmarkSuite::builder()
+        .scale(cli_args.scale)
+        .warmup_runs(cli_args.warmup_runs)
+        .runs(cli_args.count)
+        .cooldown(cli_args.cooldown)
+        .schedule(cli_args.schedule)
+        .threads(cli_args.threads)
+        .memory_threads(cli_args.mem_threads)
+        .memory_scaling(cli_args.mem_scaling)
+        .memory_numa(cli_args.mem_numa)
+        .memory_patterns(cli_args.mem_patterns.clone())
+        .memory_huge_pages(cli_args.huge_pages)
+        .memory_prefault(cli_args.mem_prefault)
+        .core_latency(cli_args.core_latency)
+        .pin_cores(cli_args.pin_cores)
+        .block_size(cli_args.block_size)
+        .disk_mode(cli_args.disk_mode)
+        .disk_cache_compare(cli_args.disk_cache_compare)
+        .disk_profiles(cli_args.disk_profiles.clone())
+        .load_threshold(cli_args.load_threshold)
+        .exclude_contaminated(cli_args.exclude_contaminated);
+    if let Some(cores) = &cli_args.core_list {
+        builder = builder.core_list(cores.clone());
+    }
+    if let Some(precondition) = cli_args.disk_precondition {
+        builder = builder.disk_precondition(precondition.multiple, precondition.passes);
+    }
+    if let Some(min_time) = cli_args.min_time {
+        builder = builder.min_time(min_time);
+    }
+    if let Some(seed) = cli_args.seed {
+        builder = builder.seed(seed);
+    }
+    if let Some(scale) = cli_args.cpu_scale {
+        builder = builder.cpu_scale(scale);
+    }
+    if let Some(scale) = cli_args.mem_scale {
+        builder = builder.memory_scale(scale);
+    }
+    if let Some(scale) = cli_args.disk_scale {
+        builder = builder.disk_scale(scale);
+    }
+    if let Some(target) = cli_args.auto_scale {
+        builder = builder.auto_scale(target);
+    }
+    if let Some(target) = &cli_args.network_target {
+        builder = builder.network_target(target.clone());
+    }
+    if let Some(path) = &cli_args.disk_path {
+        builder = builder.disk_path(path);
+    }
+    if let Some(phases) = &cli_args.benchmarks {
+        builder = builder
+            .cpu(phases.contains(&Phase::Cpu))
+            .memory(phases.contains(&Phase::Memory))
+            .concurrency(phases.contains(&Phase::Concurrency))
+            .disk(phases.contains(&Phase::Disk))
+            .network(phases.contains(&Phase::Network))
+            .gpu(phases.contains(&Phase::Gpu));
+    }
+    if !cli_args.kernels.is_empty() {
+        builder = builder.kernels(cli_args.kernels.clone());
+    }
+    if cli_args.verify {
+        builder = builder.verify(true);
+    }
+    if let Some(timeout) = cli_args.timeout {
+        builder = builder.timeout(timeout);
+    }
+    let suite = builder.build();
+
+    if cli_args.dry_run {
+        println!("Estimating the duration with quick calibration passes...");
+        let plan = suite.plan(&mut ConsoleObserver {
+            progress: None,
+            renderer: &mut renderer,
+        });
+        println!();
+        println!("{}", renderer.heading("Dry Run Plan"));
+        renderer.print(&render::plan_table(&plan, &cli_args));
+        println!();
+        println!("Nothing was benchmarked; run again without --dry-run to start.");
+        return;
+    }
 
-    let mut results = BenchmarkResults {
-        cpu: Vec::new(),
-        memory: Vec::new(),
-        disk: Vec::new(),
-    };
+    // Ctrl-C stops after the running phase, removes the disk test files, and still writes
+    // the reports for the results measured so far
+    interrupt::install();
+    match cli_args.watch {
+        Some(interval) => run_watch(&suite, &cli_args, &system_info, &mut renderer, interval),
+        None => {
+            run_suite(&suite, &cli_args, &system_info, &mut renderer);
+        }
+    }
 
-    // Run benchmarks multiple times
-    for run in 1..=cli_args.count {
-        println!("--- Run {} ---", run);
+    if interrupt::interrupted() {
+        println!("{}", renderer.heading("Benchmark Interrupted"));
+        std::process::exit(130);
+    }
+    println!("{}", renderer.heading("Benchmark Complete"));
+}
 
-        // CPU Benchmark
-        println!("Running CPU Benchmark...");
-        let cpu_start = Instant::now();
-        let cpu_result = cpu::run_cpu_benchmark_scaled(cli_args.scale, cli_args.threads);
-        let cpu_duration = cpu_start.elapsed();
-        println!(
-            "CPU Primes:              {:.0} primes/sec",
-            cpu_result.primes_per_sec
-        );
-        println!(
-            "CPU Matrix Mult (ST):    {:.2} GFLOPS",
-            cpu_result.matrix_mult_gflops
-        );
-        println!(
-            "CPU Matrix Mult (MT):    {:.2} GFLOPS",
-            cpu_result.parallel_matrix_gflops
-        );
-        println!(
-            "CPU Speedup (ST->MT):    {:.2}x",
-            cpu_result.parallel_speedup
-        );
-        println!(
-            "CPU Mandelbrot:          {:.0} pixels/sec",
-            cpu_result.mandelbrot_pixels_per_sec
-        );
-        println!(
-            "CPU FFT:                 {:.0} Msamples/sec",
-            cpu_result.fft_msamples_per_sec
+/// Re-run the suite every `interval`, measured from the start of one run to the start of the
+/// next, until the process is interrupted
+fn run_watch(
+    suite: &BenchmarkSuite,
+    cli_args: &BenchmarkArgs,
+    system_info: &SystemInfo,
+    renderer: &mut Renderer,
+    interval: Duration,
+) {
+    println!("Watch: every {:?} (stop with Ctrl-C)\n", interval);
+    // Statistics of every metric over the whole session, kept in constant memory
+    let mut session: render::SessionStats = IndexMap::new();
+    let mut completed = 0;
+    for iteration in 1.. {
+        let started = Instant::now();
+        let heading = format!(
+            "Watch Run {} ({})",
+            iteration,
+            Local::now().format("%Y-%m-%d %H:%M:%S")
         );
-        results.cpu.push(cpu_result);
-        println!("Duration:                {:?}\n", cpu_duration);
-
-        // Memory Benchmark
-        println!("Running Memory Benchmark...");
-        let mem_start = Instant::now();
-        let mem_result = memory::run_memory_benchmark_scaled(cli_args.scale);
-        let mem_duration = mem_start.elapsed();
-        println!("Memory Write: {:.2} MB/s", mem_result.write_throughput);
-        println!("Memory Read:  {:.2} MB/s", mem_result.read_throughput);
-        println!("Memory Avg:   {:.2} MB/s", mem_result.combined_throughput);
-        results.memory.push(mem_result);
-        println!("Duration:     {:?}\n", mem_duration);
-
-        // Disk Benchmark
-        println!("Running Disk Benchmark...");
-        let disk_start = Instant::now();
-        let disk_result =
-            disk::run_disk_benchmark_scaled_with_block_size(cli_args.scale, cli_args.block_size);
-        let disk_duration = disk_start.elapsed();
-        println!("Disk Write: {:.2} MB/s", disk_result.write_throughput);
-        println!("Disk Read:  {:.2} MB/s", disk_result.read_throughput);
-        println!("Disk Avg:   {:.2} MB/s", disk_result.combined_throughput);
-        results.disk.push(disk_result);
-        println!("Duration:   {:?}\n", disk_duration);
-    }
-
-    // Display summary with averages if multiple runs
-    if cli_args.count > 1 {
-        println!("=== Summary ===\n");
-
-        println!("CPU Benchmark:");
-        for (i, result) in results.cpu.iter().enumerate() {
-            println!("  Run {}:", i + 1);
-            println!(
-                "    Primes:              {:.0} primes/sec",
-                result.primes_per_sec
-            );
-            println!(
-                "    Matrix Mult (ST):    {:.2} GFLOPS",
-                result.matrix_mult_gflops
-            );
-            println!(
-                "    Matrix Mult (MT):    {:.2} GFLOPS",
-                result.parallel_matrix_gflops
-            );
-            println!("    Speedup (ST->MT):    {:.2}x", result.parallel_speedup);
-            println!(
-                "    Mandelbrot:          {:.0} pixels/sec",
-                result.mandelbrot_pixels_per_sec
-            );
-            println!(
-                "    FFT:                 {:.0} Msamples/sec",
-                result.fft_msamples_per_sec
-            );
+        println!("{}\n", renderer.heading(&heading));
+        let results = run_suite(suite, cli_args, system_info, renderer);
+        if !results.interrupted {
+            completed += 1;
+            for (category, metrics) in SuiteReport::new(&results, system_info).results {
+                let category = session.entry(category).or_default();
+                for (name, series) in metrics {
+                    let (_, stats) = category
+                        .entry(name)
+                        .or_insert_with(|| (series.unit, StreamingStats::new()));
+                    series.runs.iter().for_each(|&value| stats.push(value));
+                }
+            }
         }
-        let cpu_primes_avg =
-            results.cpu.iter().map(|r| r.primes_per_sec).sum::<f64>() / results.cpu.len() as f64;
-        let cpu_matrix_avg = results
-            .cpu
-            .iter()
-            .map(|r| r.matrix_mult_gflops)
-            .sum::<f64>()
-            / results.cpu.len() as f64;
-        let cpu_parallel_avg = results
-            .cpu
-            .iter()
-            .map(|r| r.parallel_matrix_gflops)
-            .sum::<f64>()
-            / results.cpu.len() as f64;
-        let cpu_speedup_avg =
-            results.cpu.iter().map(|r| r.parallel_speedup).sum::<f64>() / results.cpu.len() as f64;
-        let cpu_mandelbrot_avg = results
-            .cpu
-            .iter()
-            .map(|r| r.mandelbrot_pixels_per_sec)
-            .sum::<f64>()
-            / results.cpu.len() as f64;
-        let cpu_fft_avg = results
-            .cpu
-            .iter()
-            .map(|r| r.fft_msamples_per_sec)
-            .sum::<f64>()
-            / results.cpu.len() as f64;
-        println!("  Average:");
-        println!("    Primes:              {:.0} primes/sec", cpu_primes_avg);
-        println!("    Matrix Mult (ST):    {:.2} GFLOPS", cpu_matrix_avg);
-        println!("    Matrix Mult (MT):    {:.2} GFLOPS", cpu_parallel_avg);
-        println!("    Speedup (ST->MT):    {:.2}x", cpu_speedup_avg);
+        if interrupt::interrupted() {
+            break;
+        }
+
+        let elapsed = started.elapsed();
+        match interval.checked_sub(elapsed) {
+            Some(wait) => {
+                let next = Local::now() + chrono::Duration::from_std(wait).unwrap_or_default();
+                println!("Next run at {}\n", next.format("%Y-%m-%d %H:%M:%S"));
+                if !interrupt::sleep(wait) {
+                    break;
+                }
+            }
+            None => println!(
+                "Run took {:.1?} (longer than the watch interval), starting the next run now\n",
+                elapsed
+            ),
+        }
+    }
+
+    if completed > 1 {
         println!(
-            "    Mandelbrot:          {:.0} pixels/sec",
-            cpu_mandelbrot_avg
+            "\n{}\n",
+            renderer.heading(&format!("Watch Session ({} runs)", completed))
         );
-        println!("    FFT:                 {:.0} Msamples/sec\n", cpu_fft_avg);
+        for table in render::session_tables(&session) {
+            renderer.print(&table);
+            println!();
+        }
+    }
+}
 
-        println!("Memory Benchmark:");
-        for (i, result) in results.memory.iter().enumerate() {
-            println!("  Run {}:", i + 1);
-            println!("    Write: {:.2} MB/s", result.write_throughput);
-            println!("    Read:  {:.2} MB/s", result.read_throughput);
-            println!("    Avg:   {:.2} MB/s", result.combined_throughput);
+/// Run the suite once, print the results, and write the requested reports
+/// `renderer` carries the metrics of the previous --watch iteration, so the first run shows
+/// its change from them
+fn run_suite(
+    suite: &BenchmarkSuite,
+    cli_args: &BenchmarkArgs,
+    system_info: &SystemInfo,
+    renderer: &mut Renderer,
+) -> SuiteResults {
+    // Run benchmarks multiple times
+    let mut observer = ConsoleObserver {
+        progress: (!cli_args.quiet)
+            .then(|| ProgressBar::new(suite.config().runs, suite.config().phases().len())),
+        renderer,
+    };
+    let results = suite.run_with_observer(&mut observer);
+    observer.clear_progress();
+
+    // Display every run and the averages if multiple runs
+    if results.config.runs > 1 {
+        println!("{}\n", renderer.heading("Summary"));
+        let phases = [
+            results
+                .cpu
+                .iter()
+                .map(render::cpu_table)
+                .collect::<Vec<_>>(),
+            results.memory.iter().map(render::memory_table).collect(),
+            results
+                .concurrency
+                .iter()
+                .map(render::concurrency_table)
+                .collect(),
+            results.disk.iter().map(render::disk_table).collect(),
+            results.network.iter().map(render::network_table).collect(),
+            results.gpu.iter().map(render::gpu_table).collect(),
+        ];
+        for runs in phases.iter().filter(|runs| !runs.is_empty()) {
+            println!("{}", renderer.render_runs(runs));
         }
-        let mem_write_avg = results
-            .memory
-            .iter()
-            .map(|r| r.write_throughput)
-            .sum::<f64>()
-            / results.memory.len() as f64;
-        let mem_read_avg = results
-            .memory
-            .iter()
-            .map(|r| r.read_throughput)
-            .sum::<f64>()
-            / results.memory.len() as f64;
-        let mem_combined_avg = results
-            .memory
-            .iter()
-            .map(|r| r.combined_throughput)
-            .sum::<f64>()
-            / results.memory.len() as f64;
-        println!("  Average:");
-        println!("    Write: {:.2} MB/s", mem_write_avg);
-        println!("    Read:  {:.2} MB/s", mem_read_avg);
-        println!("    Avg:   {:.2} MB/s\n", mem_combined_avg);
-
-        println!("Disk Benchmark:");
-        for (i, result) in results.disk.iter().enumerate() {
-            println!("  Run {}:", i + 1);
-            println!("    Write: {:.2} MB/s", result.write_throughput);
-            println!("    Read:  {:.2} MB/s", result.read_throughput);
-            println!("    Avg:   {:.2} MB/s", result.combined_throughput);
-        }
-        let disk_write_avg = results.disk.iter().map(|r| r.write_throughput).sum::<f64>()
-            / results.disk.len() as f64;
-        let disk_read_avg =
-            results.disk.iter().map(|r| r.read_throughput).sum::<f64>() / results.disk.len() as f64;
-        let disk_combined_avg = results
-            .disk
-            .iter()
-            .map(|r| r.combined_throughput)
-            .sum::<f64>()
-            / results.disk.len() as f64;
-        println!("  Average:");
-        println!("    Write: {:.2} MB/s", disk_write_avg);
-        println!("    Read:  {:.2} MB/s", disk_read_avg);
-        println!("    Avg:   {:.2} MB/s\n", disk_combined_avg);
+    }
+
+    // Display the cache hierarchy detected in the first run (also shown for single runs)
+    if let Some(memory) = results.memory.first() {
+        if !memory.cache_probe.levels.is_empty() {
+            println!("{}", renderer.heading("Cache Hierarchy (estimated)"));
+            renderer.print(&render::cache_hierarchy_table(&memory.cache_probe));
+            println!();
+            if let Some(caches) = system_info.cpu_topology.cache_summary() {
+                println!("Caches reported by the OS: {}\n", caches);
+            }
+        }
+    }
+
+    if results.config.auto_scale.is_some() {
+        println!("Calibrated Scale: {}\n", results.config.scale_summary());
+    }
+    if let Some(cooldown) = results.cooldown_summary() {
+        println!("Cooldown: {}\n", cooldown);
+    }
+    if let Some(verification) = results.verification_summary() {
+        println!("Verification: {}\n", verification);
+    }
+
+    // Warn about thermally throttled and contaminated runs (also shown for single runs)
+    for warning in [
+        results.interruption_warning(),
+        results.throttling_warning(),
+        results.verification_warning(),
+        results.timeout_warning(),
+        results.direct_io_warning(),
+        results.contamination_warning(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        println!("{}\n", renderer.warning(&warning));
+    }
+
+    // Display composite scores (also shown for single runs)
+    if let Some(table) = render::score_table(&scoring::compute_scores(&results)) {
+        renderer.print(&table);
+        println!();
     }
 
     // Write CSV output if requested
-    if cli_args.csv {
-        if let Err(e) = write_csv_report(&cli_args, &results, &system_info) {
-            eprintln!("Error writing CSV report: {}", e);
-        } else {
-            let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-            println!("CSV report written to output_{}.csv", timestamp);
+    let output_dir = std::path::Path::new(cli_args.output_dir.as_deref().unwrap_or("."));
+    if cli_args.csv && cli_args.csv_format == CsvFormat::Long {
+        match write_long_csv_report(&results, output_dir, cli_args.units) {
+            Ok((filename, stats)) => {
+                println!("CSV report written to {} (statistics: {})", filename, stats)
+            }
+            Err(e) => eprintln!("Error writing CSV report: {}", e),
+        }
+    } else if cli_args.csv {
+        match write_csv_report(&results, system_info, output_dir, cli_args.units) {
+            Ok(filename) => println!("CSV report written to {}", filename),
+            Err(e) => eprintln!("Error writing CSV report: {}", e),
         }
     }
 
     // Write JSON output if requested
     if cli_args.json {
-        if let Err(e) = write_json_report(&cli_args, &results, &system_info) {
-            eprintln!("Error writing JSON report: {}", e);
-        } else {
-            let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-            println!("JSON report written to output_{}.json", timestamp);
+        match write_json_report(&results, system_info, output_dir) {
+            Ok(filename) => println!("JSON report written to {}", filename),
+            Err(e) => eprintln!("Error writing JSON report: {}", e),
+        }
+    }
+
+    // Write HTML report if requested
+    if cli_args.html {
+        match write_html_report(&results, system_info, output_dir, cli_args.units) {
+            Ok(filename) => println!("HTML report written to {}", filename),
+            Err(e) => eprintln!("Error writing HTML report: {}", e),
+        }
+    }
+
+    // Write Markdown report if requested
+    if cli_args.markdown {
+        match write_markdown_report(&results, system_info, output_dir, cli_args.units) {
+            Ok(filename) => println!("Markdown report written to {}", filename),
+            Err(e) => eprintln!("Error writing Markdown report: {}", e),
+        }
+    }
+
+    // Write the Mandelbrot image of the measured workload if requested
+    if let Some(path) = &cli_args.mandelbrot_image {
+        match cpu::write_mandelbrot_image(
+            std::path::Path::new(path),
+            results.config.scale_for(Phase::Cpu),
+        ) {
+            Ok((width, height, max_iter)) => println!(
+                "Mandelbrot image written to {} ({}x{}, {} iterations)",
+                path, width, height, max_iter
+            ),
+            Err(e) => eprintln!("Error writing Mandelbrot image: {}", e),
+        }
+    }
+
+    // Record the run in the history file if requested; partial results would skew the trends
+    if cli_args.history && results.interrupted {
+        println!("History entry skipped: the run was interrupted");
+    } else if cli_args.history {
+        let entry = HistoryEntry::from_report(&SuiteReport::new(&results, system_info));
+        match history::append_entry(std::path::Path::new(DEFAULT_HISTORY_FILE), &entry) {
+            Ok(()) => println!(
+                "History entry appended to {} (config {})",
+                DEFAULT_HISTORY_FILE, entry.config_hash
+            ),
+            Err(e) => eprintln!("Error writing history entry: {}", e),
+        }
+    }
+
+    // Send the JSON report to the results server if requested
+    if let Some(url) = &cli_args.upload {
+        let token = std::env::var(upload::TOKEN_ENV).ok();
+        let report = SuiteReport::new(&results, system_info);
+        match upload::upload_report(&report, url, token.as_deref(), |attempt, e, wait| {
+            eprintln!(
+                "Upload attempt {} failed ({}), retrying in {}s",
+                attempt,
+                e,
+                wait.as_secs()
+            )
+        }) {
+            Ok(upload) => println!("Report uploaded to {} (HTTP {})", url, upload.status),
+            Err(e) => eprintln!("Error uploading report to {}: {}", url, e),
+        }
+    }
+
+    // Delete the oldest reports if requested
+    if let Some(keep) = cli_args.keep_reports {
+        match rotate_reports(output_dir, keep) {
+            Ok(removed) if !removed.is_empty() => {
+                println!(
+                    "Removed {} old report(s), keeping {} per format",
+                    removed.len(),
+                    keep
+                )
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Error rotating reports: {}", e),
         }
     }
 
-    println!("=== Benchmark Complete ===");
+    results
+}
+
+fn run_history(path: &str, renderer: &Renderer) {
+    match history::load_history(std::path::Path::new(path)) {
+        Ok(entries) if entries.is_empty() => println!("No history entries in {}", path),
+        Ok(entries) => print!("{}", renderer.render_trends(&entries)),
+        Err(e) => {
+            eprintln!("Error reading history {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
 }
 
-fn write_csv_report(
-    _args: &BenchmarkArgs,
-    results: &BenchmarkResults,
-    _system_info: &SystemInfo,
-) -> std::io::Result<()> {
-    use std::fs::File;
-    use std::io::Write;
-
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-    let filename = format!("output_{}.csv", timestamp);
-    let mut file = File::create(&filename)?;
-
-    // Write header with individual runs and statistics
-    let mut header = vec!["Metric".to_string()];
-    for i in 1..=results.cpu.len() {
-        header.push(format!("Run {}", i));
-    }
-    header.extend_from_slice(&[
-        "Mean".to_string(),
-        "StdDev".to_string(),
-        "Min".to_string(),
-        "Max".to_string(),
-        "P50".to_string(),
-        "P95".to_string(),
-        "P99".to_string(),
-        "CV%".to_string(),
-    ]);
-    writeln!(file, "{}", header.join(","))?;
-
-    // Helper function to write metric with stats
-    let write_metric = |file: &mut File, name: &str, values: Vec<f64>| -> std::io::Result<()> {
-        let mut row = vec![name.to_string()];
-        for val in &values {
-            row.push(format!("{:.2}", val));
-        }
-
-        // Calculate and append statistics
-        if let Some(stats) = Statistics::from_values(&values) {
-            row.push(format!("{:.2}", stats.mean));
-            row.push(format!("{:.2}", stats.std_dev));
-            row.push(format!("{:.2}", stats.min));
-            row.push(format!("{:.2}", stats.max));
-            row.push(format!("{:.2}", stats.p50));
-            row.push(format!("{:.2}", stats.p95));
-            row.push(format!("{:.2}", stats.p99));
-            row.push(format!("{:.2}", stats.coefficient_of_variation));
-        }
-
-        writeln!(file, "{}", row.join(","))
+/// Run the stress loads for `duration` and print the stability verdict
+/// Exits with status 1 when a load found an error, and 130 when interrupted
+fn run_stress(cli_args: &BenchmarkArgs, duration: Duration, renderer: &mut Renderer) {
+    let enabled = |phase| {
+        cli_args
+            .benchmarks
+            .as_ref()
+            .is_none_or(|phases| phases.contains(&phase))
     };
+    let disk_dir = std::path::PathBuf::from(cli_args.disk_path.as_deref().unwrap_or("."));
+    if enabled(Phase::Disk) && !disk_dir.is_dir() {
+        eprintln!(
+            "Error: --disk-path {} is not a directory",
+            disk_dir.display()
+        );
+        std::process::exit(2);
+    }
+    let config = StressConfig {
+        duration,
+        scale: cli_args.scale,
+        threads: if enabled(Phase::Cpu) {
+            StressConfig::default().threads
+        } else {
+            0
+        },
+        memory_threads: if enabled(Phase::Memory) {
+            cli_args.mem_threads
+        } else {
+            0
+        },
+        disk_dir: enabled(Phase::Disk).then_some(disk_dir),
+    };
+    if config.threads == 0 && config.memory_threads == 0 && config.disk_dir.is_none() {
+        eprintln!("Error: the stress test needs at least one of the cpu, memory, and disk loads");
+        std::process::exit(2);
+    }
 
-    // CPU metrics
-    write_metric(
-        &mut file,
-        "CPU Primes (primes/sec)",
-        results.cpu.iter().map(|r| r.primes_per_sec).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "CPU Matrix ST (GFLOPS)",
-        results.cpu.iter().map(|r| r.matrix_mult_gflops).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "CPU Matrix MT (GFLOPS)",
-        results
-            .cpu
-            .iter()
-            .map(|r| r.parallel_matrix_gflops)
-            .collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "CPU Speedup (ST->MT)",
-        results.cpu.iter().map(|r| r.parallel_speedup).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "CPU Mandelbrot (pixels/sec)",
-        results
-            .cpu
+    println!("{}", renderer.heading("Stress Test"));
+    renderer.print(&render::stress_config_table(&config));
+    println!();
+    println!("Running for {:?} (stop early with Ctrl-C)...\n", duration);
+    interrupt::install();
+    let result = stress::run_stress(&config, |elapsed, loads: &[StressLoad]| {
+        let loads: Vec<String> = loads
             .iter()
-            .map(|r| r.mandelbrot_pixels_per_sec)
-            .collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "CPU FFT (Msamples/sec)",
-        results.cpu.iter().map(|r| r.fft_msamples_per_sec).collect(),
-    )?;
-
-    // Memory metrics
-    write_metric(
-        &mut file,
-        "Memory Write (MB/s)",
-        results.memory.iter().map(|r| r.write_throughput).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "Memory Read (MB/s)",
-        results.memory.iter().map(|r| r.read_throughput).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "Memory Combined (MB/s)",
-        results
-            .memory
-            .iter()
-            .map(|r| r.combined_throughput)
-            .collect(),
-    )?;
-
-    // Disk metrics
-    write_metric(
-        &mut file,
-        "Disk Write (MB/s)",
-        results.disk.iter().map(|r| r.write_throughput).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "Disk Read (MB/s)",
-        results.disk.iter().map(|r| r.read_throughput).collect(),
-    )?;
-
-    write_metric(
-        &mut file,
-        "Disk Combined (MB/s)",
-        results.disk.iter().map(|r| r.combined_throughput).collect(),
-    )?;
-
-    Ok(())
+            .map(|load| {
+                format!(
+                    "{} {} passes, {} errors",
+                    load.name, load.passes, load.errors
+                )
+            })
+            .collect();
+        println!("[{:>6.0}s] {}", elapsed.as_secs_f64(), loads.join(" | "));
+    });
+    println!();
+    renderer.print(&render::stress_table(&result));
+    println!();
+
+    for failure in result.failures() {
+        println!("{}\n", renderer.warning(&failure));
+    }
+    if result.thermal.throttled() {
+        let warning = "The CPU throttled under the combined load; check the cooling if this \
+                       machine should sustain its peak clock";
+        println!("{}\n", renderer.warning(warning));
+    }
+    println!(
+        "{}",
+        renderer.heading(&format!("Stability Verdict: {}", result.verdict()))
+    );
+    if !result.passed() {
+        std::process::exit(1);
+    }
+    if result.interrupted {
+        std::process::exit(130);
+    }
 }
 
-fn write_json_report(
-    args: &BenchmarkArgs,
-    results: &BenchmarkResults,
-    system_info: &SystemInfo,
-) -> std::io::Result<()> {
-    use std::fs::File;
-    use std::io::Write;
-
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-    let iso_timestamp = Local::now().to_rfc3339();
-    let filename = format!("output_{}.json", timestamp);
-    let mut file = File::create(&filename)?;
-
-    // Helper to create stats JSON
-    let stats_json = |values: &[f64]| -> String {
-        if let Some(stats) = Statistics::from_values(values) {
-            format!(
-                r#"{{"mean":{:.2},"std_dev":{:.2},"min":{:.2},"max":{:.2},"p50":{:.2},"p95":{:.2},"p99":{:.2},"cv_percent":{:.2}}}"#,
-                stats.mean,
-                stats.std_dev,
-                stats.min,
-                stats.max,
-                stats.p50,
-                stats.p95,
-                stats.p99,
-                stats.coefficient_of_variation
-            )
-        } else {
-            "null".to_string()
+/// Print the selectable benchmarks and kernels with their metrics, or the same as JSON
+fn run_list(json: bool, renderer: &mut Renderer) {
+    let listing = benchmark::listing();
+    if json {
+        match serde_json::to_string_pretty(&listing) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                eprintln!("Error serializing the benchmark list: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    println!("Benchmarks: {}", listing.phases.join(", "));
+    println!();
+    println!("Kernels (select by name in the `benchmarks` option of a --config file):");
+    for table in render::listing_tables(&listing) {
+        renderer.print(&table);
+    }
+}
+
+fn run_aggregate(dir: &str, json: bool, renderer: &Renderer) {
+    let loaded = match aggregate::load_reports(std::path::Path::new(dir)) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Error reading report directory {}: {}", dir, e);
+            std::process::exit(1);
         }
     };
+    for (path, e) in &loaded.skipped {
+        eprintln!("Skipping {}: {}", path.display(), e);
+    }
+    if loaded.reports.is_empty() {
+        eprintln!("Error: no JSON reports found in {}", dir);
+        std::process::exit(1);
+    }
 
-    writeln!(file, "{{")?;
-
-    // Metadata (timestamp and hostname for easy comparison)
-    writeln!(file, r#"  "metadata": {{"#)?;
-    writeln!(file, r#"    "timestamp": "{}","#, iso_timestamp)?;
-    writeln!(
-        file,
-        r#"    "hostname": "{}""#,
-        system_info.hostname.replace("\"", "\\\"")
-    )?;
-    writeln!(file, "  }},")?;
-
-    // System information
-    writeln!(file, r#"  "system_info": {{"#)?;
-    writeln!(
-        file,
-        r#"    "cpu_brand": "{}","#,
-        system_info.cpu_brand.replace("\"", "\\\"")
-    )?;
-    writeln!(
-        file,
-        r#"    "cpu_physical_cores": {},"#,
-        system_info.cpu_physical_cores
-    )?;
-    writeln!(
-        file,
-        r#"    "cpu_logical_cores": {},"#,
-        system_info.cpu_logical_cores
-    )?;
-    writeln!(
-        file,
-        r#"    "total_memory_mb": {},"#,
-        system_info.total_memory_mb
-    )?;
-    writeln!(
-        file,
-        r#"    "os_name": "{}","#,
-        system_info.os_name.replace("\"", "\\\"")
-    )?;
-    writeln!(
-        file,
-        r#"    "os_version": "{}","#,
-        system_info.os_version.replace("\"", "\\\"")
-    )?;
-    writeln!(
-        file,
-        r#"    "hostname": "{}""#,
-        system_info.hostname.replace("\"", "\\\"")
-    )?;
-    writeln!(file, "  }},")?;
-
-    // Benchmark configuration
-    writeln!(file, r#"  "configuration": {{"#)?;
-    writeln!(file, r#"    "scale": {},"#, args.scale)?;
-    writeln!(file, r#"    "runs": {},"#, args.count)?;
-    writeln!(file, r#"    "threads": {},"#, args.threads)?;
-    writeln!(file, r#"    "block_size": {}"#, args.block_size)?;
-    writeln!(file, "  }},")?;
-
-    // Results
-    writeln!(file, r#"  "results": {{"#)?;
-
-    // CPU results
-    writeln!(file, r#"    "cpu": {{"#)?;
-    let cpu_primes: Vec<f64> = results.cpu.iter().map(|r| r.primes_per_sec).collect();
-    writeln!(file, r#"      "cpu_primes_per_sec": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_primes
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&cpu_primes))?;
-    writeln!(file, "      }},")?;
-
-    let cpu_matrix: Vec<f64> = results.cpu.iter().map(|r| r.matrix_mult_gflops).collect();
-    writeln!(file, r#"      "cpu_matrix_mult_gflops_st": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_matrix
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&cpu_matrix))?;
-    writeln!(file, "      }},")?;
-
-    let cpu_parallel: Vec<f64> = results
-        .cpu
-        .iter()
-        .map(|r| r.parallel_matrix_gflops)
-        .collect();
-    writeln!(file, r#"      "cpu_matrix_mult_gflops_mt": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_parallel
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(
-        file,
-        r#"        "statistics": {}"#,
-        stats_json(&cpu_parallel)
-    )?;
-    writeln!(file, "      }},")?;
-
-    let cpu_speedup: Vec<f64> = results.cpu.iter().map(|r| r.parallel_speedup).collect();
-    writeln!(file, r#"      "cpu_parallel_speedup": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_speedup
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(
-        file,
-        r#"        "statistics": {}"#,
-        stats_json(&cpu_speedup)
-    )?;
-    writeln!(file, "      }},")?;
-
-    let cpu_mandelbrot: Vec<f64> = results
-        .cpu
-        .iter()
-        .map(|r| r.mandelbrot_pixels_per_sec)
-        .collect();
-    writeln!(file, r#"      "cpu_mandelbrot_pixels_per_sec": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_mandelbrot
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(
-        file,
-        r#"        "statistics": {}"#,
-        stats_json(&cpu_mandelbrot)
-    )?;
-    writeln!(file, "      }},")?;
-
-    let cpu_fft: Vec<f64> = results.cpu.iter().map(|r| r.fft_msamples_per_sec).collect();
-    writeln!(file, r#"      "cpu_fft_msamples_per_sec": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        cpu_fft
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&cpu_fft))?;
-    writeln!(file, "      }}")?;
-    writeln!(file, "    }},")?;
-
-    // Memory results
-    writeln!(file, r#"    "memory": {{"#)?;
-    let mem_write: Vec<f64> = results.memory.iter().map(|r| r.write_throughput).collect();
-    writeln!(file, r#"      "memory_write_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        mem_write
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&mem_write))?;
-    writeln!(file, "      }},")?;
-
-    let mem_read: Vec<f64> = results.memory.iter().map(|r| r.read_throughput).collect();
-    writeln!(file, r#"      "memory_read_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        mem_read
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&mem_read))?;
-    writeln!(file, "      }},")?;
-
-    let mem_combined: Vec<f64> = results
-        .memory
-        .iter()
-        .map(|r| r.combined_throughput)
-        .collect();
-    writeln!(file, r#"      "memory_combined_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        mem_combined
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(
-        file,
-        r#"        "statistics": {}"#,
-        stats_json(&mem_combined)
-    )?;
-    writeln!(file, "      }}")?;
-    writeln!(file, "    }},")?;
-
-    // Disk results
-    writeln!(file, r#"    "disk": {{"#)?;
-    let disk_write: Vec<f64> = results.disk.iter().map(|r| r.write_throughput).collect();
-    writeln!(file, r#"      "disk_write_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        disk_write
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&disk_write))?;
-    writeln!(file, "      }},")?;
-
-    let disk_read: Vec<f64> = results.disk.iter().map(|r| r.read_throughput).collect();
-    writeln!(file, r#"      "disk_read_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        disk_read
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(file, r#"        "statistics": {}"#, stats_json(&disk_read))?;
-    writeln!(file, "      }},")?;
-
-    let disk_combined: Vec<f64> = results.disk.iter().map(|r| r.combined_throughput).collect();
-    writeln!(file, r#"      "disk_combined_throughput_mbs": {{"#)?;
-    writeln!(
-        file,
-        r#"        "runs": [{}],"#,
-        disk_combined
-            .iter()
-            .map(|v| format!("{:.2}", v))
-            .collect::<Vec<_>>()
-            .join(",")
-    )?;
-    writeln!(
-        file,
-        r#"        "statistics": {}"#,
-        stats_json(&disk_combined)
-    )?;
-    writeln!(file, "      }}")?;
-    writeln!(file, "    }}")?;
-
-    writeln!(file, "  }}")?;
-    writeln!(file, "}}")?;
-
-    Ok(())
+    let aggregate = aggregate::aggregate(&loaded.reports);
+    if json {
+        match serde_json::to_string_pretty(&aggregate) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                eprintln!("Error serializing the aggregate: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    print!("{}", renderer.render_aggregate(&aggregate));
+}
+
+fn run_compare(baseline_path: &str, candidate_path: &str, force: bool, renderer: &Renderer) {
+    let load = |path: &str| match compare::load_report(std::path::Path::new(path)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error reading report {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    let baseline = load(baseline_path);
+    let candidate = load(candidate_path);
+
+    if let Some(differences) = compare::fingerprint_mismatch(&baseline, &candidate) {
+        if !force {
+            eprintln!(
+                "Error: the reports have different config fingerprints and measured different work"
+            );
+            for difference in &differences {
+                eprintln!("  {}", difference);
+            }
+            eprintln!("Use --force to compare them anyway");
+            std::process::exit(2);
+        }
+        println!(
+            "{}",
+            renderer.warning(
+                "the reports have different config fingerprints and measured different work"
+            )
+        );
+        for difference in &differences {
+            println!("  {}", difference);
+        }
+        println!();
+    }
+
+    let comparisons = compare::compare_reports(&baseline, &candidate);
+    print!(
+        "{}",
+        renderer.render_comparison(&baseline, &candidate, &comparisons)
+    );
 }
@@ -0,0 +1,152 @@
+/// Run manifest and configuration fingerprint
+/// The manifest lists everything that determines the work behind the shared metrics of a
+/// run: the suite and workload versions, the kernels, the resolved scales, seeds, thread
+/// counts, and access modes. Two runs with the same manifest measured the same work, so
+/// their results differ only by the machine; the fingerprint is a short hash of the
+/// manifest that makes this check cheap. Options that only add metrics (e.g. the memory
+/// access patterns or the disk profiles) and the number of runs are left out, since they
+/// do not change what the shared metrics measure.
+use crate::benchmark;
+use crate::disk::DiskMode;
+use crate::report;
+use crate::suite::{Phase, Schedule, SuiteConfig};
+use serde::{Deserialize, Serialize};
+
+/// Version of the benchmark workloads; raised whenever a kernel changes the work it does
+/// or how it counts it, so results of different workloads never share a fingerprint
+pub const WORKLOAD_VERSION: u32 = 1;
+
+/// Canonical description of the work a run did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// Version of the crate that produced the run
+    pub suite_version: String,
+    pub workload_version: u32,
+    /// Enabled phases, in execution order, e.g. "CPU"
+    pub phases: Vec<String>,
+    /// Registry names of the CPU kernels that ran
+    pub kernels: Vec<String>,
+    /// Resolved scales of the CPU, memory, and disk benchmarks, after calibration
+    pub cpu_scale: f64,
+    pub memory_scale: f64,
+    pub disk_scale: f64,
+    /// Scale of the concurrency, network, and GPU benchmarks
+    pub scale: f64,
+    pub min_time: Option<f64>,
+    pub warmup_runs: usize,
+    pub seed: Option<u64>,
+    pub threads: usize,
+    pub memory_threads: usize,
+    pub pin_cores: bool,
+    pub block_size: usize,
+    pub disk_mode: DiskMode,
+    pub schedule: Schedule,
+}
+
+impl RunManifest {
+    /// Manifest of a run with the resolved configuration `config`
+    pub fn new(config: &SuiteConfig) -> Self {
+        let kernels = if config.cpu {
+            benchmark::phase_kernels(Phase::Cpu)
+                .map(|kernel| kernel.name())
+                .filter(|name| {
+                    config.kernels.is_empty() || config.kernels.iter().any(|k| k == name)
+                })
+                .map(str::to_string)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        RunManifest {
+            suite_version: report::SUITE_VERSION.to_string(),
+            workload_version: WORKLOAD_VERSION,
+            phases: config
+                .phases()
+                .iter()
+                .map(|phase| phase.name().to_string())
+                .collect(),
+            kernels,
+            cpu_scale: config.scale_for(Phase::Cpu),
+            memory_scale: config.scale_for(Phase::Memory),
+            disk_scale: config.scale_for(Phase::Disk),
+            scale: config.scale,
+            min_time: config.min_time,
+            warmup_runs: config.warmup_runs,
+            seed: config.seed,
+            threads: config.threads,
+            memory_threads: config.memory_threads,
+            pin_cores: config.pin_cores,
+            block_size: config.block_size,
+            disk_mode: config.disk_mode,
+            schedule: config.schedule,
+        }
+    }
+
+    /// 64-bit FNV-1a hash of the manifest's JSON form as 16 hex digits, e.g.
+    /// "3f2a9c0d81b4e6f7"; equal manifests always have equal fingerprints
+    pub fn fingerprint(&self) -> String {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        // Serializing a struct of plain fields cannot fail
+        let canonical = serde_json::to_string(self).unwrap_or_default();
+        let hash = canonical.bytes().fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        });
+        format!("{:016x}", hash)
+    }
+
+    /// Fields whose values differ from `other`, e.g. "cpu_scale: 1.0 vs 0.5"
+    pub fn differences(&self, other: &RunManifest) -> Vec<String> {
+        let base = serde_json::to_value(self).unwrap_or_default();
+        let other = serde_json::to_value(other).unwrap_or_default();
+        let mut differences = Vec::new();
+        if let (Some(base), Some(other)) = (base.as_object(), other.as_object()) {
+            for (key, value) in base {
+                let other = other.get(key).unwrap_or(&serde_json::Value::Null);
+                if other != value {
+                    differences.push(format!("{}: {} vs {}", key, value, other));
+                }
+            }
+        }
+        differences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suite::BenchmarkSuite;
+
+    #[test]
+    fn test_fingerprint_follows_the_work() {
+        let manifest = |builder: crate::suite::BenchmarkSuiteBuilder| {
+            RunManifest::new(builder.build().config())
+        };
+        let base = manifest(BenchmarkSuite::builder().scale(0.5));
+        assert_eq!(base.fingerprint().len(), 16);
+        assert_eq!(
+            base.fingerprint(),
+            manifest(BenchmarkSuite::builder().scale(0.5)).fingerprint()
+        );
+        // More runs and extra memory patterns measure the same work
+        let same = manifest(
+            BenchmarkSuite::builder()
+                .scale(0.5)
+                .runs(10)
+                .memory_scaling(true),
+        );
+        assert_eq!(base.fingerprint(), same.fingerprint());
+
+        let other = manifest(BenchmarkSuite::builder().scale(0.5).cpu_scale(2.0));
+        assert_ne!(base.fingerprint(), other.fingerprint());
+        assert_eq!(base.differences(&other), vec!["cpu_scale: 0.5 vs 2.0"]);
+
+        let selected = manifest(
+            BenchmarkSuite::builder()
+                .scale(0.5)
+                .kernels(vec!["fft".to_string()]),
+        );
+        assert_eq!(selected.kernels, vec!["fft"]);
+        assert_ne!(base.fingerprint(), selected.fingerprint());
+    }
+}
@@ -0,0 +1,523 @@
+/// Network Benchmark Module
+/// Tests TCP throughput, UDP packet rate, and TCP round-trip latency
+/// Runs against an in-process loopback server by default, or against a remote
+/// instance started with `--network-server`
+use crate::error::BenchError;
+use crate::interrupt;
+use crate::progress::Progress;
+use crate::stats::Statistics;
+use crate::timing::{self, KernelTiming, KernelTimings, DEFAULT_WARMUP_RUNS, WARMUP_SCALE};
+use crate::units;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::{
+    Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_NETWORK_PORT: u16 = 9797;
+
+const BASE_TCP_BYTES: usize = 256_000_000; // 256 MB streamed per throughput test
+const BASE_UDP_PACKETS: usize = 100_000;
+const BASE_LATENCY_ROUNDS: usize = 10_000;
+const TCP_CHUNK_SIZE: usize = 128 * 1024;
+const UDP_PACKET_SIZE: usize = 64;
+const UDP_WINDOW: usize = 64; // Packets in flight before waiting for echoes
+const LATENCY_MESSAGE_SIZE: usize = 64;
+const UDP_TIMEOUT: Duration = Duration::from_millis(100);
+/// Limit for connecting and for every read and write of a TCP connection, so a server that
+/// stops responding fails the benchmark instead of hanging it, and a client that goes
+/// silent does not hold a server thread forever
+const TCP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// First byte of every TCP connection selects the server behavior
+const CMD_THROUGHPUT: u8 = b'T';
+const CMD_LATENCY: u8 = b'L';
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkResult {
+    pub tcp_throughput: f64,
+    pub udp_packets_per_sec: f64,
+    pub latency_p50_us: f64,
+    pub latency_p95_us: f64,
+    pub latency_p99_us: f64,
+    /// Raw work and elapsed time behind the rates above, keyed by field name; `latency`
+    /// holds the round trips and their summed time
+    #[serde(default)]
+    pub timings: KernelTimings,
+}
+
+pub fn run_network_benchmark() -> Result<NetworkResult, BenchError> {
+    run_network_benchmark_scaled(1.0, None)
+}
+
+/// Run the network benchmark against `target` (host:port of a server started with
+/// `run_network_server`), or against an in-process loopback server when `target` is None
+pub fn run_network_benchmark_scaled(
+    scale: f64,
+    target: Option<&str>,
+) -> Result<NetworkResult, BenchError> {
+    run_network_benchmark_with_progress(scale, target, DEFAULT_WARMUP_RUNS, &mut ())
+}
+
+/// Run the network benchmark, announcing each step to `progress`
+/// `warmup_runs` short latency exchanges run first to establish routes and wake the server
+pub fn run_network_benchmark_with_progress(
+    scale: f64,
+    target: Option<&str>,
+    warmup_runs: usize,
+    progress: &mut dyn Progress,
+) -> Result<NetworkResult, BenchError> {
+    let result = match target {
+        Some(target) => {
+            let addr = resolve(target).map_err(|e| BenchError::io("network target", e))?;
+            run_against(addr, addr, scale, warmup_runs, progress)
+        }
+        None => {
+            let server =
+                Server::loopback().map_err(|e| BenchError::io("network loopback server", e))?;
+            run_against(
+                server.tcp_addr,
+                server.udp_addr,
+                scale,
+                warmup_runs,
+                progress,
+            )
+        }
+    };
+    let result = result.map_err(|e| BenchError::io("network", stalled(e)))?;
+    timing::check_timings("network", &result.timings)?;
+    Ok(result)
+}
+
+/// Serve throughput, latency, and UDP echo requests on `bind_addr` until the process is
+/// interrupted (see `interrupt::install`)
+pub fn run_network_server(bind_addr: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(bind_addr)?;
+    let udp = UdpSocket::bind(listener.local_addr()?)?;
+    let _server = Server::start(listener, udp)?;
+    while interrupt::sleep(Duration::from_secs(1)) {}
+    Ok(())
+}
+
+fn resolve(target: &str) -> io::Result<SocketAddr> {
+    target.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("could not resolve network target {}", target),
+        )
+    })
+}
+
+/// `error` with a readable message when it is a socket timeout, which is reported as
+/// `WouldBlock` on Unix and `TimedOut` elsewhere
+fn stalled(error: io::Error) -> io::Error {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("the server did not respond within {:?}", TCP_TIMEOUT),
+        ),
+        _ => error,
+    }
+}
+
+/// Connect to `addr` with `timeout` for the connection and every read and write
+fn connect(addr: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+    let stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    Ok(stream)
+}
+
+fn run_against(
+    tcp_addr: SocketAddr,
+    udp_addr: SocketAddr,
+    scale: f64,
+    warmup_runs: usize,
+    progress: &mut dyn Progress,
+) -> io::Result<NetworkResult> {
+    // Warmup phase: short latency exchange to establish routes and wake the server
+    if warmup_runs > 0 {
+        progress.step("warmup");
+    }
+    for _ in 0..warmup_runs {
+        measure_latency(
+            tcp_addr,
+            (BASE_LATENCY_ROUNDS as f64 * scale * WARMUP_SCALE) as usize + 1,
+            TCP_TIMEOUT,
+        )?;
+    }
+
+    let tcp_bytes = ((BASE_TCP_BYTES as f64 * scale) as usize).max(TCP_CHUNK_SIZE);
+    let udp_packets = ((BASE_UDP_PACKETS as f64 * scale) as usize).max(UDP_WINDOW);
+    let latency_rounds = ((BASE_LATENCY_ROUNDS as f64 * scale) as usize).max(100);
+
+    progress.step("TCP throughput");
+    let tcp = measure_tcp_throughput(tcp_addr, tcp_bytes)?;
+    progress.step("UDP packet rate");
+    let udp = measure_udp_packet_rate(udp_addr, udp_packets)?;
+    progress.step("TCP latency");
+    let latencies = measure_latency(tcp_addr, latency_rounds, TCP_TIMEOUT)?;
+    let latency = Statistics::from_values(&latencies)
+        .ok_or_else(|| io::Error::other("no latency samples recorded"))?;
+    let round_trips = KernelTiming::new(
+        1.0,
+        latencies.len() as u64,
+        latencies.iter().sum::<f64>() / 1_000_000.0,
+    );
+
+    Ok(NetworkResult {
+        tcp_throughput: tcp.rate(),
+        udp_packets_per_sec: udp.rate(),
+        latency_p50_us: latency.p50,
+        latency_p95_us: latency.p95,
+        latency_p99_us: latency.p99,
+        timings: KernelTimings::from([
+            ("tcp_throughput".to_string(), tcp),
+            ("udp_packets_per_sec".to_string(), udp),
+            ("latency".to_string(), round_trips),
+        ]),
+    })
+}
+
+/// Stream `total_bytes` to the server and wait for its byte count acknowledgement
+/// Returns: MB sent, per second
+fn measure_tcp_throughput(addr: SocketAddr, total_bytes: usize) -> io::Result<KernelTiming> {
+    let chunk = vec![0xABu8; TCP_CHUNK_SIZE];
+    let mut stream = connect(addr, TCP_TIMEOUT)?;
+    stream.write_all(&[CMD_THROUGHPUT])?;
+
+    let start = Instant::now();
+    let mut bytes_sent = 0;
+    while bytes_sent < total_bytes {
+        let send_size = (total_bytes - bytes_sent).min(TCP_CHUNK_SIZE);
+        stream.write_all(&chunk[..send_size])?;
+        bytes_sent += send_size;
+    }
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut ack = [0u8; 8];
+    stream.read_exact(&mut ack)?;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let received = u64::from_le_bytes(ack) as usize;
+    if received != total_bytes {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("server received {} of {} bytes", received, total_bytes),
+        ));
+    }
+
+    Ok(KernelTiming::new(
+        total_bytes as f64 / units::MEBIBYTE,
+        1,
+        elapsed,
+    ))
+}
+
+/// Send windows of UDP packets and count the echoes that come back
+/// Lost packets are not counted, so loss lowers the reported rate; echoes of an earlier
+/// window that arrive late are dropped rather than counted for the current one
+/// Returns: echoed packets, per second
+fn measure_udp_packet_rate(addr: SocketAddr, total_packets: usize) -> io::Result<KernelTiming> {
+    let bind_addr: SocketAddr = if addr.is_ipv4() {
+        "0.0.0.0:0".parse().unwrap()
+    } else {
+        "[::]:0".parse().unwrap()
+    };
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.connect(addr)?;
+    socket.set_read_timeout(Some(UDP_TIMEOUT))?;
+
+    let mut packet = [0u8; UDP_PACKET_SIZE];
+    let mut reply = [0u8; UDP_PACKET_SIZE];
+    let mut sent = 0;
+    let mut received = 0;
+
+    let start = Instant::now();
+    while sent < total_packets {
+        let window_start = sent as u64;
+        let window = (total_packets - sent).min(UDP_WINDOW);
+        for _ in 0..window {
+            packet[..8].copy_from_slice(&(sent as u64).to_le_bytes());
+            socket.send(&packet)?;
+            sent += 1;
+        }
+        let mut pending = window;
+        while pending > 0 {
+            match socket.recv(&mut reply) {
+                Ok(n) => {
+                    if echoed_in_window(&reply[..n], window_start, sent as u64) {
+                        received += 1;
+                        pending -= 1;
+                    }
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    if received == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "no UDP echoes received from server",
+        ));
+    }
+
+    Ok(KernelTiming::new(received as f64, 1, elapsed))
+}
+
+/// Whether `reply` echoes one of the packets numbered `first..end`
+fn echoed_in_window(reply: &[u8], first: u64, end: u64) -> bool {
+    reply
+        .get(..8)
+        .and_then(|sequence| sequence.try_into().ok())
+        .map(u64::from_le_bytes)
+        .is_some_and(|sequence| (first..end).contains(&sequence))
+}
+
+/// Ping-pong small messages over a TCP_NODELAY connection, failing when the server does not
+/// answer within `timeout`
+/// Returns: round-trip time of each exchange in microseconds
+fn measure_latency(addr: SocketAddr, rounds: usize, timeout: Duration) -> io::Result<Vec<f64>> {
+    let mut stream = connect(addr, timeout)?;
+    stream.set_nodelay(true)?;
+    stream.write_all(&[CMD_LATENCY])?;
+
+    let message = [0x5Au8; LATENCY_MESSAGE_SIZE];
+    let mut reply = [0u8; LATENCY_MESSAGE_SIZE];
+    let mut latencies = Vec::with_capacity(rounds);
+
+    for _ in 0..rounds {
+        let start = Instant::now();
+        stream.write_all(&message)?;
+        stream.read_exact(&mut reply)?;
+        latencies.push(timing::measured_since(start).as_secs_f64() * 1_000_000.0);
+    }
+
+    Ok(latencies)
+}
+
+/// Server threads for a TCP listener and a UDP socket, stopped when dropped
+struct Server {
+    tcp_addr: SocketAddr,
+    udp_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Server {
+    /// In-process server on ephemeral loopback ports
+    fn loopback() -> io::Result<Self> {
+        Server::start(
+            TcpListener::bind("127.0.0.1:0")?,
+            UdpSocket::bind("127.0.0.1:0")?,
+        )
+    }
+
+    fn start(listener: TcpListener, udp: UdpSocket) -> io::Result<Self> {
+        let tcp_addr = listener.local_addr()?;
+        let udp_addr = udp.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let tcp_stop = Arc::clone(&stop);
+        let udp_stop = Arc::clone(&stop);
+        let handles = vec![
+            thread::spawn(move || serve_tcp(listener, tcp_stop)),
+            thread::spawn(move || serve_udp(udp, udp_stop)),
+        ];
+
+        Ok(Server {
+            tcp_addr,
+            udp_addr,
+            stop,
+            handles,
+        })
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept() so the listener thread can observe the stop flag; a
+        // listener on all interfaces is reached through loopback
+        let mut wake = self.tcp_addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(match wake {
+                SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+            });
+        }
+        let _ = TcpStream::connect_timeout(&wake, TCP_TIMEOUT);
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve_tcp(listener: TcpListener, stop: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        if let Ok(stream) = stream {
+            // Connection errors only affect the client that caused them
+            thread::spawn(move || {
+                let _ = handle_tcp_connection(stream);
+            });
+        }
+    }
+}
+
+fn handle_tcp_connection(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TCP_TIMEOUT))?;
+    stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+    let mut command = [0u8; 1];
+    stream.read_exact(&mut command)?;
+
+    match command[0] {
+        CMD_THROUGHPUT => {
+            // Sink: count bytes until the client shuts down its side, then acknowledge
+            let mut buffer = vec![0u8; TCP_CHUNK_SIZE];
+            let mut total = 0u64;
+            loop {
+                let n = stream.read(&mut buffer)?;
+                if n == 0 {
+                    break;
+                }
+                total += n as u64;
+            }
+            stream.write_all(&total.to_le_bytes())
+        }
+        CMD_LATENCY => {
+            // Echo fixed-size messages until the client disconnects
+            stream.set_nodelay(true)?;
+            let mut message = [0u8; LATENCY_MESSAGE_SIZE];
+            loop {
+                match stream.read_exact(&mut message) {
+                    Ok(()) => stream.write_all(&message)?,
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unknown network benchmark command",
+        )),
+    }
+}
+
+fn serve_udp(socket: UdpSocket, stop: Arc<AtomicBool>) {
+    // Periodic timeout lets the loop observe the stop flag
+    let _ = socket.set_read_timeout(Some(UDP_TIMEOUT));
+    let mut buffer = [0u8; 2048];
+    while !stop.load(Ordering::SeqCst) {
+        if let Ok((n, peer)) = socket.recv_from(&mut buffer) {
+            let _ = socket.send_to(&buffer[..n], peer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_benchmark_loopback() {
+        // Use lightweight scale for CI/testing
+        let result = run_network_benchmark_scaled(0.05, None).expect("loopback benchmark failed");
+        assert!(result.tcp_throughput > 0.0);
+        assert!(result.udp_packets_per_sec > 0.0);
+        assert!(result.latency_p50_us > 0.0);
+    }
+
+    #[test]
+    fn test_network_latency_percentiles_ordered() {
+        let result = run_network_benchmark_scaled(0.05, None).expect("loopback benchmark failed");
+        assert!(result.latency_p50_us <= result.latency_p95_us);
+        assert!(result.latency_p95_us <= result.latency_p99_us);
+    }
+
+    #[test]
+    fn test_network_benchmark_against_target() {
+        let server = Server::loopback().expect("failed to start loopback server");
+        let target = server.tcp_addr.to_string();
+        // Explicit target uses the same port for TCP and UDP, so only check TCP paths here
+        let addr = resolve(&target).unwrap();
+        let throughput = measure_tcp_throughput(addr, 1_000_000).unwrap();
+        let latencies = measure_latency(addr, 50, TCP_TIMEOUT).unwrap();
+        assert!(throughput.work > 0.0 && throughput.rate() > 0.0);
+        assert_eq!(latencies.len(), 50);
+    }
+
+    #[test]
+    fn test_network_unresolvable_target() {
+        assert!(run_network_benchmark_scaled(0.05, Some("not a host")).is_err());
+    }
+
+    #[test]
+    fn test_udp_late_echoes_are_not_counted() {
+        let echo = |sequence: u64| {
+            let mut reply = [0u8; UDP_PACKET_SIZE];
+            reply[..8].copy_from_slice(&sequence.to_le_bytes());
+            reply
+        };
+        assert!(echoed_in_window(&echo(64), 64, 128));
+        assert!(echoed_in_window(&echo(127), 64, 128));
+        // An echo of the previous window arriving after its timeout
+        assert!(!echoed_in_window(&echo(63), 64, 128));
+        assert!(!echoed_in_window(&echo(128), 64, 128));
+        assert!(!echoed_in_window(&[0u8; 4], 0, 128));
+    }
+
+    #[test]
+    fn test_network_silent_server_times_out() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let timeout = Duration::from_millis(100);
+        let start = Instant::now();
+        let error = measure_latency(addr, 1, timeout).unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        ));
+        assert!(start.elapsed() >= timeout && start.elapsed() < TCP_TIMEOUT);
+        assert_eq!(stalled(error).kind(), io::ErrorKind::TimedOut);
+        drop(listener);
+    }
+
+    #[test]
+    fn test_network_server_on_all_interfaces_stops() {
+        let listener = TcpListener::bind("0.0.0.0:0").unwrap();
+        let udp = UdpSocket::bind(listener.local_addr().unwrap()).unwrap();
+        let server = Server::start(listener, udp).unwrap();
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, server.tcp_addr.port()));
+        assert_eq!(measure_latency(addr, 10, TCP_TIMEOUT).unwrap().len(), 10);
+        // Dropping joins the server threads, which must see the stop flag
+        drop(server);
+    }
+
+    #[test]
+    fn test_network_server_rejects_unknown_command() {
+        let server = Server::loopback().expect("failed to start loopback server");
+        let mut stream = TcpStream::connect(server.tcp_addr).unwrap();
+        stream.write_all(b"?").unwrap();
+        // Server closes the connection without replying
+        let mut buffer = [0u8; 1];
+        assert_eq!(stream.read(&mut buffer).unwrap_or(0), 0);
+    }
+}
@@ -0,0 +1,381 @@
+/// Memory buffers backed by huge pages
+/// With 4 KB pages a 512 MB buffer spans 131072 pages, far more than any TLB holds, so
+/// streaming through it keeps missing the TLB; 2 MB pages cut the page count 512-fold.
+/// Linux first asks for explicitly reserved pages (`MAP_HUGETLB`, which needs
+/// `vm.nr_hugepages`) and falls back to transparent huge pages (`madvise(MADV_HUGEPAGE)`).
+/// Windows uses large pages (`MEM_LARGE_PAGES`), which need the "Lock pages in memory"
+/// user right. Elsewhere, or when the OS refuses, buffers come from the heap.
+/// Buffers of fresh regular pages are mapped straight from the OS, so their first touch
+/// faults every page in; the heap may hand back memory it already faulted in.
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// How the pages of a huge-page buffer were obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HugePageKind {
+    /// Pages reserved by the administrator (Linux `MAP_HUGETLB`)
+    Explicit,
+    /// Transparent huge pages requested with `madvise`; the kernel may still use 4 KB
+    /// pages where no 2 MB page is free
+    Transparent,
+    /// Windows large pages
+    Large,
+}
+
+impl HugePageKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            HugePageKind::Explicit => "explicit",
+            HugePageKind::Transparent => "transparent",
+            HugePageKind::Large => "large",
+        }
+    }
+}
+
+/// Zero-filled byte buffer, on huge pages when requested and available
+pub struct PageBuffer {
+    memory: Memory,
+    len: usize,
+}
+
+enum Memory {
+    Heap(Vec<u8>),
+    Mapped {
+        ptr: NonNull<u8>,
+        size: usize,
+        /// None for regular pages
+        kind: Option<HugePageKind>,
+    },
+}
+
+// SAFETY: the buffer owns its mapping exclusively, as a Vec owns its heap memory
+unsafe impl Send for PageBuffer {}
+
+impl PageBuffer {
+    /// `len` zero bytes; with `huge_pages` the buffer is mapped on huge pages if the OS
+    /// provides them and allocated on the heap otherwise
+    pub fn zeroed(len: usize, huge_pages: bool) -> Self {
+        let mapped = if huge_pages && len > 0 {
+            map_huge(len)
+        } else {
+            None
+        };
+        let memory = match mapped {
+            Some((ptr, size, kind)) => Memory::Mapped {
+                ptr,
+                size,
+                kind: Some(kind),
+            },
+            None => Memory::Heap(vec![0u8; len]),
+        };
+        PageBuffer { memory, len }
+    }
+
+    /// `len` zero bytes on regular pages mapped from the OS, none of them resident until
+    /// first touched; from the heap where the OS cannot map them
+    pub fn fresh(len: usize) -> Self {
+        let mapped = if len > 0 { map_regular(len) } else { None };
+        let memory = match mapped {
+            Some((ptr, size)) => Memory::Mapped {
+                ptr,
+                size,
+                kind: None,
+            },
+            None => Memory::Heap(vec![0u8; len]),
+        };
+        PageBuffer { memory, len }
+    }
+
+    /// Kind of huge pages behind the buffer; None for regular pages
+    pub fn kind(&self) -> Option<HugePageKind> {
+        match self.memory {
+            Memory::Heap(_) => None,
+            Memory::Mapped { kind, .. } => kind,
+        }
+    }
+}
+
+/// Size of a regular page, e.g. 4 KB on x86_64 and 16 KB on Apple Silicon
+pub fn page_size() -> usize {
+    #[cfg(unix)]
+    {
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if size > 0 {
+            return size as usize;
+        }
+    }
+    // Windows uses 4 KB pages on every CPU it runs on
+    4096
+}
+
+/// Kind of huge pages this process can get right now; None where only regular pages are
+/// available
+pub fn huge_page_kind() -> Option<HugePageKind> {
+    PageBuffer::zeroed(1, true).kind()
+}
+
+impl Deref for PageBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.memory {
+            Memory::Heap(buffer) => buffer,
+            // SAFETY: the mapping holds at least `len` bytes, zeroed by the OS
+            Memory::Mapped { ptr, .. } => unsafe {
+                std::slice::from_raw_parts(ptr.as_ptr(), self.len)
+            },
+        }
+    }
+}
+
+impl DerefMut for PageBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match &mut self.memory {
+            Memory::Heap(buffer) => buffer,
+            // SAFETY: the mapping holds at least `len` bytes and is owned by this buffer
+            Memory::Mapped { ptr, .. } => unsafe {
+                std::slice::from_raw_parts_mut(ptr.as_ptr(), self.len)
+            },
+        }
+    }
+}
+
+impl Drop for PageBuffer {
+    fn drop(&mut self) {
+        if let Memory::Mapped { ptr, size, .. } = self.memory {
+            unmap(ptr, size);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Returns the mapping, its size (`len` rounded up to whole pages), and the page kind
+#[cfg(target_os = "linux")]
+fn map_huge(len: usize) -> Option<(NonNull<u8>, usize, HugePageKind)> {
+    let size = len.next_multiple_of(HUGE_PAGE_SIZE);
+    let map = |flags: libc::c_int| {
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags,
+                -1,
+                0,
+            )
+        };
+        (ptr != libc::MAP_FAILED).then_some(ptr)
+    };
+
+    if let Some(ptr) = map(libc::MAP_HUGETLB) {
+        return Some((NonNull::new(ptr.cast())?, size, HugePageKind::Explicit));
+    }
+    let ptr = map(0)?;
+    if unsafe { libc::madvise(ptr, size, libc::MADV_HUGEPAGE) } != 0 {
+        // Transparent huge pages are disabled or unsupported
+        unsafe { libc::munmap(ptr, size) };
+        return None;
+    }
+    Some((NonNull::new(ptr.cast())?, size, HugePageKind::Transparent))
+}
+
+/// Returns the mapping and its size (`len` rounded up to whole pages)
+#[cfg(unix)]
+fn map_regular(len: usize) -> Option<(NonNull<u8>, usize)> {
+    let size = len.next_multiple_of(page_size());
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return None;
+    }
+    Some((NonNull::new(ptr.cast())?, size))
+}
+
+#[cfg(unix)]
+fn unmap(ptr: NonNull<u8>, size: usize) {
+    unsafe {
+        libc::munmap(ptr.as_ptr().cast(), size);
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    pub struct Luid {
+        pub low: u32,
+        pub high: i32,
+    }
+
+    #[repr(C)]
+    pub struct TokenPrivileges {
+        pub count: u32,
+        pub luid: Luid,
+        pub attributes: u32,
+    }
+
+    pub const MEM_COMMIT: u32 = 0x1000;
+    pub const MEM_RESERVE: u32 = 0x2000;
+    pub const MEM_RELEASE: u32 = 0x8000;
+    pub const MEM_LARGE_PAGES: u32 = 0x2000_0000;
+    pub const PAGE_READWRITE: u32 = 0x04;
+    pub const TOKEN_ADJUST_PRIVILEGES: u32 = 0x20;
+    pub const SE_PRIVILEGE_ENABLED: u32 = 0x02;
+    pub const ERROR_SUCCESS: u32 = 0;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetLargePageMinimum() -> usize;
+        pub fn VirtualAlloc(
+            address: *mut c_void,
+            size: usize,
+            allocation_type: u32,
+            protect: u32,
+        ) -> *mut c_void;
+        pub fn VirtualFree(address: *mut c_void, size: usize, free_type: u32) -> i32;
+        pub fn GetCurrentProcess() -> *mut c_void;
+        pub fn CloseHandle(handle: *mut c_void) -> i32;
+        pub fn GetLastError() -> u32;
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        pub fn OpenProcessToken(process: *mut c_void, access: u32, token: *mut *mut c_void) -> i32;
+        pub fn LookupPrivilegeValueW(system: *const u16, name: *const u16, luid: *mut Luid) -> i32;
+        pub fn AdjustTokenPrivileges(
+            token: *mut c_void,
+            disable_all: i32,
+            new_state: *const TokenPrivileges,
+            length: u32,
+            previous: *mut c_void,
+            return_length: *mut u32,
+        ) -> i32;
+    }
+}
+
+/// Enable SeLockMemoryPrivilege for this process; it is held but disabled by default for
+/// users with the "Lock pages in memory" right, and large pages fail without it
+#[cfg(windows)]
+fn enable_lock_memory_privilege() -> bool {
+    use windows::*;
+    static ENABLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ENABLED.get_or_init(|| unsafe {
+        let mut token = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &mut token) == 0 {
+            return false;
+        }
+        let name: Vec<u16> = "SeLockMemoryPrivilege\0".encode_utf16().collect();
+        let mut privileges = TokenPrivileges {
+            count: 1,
+            luid: Luid { low: 0, high: 0 },
+            attributes: SE_PRIVILEGE_ENABLED,
+        };
+        let enabled = LookupPrivilegeValueW(std::ptr::null(), name.as_ptr(), &mut privileges.luid)
+            != 0
+            && AdjustTokenPrivileges(
+                token,
+                0,
+                &privileges,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            ) != 0
+            // Succeeds without assigning the privilege when the user lacks the right
+            && GetLastError() == ERROR_SUCCESS;
+        CloseHandle(token);
+        enabled
+    })
+}
+
+#[cfg(windows)]
+fn map_huge(len: usize) -> Option<(NonNull<u8>, usize, HugePageKind)> {
+    use windows::*;
+    let page_size = unsafe { GetLargePageMinimum() };
+    if page_size == 0 || !enable_lock_memory_privilege() {
+        return None;
+    }
+    let size = len.next_multiple_of(page_size);
+    let ptr = unsafe {
+        VirtualAlloc(
+            std::ptr::null_mut(),
+            size,
+            MEM_COMMIT | MEM_RESERVE | MEM_LARGE_PAGES,
+            PAGE_READWRITE,
+        )
+    };
+    Some((NonNull::new(ptr.cast())?, size, HugePageKind::Large))
+}
+
+/// Committed pages are zero-filled on first touch, like anonymous mappings elsewhere
+#[cfg(windows)]
+fn map_regular(len: usize) -> Option<(NonNull<u8>, usize)> {
+    use windows::*;
+    let size = len.next_multiple_of(page_size());
+    let ptr = unsafe {
+        VirtualAlloc(
+            std::ptr::null_mut(),
+            size,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_READWRITE,
+        )
+    };
+    Some((NonNull::new(ptr.cast())?, size))
+}
+
+#[cfg(windows)]
+fn unmap(ptr: NonNull<u8>, _size: usize) {
+    unsafe {
+        windows::VirtualFree(ptr.as_ptr().cast(), 0, windows::MEM_RELEASE);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn map_huge(_len: usize) -> Option<(NonNull<u8>, usize, HugePageKind)> {
+    None
+}
+
+#[cfg(not(any(unix, windows)))]
+fn map_regular(_len: usize) -> Option<(NonNull<u8>, usize)> {
+    None
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unmap(_ptr: NonNull<u8>, _size: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_buffer_is_zeroed_and_writable() {
+        for huge_pages in [false, true] {
+            let mut buffer = PageBuffer::zeroed(3 * 1024 * 1024 + 7, huge_pages);
+            assert_eq!(buffer.len(), 3 * 1024 * 1024 + 7);
+            assert!(buffer.iter().all(|&byte| byte == 0));
+            buffer.fill(0xAB);
+            assert_eq!(buffer[buffer.len() - 1], 0xAB);
+        }
+        assert!(PageBuffer::zeroed(64, false).kind().is_none());
+        assert!(PageBuffer::zeroed(0, true).is_empty());
+
+        let mut fresh = PageBuffer::fresh(3 * page_size() + 7);
+        assert!(fresh.iter().all(|&byte| byte == 0) && fresh.kind().is_none());
+        fresh.fill(0xCD);
+        assert_eq!(fresh[fresh.len() - 1], 0xCD);
+        assert!(PageBuffer::fresh(0).is_empty());
+        assert!(page_size().is_power_of_two());
+    }
+}
@@ -0,0 +1,245 @@
+/// Hardware performance counters per benchmark kernel
+/// With the `perf` feature on Linux, `perf_event_open` counts the instructions, cycles, and
+/// last-level cache references and misses of the benchmark process (user space only, so
+/// the default `perf_event_paranoid` level of 2 suffices). The counters are opened before
+/// the thread pool is spawned so they also count its workers, and each kernel is charged
+/// with the counts since the previous one, which turns a raw rate into an explanation: a
+/// low IPC or a high miss rate shows what the kernel waited on. Without the feature, on
+/// other platforms, or where the PMU is not exposed (most virtual machines), nothing is
+/// recorded.
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Readings closer together than this belong to the same kernel run (e.g. the sieve's
+/// prime rate and byte rate), which share its counts
+const SAME_RUN_SECS: f64 = 0.001;
+
+/// Counts of one kernel run, in user space of all benchmark threads
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct KernelCounters {
+    pub instructions: u64,
+    pub cycles: u64,
+    /// Last-level cache accesses and misses; None where the CPU does not count them
+    pub llc_references: Option<u64>,
+    pub llc_misses: Option<u64>,
+}
+
+impl KernelCounters {
+    /// Instructions per cycle
+    pub fn ipc(&self) -> Option<f64> {
+        (self.cycles > 0).then(|| self.instructions as f64 / self.cycles as f64)
+    }
+
+    /// Fraction of last-level cache accesses that missed
+    pub fn llc_miss_rate(&self) -> Option<f64> {
+        match (self.llc_references?, self.llc_misses?) {
+            (0, _) => None,
+            (references, misses) => Some(misses as f64 / references as f64),
+        }
+    }
+}
+
+/// Counts per kernel, keyed like the `timings` of a result
+pub type KernelCounterMap = IndexMap<String, KernelCounters>;
+
+/// Charges the hardware counts to the kernels of a benchmark as they finish
+pub struct CounterTracker {
+    counters: sys::Counters,
+    last: [Option<u64>; 4],
+    last_at: Instant,
+    last_counts: Option<KernelCounters>,
+    kernels: KernelCounterMap,
+}
+
+impl CounterTracker {
+    /// Start counting for this thread and the threads it spawns from now on; None where
+    /// the instruction and cycle counters cannot be opened
+    pub fn start() -> Option<Self> {
+        let counters = sys::Counters::open()?;
+        let last = counters.read();
+        Some(Self {
+            counters,
+            last,
+            last_at: Instant::now(),
+            last_counts: None,
+            kernels: KernelCounterMap::new(),
+        })
+    }
+
+    /// Start the next kernel's interval now, leaving out what ran since the previous
+    /// kernel (e.g. warmup)
+    pub fn mark(&mut self) {
+        self.last = self.counters.read();
+        self.last_at = Instant::now();
+        self.last_counts = None;
+    }
+
+    /// Record kernel `name` as having run since the previous kernel or mark
+    pub fn record(&mut self, name: &str) {
+        if self.last_at.elapsed().as_secs_f64() >= SAME_RUN_SECS {
+            let now = self.counters.read();
+            let delta = |i: usize| Some(now[i]?.saturating_sub(self.last[i]?));
+            self.last_counts = Some(KernelCounters {
+                instructions: delta(0).unwrap_or(0),
+                cycles: delta(1).unwrap_or(0),
+                llc_references: delta(2),
+                llc_misses: delta(3),
+            });
+            self.last = now;
+            self.last_at = Instant::now();
+        }
+        if let Some(counts) = self.last_counts {
+            self.kernels.insert(name.to_string(), counts);
+        }
+    }
+
+    pub fn finish(self) -> KernelCounterMap {
+        self.kernels
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod sys {
+    use std::io::Read;
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    /// Instructions, cycles, LLC references, and LLC misses
+    const EVENTS: [u64; 4] = [1, 0, 2, 3];
+    const FLAG_INHERIT: u64 = 1 << 1;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+    const READ_TIME_ENABLED_AND_RUNNING: u64 = 1 | 2;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 8;
+
+    /// `struct perf_event_attr` up to PERF_ATTR_SIZE_VER5; the fields this module does not
+    /// set stay zero
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        reserved: [u64; 8],
+    }
+
+    pub struct Counters {
+        files: [Option<std::fs::File>; 4],
+    }
+
+    impl Counters {
+        pub fn open() -> Option<Self> {
+            let files = EVENTS.map(open_counter);
+            (files[0].is_some() && files[1].is_some()).then_some(Self { files })
+        }
+
+        /// Current counts, scaled up for the time a counter was multiplexed out
+        pub fn read(&self) -> [Option<u64>; 4] {
+            let read = |file: &Option<std::fs::File>| {
+                let mut buffer = [0u8; 24];
+                let mut file: &std::fs::File = file.as_ref()?;
+                file.read_exact(&mut buffer).ok()?;
+                let word =
+                    |i: usize| u64::from_ne_bytes(buffer[i * 8..i * 8 + 8].try_into().unwrap());
+                let (value, enabled, running) = (word(0), word(1), word(2));
+                Some(if running > 0 && running < enabled {
+                    (value as f64 * enabled as f64 / running as f64) as u64
+                } else {
+                    value
+                })
+            };
+            [
+                read(&self.files[0]),
+                read(&self.files[1]),
+                read(&self.files[2]),
+                read(&self.files[3]),
+            ]
+        }
+    }
+
+    fn open_counter(config: u64) -> Option<std::fs::File> {
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: READ_TIME_ENABLED_AND_RUNNING,
+            flags: FLAG_INHERIT | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+        // SAFETY: attr outlives the call; pid 0 and cpu -1 count this thread and, through
+        // `inherit`, the threads it creates later, on any CPU
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0,
+                -1,
+                -1,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        // SAFETY: a non-negative result is a new file descriptor owned by nobody else
+        (fd >= 0).then(|| std::fs::File::from(unsafe { OwnedFd::from_raw_fd(fd as i32) }))
+    }
+}
+
+/// Counters are unavailable without the `perf` feature or outside Linux
+#[cfg(not(all(feature = "perf", target_os = "linux")))]
+mod sys {
+    pub enum Counters {}
+
+    impl Counters {
+        pub fn open() -> Option<Self> {
+            None
+        }
+
+        pub fn read(&self) -> [Option<u64>; 4] {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_counter_ratios() {
+        let counts = KernelCounters {
+            instructions: 3000,
+            cycles: 1000,
+            llc_references: Some(200),
+            llc_misses: Some(50),
+        };
+        assert_eq!(counts.ipc(), Some(3.0));
+        assert_eq!(counts.llc_miss_rate(), Some(0.25));
+        assert!(KernelCounters::default().ipc().is_none());
+        assert!(KernelCounters::default().llc_miss_rate().is_none());
+    }
+
+    #[test]
+    fn test_counter_tracker() {
+        let Some(mut tracker) = CounterTracker::start() else {
+            // No feature, no Linux, or no PMU (e.g. in a virtual machine)
+            return;
+        };
+        let work = |n: u64| (0..n).fold(0u64, |acc, i| acc.wrapping_mul(31).wrapping_add(i));
+        std::hint::black_box(work(1_000_000));
+        tracker.mark();
+        std::thread::scope(|scope| {
+            scope.spawn(|| std::hint::black_box(work(10_000_000)));
+        });
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        tracker.record("work");
+        tracker.record("same_run");
+        let kernels = tracker.finish();
+        assert!(kernels["work"].instructions >= 10_000_000);
+        assert!(kernels["work"].ipc().unwrap() > 0.0);
+        assert_eq!(kernels["same_run"], kernels["work"]);
+    }
+}
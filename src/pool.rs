@@ -0,0 +1,233 @@
+/// Thread pool shared by the parallel benchmarks
+/// Workers are spawned (and pinned) once per benchmark, so timed regions measure the
+/// kernels rather than thread creation. Work is handed out in chunks from a shared counter:
+/// workers that finish early claim the remaining chunks instead of idling, and every chunk
+/// is an exclusive output slice, so results need no lock-protected merge.
+use crate::affinity;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Borrowed job; only valid while `ThreadPool::broadcast` is waiting for it to finish
+type Job = &'static (dyn Fn(usize) + Sync);
+
+struct State {
+    job: Option<Job>,
+    generation: u64,
+    remaining: usize,
+    panicked: bool,
+    shutdown: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    work_ready: Condvar,
+    work_done: Condvar,
+}
+
+/// Fixed set of worker threads that run one job at a time
+/// Jobs must not call back into the pool they run on
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+    dispatch: Mutex<()>,
+}
+
+impl ThreadPool {
+    /// Spawn `threads` workers (at least one); with a non-empty `cores` list, worker `i` is
+    /// pinned to `cores[i % cores.len()]`
+    pub fn new(threads: usize, cores: &[usize]) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                job: None,
+                generation: 0,
+                remaining: 0,
+                panicked: false,
+                shutdown: false,
+            }),
+            work_ready: Condvar::new(),
+            work_done: Condvar::new(),
+        });
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let shared = Arc::clone(&shared);
+                let core = affinity::core_for(cores, index);
+                thread::Builder::new()
+                    .name(format!("bench-worker-{}", index))
+                    .spawn(move || {
+                        affinity::pin_worker(core);
+                        worker_loop(&shared, index);
+                    })
+                    .expect("failed to spawn benchmark worker thread")
+            })
+            .collect();
+        Self {
+            shared,
+            workers,
+            dispatch: Mutex::new(()),
+        }
+    }
+
+    /// Number of worker threads
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Run `job(worker_index)` once on every worker and wait until all have finished
+    /// Panics if the job panicked on any worker; the pool stays usable afterwards
+    pub fn broadcast(&self, job: impl Fn(usize) + Sync) {
+        let _dispatch = self.dispatch.lock().unwrap_or_else(|e| e.into_inner());
+        let job: &(dyn Fn(usize) + Sync) = &job;
+        // SAFETY: the reference is only reachable through `state.job`, which is cleared
+        // below after every worker has finished with it, and this function does not
+        // return before then, so the job outlives all uses of the extended reference
+        let job: Job = unsafe { std::mem::transmute::<&(dyn Fn(usize) + Sync), Job>(job) };
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.job = Some(job);
+        state.generation += 1;
+        state.remaining = self.workers.len();
+        state.panicked = false;
+        self.shared.work_ready.notify_all();
+        while state.remaining > 0 {
+            state = self.shared.work_done.wait(state).unwrap();
+        }
+        state.job = None;
+        let panicked = state.panicked;
+        drop(state);
+
+        if panicked {
+            panic!("benchmark worker thread panicked");
+        }
+    }
+
+    /// Run `job(worker_index)` on every worker and collect the results in worker order
+    pub fn map<T: Send>(&self, job: impl Fn(usize) -> T + Sync) -> Vec<T> {
+        let results: Vec<Mutex<Option<T>>> =
+            (0..self.threads()).map(|_| Mutex::new(None)).collect();
+        self.broadcast(|index| {
+            *results[index].lock().unwrap() = Some(job(index));
+        });
+        results
+            .into_iter()
+            .map(|result| {
+                result
+                    .into_inner()
+                    .unwrap()
+                    .expect("every worker runs the job")
+            })
+            .collect()
+    }
+
+    /// Split `data` into chunks of `chunk_len` elements and run `job(chunk_index, chunk)`
+    /// on each; every chunk is claimed by exactly one worker through a shared counter
+    pub fn for_each_chunk_mut<T: Send>(
+        &self,
+        data: &mut [T],
+        chunk_len: usize,
+        job: impl Fn(usize, &mut [T]) + Sync,
+    ) {
+        let chunks: Vec<Mutex<&mut [T]>> =
+            data.chunks_mut(chunk_len.max(1)).map(Mutex::new).collect();
+        let next = AtomicUsize::new(0);
+        self.broadcast(|_| loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(chunk) = chunks.get(index) else {
+                break;
+            };
+            job(index, &mut chunk.lock().unwrap());
+        });
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        {
+            let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.shutdown = true;
+            self.shared.work_ready.notify_all();
+        }
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn worker_loop(shared: &Shared, index: usize) {
+    let mut seen = 0;
+    loop {
+        let job = {
+            let mut state = shared.state.lock().unwrap();
+            while state.generation == seen && !state.shutdown {
+                state = shared.work_ready.wait(state).unwrap();
+            }
+            if state.shutdown {
+                return;
+            }
+            seen = state.generation;
+            state.job.expect("a new generation always has a job")
+        };
+
+        // Catch panics so that the dispatcher is always told the job has finished
+        let result = panic::catch_unwind(AssertUnwindSafe(|| job(index)));
+
+        let mut state = shared.state.lock().unwrap();
+        state.panicked |= result.is_err();
+        state.remaining -= 1;
+        if state.remaining == 0 {
+            shared.work_done.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadcast_runs_on_every_worker() {
+        let pool = ThreadPool::new(4, &[]);
+        assert_eq!(pool.threads(), 4);
+        let calls = AtomicUsize::new(0);
+        pool.broadcast(|_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        pool.broadcast(|_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(calls.load(Ordering::Relaxed), 8);
+        // Zero threads still spawns one worker
+        assert_eq!(ThreadPool::new(0, &[]).threads(), 1);
+    }
+
+    #[test]
+    fn test_map_returns_results_in_worker_order() {
+        let pool = ThreadPool::new(3, &[]);
+        assert_eq!(pool.map(|index| index * 10), vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn test_for_each_chunk_mut_visits_every_chunk_once() {
+        let pool = ThreadPool::new(3, &[]);
+        let mut data = vec![0usize; 100];
+        pool.for_each_chunk_mut(&mut data, 7, |chunk_index, chunk| {
+            for value in chunk.iter_mut() {
+                *value += chunk_index + 1;
+            }
+        });
+        for (i, value) in data.iter().enumerate() {
+            assert_eq!(*value, i / 7 + 1);
+        }
+    }
+
+    #[test]
+    fn test_worker_panic_propagates_and_pool_recovers() {
+        let pool = ThreadPool::new(2, &[]);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.broadcast(|index| assert_ne!(index, 1, "worker failure"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(pool.map(|index| index), vec![0, 1]);
+    }
+}
@@ -0,0 +1,243 @@
+/// Energy measurement of the CPU benchmark
+/// Reads the CPU package energy counters of Intel RAPL (also implemented by recent AMD
+/// CPUs) through the Linux powercap interface, so each kernel can be reported as
+/// performance per watt next to its raw rate. Most distributions only let root read the
+/// counters; elsewhere, and on platforms without powercap (macOS SMC sensors are not
+/// accessible without private frameworks), no energy is reported.
+use crate::timing::KernelTimings;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Readings closer together than this belong to the same kernel run (e.g. the sieve's
+/// prime rate and byte rate), which share the power measured for the run
+const SAME_RUN_SECS: f64 = 0.001;
+
+/// Energy used by one CPU benchmark run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyResult {
+    /// Package energy from the first to the last kernel
+    pub joules: f64,
+    pub seconds: f64,
+    /// Average package power while each kernel ran, keyed like `CpuResult::timings`
+    pub kernel_watts: IndexMap<String, f64>,
+}
+
+impl EnergyResult {
+    pub fn average_watts(&self) -> f64 {
+        self.joules / self.seconds
+    }
+
+    /// Rate of kernel `name` per watt, e.g. GFLOPS/W, which equals work per joule
+    pub fn per_watt(&self, name: &str, timings: &KernelTimings) -> Option<f64> {
+        let watts = *self.kernel_watts.get(name)?;
+        let timing = timings.get(name)?;
+        (watts > 0.0).then(|| timing.rate() / watts)
+    }
+}
+
+/// One powercap zone: a CPU package
+struct Zone {
+    energy_file: PathBuf,
+    /// The counter wraps to zero after this many microjoules
+    max_range_uj: u64,
+}
+
+/// Cumulative energy counters of every package at one moment
+#[derive(Debug, Clone)]
+pub struct EnergyReading {
+    at: Instant,
+    counters_uj: Vec<u64>,
+}
+
+/// Package energy counters of the CPUs
+pub struct EnergyMeter {
+    zones: Vec<Zone>,
+}
+
+impl EnergyMeter {
+    /// The package counters of this system; None where they are missing or unreadable
+    #[cfg(target_os = "linux")]
+    pub fn open() -> Option<Self> {
+        Self::from_powercap(Path::new("/sys/class/powercap"))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open() -> Option<Self> {
+        None
+    }
+
+    /// The package zones under `root` (normally /sys/class/powercap): the top-level
+    /// `intel-rapl:N` zones named "package-N"; subzones such as the cores are part of
+    /// their package, and the platform ("psys") zone would count the packages twice
+    pub fn from_powercap(root: &Path) -> Option<Self> {
+        let read = |path: PathBuf| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        let mut zones: Vec<(String, Zone)> = std::fs::read_dir(root)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                let index = name.strip_prefix("intel-rapl:")?;
+                index.parse::<usize>().ok()?;
+                let dir = entry.path();
+                read(dir.join("name"))?
+                    .starts_with("package")
+                    .then_some(())?;
+                let zone = Zone {
+                    energy_file: dir.join("energy_uj"),
+                    max_range_uj: read(dir.join("max_energy_range_uj"))?.parse().ok()?,
+                };
+                Some((name, zone))
+            })
+            .collect();
+        zones.sort_by(|a, b| a.0.cmp(&b.0));
+        let meter = Self {
+            zones: zones.into_iter().map(|(_, zone)| zone).collect(),
+        };
+        (!meter.zones.is_empty() && meter.read().is_some()).then_some(meter)
+    }
+
+    /// Current counters; None if any of them cannot be read
+    pub fn read(&self) -> Option<EnergyReading> {
+        let counters_uj = self
+            .zones
+            .iter()
+            .map(|zone| {
+                std::fs::read_to_string(&zone.energy_file)
+                    .ok()?
+                    .trim()
+                    .parse()
+                    .ok()
+            })
+            .collect::<Option<Vec<u64>>>()?;
+        Some(EnergyReading {
+            at: Instant::now(),
+            counters_uj,
+        })
+    }
+
+    /// Energy used by all packages between two readings, allowing each counter to wrap once
+    pub fn joules_between(&self, start: &EnergyReading, end: &EnergyReading) -> f64 {
+        let microjoules: u64 = self
+            .zones
+            .iter()
+            .zip(start.counters_uj.iter().zip(&end.counters_uj))
+            .map(|(zone, (&start, &end))| {
+                if end >= start {
+                    end - start
+                } else {
+                    zone.max_range_uj - start + end
+                }
+            })
+            .sum();
+        microjoules as f64 / 1e6
+    }
+}
+
+/// Attributes package energy to the kernels of a benchmark as they finish
+pub struct EnergyTracker {
+    meter: EnergyMeter,
+    start: EnergyReading,
+    last: EnergyReading,
+    last_watts: Option<f64>,
+    kernel_watts: IndexMap<String, f64>,
+}
+
+impl EnergyTracker {
+    /// Start measuring; None where the energy counters cannot be read
+    pub fn start() -> Option<Self> {
+        Self::with_meter(EnergyMeter::open()?)
+    }
+
+    pub fn with_meter(meter: EnergyMeter) -> Option<Self> {
+        let start = meter.read()?;
+        Some(Self {
+            meter,
+            last: start.clone(),
+            start,
+            last_watts: None,
+            kernel_watts: IndexMap::new(),
+        })
+    }
+
+    /// Record kernel `name` as having run since the previous kernel (or the start)
+    pub fn record(&mut self, name: &str) {
+        let Some(now) = self.meter.read() else {
+            return;
+        };
+        let seconds = now.at.duration_since(self.last.at).as_secs_f64();
+        if seconds >= SAME_RUN_SECS {
+            self.last_watts = Some(self.meter.joules_between(&self.last, &now) / seconds);
+            self.last = now;
+        }
+        if let Some(watts) = self.last_watts {
+            self.kernel_watts.insert(name.to_string(), watts);
+        }
+    }
+
+    /// Energy from the start to the last recorded kernel
+    pub fn finish(self) -> EnergyResult {
+        EnergyResult {
+            joules: self.meter.joules_between(&self.start, &self.last),
+            seconds: self.last.at.duration_since(self.start.at).as_secs_f64(),
+            kernel_watts: self.kernel_watts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing::KernelTiming;
+
+    #[test]
+    fn test_energy_from_powercap() {
+        let root = std::env::temp_dir().join(format!("hsbench_rapl_{}", std::process::id()));
+        let write = |file: &str, value: &str| {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        for (zone, name) in [
+            ("intel-rapl:0", "package-0"),
+            ("intel-rapl:0:0", "core"),
+            ("intel-rapl:1", "psys"),
+        ] {
+            write(&format!("{}/name", zone), &format!("{}\n", name));
+            write(&format!("{}/max_energy_range_uj", zone), "1000000000\n");
+            write(&format!("{}/energy_uj", zone), "999000000\n");
+        }
+        let meter = EnergyMeter::from_powercap(&root).unwrap();
+        assert_eq!(meter.zones.len(), 1);
+
+        let mut tracker = EnergyTracker::with_meter(meter).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        // 2 J later the counter has wrapped
+        write("intel-rapl:0/energy_uj", "1000000\n");
+        tracker.record("primes_per_sec");
+        tracker.record("sieve_mibs");
+        let energy = tracker.finish();
+        let unreadable = EnergyMeter::from_powercap(Path::new("/nonexistent"));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!((energy.joules - 2.0).abs() < 1e-9);
+        let watts = energy.kernel_watts["primes_per_sec"];
+        assert!(watts > 0.0 && watts <= 100.0);
+        assert_eq!(energy.kernel_watts["sieve_mibs"], watts);
+        assert!((energy.average_watts() - 2.0 / energy.seconds).abs() < 1e-9);
+
+        let timings = KernelTimings::from([(
+            "primes_per_sec".to_string(),
+            KernelTiming::new(1000.0, 1, 1.0),
+        )]);
+        let per_watt = energy.per_watt("primes_per_sec", &timings).unwrap();
+        assert!((per_watt - 1000.0 / watts).abs() < 1e-9);
+        assert!(energy.per_watt("aes_mibs", &timings).is_none());
+        assert!(unreadable.is_none());
+    }
+}
@@ -0,0 +1,227 @@
+/// Progress reporting for long benchmark runs
+/// Benchmarks announce each step through the `Progress` callback trait, and `ProgressBar`
+/// turns the suite's run, phase, and step events into a single status line with the
+/// elapsed time and an estimate of the time remaining
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 20;
+
+/// Receives the name of each benchmark step as it starts
+pub trait Progress {
+    fn step(&mut self, name: &str);
+}
+
+/// Progress sink that ignores all steps
+impl Progress for () {
+    fn step(&mut self, _name: &str) {}
+}
+
+/// Console status line of the form
+/// `Run 2/10 [####----------------]  20% | Memory: STREAM | 1m 05s elapsed, ETA 4m 20s`
+/// Drawn on stderr, and only when stderr is a terminal
+pub struct ProgressBar {
+    total_runs: usize,
+    phases_per_run: usize,
+    enabled: bool,
+    drawn: bool,
+    start: Instant,
+    run_start: Instant,
+    run_durations: Vec<Duration>,
+    run: usize,
+    phases_started: usize,
+    phase: String,
+    step: String,
+}
+
+impl ProgressBar {
+    pub fn new(total_runs: usize, phases_per_run: usize) -> Self {
+        let now = Instant::now();
+        Self {
+            total_runs: total_runs.max(1),
+            phases_per_run: phases_per_run.max(1),
+            enabled: io::stderr().is_terminal(),
+            drawn: false,
+            start: now,
+            run_start: now,
+            run_durations: Vec::new(),
+            run: 0,
+            phases_started: 0,
+            phase: String::new(),
+            step: String::new(),
+        }
+    }
+
+    pub fn run_started(&mut self, run: usize) {
+        let now = Instant::now();
+        if self.run > 0 {
+            self.run_durations.push(now - self.run_start);
+        }
+        self.run = run;
+        self.run_start = now;
+        self.phases_started = 0;
+        self.phase.clear();
+        self.step.clear();
+        self.draw();
+    }
+
+    pub fn phase_started(&mut self, name: &str) {
+        self.phases_started += 1;
+        self.phase = name.to_string();
+        self.step.clear();
+        self.draw();
+    }
+
+    /// Fraction of the whole suite completed, counting finished phases only
+    pub fn fraction(&self) -> f64 {
+        let run_fraction =
+            self.phases_started.saturating_sub(1) as f64 / self.phases_per_run as f64;
+        ((self.run_durations.len() as f64 + run_fraction) / self.total_runs as f64).min(1.0)
+    }
+
+    /// Estimated time until the suite finishes; None until there is something to go by
+    pub fn eta(&self) -> Option<Duration> {
+        let run_fraction =
+            self.phases_started.saturating_sub(1) as f64 / self.phases_per_run as f64;
+        estimate_remaining(
+            &self.run_durations,
+            self.total_runs,
+            self.run_start.elapsed(),
+            run_fraction,
+        )
+    }
+
+    pub fn status_line(&self) -> String {
+        let fraction = self.fraction();
+        let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+        let mut line = format!(
+            "Run {}/{} [{}{}] {:>3.0}%",
+            self.run,
+            self.total_runs,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            fraction * 100.0
+        );
+        if !self.phase.is_empty() {
+            line.push_str(&format!(" | {}", self.phase));
+            if !self.step.is_empty() {
+                line.push_str(&format!(": {}", self.step));
+            }
+        }
+        line.push_str(&format!(
+            " | {} elapsed, ETA {}",
+            format_duration(self.start.elapsed()),
+            self.eta().map_or("--".to_string(), format_duration)
+        ));
+        line
+    }
+
+    /// Erase the status line so that regular output can be printed
+    pub fn clear(&mut self) {
+        if self.drawn {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+            self.drawn = false;
+        }
+    }
+
+    fn draw(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        eprint!("\r\x1b[2K{}", self.status_line());
+        let _ = io::stderr().flush();
+        self.drawn = true;
+    }
+}
+
+impl Progress for ProgressBar {
+    fn step(&mut self, name: &str) {
+        self.step = name.to_string();
+        self.draw();
+    }
+}
+
+/// Time remaining for a suite of `total_runs` runs
+/// Once a run has completed, its average duration is used for the remaining runs;
+/// during the first run the elapsed time is extrapolated from `run_fraction`
+fn estimate_remaining(
+    run_durations: &[Duration],
+    total_runs: usize,
+    run_elapsed: Duration,
+    run_fraction: f64,
+) -> Option<Duration> {
+    let completed = run_durations.len();
+    let run_estimate = if completed > 0 {
+        run_durations.iter().sum::<Duration>() / completed as u32
+    } else if run_fraction > 0.0 {
+        run_elapsed.div_f64(run_fraction)
+    } else {
+        return None;
+    };
+    let later_runs = total_runs.saturating_sub(completed + 1) as u32;
+    Some(run_estimate.saturating_sub(run_elapsed) + run_estimate * later_runs)
+}
+
+/// Compact human-readable duration, e.g. "45s", "3m 07s", or "1h 02m"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(187)), "3m 07s");
+        assert_eq!(format_duration(Duration::from_secs(3720)), "1h 02m");
+    }
+
+    #[test]
+    fn test_estimate_remaining_first_run() {
+        assert!(estimate_remaining(&[], 3, Duration::from_secs(10), 0.0).is_none());
+        // Half of the first run took 10s: 10s left in this run plus two 20s runs
+        let eta = estimate_remaining(&[], 3, Duration::from_secs(10), 0.5).unwrap();
+        assert_eq!(eta, Duration::from_secs(50));
+    }
+
+    #[test]
+    fn test_estimate_remaining_uses_completed_runs() {
+        let runs = [Duration::from_secs(20), Duration::from_secs(40)];
+        // Average run is 30s: 25s left in run 3 and one more run
+        let eta = estimate_remaining(&runs, 4, Duration::from_secs(5), 0.9).unwrap();
+        assert_eq!(eta, Duration::from_secs(55));
+        // A run that overruns the average does not produce a negative estimate
+        let eta = estimate_remaining(&runs, 3, Duration::from_secs(45), 0.5).unwrap();
+        assert_eq!(eta, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_progress_bar_fraction_and_status() {
+        let mut bar = ProgressBar::new(2, 2);
+        bar.enabled = false;
+        bar.run_started(1);
+        bar.phase_started("CPU");
+        assert_eq!(bar.fraction(), 0.0);
+        bar.phase_started("Memory");
+        bar.step("STREAM");
+        assert_eq!(bar.fraction(), 0.25);
+
+        let line = bar.status_line();
+        assert!(line.starts_with("Run 1/2 [#####---------------]  25%"));
+        assert!(line.contains("| Memory: STREAM |"));
+
+        bar.run_started(2);
+        assert_eq!(bar.fraction(), 0.5);
+        assert!(bar.eta().is_some());
+    }
+}
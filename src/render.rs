@@ -0,0 +1,2078 @@
+/// Console rendering of benchmark results
+/// Results are printed as aligned tables of labeled metrics. A metric printed again (the
+/// next run of the suite, or the next --watch iteration) is followed by its change from the
+/// previous value, colored green when it improved and red when it regressed.
+use crate::args::BenchmarkArgs;
+use hs_benchmark_suite::aggregate::Aggregate;
+use hs_benchmark_suite::benchmark::{self, Listing};
+use hs_benchmark_suite::compare::{self, MetricComparison, Verdict};
+use hs_benchmark_suite::concurrency::ConcurrencyResult;
+use hs_benchmark_suite::cpu::CpuResult;
+use hs_benchmark_suite::disk::{DiskMode, DiskResult, DiskTarget, IoMode};
+use hs_benchmark_suite::gpu::GpuResult;
+use hs_benchmark_suite::history::{self, HistoryEntry, MetricTrend, DEFAULT_HISTORY_FILE};
+use hs_benchmark_suite::memory::{self, CacheProbeResult, MemoryResult};
+use hs_benchmark_suite::network::NetworkResult;
+use hs_benchmark_suite::progress::format_duration;
+use hs_benchmark_suite::report::{report_path, SuiteReport};
+use hs_benchmark_suite::scoring::{self, Scores};
+use hs_benchmark_suite::stats::{HistogramSummary, StreamingStats};
+use hs_benchmark_suite::stress::{StressConfig, StressResult};
+use hs_benchmark_suite::sysinfo_capture::{SimdLevel, SystemInfo};
+use hs_benchmark_suite::thermal::ThermalResult;
+use hs_benchmark_suite::timing::{self, KernelTiming, DEFAULT_WARMUP_RUNS};
+use hs_benchmark_suite::units::{Unit, UnitSystem};
+use hs_benchmark_suite::{Phase, Schedule, SuitePlan};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Duration;
+
+/// Changes smaller than this many percent are left uncolored as run-to-run noise
+const NOISE_PERCENT: f64 = 1.0;
+
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// When to color the console output (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            _ => None,
+        }
+    }
+
+    /// Whether output is colored in this mode
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/// Which direction of change is an improvement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    HigherIsBetter,
+    LowerIsBetter,
+    /// Conditions such as clock speed or temperature, shown without a change
+    Neutral,
+}
+
+struct Row {
+    label: String,
+    /// None for text rows
+    value: Option<f64>,
+    precision: usize,
+    unit: Unit,
+    /// Text of text rows; metric rows format their value when rendered
+    text: String,
+    note: String,
+    direction: Direction,
+}
+
+/// Labeled metrics of one benchmark, printed with aligned labels, values, and units
+pub struct Table {
+    title: String,
+    rows: Vec<Row>,
+}
+
+impl Table {
+    /// `title` is printed above the rows and keys the changes between runs; an empty title
+    /// prints the rows alone
+    pub fn new(title: &str) -> Self {
+        Table {
+            title: title.to_string(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a metric where a higher value is better, such as a throughput
+    pub fn rate(&mut self, label: &str, value: f64, precision: usize, unit: Unit) -> &mut Self {
+        self.metric(label, value, precision, unit, Direction::HigherIsBetter)
+    }
+
+    /// Add a metric where a lower value is better, such as a latency
+    pub fn latency(&mut self, label: &str, value: f64, precision: usize, unit: Unit) -> &mut Self {
+        self.metric(label, value, precision, unit, Direction::LowerIsBetter)
+    }
+
+    /// Add a measured condition that is neither better nor worse when it changes
+    pub fn value(&mut self, label: &str, value: f64, precision: usize, unit: Unit) -> &mut Self {
+        self.metric(label, value, precision, unit, Direction::Neutral)
+    }
+
+    /// Add a row of text, such as a name or a duration
+    pub fn text(&mut self, label: &str, text: impl Into<String>) -> &mut Self {
+        self.rows.push(Row {
+            label: label.to_string(),
+            value: None,
+            precision: 0,
+            unit: Unit::Score,
+            text: text.into(),
+            note: String::new(),
+            direction: Direction::Neutral,
+        });
+        self
+    }
+
+    /// Annotate the last row, e.g. with a speedup or the code path used
+    pub fn note(&mut self, note: impl Into<String>) -> &mut Self {
+        if let Some(row) = self.rows.last_mut() {
+            row.note = note.into();
+        }
+        self
+    }
+
+    /// Show the last row's value in `unit` instead of the unit it was measured in, e.g.
+    /// ops/sec as M/s; ignored when the units measure different quantities
+    pub fn shown_in(&mut self, unit: Unit) -> &mut Self {
+        if let Some(row) = self.rows.last_mut() {
+            if let Some(value) = row.value.and_then(|value| row.unit.convert(value, unit)) {
+                row.value = Some(value);
+                row.unit = unit;
+            }
+        }
+        self
+    }
+
+    fn metric(
+        &mut self,
+        label: &str,
+        value: f64,
+        precision: usize,
+        unit: Unit,
+        direction: Direction,
+    ) -> &mut Self {
+        self.rows.push(Row {
+            label: label.to_string(),
+            value: Some(value),
+            precision,
+            unit,
+            text: String::new(),
+            note: String::new(),
+            direction,
+        });
+        self
+    }
+}
+
+/// Prints tables, headings, and warnings, colored or not, and remembers every printed
+/// metric so the next table with the same title shows the change
+pub struct Renderer {
+    color: bool,
+    units: UnitSystem,
+    previous: HashMap<String, f64>,
+}
+
+impl Renderer {
+    pub fn new(mode: ColorMode) -> Self {
+        Renderer {
+            color: mode.enabled(),
+            units: UnitSystem::Si,
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Show byte rates with the prefixes of `units`
+    pub fn with_units(mut self, units: UnitSystem) -> Self {
+        self.units = units;
+        self
+    }
+
+    pub fn print(&mut self, table: &Table) {
+        print!("{}", self.render(table));
+    }
+
+    /// `=== text ===`, bold when colored
+    pub fn heading(&self, text: &str) -> String {
+        self.paint(BOLD, &format!("=== {} ===", text))
+    }
+
+    /// `WARNING: text`, yellow when colored
+    pub fn warning(&self, text: &str) -> String {
+        self.paint(YELLOW, &format!("WARNING: {}", text))
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Lines of `table`, with the change of every metric that was printed before
+    fn render(&mut self, table: &Table) -> String {
+        let deltas: Vec<Option<(String, Option<bool>)>> = table
+            .rows
+            .iter()
+            .map(|row| {
+                let value = row.value?;
+                let key = format!("{}/{}", table.title, row.label);
+                let previous = self.previous.insert(key, value)?;
+                delta(previous, value, row.direction)
+            })
+            .collect();
+        let has_deltas = deltas.iter().any(Option::is_some);
+
+        let numbers = || {
+            table.rows.iter().filter_map(|row| {
+                let (value, unit) = row.unit.display(row.value?, self.units);
+                Some((format!("{:.*}", row.precision, value), unit))
+            })
+        };
+        let label_width = widest(table.rows.iter().map(|row| &row.label));
+        let value_width = widest(numbers().map(|(text, _)| text));
+        let unit_width = widest(numbers().map(|(_, unit)| unit.symbol()));
+
+        let mut out = String::new();
+        if !table.title.is_empty() {
+            out.push_str(&self.paint(BOLD, &table.title));
+            out.push('\n');
+        }
+        for (row, delta) in table.rows.iter().zip(deltas) {
+            let mut line = format!("  {:<width$}  ", row.label, width = label_width);
+            if let Some(value) = row.value {
+                let (value, unit) = row.unit.display(value, self.units);
+                line.push_str(&format!(
+                    "{:>value_width$} {:<unit_width$}",
+                    format!("{:.*}", row.precision, value),
+                    unit.symbol(),
+                    value_width = value_width,
+                    unit_width = unit_width
+                ));
+                if has_deltas {
+                    // Pad before coloring, the escape codes have no width on screen
+                    let (text, improved) = delta.unwrap_or_default();
+                    let text = format!("{:>8}", text);
+                    line.push_str(&match improved {
+                        Some(true) => self.paint(GREEN, &text),
+                        Some(false) => self.paint(RED, &text),
+                        None => text,
+                    });
+                }
+            } else {
+                line.push_str(&row.text);
+            }
+            if !row.note.is_empty() {
+                line.push_str("  ");
+                line.push_str(&row.note);
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// One column per run and the average of the runs for every metric of `runs`, which
+    /// are tables of the same benchmark; text rows are left out
+    pub fn render_runs(&self, runs: &[Table]) -> String {
+        let Some(first) = runs.first() else {
+            return String::new();
+        };
+        // Metrics in the order they first appear, as optional rows may be missing in a run
+        let mut labels: Vec<&Row> = Vec::new();
+        for row in runs.iter().flat_map(|table| &table.rows) {
+            if row.value.is_some() && !labels.iter().any(|seen| seen.label == row.label) {
+                labels.push(row);
+            }
+        }
+
+        let mut lines: Vec<(String, Vec<String>, Unit)> = Vec::new();
+        for row in labels {
+            let values: Vec<Option<f64>> = runs
+                .iter()
+                .map(|table| {
+                    table
+                        .rows
+                        .iter()
+                        .find(|other| other.label == row.label)
+                        .and_then(|other| other.value)
+                })
+                .collect();
+            let measured: Vec<f64> = values.iter().flatten().copied().collect();
+            let average = measured.iter().sum::<f64>() / measured.len() as f64;
+            let shown = |value: f64| {
+                let (value, _) = row.unit.display(value, self.units);
+                format!("{:.*}", row.precision, value)
+            };
+            let mut cells: Vec<String> = values
+                .iter()
+                .map(|value| value.map_or_else(|| "-".to_string(), shown))
+                .collect();
+            cells.push(shown(average));
+            lines.push((row.label.clone(), cells, row.unit.in_system(self.units)));
+        }
+
+        let mut headers: Vec<String> = (1..=runs.len()).map(|run| format!("Run {}", run)).collect();
+        headers.push("Average".to_string());
+        let label_width = widest(lines.iter().map(|(label, _, _)| label));
+        let cell_width = widest(lines.iter().flat_map(|(_, cells, _)| cells).chain(&headers));
+
+        let mut out = self.paint(BOLD, &first.title);
+        out.push('\n');
+        let header: Vec<String> = headers
+            .iter()
+            .map(|header| format!("{:>width$}", header, width = cell_width))
+            .collect();
+        out.push_str(&format!(
+            "  {:<width$}  {}\n",
+            "",
+            header.join(" "),
+            width = label_width
+        ));
+        for (label, cells, unit) in &lines {
+            let cells: Vec<String> = cells
+                .iter()
+                .map(|cell| format!("{:>width$}", cell, width = cell_width))
+                .collect();
+            let line = format!(
+                "  {:<width$}  {} {}",
+                label,
+                cells.join(" "),
+                unit,
+                width = label_width
+            );
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Comparison of two reports, one line per metric, improvements in green and regressions
+    /// in red
+    pub fn render_comparison(
+        &self,
+        baseline: &SuiteReport,
+        candidate: &SuiteReport,
+        comparisons: &[MetricComparison],
+    ) -> String {
+        let mut out = format!("{}\n", self.heading("Benchmark Comparison"));
+        out.push_str(&format!(
+            "Baseline:  {}\n",
+            compare::report_origin(baseline)
+        ));
+        out.push_str(&format!(
+            "Candidate: {}\n\n",
+            compare::report_origin(candidate)
+        ));
+
+        if let (Some(base), Some(other)) =
+            (&baseline.config_fingerprint, &candidate.config_fingerprint)
+        {
+            out.push_str(&format!("Config Fingerprint: {} vs {}\n\n", base, other));
+        }
+
+        let differences = compare::configuration_differences(baseline, candidate);
+        if !differences.is_empty() {
+            out.push_str(
+                &self.warning("benchmark configuration differs; comparison may be misleading"),
+            );
+            out.push('\n');
+            for difference in &differences {
+                out.push_str(&format!("  {}\n", difference));
+            }
+            out.push('\n');
+        }
+
+        out.push_str(&format!(
+            "{:<36} {:>16} {:>16} {:>14} {:>9} {:>30}  Verdict\n",
+            "Metric", "Baseline", "Candidate", "Delta", "Change", "Delta 95% CI"
+        ));
+        for c in comparisons {
+            let interval = match c.delta_ci95 {
+                Some((low, high)) => format!("[{:+.2}, {:+.2}]", low, high),
+                None => "n/a".to_string(),
+            };
+            let line = format!(
+                "{:<36} {:>16.2} {:>16.2} {:>+14.2} {:>+8.2}% {:>30}  {}",
+                c.metric,
+                c.baseline.mean,
+                c.candidate.mean,
+                c.delta,
+                c.percent_change,
+                interval,
+                c.verdict.label()
+            );
+            out.push_str(&match c.verdict {
+                Verdict::Improvement => self.paint(GREEN, &line),
+                Verdict::Regression => self.paint(RED, &line),
+                Verdict::Unchanged => line,
+            });
+            out.push('\n');
+        }
+
+        let count = |verdict: Verdict| comparisons.iter().filter(|c| c.verdict == verdict).count();
+        let (improvements, regressions) = (count(Verdict::Improvement), count(Verdict::Regression));
+        out.push_str(&format!(
+            "\n{} metrics compared: {} improved, {} regressed, {} within noise\n",
+            comparisons.len(),
+            improvements,
+            regressions,
+            comparisons.len() - improvements - regressions
+        ));
+        out
+    }
+
+    /// Trend of every metric for each hostname and configuration in `entries`, improvements
+    /// in green and regressions in red
+    pub fn render_trends(&self, entries: &[HistoryEntry]) -> String {
+        let mut out = String::new();
+        for ((hostname, hash), group) in history::group_entries(entries) {
+            let first = group.first().expect("groups are never empty");
+            let last = group.last().expect("groups are never empty");
+            out.push_str(&self.heading(&format!(
+                "{} (config {}): {} entries, {} .. {}",
+                hostname,
+                hash,
+                group.len(),
+                first.timestamp,
+                last.timestamp
+            )));
+            out.push('\n');
+            let trends = history::metric_trends(&group);
+            out.push_str(&format!(
+                "{:<40} {:>16} {:>16} {:>9} {:>16}  {:<12} Verdict\n",
+                "Metric", "Previous Mean", "Latest", "Change", "Drift (R²)", "Trend"
+            ));
+            for t in &trends {
+                // Show at most the last 12 entries so the table stays readable
+                let recent = &t.values[t.values.len().saturating_sub(12)..];
+                let drift = match t.fit {
+                    Some(fit) if t.values.len() >= history::MIN_DRIFT_ENTRIES => {
+                        format!("{:+.2}% ({:.2})", t.drift_percent, fit.r_squared)
+                    }
+                    _ => "-".to_string(),
+                };
+                let verdict = match t.drift {
+                    Verdict::Regression => format!("{} (degrading)", t.verdict.label()),
+                    Verdict::Improvement => format!("{} (improving)", t.verdict.label()),
+                    Verdict::Unchanged => t.verdict.label().to_string(),
+                };
+                let line = format!(
+                    "{:<40} {:>16.2} {:>16.2} {:>+8.2}% {:>16}  {:<12} {}",
+                    t.metric,
+                    t.previous_mean,
+                    t.latest,
+                    t.percent_change,
+                    drift,
+                    history::sparkline(recent),
+                    verdict
+                );
+                let regressed = t.verdict == Verdict::Regression || t.drift == Verdict::Regression;
+                let improved = t.verdict == Verdict::Improvement || t.drift == Verdict::Improvement;
+                out.push_str(&match (regressed, improved) {
+                    (true, _) => self.paint(RED, &line),
+                    (false, true) => self.paint(GREEN, &line),
+                    (false, false) => line,
+                });
+                out.push('\n');
+            }
+
+            let steps: Vec<_> = trends
+                .iter()
+                .filter_map(|t| Some((t, t.change_point?)))
+                .collect();
+            if !steps.is_empty() {
+                out.push_str("\nLevel changes:\n");
+            }
+            for (t, point) in steps {
+                // The values skip entries without the metric, so count only those that have it
+                let since = group
+                    .iter()
+                    .filter(|entry| entry.metrics.contains_key(&t.metric))
+                    .nth(point.index)
+                    .map_or("?", |entry| entry.timestamp.as_str());
+                out.push_str(&format!(
+                    "  {}: {:.2} -> {:.2} ({:+.2}%) since {}\n",
+                    t.metric,
+                    point.mean_before,
+                    point.mean_after,
+                    (point.mean_after - point.mean_before) / point.mean_before.abs() * 100.0,
+                    since
+                ));
+            }
+
+            let count = |f: fn(&MetricTrend) -> bool| trends.iter().filter(|t| f(t)).count();
+            out.push_str(&format!(
+                "\n{} metrics tracked, {} regressed in the latest entry, {} degrading gradually\n\n",
+                trends.len(),
+                count(|t| t.verdict == Verdict::Regression),
+                count(|t| t.drift == Verdict::Regression)
+            ));
+        }
+        out
+    }
+
+    /// Score ranking and per-metric table of an aggregate, the best host of every metric in
+    /// green and the worst in red
+    pub fn render_aggregate(&self, aggregate: &Aggregate) -> String {
+        let score = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0}", v));
+
+        let mut out = self.heading(&format!(
+            "Benchmark Aggregate: {} hosts",
+            aggregate.hosts.len()
+        ));
+        out.push('\n');
+        if aggregate.superseded > 0 {
+            out.push_str(&format!(
+                "{} older report(s) of the same hosts left out; the latest report of each host is used\n",
+                aggregate.superseded
+            ));
+        }
+        let fingerprints = aggregate.fingerprints();
+        if fingerprints.len() > 1 {
+            out.push_str(&self.warning(&format!(
+                "the hosts ran with {} different config fingerprints and measured different work; rankings may be misleading",
+                fingerprints.len()
+            )));
+            out.push('\n');
+        }
+        out.push('\n');
+
+        out.push_str(&format!(
+            "{:>4}  {:<32} {:>9} {:>9} {:>9} {:>9}  Report\n",
+            "Rank", "Host", "Overall", "CPU", "Memory", "Disk"
+        ));
+        for (rank, host) in aggregate.hosts.iter().enumerate() {
+            out.push_str(&format!(
+                "{:>4}  {:<32} {:>9} {:>9} {:>9} {:>9}  {}\n",
+                rank + 1,
+                host.hostname,
+                score(host.scores.overall),
+                score(host.scores.cpu),
+                score(host.scores.memory),
+                score(host.scores.disk),
+                host.timestamp
+            ));
+        }
+        if let Some(cv) = aggregate.score_cv_percent {
+            out.push_str(&format!("\nOverall score CV across hosts: {:.1}%\n", cv));
+        }
+        out.push('\n');
+
+        // One column per host, in score order, holding the host's mean and rank
+        let mut header = format!("{:<40}", "Metric");
+        for host in &aggregate.hosts {
+            header.push_str(&format!(
+                " {:>width$}",
+                host_column(&host.hostname),
+                width = HOST_COLUMN_WIDTH
+            ));
+        }
+        out.push_str(&format!("{} {:>8}\n", header, "CV%"));
+        for metric in &aggregate.metrics {
+            let mut line = format!("{:<40}", metric.metric);
+            let last = metric.ranking.len();
+            for host in &aggregate.hosts {
+                let rank = metric.rank_of(&host.hostname);
+                let cell = match rank {
+                    Some(rank) => format!("{:.2} #{}", metric.ranking[rank - 1].value, rank),
+                    None => "-".to_string(),
+                };
+                // Pad before coloring, the escape codes have no width on screen
+                let cell = format!(" {:>width$}", cell, width = HOST_COLUMN_WIDTH);
+                line.push_str(&match rank {
+                    Some(1) if last > 1 => self.paint(GREEN, &cell),
+                    Some(rank) if last > 1 && rank == last => self.paint(RED, &cell),
+                    _ => cell,
+                });
+            }
+            let cv = metric
+                .cv_percent
+                .map_or("-".to_string(), |cv| format!("{:.1}", cv));
+            out.push_str(&format!("{} {:>8}\n", line, cv));
+        }
+        out.push_str(&format!(
+            "\n{} metrics across {} hosts; #1 is the best host (lowest for latencies)\n",
+            aggregate.metrics.len(),
+            aggregate.hosts.len()
+        ));
+        out
+    }
+}
+
+/// Change from `previous` to `value` as text and, outside the noise, whether it is an
+/// improvement
+fn delta(previous: f64, value: f64, direction: Direction) -> Option<(String, Option<bool>)> {
+    if direction == Direction::Neutral || previous == 0.0 {
+        return None;
+    }
+    let percent = (value - previous) / previous.abs() * 100.0;
+    let improved = (percent.abs() >= NOISE_PERCENT)
+        .then_some((percent > 0.0) == (direction == Direction::HigherIsBetter));
+    Some((format!("{:+.1}%", percent), improved))
+}
+
+fn widest(texts: impl Iterator<Item = impl AsRef<str>>) -> usize {
+    texts
+        .map(|text| text.as_ref().chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+fn speedup(factor: f64) -> String {
+    format!("({:.2}x)", factor)
+}
+
+/// Spread of the round times behind a rate, e.g. "rounds P50 1.21 ms, P99 1.5 ms, CV 2.1%";
+/// empty for kernels timed in batches
+fn round_spread(timing: Option<&KernelTiming>) -> String {
+    match timing.and_then(|timing| timing.round_spread) {
+        Some(spread) => format!(
+            "rounds P50 {}, P99 {}, CV {:.1}%",
+            format_latency_us(spread.p50_us),
+            format_latency_us(spread.p99_us),
+            spread.cv_percent
+        ),
+        None => String::new(),
+    }
+}
+
+/// Single-precision rate relative to the double-precision one, e.g. "(1.98x f64)"
+fn precision_gain(f32_rate: f64, f64_rate: f64) -> String {
+    format!("({:.2}x f64)", f32_rate / f64_rate)
+}
+
+/// Predicted duration, e.g. "~3m 07s"; estimates below a second are not worth the digits
+fn estimate(secs: f64) -> String {
+    if secs < 1.0 {
+        "<1s".to_string()
+    } else {
+        format!("~{}", format_duration(Duration::from_secs_f64(secs)))
+    }
+}
+
+/// Options of the run about to start
+pub fn config_table(args: &BenchmarkArgs) -> Table {
+    let mut table = Table::new("");
+    if let Some(config) = &args.config {
+        match &args.profile {
+            Some(profile) => table.text("Config", format!("{} (profile {})", config, profile)),
+            None => table.text("Config", config.as_str()),
+        };
+    }
+    if let Some(phases) = &args.benchmarks {
+        let names: Vec<&str> = phases.iter().map(|phase| phase.name()).collect();
+        table.text("Benchmarks", names.join(", "));
+    }
+    if !args.kernels.is_empty() {
+        let names: Vec<&str> = benchmark::registry()
+            .filter(|kernel| args.kernels.iter().any(|name| name == kernel.name()))
+            .map(|kernel| kernel.description())
+            .collect();
+        table.text("Kernels", names.join(", "));
+    }
+    table.text("Scale", args.scale.to_string());
+    for (label, scale) in [
+        ("CPU Scale", args.cpu_scale),
+        ("Memory Scale", args.mem_scale),
+        ("Disk Scale", args.disk_scale),
+    ] {
+        if let Some(scale) = scale {
+            table.text(label, scale.to_string());
+        }
+    }
+    if let Some(target) = args.auto_scale {
+        table.text(
+            "Auto Scale",
+            format!("calibrating for {}s per kernel pass", target),
+        );
+    }
+    if let Some(min_time) = args.min_time {
+        table.text("Min Time", format!("{}s per benchmark", min_time));
+    }
+    if args.warmup_runs != DEFAULT_WARMUP_RUNS {
+        table.text("Warmup Runs", args.warmup_runs.to_string());
+    }
+    if let Some(seed) = args.seed {
+        table.text("Seed", seed.to_string());
+    }
+    if args.verify {
+        table.text("Verify", "check kernel output against references");
+    }
+    table.text("Runs", args.count.to_string());
+    if args.cooldown > 0.0 {
+        table.text("Cooldown", format!("{}s before each phase", args.cooldown));
+    }
+    if args.schedule != Schedule::Serial {
+        table.text("Schedule", args.schedule.description());
+    }
+    if let Some(timeout) = args.timeout {
+        let limit = if timeout == 0.0 {
+            "disabled".to_string()
+        } else {
+            format!("{}s per step", timeout)
+        };
+        table.text("Step Timeout", limit);
+    }
+    table.text("Threads", args.threads.to_string());
+    table.text("Memory Threads", args.mem_threads.to_string());
+    if !args.mem_patterns.is_empty() {
+        let names: Vec<&str> = args
+            .mem_patterns
+            .iter()
+            .map(|pattern| pattern.name())
+            .collect();
+        table.text("Memory Patterns", names.join(", "));
+    }
+    table.text(
+        "Load Threshold",
+        format!(
+            "{}% of CPU{}",
+            args.load_threshold,
+            if args.exclude_contaminated {
+                " (contaminated runs excluded)"
+            } else {
+                ""
+            }
+        ),
+    );
+    if args.pin_cores {
+        match &args.core_list {
+            Some(cores) => table.text("Core Pinning", format!("cores {:?}", cores)),
+            None => table.text("Core Pinning", "all available cores"),
+        };
+    }
+    let disk_target = DiskTarget::describe(std::path::Path::new(
+        args.disk_path.as_deref().unwrap_or("."),
+    ));
+    table.text("Disk Path", disk_target.summary());
+    table.text("Disk Mode", args.disk_mode.name());
+    if let Some(precondition) = &args.disk_precondition {
+        table.text("Disk Precondition", precondition.summary());
+    }
+    if !args.disk_profiles.is_empty() {
+        let names: Vec<&str> = args
+            .disk_profiles
+            .iter()
+            .map(|profile| profile.name())
+            .collect();
+        table.text("Disk Profiles", names.join(", "));
+    }
+    table.text(
+        "Network",
+        args.network_target.as_deref().unwrap_or("loopback"),
+    );
+    if let Some(dir) = &args.output_dir {
+        table.text("Output Dir", dir.as_str());
+    }
+    if let Some(path) = &args.mandelbrot_image {
+        table.text("Mandelbrot Image", path.as_str());
+    }
+    table
+}
+
+/// Plan of `--dry-run`: what each phase would run at, the memory and disk it needs, the
+/// files it writes, and how long it is predicted to take
+pub fn plan_table(plan: &SuitePlan, args: &BenchmarkArgs) -> Table {
+    let config = &plan.config;
+    let mut table = Table::new("");
+    for phase in &plan.phases {
+        let estimate = match phase.estimated_secs {
+            Some(secs) => format!("{} per run", estimate(secs)),
+            None => "not estimated".to_string(),
+        };
+        let text = match phase.phase {
+            Phase::Network | Phase::Gpu => estimate,
+            _ => format!("scale {:.3}, {}", phase.scale, estimate),
+        };
+        table.text(phase.phase.name(), text);
+    }
+    let cores = config.pinned_cores();
+    if !cores.is_empty() {
+        table.text("Pinned Cores", format!("{:?}", cores));
+    }
+    if let Some(footprint) = &plan.memory_footprint {
+        table.text("Memory Footprint", footprint.summary());
+    }
+    if let (Some(bytes), Some(target)) = (plan.disk_bytes, &plan.disk_target) {
+        table.text(
+            "Disk Space",
+            format!("{:.1} GB on {}", bytes as f64 / 1e9, target.summary()),
+        );
+    }
+
+    let output_dir = Path::new(args.output_dir.as_deref().unwrap_or("."));
+    let reports: Vec<String> = [
+        (args.csv, "csv"),
+        (args.json, "json"),
+        (args.html, "html"),
+        (args.markdown, "md"),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, extension)| report_path(output_dir, extension).display().to_string())
+    .collect();
+    if !reports.is_empty() {
+        table.text("Reports", reports.join(", "));
+    }
+    if args.history {
+        table.text("History File", DEFAULT_HISTORY_FILE);
+    }
+
+    let mut duration = estimate(plan.estimated_secs());
+    if plan.is_partial() {
+        duration += " plus the phases not estimated";
+    }
+    table.text("Estimated Duration", duration);
+    table
+}
+
+/// Results of the CPU benchmark; when only some kernels were selected, their rates as the
+/// registry names them
+pub fn cpu_table(result: &CpuResult) -> Table {
+    let mut table = Table::new(Phase::Cpu.name());
+    let ran_every_kernel = benchmark::phase_kernels(Phase::Cpu)
+        .flat_map(|kernel| kernel.metrics())
+        .all(|metric| result.timings.contains_key(metric.name));
+    if ran_every_kernel {
+        cpu_kernel_rows(&mut table, result);
+    } else {
+        for kernel in benchmark::phase_kernels(Phase::Cpu) {
+            for metric in kernel.metrics() {
+                if let Some(timing) = result.timings.get(metric.name) {
+                    table
+                        .rate(kernel.description(), timing.rate(), 2, metric.unit)
+                        .note(format!("{} {}", metric.name, round_spread(Some(timing))));
+                }
+            }
+        }
+    }
+
+    thermal_rows(&mut table, &result.thermal);
+    if let Some(energy) = &result.energy {
+        table
+            .value("Energy", energy.joules, 1, Unit::Joules)
+            .note(format!("{:.1} W average", energy.average_watts()));
+        if let Some(per_watt) = energy.per_watt("parallel_matrix_gflops", &result.timings) {
+            table.rate("Matrix Mult (MT)/W", per_watt, 3, Unit::GflopsPerWatt);
+        }
+    }
+    if !result.verification.is_empty() {
+        let failed: Vec<&str> = result
+            .verification
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.kernel.as_str())
+            .collect();
+        let checked = format!(
+            "{} of {} kernels match the reference",
+            result.verification.len() - failed.len(),
+            result.verification.len()
+        );
+        if failed.is_empty() {
+            table.text("Verified", checked);
+        } else {
+            table
+                .text("Verified", checked)
+                .note(format!("FAILED: {}", failed.join(", ")));
+        }
+    }
+    table
+}
+
+/// Rows of the full CPU benchmark, with speedups and the SIMD and AES paths
+fn cpu_kernel_rows(table: &mut Table, result: &CpuResult) {
+    table.rate("Primes (ST)", result.primes_per_sec, 0, Unit::PrimesPerSec);
+    table
+        .rate(
+            "Primes (MT)",
+            result.parallel_primes_per_sec,
+            0,
+            Unit::PrimesPerSec,
+        )
+        .note(speedup(result.primes_speedup));
+    table.rate("Sieve", result.sieve_primes_per_sec, 0, Unit::PrimesPerSec);
+    table.rate(
+        "Sieve Throughput",
+        result.sieve_mibs,
+        2,
+        Unit::MebibytesPerSec,
+    );
+    table.rate("Integer", result.integer_giops, 2, Unit::Giops);
+    table.rate(
+        "Branch (Sorted)",
+        result.branch_sorted_melem_per_sec,
+        0,
+        Unit::MelemPerSec,
+    );
+    table.rate(
+        "Branch (Unsorted)",
+        result.branch_unsorted_melem_per_sec,
+        0,
+        Unit::MelemPerSec,
+    );
+    table.latency(
+        "Branch Mispredict",
+        result.branch_mispredict_ns,
+        1,
+        Unit::Nanoseconds,
+    );
+    table.rate(
+        "Sort (u64)",
+        result.sort_u64_melem_per_sec,
+        2,
+        Unit::MelemPerSec,
+    );
+    table.rate(
+        "Sort (Strings)",
+        result.sort_string_melem_per_sec,
+        2,
+        Unit::MelemPerSec,
+    );
+    table.rate("Regex", result.regex_mibs, 2, Unit::MebibytesPerSec);
+    table
+        .rate("AES-128 (CTR)", result.aes_mibs, 2, Unit::MebibytesPerSec)
+        .note(if result.aes_hardware {
+            "[hardware]"
+        } else {
+            "[software]"
+        });
+    table.rate(
+        "Matrix Mult (ST)",
+        result.matrix_mult_gflops,
+        2,
+        Unit::Gflops,
+    );
+    table
+        .rate(
+            "Matrix Blocked (ST)",
+            result.matrix_mult_blocked_gflops,
+            2,
+            Unit::Gflops,
+        )
+        .note(format!("[{}]", result.matrix_simd));
+    table.rate(
+        "Matrix Scalar",
+        result.matrix_scalar_gflops,
+        2,
+        Unit::Gflops,
+    );
+    table
+        .rate("Matrix SIMD", result.matrix_simd_gflops, 2, Unit::Gflops)
+        .note(format!(
+            "[{}] {}",
+            SimdLevel::detect().name(),
+            speedup(result.matrix_simd_gflops / result.matrix_scalar_gflops)
+        ));
+    table
+        .rate("Matrix f32 (ST)", result.matrix_f32_gflops, 2, Unit::Gflops)
+        .note(precision_gain(
+            result.matrix_f32_gflops,
+            result.matrix_mult_blocked_gflops,
+        ));
+    table.rate("GEMM i8->i32 (ST)", result.gemm_i8_gops, 2, Unit::Gops);
+    table
+        .rate(
+            "Matrix Mult (MT)",
+            result.parallel_matrix_gflops,
+            2,
+            Unit::Gflops,
+        )
+        .note(speedup(result.parallel_speedup));
+    let timing = |name: &str| result.timings.get(name);
+    table
+        .rate(
+            "Mandelbrot (ST)",
+            result.mandelbrot_pixels_per_sec,
+            0,
+            Unit::PixelsPerSec,
+        )
+        .note(round_spread(timing("mandelbrot_pixels_per_sec")));
+    table
+        .rate(
+            "Mandelbrot (MT)",
+            result.parallel_mandelbrot_pixels_per_sec,
+            0,
+            Unit::PixelsPerSec,
+        )
+        .note(format!(
+            "{} {}",
+            speedup(result.mandelbrot_speedup),
+            round_spread(timing("parallel_mandelbrot_pixels_per_sec"))
+        ));
+    table
+        .rate(
+            "Mandelbrot f32 (ST)",
+            result.mandelbrot_f32_pixels_per_sec,
+            0,
+            Unit::PixelsPerSec,
+        )
+        .note(precision_gain(
+            result.mandelbrot_f32_pixels_per_sec,
+            result.mandelbrot_pixels_per_sec,
+        ));
+    table.rate(
+        "Ray Trace (ST)",
+        result.raytrace_rays_per_sec,
+        0,
+        Unit::RaysPerSec,
+    );
+    table
+        .rate(
+            "Ray Trace (MT)",
+            result.parallel_raytrace_rays_per_sec,
+            0,
+            Unit::RaysPerSec,
+        )
+        .note(speedup(result.raytrace_speedup));
+    table.rate(
+        "Monte Carlo (ST)",
+        result.monte_carlo_msamples_per_sec,
+        2,
+        Unit::MsamplesPerSec,
+    );
+    table
+        .rate(
+            "Monte Carlo (MT)",
+            result.parallel_monte_carlo_msamples_per_sec,
+            2,
+            Unit::MsamplesPerSec,
+        )
+        .note(speedup(result.monte_carlo_speedup));
+    table.rate(
+        "N-Body (f64)",
+        result.nbody_minteractions_per_sec,
+        0,
+        Unit::MinteractionsPerSec,
+    );
+    table
+        .rate(
+            "N-Body (f32)",
+            result.nbody_f32_minteractions_per_sec,
+            0,
+            Unit::MinteractionsPerSec,
+        )
+        .note(precision_gain(
+            result.nbody_f32_minteractions_per_sec,
+            result.nbody_minteractions_per_sec,
+        ));
+    table
+        .rate(
+            "FFT (ST)",
+            result.fft_msamples_per_sec,
+            0,
+            Unit::MsamplesPerSec,
+        )
+        .note(round_spread(timing("fft_msamples_per_sec")));
+    table
+        .rate(
+            "FFT (MT)",
+            result.parallel_fft_msamples_per_sec,
+            0,
+            Unit::MsamplesPerSec,
+        )
+        .note(format!(
+            "{} {}",
+            speedup(result.fft_speedup),
+            round_spread(timing("parallel_fft_msamples_per_sec"))
+        ));
+    for rate in &result.fft_sizes {
+        let size = rate.size_label();
+        table.rate(
+            &format!("FFT {} (Complex)", size),
+            rate.complex_msamples_per_sec,
+            0,
+            Unit::MsamplesPerSec,
+        );
+        table.rate(
+            &format!("FFT {} (Real)", size),
+            rate.real_msamples_per_sec,
+            0,
+            Unit::MsamplesPerSec,
+        );
+    }
+    table.rate(
+        "Compress",
+        result.compression_mibs,
+        2,
+        Unit::MebibytesPerSec,
+    );
+    table.rate(
+        "Decompress",
+        result.decompression_mibs,
+        2,
+        Unit::MebibytesPerSec,
+    );
+}
+
+pub fn memory_table(result: &MemoryResult) -> Table {
+    let mut table = Table::new(Phase::Memory.name());
+    table
+        .rate("Write", result.write_throughput, 2, Unit::MebibytesPerSec)
+        .note(if result.prefaulted {
+            "[pre-faulted]"
+        } else {
+            "[incl. page faults]"
+        });
+    table.rate("Read", result.read_throughput, 2, Unit::MebibytesPerSec);
+    table.rate("Avg", result.combined_throughput, 2, Unit::MebibytesPerSec);
+    table.text("Footprint", result.footprint.summary());
+    table.rate(
+        "STREAM Copy",
+        result.stream_copy_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table.rate(
+        "STREAM Scale",
+        result.stream_scale_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table.rate(
+        "STREAM Add",
+        result.stream_add_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table.rate(
+        "STREAM Triad",
+        result.stream_triad_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table.rate(
+        "Triad Scalar",
+        result.triad_scalar_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table
+        .rate(
+            "Triad SIMD",
+            result.triad_simd_gbs,
+            2,
+            Unit::GigabytesPerSec,
+        )
+        .note(format!(
+            "[{}] {}",
+            SimdLevel::detect().name(),
+            speedup(result.triad_simd_gbs / result.triad_scalar_gbs)
+        ));
+    table.rate(
+        "Stores (Cached)",
+        result.write_cached_gbs,
+        2,
+        Unit::GigabytesPerSec,
+    );
+    table
+        .rate(
+            "Stores (Non-Temporal)",
+            result.write_nt_gbs,
+            2,
+            Unit::GigabytesPerSec,
+        )
+        .note(format!(
+            "{}{}",
+            speedup(result.write_nt_gbs / result.write_cached_gbs),
+            if memory::NON_TEMPORAL_STORES {
+                ""
+            } else {
+                " [not supported, regular stores]"
+            }
+        ));
+    table.rate(
+        "Page Faults",
+        result.page_faults_per_sec,
+        0,
+        Unit::PagesPerSec,
+    );
+    table
+        .rate(
+            "Alloc Small",
+            result.alloc_small_per_sec,
+            2,
+            Unit::AllocsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .rate(
+            "Alloc Medium",
+            result.alloc_medium_per_sec,
+            2,
+            Unit::AllocsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .rate(
+            "Alloc Large",
+            result.alloc_large_per_sec,
+            3,
+            Unit::AllocsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .rate(
+            "Alloc Small (MT)",
+            result.alloc_small_mt_per_sec,
+            2,
+            Unit::AllocsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .rate(
+            "Alloc Fragmented",
+            result.alloc_fragmented_per_sec,
+            2,
+            Unit::AllocsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    for level in &result.cache_probe.levels {
+        table.rate(
+            &format!("Cache {}", level.name),
+            level.bandwidth_gbs,
+            2,
+            Unit::GigabytesPerSec,
+        );
+        if let Some(capacity) = level.capacity_bytes {
+            table.note(memory::format_size(capacity));
+        }
+    }
+    for point in &result.thread_scaling {
+        table
+            .rate(
+                &format!("{} Threads", point.threads),
+                point.combined_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!(
+                "write {:.2} MB/s, read {:.2} MB/s",
+                point.write_throughput, point.read_throughput
+            ));
+    }
+    if let Some(numa) = &result.numa {
+        table.rate(
+            "NUMA Local Read",
+            numa.local_read_gbs,
+            2,
+            Unit::GigabytesPerSec,
+        );
+        table
+            .rate(
+                "NUMA Remote Read",
+                numa.remote_read_gbs,
+                2,
+                Unit::GigabytesPerSec,
+            )
+            .note(format!("({:.2}x penalty)", numa.bandwidth_penalty()));
+        table.latency(
+            "NUMA Local Latency",
+            numa.local_latency_ns,
+            0,
+            Unit::Nanoseconds,
+        );
+        table
+            .latency(
+                "NUMA Remote Latency",
+                numa.remote_latency_ns,
+                0,
+                Unit::Nanoseconds,
+            )
+            .note(format!("({:.2}x penalty)", numa.latency_penalty()));
+    }
+    for pattern in &result.access_patterns {
+        table.rate(
+            &format!("Pattern {}", pattern.pattern.label()),
+            pattern.read_gbs,
+            2,
+            Unit::GigabytesPerSec,
+        );
+    }
+    if let Some(huge_pages) = &result.huge_pages {
+        table.rate(
+            "Huge Pages Write",
+            huge_pages.write_throughput,
+            2,
+            Unit::MebibytesPerSec,
+        );
+        table.rate(
+            "Huge Pages Read",
+            huge_pages.read_throughput,
+            2,
+            Unit::MebibytesPerSec,
+        );
+        table
+            .rate(
+                "Huge Pages Avg",
+                huge_pages.combined_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!(
+                "({:.2}x, {} pages)",
+                huge_pages.gain(result),
+                huge_pages.kind.name()
+            ));
+    }
+    table
+}
+
+pub fn concurrency_table(result: &ConcurrencyResult) -> Table {
+    let mut table = Table::new(Phase::Concurrency.name());
+    for point in &result.points {
+        let threads = point.threads;
+        table
+            .rate(
+                &format!("Mutex ({} threads)", threads),
+                point.mutex_uncontended_ops_per_sec,
+                2,
+                Unit::OpsPerSec,
+            )
+            .shown_in(Unit::MillionsPerSec);
+        table
+            .rate(
+                &format!("Contended ({} threads)", threads),
+                point.mutex_contended_ops_per_sec,
+                2,
+                Unit::OpsPerSec,
+            )
+            .shown_in(Unit::MillionsPerSec);
+        table
+            .rate(
+                &format!("Atomic ({} threads)", threads),
+                point.atomic_ops_per_sec,
+                2,
+                Unit::OpsPerSec,
+            )
+            .shown_in(Unit::MillionsPerSec);
+        table
+            .rate(
+                &format!("Channel ({} threads)", threads),
+                point.channel_messages_per_sec,
+                2,
+                Unit::MessagesPerSec,
+            )
+            .shown_in(Unit::MillionMessagesPerSec);
+    }
+    table.latency(
+        "Thread Spawn",
+        result.thread_spawn_us,
+        2,
+        Unit::Microseconds,
+    );
+    table.latency(
+        "Context Switch",
+        result.context_switch_us,
+        2,
+        Unit::Microseconds,
+    );
+    let false_sharing = &result.false_sharing;
+    table
+        .rate(
+            "False Sharing (Adjacent)",
+            false_sharing.adjacent_ops_per_sec,
+            2,
+            Unit::OpsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .rate(
+            "False Sharing (Padded)",
+            false_sharing.padded_ops_per_sec,
+            2,
+            Unit::OpsPerSec,
+        )
+        .shown_in(Unit::MillionsPerSec);
+    table
+        .latency(
+            "False Sharing Penalty",
+            false_sharing.penalty(),
+            2,
+            Unit::Ratio,
+        )
+        .note(format!("({} threads)", false_sharing.threads));
+    if let Some(matrix) = &result.core_latency {
+        if let Some((min, mean, max)) = matrix.summary() {
+            table
+                .latency("Core Latency Min", min, 1, Unit::Nanoseconds)
+                .note(format!("({} cores)", matrix.cores.len()));
+            table.latency("Core Latency Mean", mean, 1, Unit::Nanoseconds);
+            table.latency("Core Latency Max", max, 1, Unit::Nanoseconds);
+        }
+    }
+    table
+}
+
+pub fn disk_table(result: &DiskResult) -> Table {
+    let mut table = Table::new(Phase::Disk.name());
+    table
+        .rate("Write", result.write_throughput, 2, Unit::MebibytesPerSec)
+        .note(match (result.mode, result.io_mode) {
+            (DiskMode::Direct, Some(IoMode::Buffered)) => {
+                "[buffered: direct I/O not honored]".to_string()
+            }
+            (_, Some(io_mode)) => format!("[{}]", io_mode.name()),
+            (_, None) => String::new(),
+        });
+    table
+        .rate("Read", result.read_throughput, 2, Unit::MebibytesPerSec)
+        .note(match result.cache_bypassed {
+            Some(true) => "[uncached]",
+            Some(false) => "[cached: measures memory]",
+            None => "",
+        });
+    table.rate("Avg", result.combined_throughput, 2, Unit::MebibytesPerSec);
+    if let Some(precondition) = &result.precondition {
+        table
+            .rate(
+                "Burst Write",
+                precondition.burst_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!("(start of a {}x dataset)", precondition.multiple));
+        let passes = precondition.pass_throughput.len();
+        table
+            .rate(
+                "Steady Write",
+                precondition.steady_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(if passes > 1 {
+                format!("(last of {} passes)", passes)
+            } else {
+                String::new()
+            });
+    }
+    if let Some(cached) = &result.cached {
+        table.rate(
+            "Cached Write",
+            cached.write_throughput,
+            2,
+            Unit::MebibytesPerSec,
+        );
+        table
+            .rate(
+                "Cached Read",
+                cached.read_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!("({:.2}x uncached)", cached.read_speedup(result)));
+        table
+            .rate(
+                "Cached Avg",
+                cached.combined_throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!("({:.2}x uncached)", cached.speedup(result)));
+    }
+    table
+        .rate("fsync", result.fsync_ops_per_sec, 0, Unit::OpsPerSec)
+        .note(format!(
+            "P50 {:.1} us, P95 {:.1} us, P99 {:.1} us",
+            result.fsync_latency_p50_us, result.fsync_latency_p95_us, result.fsync_latency_p99_us
+        ));
+    for (label, latency) in [
+        ("Write Latency P99", &result.write_latency),
+        ("Read Latency P99", &result.read_latency),
+    ] {
+        table
+            .latency(label, latency.p99_us, 1, Unit::Microseconds)
+            .note(format!(
+                "P50 {:.1} us, P90 {:.1} us, P99.9 {:.1} us, Max {:.1} us",
+                latency.p50_us, latency.p90_us, latency.p999_us, latency.max_us
+            ));
+    }
+    for sweep in &result.block_size_sweep {
+        let size = sweep.block_size / 1024;
+        table.rate(
+            &format!("{} KB Write", size),
+            sweep.write_throughput,
+            2,
+            Unit::MebibytesPerSec,
+        );
+        table.rate(
+            &format!("{} KB Read", size),
+            sweep.read_throughput,
+            2,
+            Unit::MebibytesPerSec,
+        );
+    }
+    let metadata = &result.metadata;
+    table.rate("Create", metadata.create_ops_per_sec, 0, Unit::OpsPerSec);
+    table.rate("Stat", metadata.stat_ops_per_sec, 0, Unit::OpsPerSec);
+    table.rate("Rename", metadata.rename_ops_per_sec, 0, Unit::OpsPerSec);
+    table.rate("Delete", metadata.delete_ops_per_sec, 0, Unit::OpsPerSec);
+    let small_files = &result.small_files;
+    table
+        .rate(
+            "Small Files",
+            small_files.files_per_sec,
+            0,
+            Unit::FilesPerSec,
+        )
+        .note(format!("({:.2} MB/s)", small_files.throughput));
+    table
+        .rate(
+            "Small Files (Synced)",
+            small_files.synced_files_per_sec,
+            0,
+            Unit::FilesPerSec,
+        )
+        .note(format!("({:.2} MB/s)", small_files.synced_throughput));
+    for profile in &result.profiles {
+        let name = profile.profile.label();
+        table
+            .rate(
+                &format!("{} Profile", name),
+                profile.throughput,
+                2,
+                Unit::MebibytesPerSec,
+            )
+            .note(format!("{:.0} IOPS", profile.iops));
+        for (direction, latency) in [
+            ("Read", &profile.read_latency),
+            ("Write", &profile.write_latency),
+        ] {
+            if latency.samples > 0 {
+                table
+                    .latency(
+                        &format!("{} {} P99", name, direction),
+                        latency.p99_us,
+                        1,
+                        Unit::Microseconds,
+                    )
+                    .note(format!(
+                        "P50 {:.1} us, Max {:.1} us",
+                        latency.p50_us, latency.max_us
+                    ));
+            }
+        }
+    }
+    table
+}
+
+pub fn network_table(result: &NetworkResult) -> Table {
+    let mut table = Table::new(Phase::Network.name());
+    table.rate("TCP", result.tcp_throughput, 2, Unit::MebibytesPerSec);
+    table.rate("UDP", result.udp_packets_per_sec, 0, Unit::PacketsPerSec);
+    table.latency("RTT P50", result.latency_p50_us, 1, Unit::Microseconds);
+    table.latency("RTT P95", result.latency_p95_us, 1, Unit::Microseconds);
+    table.latency("RTT P99", result.latency_p99_us, 1, Unit::Microseconds);
+    table
+}
+
+pub fn gpu_table(result: &GpuResult) -> Table {
+    let mut table = Table::new(Phase::Gpu.name());
+    table.text(
+        "Adapter",
+        format!("{} ({})", result.adapter_name, result.backend),
+    );
+    table.rate("Matrix", result.matmul_gflops, 2, Unit::Gflops);
+    table.rate("Bandwidth", result.bandwidth_gbs, 2, Unit::GigabytesPerSec);
+    table
+}
+
+/// Composite scores; None when no scored benchmark ran
+/// Peak and sustained CPU frequency, flagged when throttled, and the maximum temperature
+fn thermal_rows(table: &mut Table, thermal: &ThermalResult) {
+    if let (Some(peak), Some(sustained)) =
+        (thermal.peak_frequency_mhz, thermal.sustained_frequency_mhz)
+    {
+        table
+            .value("Frequency", peak, 0, Unit::Megahertz)
+            .note(format!(
+                "peak, {:.0} MHz sustained{}",
+                sustained,
+                if thermal.throttled() {
+                    " (THROTTLED)"
+                } else {
+                    ""
+                }
+            ));
+    }
+    if let Some(temperature) = thermal.max_temperature_c {
+        table
+            .value("Temperature", temperature, 0, Unit::Celsius)
+            .note("max");
+    }
+}
+
+/// Loads of a stress test
+pub fn stress_config_table(config: &StressConfig) -> Table {
+    let mut table = Table::new("");
+    table.text("Duration", format!("{:?}", config.duration));
+    table.text("Scale", config.scale.to_string());
+    if config.threads > 0 {
+        table.text("CPU Workers", config.threads.to_string());
+    }
+    if config.memory_threads > 0 {
+        table.text("Memory Workers", config.memory_threads.to_string());
+    }
+    if let Some(dir) = &config.disk_dir {
+        table.text("Disk Path", dir.display().to_string());
+    }
+    table
+}
+
+/// Passes and errors of every stress load, with the CPU frequency and temperature
+pub fn stress_table(result: &StressResult) -> Table {
+    let mut table = Table::new("Stress Test");
+    for load in &result.loads {
+        table.text(
+            &load.name,
+            format!("{} passes, {} errors", load.passes, load.errors),
+        );
+    }
+    thermal_rows(&mut table, &result.thermal);
+    table.text("Duration", format!("{:.1}s", result.elapsed_secs));
+    if result.interrupted {
+        table.note("interrupted");
+    }
+    table
+}
+
+pub fn score_table(scores: &Scores) -> Option<Table> {
+    let overall = scores.overall?;
+    let mut table = Table::new(&format!(
+        "Score (reference system = {:.0})",
+        scoring::REFERENCE_SCORE
+    ));
+    for (name, score) in [
+        ("CPU", scores.cpu),
+        ("Memory", scores.memory),
+        ("Disk", scores.disk),
+    ] {
+        if let Some(score) = score {
+            table.rate(name, score, 0, Unit::Score);
+        }
+    }
+    table.rate("Overall", overall, 0, Unit::Score);
+    Some(table)
+}
+
+/// Width of the longest bar of `histogram_text`
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+/// Latency distribution with one bar per power of two, headed by `title` and the sample
+/// count; None when nothing was recorded
+pub fn histogram_text(title: &str, summary: &HistogramSummary) -> Option<String> {
+    let octaves = summary.octaves();
+    let most = octaves.iter().map(|bucket| bucket.count).max()?;
+    let bounds: Vec<String> = octaves
+        .iter()
+        .map(|bucket| format_latency_us(bucket.upper_us))
+        .collect();
+    let bound_width = widest(bounds.iter());
+    let mut text = format!("{} ({} samples)\n", title, summary.samples);
+    for (bucket, bound) in octaves.iter().zip(&bounds) {
+        // Every non-empty bucket gets at least one block, so the tail stays visible
+        let length = (bucket.count as f64 / most as f64 * HISTOGRAM_BAR_WIDTH as f64).ceil();
+        text.push_str(&format!(
+            "  < {:>bound_width$}  {:<HISTOGRAM_BAR_WIDTH$}  {}\n",
+            bound,
+            "█".repeat(length as usize),
+            bucket.count,
+        ));
+    }
+    Some(text)
+}
+
+/// `us` in the largest unit that keeps it at least 1, e.g. "128 us" or "16.8 ms"
+fn format_latency_us(us: f64) -> String {
+    if us >= 1_000_000.0 {
+        format!("{:.1} s", us / 1_000_000.0)
+    } else if us >= 1000.0 {
+        format!("{:.1} ms", us / 1000.0)
+    } else if us >= 10.0 {
+        format!("{:.0} us", us)
+    } else {
+        format!("{:.2} us", us)
+    }
+}
+
+/// Statistics of a --watch session: category -> metric -> (unit, statistics)
+pub type SessionStats = IndexMap<String, IndexMap<String, (Option<Unit>, StreamingStats)>>;
+
+/// One table per report category with the session mean and CV of each metric
+pub fn session_tables(session: &SessionStats) -> Vec<Table> {
+    session
+        .iter()
+        .map(|(category, metrics)| {
+            let mut table = Table::new(category);
+            for (name, (unit, stats)) in metrics {
+                let Some(stats) = stats.statistics() else {
+                    continue;
+                };
+                let unit = unit.unwrap_or(Unit::Score);
+                // Report keys carry their unit, which the table appends itself
+                let label = name
+                    .strip_suffix(&format!(" ({})", unit.symbol()))
+                    .unwrap_or(name);
+                table.value(label, stats.mean, 2, unit).note(format!(
+                    "CV {:.1}%, {} values",
+                    stats.coefficient_of_variation, stats.count
+                ));
+            }
+            table
+        })
+        .collect()
+}
+
+/// Width of the per-host columns of the aggregate metric table; longer hostnames are
+/// shortened
+const HOST_COLUMN_WIDTH: usize = 18;
+
+/// `hostname` shortened to fit a host column
+fn host_column(hostname: &str) -> String {
+    if hostname.chars().count() <= HOST_COLUMN_WIDTH {
+        hostname.to_string()
+    } else {
+        let head: String = hostname.chars().take(HOST_COLUMN_WIDTH - 1).collect();
+        format!("{}~", head)
+    }
+}
+
+/// Hardware and OS of the machine, printed before the benchmarks run
+pub fn system_info_table(info: &SystemInfo) -> Table {
+    let mut table = Table::new("");
+    table.text("CPU", &info.cpu_brand);
+    table.text(
+        "Cores",
+        format!(
+            "{} physical, {} logical",
+            info.cpu_physical_cores, info.cpu_logical_cores
+        ),
+    );
+    table.text("Memory", format!("{} MB", info.total_memory_mb));
+    table.text("OS", format!("{} {}", info.os_name, info.os_version));
+    if !info.simd_features.is_empty() {
+        table.text(
+            "SIMD",
+            format!(
+                "{} (kernels use {})",
+                info.simd_features.join(" "),
+                info.simd_level
+            ),
+        );
+    }
+    table.text(
+        "AES Acceleration",
+        info.aes_acceleration
+            .as_deref()
+            .unwrap_or("none (software)"),
+    );
+    if let Some(frequency) = info.cpu_frequency.summary() {
+        table.text("CPU Frequency", frequency);
+    }
+    if let Some(core_types) = info.cpu_topology.core_type_summary() {
+        table.text("Core Types", core_types);
+    }
+    if let Some(caches) = info.cpu_topology.cache_summary() {
+        table.text("Caches", caches);
+    }
+    if let Some(numa) = info.numa_summary() {
+        table.text("NUMA", numa);
+    }
+    if let Some(power) = info.power.summary() {
+        table.text("Power", power);
+    }
+    if let Some(gpu) = &info.gpu_adapter {
+        table.text("GPU", gpu);
+    }
+    // Also calibrates the clock before any benchmark runs
+    table.text("Timer", timing::calibration().to_string());
+    table.text("Hostname", &info.hostname);
+    table
+}
+
+/// Levels of the memory hierarchy estimated by the cache probe, with the working set size
+/// each level held
+pub fn cache_hierarchy_table(probe: &CacheProbeResult) -> Table {
+    let mut table = Table::new("");
+    for level in &probe.levels {
+        table.rate(&level.name, level.bandwidth_gbs, 2, Unit::GigabytesPerSec);
+        if let Some(capacity) = level.capacity_bytes {
+            table.note(memory::format_size(capacity));
+        }
+    }
+    table
+}
+
+/// One table per registered kernel, titled with its phase and description, listing the
+/// metrics it reports and their units
+pub fn listing_tables(listing: &Listing) -> Vec<Table> {
+    listing
+        .kernels
+        .iter()
+        .map(|kernel| {
+            let mut table = Table::new(&format!(
+                "{} ({}): {}",
+                kernel.name, kernel.phase, kernel.description
+            ));
+            for metric in kernel.metrics {
+                table.text(metric.name, metric.unit.symbol());
+            }
+            table
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hs_benchmark_suite::aggregate;
+    use hs_benchmark_suite::report::MetricSeries;
+    use hs_benchmark_suite::{PhasePlan, SuiteConfig};
+
+    fn sample_table(write: f64, latency: f64) -> Table {
+        let mut table = Table::new("Disk");
+        table.rate("Write", write, 2, Unit::MegabytesPerSec);
+        table
+            .latency("fsync Latency", latency, 1, Unit::Microseconds)
+            .note("P99");
+        table.text("Duration", "1.5s");
+        table
+    }
+
+    #[test]
+    fn test_color_mode_names() {
+        assert_eq!(ColorMode::from_name("auto"), Some(ColorMode::Auto));
+        assert_eq!(ColorMode::from_name("always"), Some(ColorMode::Always));
+        assert_eq!(ColorMode::from_name("never"), Some(ColorMode::Never));
+        assert_eq!(ColorMode::from_name("sometimes"), None);
+        assert!(ColorMode::Always.enabled());
+        assert!(!ColorMode::Never.enabled());
+    }
+
+    #[test]
+    fn test_table_columns_are_aligned() {
+        let mut renderer = Renderer::new(ColorMode::Never);
+        let text = renderer.render(&sample_table(1234.5, 80.0));
+        assert_eq!(
+            text,
+            "Disk\n  Write          1234.50 MB/s\n  fsync Latency     80.0 us    P99\n  Duration       1.5s\n"
+        );
+    }
+
+    #[test]
+    fn test_changes_from_the_previous_run() {
+        let mut renderer = Renderer::new(ColorMode::Always);
+        renderer.render(&sample_table(1000.0, 80.0));
+        let text = renderer.render(&sample_table(1100.0, 80.4));
+        // Higher write throughput is an improvement; the latency change is noise
+        assert!(text.contains(&format!("{}  +10.0%{}", GREEN, RESET)));
+        assert!(text.contains("   +0.5%"));
+        assert!(!text.contains(RED));
+
+        let text = renderer.render(&sample_table(1100.0, 100.0));
+        assert!(text.contains(&format!("{}  +24.4%{}", RED, RESET)));
+    }
+
+    #[test]
+    fn test_runs_are_averaged() {
+        let renderer = Renderer::new(ColorMode::Never);
+        let text = renderer.render_runs(&[sample_table(100.0, 10.0), sample_table(300.0, 20.0)]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].ends_with("Run 1   Run 2 Average"));
+        assert!(lines[2].ends_with("100.00  300.00  200.00 MB/s"));
+        assert!(lines[3].ends_with("10.0    20.0    15.0 us"));
+    }
+
+    #[test]
+    fn test_byte_rates_follow_the_unit_system() {
+        let mut renderer = Renderer::new(ColorMode::Never).with_units(UnitSystem::Binary);
+        let text = renderer.render(&sample_table(1000.0, 80.0));
+        assert!(text.contains("  Write          953.67 MiB/s\n"), "{}", text);
+
+        let mut table = Table::new("Memory");
+        table.rate("Write", 1000.0, 2, Unit::MebibytesPerSec);
+        let renderer = Renderer::new(ColorMode::Never);
+        let text = renderer.render_runs(&[table]);
+        assert!(text.ends_with("1048.58 1048.58 MB/s\n"), "{}", text);
+    }
+
+    #[test]
+    fn test_rows_shown_in_another_unit() {
+        let mut table = Table::new("");
+        table
+            .rate("Atomic", 2_500_000.0, 2, Unit::OpsPerSec)
+            .shown_in(Unit::MillionsPerSec);
+        // Units of different quantities are left alone
+        table
+            .rate("Write", 1234.5, 2, Unit::MegabytesPerSec)
+            .shown_in(Unit::Gflops);
+        let text = Renderer::new(ColorMode::Never).render(&table);
+        assert_eq!(text, "  Atomic     2.50 M/s\n  Write   1234.50 MB/s\n");
+    }
+
+    #[test]
+    fn test_histogram_text() {
+        use hs_benchmark_suite::stats::HistogramBucket;
+        assert_eq!(histogram_text("Write", &HistogramSummary::default()), None);
+        let summary = HistogramSummary {
+            samples: 1000,
+            buckets: vec![
+                HistogramBucket {
+                    upper_us: 131.072,
+                    count: 990,
+                },
+                HistogramBucket {
+                    upper_us: 16777.216,
+                    count: 10,
+                },
+            ],
+            ..HistogramSummary::default()
+        };
+        let text = histogram_text("Write Latency", &summary).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Write Latency (1000 samples)");
+        assert_eq!(lines[1], format!("  <  131 us  {}  990", "█".repeat(40)));
+        assert_eq!(lines[2], format!("  < 16.8 ms  █{}  10", " ".repeat(39)));
+    }
+
+    #[test]
+    fn test_session_tables() {
+        let mut stats = StreamingStats::new();
+        [100.0, 110.0, 90.0]
+            .iter()
+            .for_each(|&value| stats.push(value));
+        let mut session = SessionStats::new();
+        session.entry("Memory".to_string()).or_default().insert(
+            "Read (MiB/s)".to_string(),
+            (Some(Unit::MebibytesPerSec), stats),
+        );
+        let tables = session_tables(&session);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].title, "Memory");
+        let text = Renderer::new(ColorMode::Never)
+            .with_units(UnitSystem::Binary)
+            .render(&tables[0]);
+        assert!(text.contains("Read"), "{}", text);
+        assert!(!text.contains("(MiB/s)"), "{}", text);
+        assert!(text.contains("100.00 MiB/s"), "{}", text);
+        assert!(text.contains("CV 8.2%, 3 values"), "{}", text);
+    }
+
+    #[test]
+    fn test_plan_table() {
+        let plan = SuitePlan {
+            config: SuiteConfig {
+                runs: 2,
+                ..SuiteConfig::default()
+            },
+            phases: vec![
+                PhasePlan {
+                    phase: Phase::Cpu,
+                    scale: 8.0,
+                    estimated_secs: Some(90.0),
+                },
+                PhasePlan {
+                    phase: Phase::Network,
+                    scale: 8.0,
+                    estimated_secs: None,
+                },
+            ],
+            memory_footprint: None,
+            disk_target: None,
+            disk_bytes: None,
+        };
+        let args = BenchmarkArgs {
+            json: true,
+            output_dir: Some("reports".to_string()),
+            ..BenchmarkArgs::default()
+        };
+        let text = Renderer::new(ColorMode::Never).render(&plan_table(&plan, &args));
+        assert!(text.contains("CPU                 scale 8.000, ~1m 30s per run"));
+        assert!(text.contains("Network             not estimated"));
+        assert!(text.contains("Reports             reports/output_"));
+        assert!(text.contains("Estimated Duration  ~3m 00s plus the phases not estimated"));
+    }
+
+    /// Report of `hostname` with one primes rate and one latency, as aggregated or compared
+    fn host_report(hostname: &str, primes: f64, latency: f64) -> SuiteReport {
+        let json = serde_json::json!({
+            "metadata": {"timestamp": "2026-01-25T14:30:22+01:00", "hostname": hostname},
+            "system_info": {"cpu_brand": "CPU", "cpu_physical_cores": 4, "cpu_logical_cores": 8,
+                "total_memory_mb": 1024, "os_name": "OS", "os_version": "1", "hostname": hostname},
+            "results": {}
+        });
+        let mut report: SuiteReport = serde_json::from_value(json).unwrap();
+        let cpu = report.results.entry("cpu".to_string()).or_default();
+        cpu.insert(
+            "cpu_primes_per_sec".to_string(),
+            MetricSeries::from_values(vec![primes]),
+        );
+        cpu.insert(
+            "cpu_latency_us".to_string(),
+            MetricSeries::from_values(vec![latency]),
+        );
+        report
+    }
+
+    #[test]
+    fn test_comparison_colors_verdicts() {
+        let baseline = host_report("base", 100.0, 10.0);
+        let candidate = host_report("cand", 120.0, 10.1);
+        let comparisons = compare::compare_reports(&baseline, &candidate);
+        let text =
+            Renderer::new(ColorMode::Always).render_comparison(&baseline, &candidate, &comparisons);
+        assert!(text.contains("Baseline:  base (2026-01-25T14:30:22+01:00)"));
+        let primes = text
+            .lines()
+            .find(|line| line.contains("cpu_primes_per_sec"))
+            .unwrap();
+        assert!(primes.starts_with(GREEN) && primes.ends_with(RESET));
+        let latency = text
+            .lines()
+            .find(|line| line.contains("cpu_latency_us"))
+            .unwrap();
+        assert!(!latency.contains(RESET));
+        assert!(text.contains("2 metrics compared: 1 improved, 0 regressed, 1 within noise"));
+    }
+
+    #[test]
+    fn test_aggregate_marks_best_and_worst_hosts() {
+        let aggregate = aggregate::aggregate(&[
+            host_report("fast", 200.0, 5.0),
+            host_report("a-very-long-hostname.example.com", 100.0, 10.0),
+        ]);
+        let text = Renderer::new(ColorMode::Always).render_aggregate(&aggregate);
+        assert!(text.contains("a-very-long-hostn~"));
+        let primes = text
+            .lines()
+            .find(|line| line.starts_with("cpu_primes_per_sec"))
+            .unwrap();
+        assert!(primes.contains(&format!("{}{:>19}{}", GREEN, "200.00 #1", RESET)));
+        assert!(primes.contains(&format!("{}{:>19}{}", RED, "100.00 #2", RESET)));
+
+        let plain = Renderer::new(ColorMode::Never).render_aggregate(&aggregate);
+        assert!(!plain.contains(RESET));
+    }
+
+    #[test]
+    fn test_listing_tables() {
+        let listing = benchmark::listing();
+        let tables = listing_tables(&listing);
+        assert_eq!(tables.len(), listing.kernels.len());
+        let text = Renderer::new(ColorMode::Never).render(&tables[0]);
+        let kernel = &listing.kernels[0];
+        assert!(text.starts_with(&format!("{} ({}): ", kernel.name, kernel.phase)));
+        assert!(text.contains(kernel.metrics[0].name));
+    }
+}
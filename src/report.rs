@@ -0,0 +1,2193 @@
+/// Report writers for benchmark results
+/// Produces timestamped CSV and JSON files with per-run values and statistics
+/// JSON reports are serialized through serde and can be loaded back with `SuiteReport::load`
+use crate::concurrency::{ConcurrencyPoint, ConcurrencyResult};
+use crate::cpu::{CpuResult, FftSizeRate};
+use crate::disk::{
+    self, CachedResult, DiskProfileResult, DiskResult, DiskTarget, MetadataResult,
+    PreconditionResult, SmallFilesResult,
+};
+use crate::gpu::GpuResult;
+use crate::load::BackgroundLoad;
+use crate::manifest::RunManifest;
+use crate::memory::{AccessPattern, HugePageResult, MemoryResult, NumaResult};
+use crate::network::NetworkResult;
+use crate::power::EnergyResult;
+use crate::scoring::{self, Scores};
+use crate::stats::{HistogramSummary, Statistics};
+use crate::suite::{Cooldown, SuiteConfig, SuiteResults, TimedOutStep};
+use crate::sysinfo_capture::SystemInfo;
+use crate::thermal::ThermalResult;
+use crate::timing::{self, TimerCalibration};
+use crate::units::{Unit, UnitSystem};
+use chrono::Local;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Version of the crate that wrote a report
+pub const SUITE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Git commit the binary was built from, with a "-dirty" suffix for uncommitted changes;
+/// None when it was not built from a git checkout
+pub const GIT_COMMIT: Option<&str> = option_env!("HS_BENCHMARK_BUILD_COMMIT");
+
+/// Version and commit of the build, e.g. "0.3.2 (commit 1a2b3c4d5e6f)"
+pub fn build_summary() -> String {
+    match GIT_COMMIT {
+        Some(commit) => format!("{} (commit {})", SUITE_VERSION, commit),
+        None => SUITE_VERSION.to_string(),
+    }
+}
+
+/// Per-run values of one metric together with their statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSeries {
+    /// Unit of the values; None in reports of older versions, whose labels carried the unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Unit>,
+    pub runs: Vec<f64>,
+    pub statistics: Option<Statistics>,
+}
+
+impl MetricSeries {
+    pub fn from_values(values: Vec<f64>) -> Self {
+        let statistics = Statistics::from_values(&values);
+        MetricSeries {
+            unit: None,
+            runs: values,
+            statistics,
+        }
+    }
+
+    pub fn with_unit(mut self, unit: Unit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+}
+
+/// Metrics grouped by category (`cpu`, `memory`, ...) and keyed by metric name
+pub type ReportResults = IndexMap<String, IndexMap<String, MetricSeries>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportMetadata {
+    pub timestamp: String,
+    pub hostname: String,
+    /// Crate version and git commit of the build that wrote the report; kernels change
+    /// between versions, so results of different builds may not be comparable. Empty and
+    /// None in reports of older versions
+    #[serde(default)]
+    pub suite_version: String,
+    #[serde(default)]
+    pub git_commit: Option<String>,
+    /// The run was interrupted and the report holds partial results
+    #[serde(default)]
+    pub interrupted: bool,
+    /// Overhead and resolution of the clock the latencies were measured with; None in
+    /// reports of older versions
+    #[serde(default)]
+    pub timer: Option<TimerCalibration>,
+}
+
+/// Structured per-run results, as produced by the benchmark modules
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunResults {
+    pub cpu: Vec<CpuResult>,
+    pub memory: Vec<MemoryResult>,
+    pub concurrency: Vec<ConcurrencyResult>,
+    pub disk: Vec<DiskResult>,
+    pub network: Vec<NetworkResult>,
+    pub gpu: Vec<GpuResult>,
+    /// CPU used by other processes, one entry per run including excluded runs
+    pub background_load: Vec<BackgroundLoad>,
+    /// Idle periods of the configured cooldown
+    pub cooldowns: Vec<Cooldown>,
+    /// Steps cut short by the step timeout
+    pub timed_out: Vec<TimedOutStep>,
+}
+
+/// Complete JSON report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiteReport {
+    pub metadata: ReportMetadata,
+    pub system_info: SystemInfo,
+    #[serde(default)]
+    pub configuration: SuiteConfig,
+    /// What determined the work behind the metrics; None in reports of older versions
+    #[serde(default)]
+    pub manifest: Option<RunManifest>,
+    /// `RunManifest::fingerprint` of `manifest`; equal for runs that measured the same work
+    #[serde(default)]
+    pub config_fingerprint: Option<String>,
+    /// Filesystem the disk benchmark ran on
+    #[serde(default)]
+    pub disk_target: Option<DiskTarget>,
+    /// Mean composite scores over all runs
+    #[serde(default)]
+    pub scores: Scores,
+    pub results: ReportResults,
+    #[serde(default)]
+    pub run_results: RunResults,
+}
+
+impl SuiteReport {
+    pub fn new(results: &SuiteResults, system_info: &SystemInfo) -> Self {
+        let mut report_results = ReportResults::new();
+        for row in metric_rows(results) {
+            report_results
+                .entry(row.category.to_string())
+                .or_default()
+                .insert(
+                    row.key.to_string(),
+                    MetricSeries::from_values(row.values).with_unit(row.unit),
+                );
+        }
+
+        let manifest = RunManifest::new(&results.config);
+        SuiteReport {
+            metadata: ReportMetadata {
+                timestamp: Local::now().to_rfc3339(),
+                hostname: system_info.hostname.clone(),
+                suite_version: SUITE_VERSION.to_string(),
+                git_commit: GIT_COMMIT.map(str::to_string),
+                interrupted: results.interrupted,
+                timer: Some(timing::calibration()),
+            },
+            system_info: system_info.clone(),
+            configuration: results.config.clone(),
+            manifest: Some(manifest.clone()),
+            config_fingerprint: Some(manifest.fingerprint()),
+            disk_target: results.disk_target.clone(),
+            scores: scoring::compute_scores(results),
+            results: report_results,
+            run_results: RunResults {
+                cpu: results.cpu.clone(),
+                memory: results.memory.clone(),
+                concurrency: results.concurrency.clone(),
+                disk: results.disk.clone(),
+                network: results.network.clone(),
+                gpu: results.gpu.clone(),
+                background_load: results.background_load.clone(),
+                cooldowns: results.cooldowns.clone(),
+                timed_out: results.timed_out.clone(),
+            },
+        }
+    }
+
+    /// Load a JSON report previously written by `write_json_report`; metrics of earlier
+    /// versions are renamed as `current_metric_names` describes
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut report: SuiteReport =
+            serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            })?;
+        for metrics in report.results.values_mut() {
+            *metrics = current_metric_names(std::mem::take(metrics));
+        }
+        Ok(report)
+    }
+}
+
+/// `metrics` with the names of earlier versions replaced by the current ones, in the same
+/// order: MiB/s metrics were named `_mbs` before they were renamed `_mibs`
+pub fn current_metric_names<V>(metrics: IndexMap<String, V>) -> IndexMap<String, V> {
+    metrics
+        .into_iter()
+        .map(|(name, value)| match name.strip_suffix("_mbs") {
+            Some(stem) => (format!("{}_mibs", stem), value),
+            None => (name, value),
+        })
+        .collect()
+}
+
+/// One reported metric: JSON category and key, CSV label, unit, and per-run values
+pub(crate) struct MetricRow {
+    pub(crate) category: &'static str,
+    pub(crate) key: String,
+    pub(crate) label: String,
+    pub(crate) unit: Unit,
+    pub(crate) values: Vec<f64>,
+}
+
+impl MetricRow {
+    /// The row with its byte rate shown under `units`: the values converted and the unit
+    /// in the label replaced, e.g. "Memory Write (MiB/s)" as "Memory Write (MB/s)"
+    pub(crate) fn in_system(mut self, units: UnitSystem) -> Self {
+        let unit = self.unit.in_system(units);
+        if unit != self.unit {
+            for value in &mut self.values {
+                *value = self.unit.display(*value, units).0;
+            }
+            self.label = self.label.replace(
+                &format!("({})", self.unit.symbol()),
+                &format!("({})", unit.symbol()),
+            );
+            self.unit = unit;
+        }
+        self
+    }
+}
+
+/// Every metric in output order, with byte rates shown under `units`; JSON reports keep
+/// the units the metrics were measured in
+pub(crate) fn display_rows(results: &SuiteResults, units: UnitSystem) -> Vec<MetricRow> {
+    metric_rows(results)
+        .into_iter()
+        .map(|row| row.in_system(units))
+        .collect()
+}
+
+/// Every metric written to the reports, in output order
+fn metric_rows(results: &SuiteResults) -> Vec<MetricRow> {
+    fn row<T>(
+        category: &'static str,
+        key: &str,
+        label: &str,
+        unit: Unit,
+        runs: &[T],
+        value: impl Fn(&T) -> f64,
+    ) -> MetricRow {
+        MetricRow {
+            category,
+            key: key.to_string(),
+            label: label.to_string(),
+            unit,
+            values: runs.iter().map(value).collect(),
+        }
+    }
+
+    let cpu = &results.cpu;
+    let memory = &results.memory;
+    let concurrency = &results.concurrency;
+    let disk = &results.disk;
+    let network = &results.network;
+    let gpu = &results.gpu;
+    let scores = scoring::run_scores(results);
+
+    let mut rows = vec![
+        // CPU metrics
+        row(
+            "cpu",
+            "cpu_primes_per_sec",
+            "CPU Primes (primes/sec)",
+            Unit::PrimesPerSec,
+            cpu,
+            |r| r.primes_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_primes_per_sec_mt",
+            "CPU Primes MT (primes/sec)",
+            Unit::PrimesPerSec,
+            cpu,
+            |r| r.parallel_primes_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_primes_speedup",
+            "CPU Primes Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.primes_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_sieve_primes_per_sec",
+            "CPU Sieve (primes/sec)",
+            Unit::PrimesPerSec,
+            cpu,
+            |r| r.sieve_primes_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_sieve_mibs",
+            "CPU Sieve (MiB/s)",
+            Unit::MebibytesPerSec,
+            cpu,
+            |r| r.sieve_mibs,
+        ),
+        row(
+            "cpu",
+            "cpu_integer_giops",
+            "CPU Integer (GIOPS)",
+            Unit::Giops,
+            cpu,
+            |r| r.integer_giops,
+        ),
+        row(
+            "cpu",
+            "cpu_branch_sorted_melem_per_sec",
+            "CPU Branch Sorted (Melem/s)",
+            Unit::MelemPerSec,
+            cpu,
+            |r| r.branch_sorted_melem_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_branch_unsorted_melem_per_sec",
+            "CPU Branch Unsorted (Melem/s)",
+            Unit::MelemPerSec,
+            cpu,
+            |r| r.branch_unsorted_melem_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_branch_mispredict_ns",
+            "CPU Branch Mispredict (ns)",
+            Unit::Nanoseconds,
+            cpu,
+            |r| r.branch_mispredict_ns,
+        ),
+        row(
+            "cpu",
+            "cpu_sort_u64_melem_per_sec",
+            "CPU Sort u64 (Melem/s)",
+            Unit::MelemPerSec,
+            cpu,
+            |r| r.sort_u64_melem_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_sort_string_melem_per_sec",
+            "CPU Sort Strings (Melem/s)",
+            Unit::MelemPerSec,
+            cpu,
+            |r| r.sort_string_melem_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_regex_mibs",
+            "CPU Regex (MiB/s)",
+            Unit::MebibytesPerSec,
+            cpu,
+            |r| r.regex_mibs,
+        ),
+        row(
+            "cpu",
+            "cpu_aes_mibs",
+            "CPU AES-128 CTR (MiB/s)",
+            Unit::MebibytesPerSec,
+            cpu,
+            |r| r.aes_mibs,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_st",
+            "CPU Matrix ST (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.matrix_mult_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_blocked",
+            "CPU Matrix Blocked (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.matrix_mult_blocked_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_scalar",
+            "CPU Matrix Scalar (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.matrix_scalar_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_simd",
+            "CPU Matrix SIMD (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.matrix_simd_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_f32",
+            "CPU Matrix f32 (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.matrix_f32_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_gemm_i8_gops",
+            "CPU GEMM i8 (GOPS)",
+            Unit::Gops,
+            cpu,
+            |r| r.gemm_i8_gops,
+        ),
+        row(
+            "cpu",
+            "cpu_matrix_mult_gflops_mt",
+            "CPU Matrix MT (GFLOPS)",
+            Unit::Gflops,
+            cpu,
+            |r| r.parallel_matrix_gflops,
+        ),
+        row(
+            "cpu",
+            "cpu_parallel_speedup",
+            "CPU Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.parallel_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_mandelbrot_pixels_per_sec",
+            "CPU Mandelbrot (pixels/sec)",
+            Unit::PixelsPerSec,
+            cpu,
+            |r| r.mandelbrot_pixels_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_mandelbrot_pixels_per_sec_mt",
+            "CPU Mandelbrot MT (pixels/sec)",
+            Unit::PixelsPerSec,
+            cpu,
+            |r| r.parallel_mandelbrot_pixels_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_mandelbrot_speedup",
+            "CPU Mandelbrot Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.mandelbrot_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_mandelbrot_pixels_per_sec_f32",
+            "CPU Mandelbrot f32 (pixels/sec)",
+            Unit::PixelsPerSec,
+            cpu,
+            |r| r.mandelbrot_f32_pixels_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_raytrace_rays_per_sec",
+            "CPU Ray Trace (rays/sec)",
+            Unit::RaysPerSec,
+            cpu,
+            |r| r.raytrace_rays_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_raytrace_rays_per_sec_mt",
+            "CPU Ray Trace MT (rays/sec)",
+            Unit::RaysPerSec,
+            cpu,
+            |r| r.parallel_raytrace_rays_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_raytrace_speedup",
+            "CPU Ray Trace Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.raytrace_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_monte_carlo_msamples_per_sec",
+            "CPU Monte Carlo (Msamples/sec)",
+            Unit::MsamplesPerSec,
+            cpu,
+            |r| r.monte_carlo_msamples_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_monte_carlo_msamples_per_sec_mt",
+            "CPU Monte Carlo MT (Msamples/sec)",
+            Unit::MsamplesPerSec,
+            cpu,
+            |r| r.parallel_monte_carlo_msamples_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_monte_carlo_speedup",
+            "CPU Monte Carlo Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.monte_carlo_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_nbody_minteractions_per_sec",
+            "CPU N-Body (Minteractions/sec)",
+            Unit::MinteractionsPerSec,
+            cpu,
+            |r| r.nbody_minteractions_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_nbody_minteractions_per_sec_f32",
+            "CPU N-Body f32 (Minteractions/sec)",
+            Unit::MinteractionsPerSec,
+            cpu,
+            |r| r.nbody_f32_minteractions_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_fft_msamples_per_sec",
+            "CPU FFT (Msamples/sec)",
+            Unit::MsamplesPerSec,
+            cpu,
+            |r| r.fft_msamples_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_fft_msamples_per_sec_mt",
+            "CPU FFT MT (Msamples/sec)",
+            Unit::MsamplesPerSec,
+            cpu,
+            |r| r.parallel_fft_msamples_per_sec,
+        ),
+        row(
+            "cpu",
+            "cpu_fft_speedup",
+            "CPU FFT Speedup (ST->MT)",
+            Unit::Ratio,
+            cpu,
+            |r| r.fft_speedup,
+        ),
+        row(
+            "cpu",
+            "cpu_compression_mibs",
+            "CPU Compress (MiB/s)",
+            Unit::MebibytesPerSec,
+            cpu,
+            |r| r.compression_mibs,
+        ),
+        row(
+            "cpu",
+            "cpu_decompression_mibs",
+            "CPU Decompress (MiB/s)",
+            Unit::MebibytesPerSec,
+            cpu,
+            |r| r.decompression_mibs,
+        ),
+        // Memory metrics
+        row(
+            "memory",
+            "memory_write_throughput_mibs",
+            "Memory Write (MiB/s)",
+            Unit::MebibytesPerSec,
+            memory,
+            |r| r.write_throughput,
+        ),
+        row(
+            "memory",
+            "memory_read_throughput_mibs",
+            "Memory Read (MiB/s)",
+            Unit::MebibytesPerSec,
+            memory,
+            |r| r.read_throughput,
+        ),
+        row(
+            "memory",
+            "memory_combined_throughput_mibs",
+            "Memory Combined (MiB/s)",
+            Unit::MebibytesPerSec,
+            memory,
+            |r| r.combined_throughput,
+        ),
+        row(
+            "memory",
+            "memory_stream_copy_gbs",
+            "STREAM Copy (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.stream_copy_gbs,
+        ),
+        row(
+            "memory",
+            "memory_stream_scale_gbs",
+            "STREAM Scale (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.stream_scale_gbs,
+        ),
+        row(
+            "memory",
+            "memory_stream_add_gbs",
+            "STREAM Add (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.stream_add_gbs,
+        ),
+        row(
+            "memory",
+            "memory_stream_triad_gbs",
+            "STREAM Triad (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.stream_triad_gbs,
+        ),
+        row(
+            "memory",
+            "memory_triad_scalar_gbs",
+            "Triad Scalar, L2 (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.triad_scalar_gbs,
+        ),
+        row(
+            "memory",
+            "memory_triad_simd_gbs",
+            "Triad SIMD, L2 (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.triad_simd_gbs,
+        ),
+        row(
+            "memory",
+            "memory_write_cached_gbs",
+            "Write Cached Stores (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.write_cached_gbs,
+        ),
+        row(
+            "memory",
+            "memory_write_nt_gbs",
+            "Write Non-Temporal Stores (GB/s)",
+            Unit::GigabytesPerSec,
+            memory,
+            |r| r.write_nt_gbs,
+        ),
+        row(
+            "memory",
+            "memory_page_faults_per_sec",
+            "Page Faults (pages/sec)",
+            Unit::PagesPerSec,
+            memory,
+            |r| r.page_faults_per_sec,
+        ),
+        row(
+            "memory",
+            "memory_alloc_small_per_sec",
+            "Alloc Small (allocs/sec)",
+            Unit::AllocsPerSec,
+            memory,
+            |r| r.alloc_small_per_sec,
+        ),
+        row(
+            "memory",
+            "memory_alloc_medium_per_sec",
+            "Alloc Medium (allocs/sec)",
+            Unit::AllocsPerSec,
+            memory,
+            |r| r.alloc_medium_per_sec,
+        ),
+        row(
+            "memory",
+            "memory_alloc_large_per_sec",
+            "Alloc Large (allocs/sec)",
+            Unit::AllocsPerSec,
+            memory,
+            |r| r.alloc_large_per_sec,
+        ),
+        row(
+            "memory",
+            "memory_alloc_small_mt_per_sec",
+            "Alloc Small MT (allocs/sec)",
+            Unit::AllocsPerSec,
+            memory,
+            |r| r.alloc_small_mt_per_sec,
+        ),
+        row(
+            "memory",
+            "memory_alloc_fragmented_per_sec",
+            "Alloc Fragmented (allocs/sec)",
+            Unit::AllocsPerSec,
+            memory,
+            |r| r.alloc_fragmented_per_sec,
+        ),
+        // Concurrency metrics
+        row(
+            "concurrency",
+            "concurrency_thread_spawn_us",
+            "Thread Spawn (us)",
+            Unit::Microseconds,
+            concurrency,
+            |r| r.thread_spawn_us,
+        ),
+        row(
+            "concurrency",
+            "concurrency_context_switch_us",
+            "Context Switch (us)",
+            Unit::Microseconds,
+            concurrency,
+            |r| r.context_switch_us,
+        ),
+        row(
+            "concurrency",
+            "concurrency_false_sharing_adjacent_ops_per_sec",
+            "False Sharing Adjacent (ops/sec)",
+            Unit::OpsPerSec,
+            concurrency,
+            |r| r.false_sharing.adjacent_ops_per_sec,
+        ),
+        row(
+            "concurrency",
+            "concurrency_false_sharing_padded_ops_per_sec",
+            "False Sharing Padded (ops/sec)",
+            Unit::OpsPerSec,
+            concurrency,
+            |r| r.false_sharing.padded_ops_per_sec,
+        ),
+        row(
+            "concurrency",
+            "concurrency_false_sharing_penalty",
+            "False Sharing Penalty (x)",
+            Unit::Ratio,
+            concurrency,
+            |r| r.false_sharing.penalty(),
+        ),
+        // Disk metrics
+        row(
+            "disk",
+            "disk_write_throughput_mibs",
+            "Disk Write (MiB/s)",
+            Unit::MebibytesPerSec,
+            disk,
+            |r| r.write_throughput,
+        ),
+        row(
+            "disk",
+            "disk_read_throughput_mibs",
+            "Disk Read (MiB/s)",
+            Unit::MebibytesPerSec,
+            disk,
+            |r| r.read_throughput,
+        ),
+        row(
+            "disk",
+            "disk_combined_throughput_mibs",
+            "Disk Combined (MiB/s)",
+            Unit::MebibytesPerSec,
+            disk,
+            |r| r.combined_throughput,
+        ),
+        row(
+            "disk",
+            "disk_fsync_ops_per_sec",
+            "Disk fsync (ops/sec)",
+            Unit::OpsPerSec,
+            disk,
+            |r| r.fsync_ops_per_sec,
+        ),
+        row(
+            "disk",
+            "disk_fsync_latency_p50_us",
+            "Disk fsync P50 (us)",
+            Unit::Microseconds,
+            disk,
+            |r| r.fsync_latency_p50_us,
+        ),
+        row(
+            "disk",
+            "disk_fsync_latency_p95_us",
+            "Disk fsync P95 (us)",
+            Unit::Microseconds,
+            disk,
+            |r| r.fsync_latency_p95_us,
+        ),
+        row(
+            "disk",
+            "disk_fsync_latency_p99_us",
+            "Disk fsync P99 (us)",
+            Unit::Microseconds,
+            disk,
+            |r| r.fsync_latency_p99_us,
+        ),
+        // Network metrics
+        row(
+            "network",
+            "network_tcp_throughput_mibs",
+            "Network TCP (MiB/s)",
+            Unit::MebibytesPerSec,
+            network,
+            |r| r.tcp_throughput,
+        ),
+        row(
+            "network",
+            "network_udp_packets_per_sec",
+            "Network UDP (packets/sec)",
+            Unit::PacketsPerSec,
+            network,
+            |r| r.udp_packets_per_sec,
+        ),
+        row(
+            "network",
+            "network_latency_p50_us",
+            "Network RTT P50 (us)",
+            Unit::Microseconds,
+            network,
+            |r| r.latency_p50_us,
+        ),
+        row(
+            "network",
+            "network_latency_p95_us",
+            "Network RTT P95 (us)",
+            Unit::Microseconds,
+            network,
+            |r| r.latency_p95_us,
+        ),
+        row(
+            "network",
+            "network_latency_p99_us",
+            "Network RTT P99 (us)",
+            Unit::Microseconds,
+            network,
+            |r| r.latency_p99_us,
+        ),
+        // GPU metrics
+        row(
+            "gpu",
+            "gpu_matmul_gflops",
+            "GPU Matrix (GFLOPS)",
+            Unit::Gflops,
+            gpu,
+            |r| r.matmul_gflops,
+        ),
+        row(
+            "gpu",
+            "gpu_bandwidth_gbs",
+            "GPU Bandwidth (GB/s)",
+            Unit::GigabytesPerSec,
+            gpu,
+            |r| r.bandwidth_gbs,
+        ),
+    ];
+
+    // FFT size sweep, a complex and a real-input row per size of the first run
+    let fft_sizes: Vec<FftSizeRate> = cpu.first().map(|r| r.fft_sizes.clone()).unwrap_or_default();
+    type FftVariant = fn(&FftSizeRate) -> f64;
+    let fft_variants: [(&str, &str, FftVariant); 2] = [
+        ("", "Complex", |s| s.complex_msamples_per_sec),
+        ("real_", "Real", |s| s.real_msamples_per_sec),
+    ];
+    for size in fft_sizes {
+        let label = size.size_label();
+        for (variant, name, value) in fft_variants {
+            rows.push(MetricRow {
+                category: "cpu",
+                key: format!(
+                    "cpu_{}fft_{}_msamples_per_sec",
+                    variant,
+                    label.to_lowercase()
+                ),
+                label: format!("CPU FFT {} {} (Msamples/sec)", label, name),
+                unit: Unit::MsamplesPerSec,
+                values: cpu
+                    .iter()
+                    .filter_map(|r| r.fft_sizes.iter().find(|s| s.size == size.size))
+                    .map(value)
+                    .collect(),
+            });
+        }
+    }
+
+    // Cache probe, one row per cache level detected in the first run
+    let level_names: Vec<String> = memory
+        .first()
+        .map(|r| {
+            r.cache_probe
+                .levels
+                .iter()
+                .map(|l| l.name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    for name in level_names {
+        rows.push(MetricRow {
+            category: "memory",
+            key: format!("memory_cache_{}_gbs", name.to_lowercase()),
+            label: format!("Cache Probe {} (GB/s)", name),
+            unit: Unit::GigabytesPerSec,
+            values: memory
+                .iter()
+                .filter_map(|r| r.cache_probe.levels.iter().find(|l| l.name == name))
+                .map(|l| l.bandwidth_gbs)
+                .collect(),
+        });
+    }
+
+    // Memory thread-scaling sweep, one row per thread count of the first run
+    let thread_counts: Vec<usize> = memory
+        .first()
+        .map(|r| r.thread_scaling.iter().map(|p| p.threads).collect())
+        .unwrap_or_default();
+    for threads in thread_counts {
+        rows.push(MetricRow {
+            category: "memory",
+            key: format!("memory_scaling_{}t_mibs", threads),
+            label: format!("Memory {} Threads (MiB/s)", threads),
+            unit: Unit::MebibytesPerSec,
+            values: memory
+                .iter()
+                .filter_map(|r| r.thread_scaling.iter().find(|p| p.threads == threads))
+                .map(|p| p.combined_throughput)
+                .collect(),
+        });
+    }
+
+    // NUMA comparison, when the first run measured one
+    if memory.first().is_some_and(|r| r.numa.is_some()) {
+        type NumaMetric = fn(&NumaResult) -> f64;
+        let numa_metrics: [(&str, &str, Unit, NumaMetric); 6] = [
+            (
+                "memory_numa_local_read_gbs",
+                "NUMA Local Read (GB/s)",
+                Unit::GigabytesPerSec,
+                |n| n.local_read_gbs,
+            ),
+            (
+                "memory_numa_remote_read_gbs",
+                "NUMA Remote Read (GB/s)",
+                Unit::GigabytesPerSec,
+                |n| n.remote_read_gbs,
+            ),
+            (
+                "memory_numa_local_latency_ns",
+                "NUMA Local Latency (ns)",
+                Unit::Nanoseconds,
+                |n| n.local_latency_ns,
+            ),
+            (
+                "memory_numa_remote_latency_ns",
+                "NUMA Remote Latency (ns)",
+                Unit::Nanoseconds,
+                |n| n.remote_latency_ns,
+            ),
+            (
+                "memory_numa_bandwidth_penalty",
+                "NUMA Bandwidth Penalty (x)",
+                Unit::Ratio,
+                NumaResult::bandwidth_penalty,
+            ),
+            (
+                "memory_numa_latency_penalty",
+                "NUMA Latency Penalty (x)",
+                Unit::Ratio,
+                NumaResult::latency_penalty,
+            ),
+        ];
+        for (key, label, unit, metric) in numa_metrics {
+            rows.push(MetricRow {
+                category: "memory",
+                key: key.to_string(),
+                label: label.to_string(),
+                unit,
+                values: memory
+                    .iter()
+                    .filter_map(|r| r.numa.as_ref())
+                    .map(metric)
+                    .collect(),
+            });
+        }
+    }
+
+    // Core-to-core latency summary, when the first run measured it; the matrix itself is
+    // in the JSON results and the HTML report
+    if concurrency
+        .first()
+        .is_some_and(|r| r.core_latency.is_some())
+    {
+        type CoreLatencyValue = fn((f64, f64, f64)) -> f64;
+        let latency_metrics: [(&str, &str, CoreLatencyValue); 3] = [
+            (
+                "concurrency_core_latency_min_ns",
+                "Core Latency Min (ns)",
+                |s| s.0,
+            ),
+            (
+                "concurrency_core_latency_mean_ns",
+                "Core Latency Mean (ns)",
+                |s| s.1,
+            ),
+            (
+                "concurrency_core_latency_max_ns",
+                "Core Latency Max (ns)",
+                |s| s.2,
+            ),
+        ];
+        for (key, label, metric) in latency_metrics {
+            rows.push(MetricRow {
+                category: "concurrency",
+                key: key.to_string(),
+                label: label.to_string(),
+                unit: Unit::Nanoseconds,
+                values: concurrency
+                    .iter()
+                    .filter_map(|r| r.core_latency.as_ref()?.summary())
+                    .map(metric)
+                    .collect(),
+            });
+        }
+    }
+
+    // Access patterns, one row per pattern of the first run
+    let patterns: Vec<AccessPattern> = memory
+        .first()
+        .map(|r| r.access_patterns.iter().map(|p| p.pattern).collect())
+        .unwrap_or_default();
+    for pattern in patterns {
+        rows.push(MetricRow {
+            category: "memory",
+            key: format!("memory_pattern_{}_gbs", pattern.name()),
+            label: format!("Memory Read {} (GB/s)", pattern.label()),
+            unit: Unit::GigabytesPerSec,
+            values: memory
+                .iter()
+                .filter_map(|r| r.access_patterns.iter().find(|p| p.pattern == pattern))
+                .map(|p| p.read_gbs)
+                .collect(),
+        });
+    }
+
+    // Huge-page passes, when the first run measured them
+    if memory.first().is_some_and(|r| r.huge_pages.is_some()) {
+        type HugePageMetric = fn(&MemoryResult, &HugePageResult) -> f64;
+        let huge_page_metrics: [(&str, &str, Unit, HugePageMetric); 4] = [
+            (
+                "memory_huge_pages_write_mibs",
+                "Huge Pages Write (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, h| h.write_throughput,
+            ),
+            (
+                "memory_huge_pages_read_mibs",
+                "Huge Pages Read (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, h| h.read_throughput,
+            ),
+            (
+                "memory_huge_pages_combined_mibs",
+                "Huge Pages Avg (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, h| h.combined_throughput,
+            ),
+            (
+                "memory_huge_pages_gain",
+                "Huge Pages Gain (x)",
+                Unit::Ratio,
+                |r, h| h.gain(r),
+            ),
+        ];
+        for (key, label, unit, metric) in huge_page_metrics {
+            rows.push(MetricRow {
+                category: "memory",
+                key: key.to_string(),
+                label: label.to_string(),
+                unit,
+                values: memory
+                    .iter()
+                    .filter_map(|r| r.huge_pages.as_ref().map(|h| metric(r, h)))
+                    .collect(),
+            });
+        }
+    }
+
+    // Concurrency sweep, one row per primitive and thread count of the first run
+    let concurrency_points: Vec<usize> = concurrency
+        .first()
+        .map(|r| r.points.iter().map(|p| p.threads).collect())
+        .unwrap_or_default();
+    type PointRate = fn(&ConcurrencyPoint) -> f64;
+    let primitives: [(&str, &str, Unit, PointRate); 4] = [
+        (
+            "mutex_uncontended_ops_per_sec",
+            "Mutex Uncontended",
+            Unit::OpsPerSec,
+            |p| p.mutex_uncontended_ops_per_sec,
+        ),
+        (
+            "mutex_contended_ops_per_sec",
+            "Mutex Contended",
+            Unit::OpsPerSec,
+            |p| p.mutex_contended_ops_per_sec,
+        ),
+        (
+            "atomic_ops_per_sec",
+            "Atomic Increment",
+            Unit::OpsPerSec,
+            |p| p.atomic_ops_per_sec,
+        ),
+        (
+            "channel_msgs_per_sec",
+            "Channel",
+            Unit::MessagesPerSec,
+            |p| p.channel_messages_per_sec,
+        ),
+    ];
+    for (key, label, unit, rate) in primitives {
+        for &threads in &concurrency_points {
+            rows.push(MetricRow {
+                category: "concurrency",
+                key: format!("concurrency_{}t_{}", threads, key),
+                label: format!("{} {} Threads ({})", label, threads, unit),
+                unit,
+                values: concurrency
+                    .iter()
+                    .filter_map(|r| r.points.iter().find(|p| p.threads == threads))
+                    .map(rate)
+                    .collect(),
+            });
+        }
+    }
+
+    // CPU frequency and temperature; runs without a reading are omitted
+    type ThermalReading = fn(&ThermalResult) -> Option<f64>;
+    let thermal_readings: [(&str, &str, Unit, ThermalReading); 3] = [
+        (
+            "cpu_peak_frequency_mhz",
+            "CPU Peak Frequency (MHz)",
+            Unit::Megahertz,
+            |t| t.peak_frequency_mhz,
+        ),
+        (
+            "cpu_sustained_frequency_mhz",
+            "CPU Sustained Frequency (MHz)",
+            Unit::Megahertz,
+            |t| t.sustained_frequency_mhz,
+        ),
+        (
+            "cpu_max_temperature_c",
+            "CPU Max Temperature (C)",
+            Unit::Celsius,
+            |t| t.max_temperature_c,
+        ),
+    ];
+    for (key, label, unit, reading) in thermal_readings {
+        rows.push(MetricRow {
+            category: "cpu",
+            key: key.to_string(),
+            label: label.to_string(),
+            unit,
+            values: cpu.iter().filter_map(|r| reading(&r.thermal)).collect(),
+        });
+    }
+
+    // Package energy, when the first run measured it: totals, then every kernel's rate per
+    // watt under its timing name
+    if let Some(energy) = cpu.first().and_then(|r| r.energy.as_ref()) {
+        let energies = || cpu.iter().filter_map(|r| r.energy.as_ref());
+        rows.push(MetricRow {
+            category: "cpu",
+            key: "cpu_energy_joules".to_string(),
+            label: "CPU Energy (J)".to_string(),
+            unit: Unit::Joules,
+            values: energies().map(|e| e.joules).collect(),
+        });
+        rows.push(MetricRow {
+            category: "cpu",
+            key: "cpu_average_watts".to_string(),
+            label: "CPU Average Power (W)".to_string(),
+            unit: Unit::Watts,
+            values: energies().map(EnergyResult::average_watts).collect(),
+        });
+        for name in energy.kernel_watts.keys() {
+            rows.push(MetricRow {
+                category: "cpu",
+                key: format!("cpu_{}_per_watt", name),
+                label: format!("CPU {} per Watt", name.replace('_', " ")),
+                unit: Unit::PerWatt,
+                values: cpu
+                    .iter()
+                    .filter_map(|r| r.energy.as_ref()?.per_watt(name, &r.timings))
+                    .collect(),
+            });
+        }
+    }
+
+    // Disk block-size sweep, one write and one read row per swept block size
+    for block_size in disk::SWEEP_BLOCK_SIZES {
+        let size = block_size_name(block_size);
+        let sweep = |r: &DiskResult| {
+            r.block_size_sweep
+                .iter()
+                .find(|s| s.block_size == block_size)
+                .cloned()
+        };
+        rows.push(MetricRow {
+            category: "disk",
+            key: format!("disk_write_{}_mibs", size.to_lowercase()),
+            label: format!("Disk Write {} (MiB/s)", size),
+            unit: Unit::MebibytesPerSec,
+            values: disk
+                .iter()
+                .filter_map(sweep)
+                .map(|s| s.write_throughput)
+                .collect(),
+        });
+        rows.push(MetricRow {
+            category: "disk",
+            key: format!("disk_read_{}_mibs", size.to_lowercase()),
+            label: format!("Disk Read {} (MiB/s)", size),
+            unit: Unit::MebibytesPerSec,
+            values: disk
+                .iter()
+                .filter_map(sweep)
+                .map(|s| s.read_throughput)
+                .collect(),
+        });
+    }
+
+    // Burst and steady-state writes of preconditioned runs; other runs are omitted
+    type PreconditionRate = fn(&PreconditionResult) -> f64;
+    let precondition_rates: [(&str, &str, PreconditionRate); 2] = [
+        ("burst", "Burst", |p| p.burst_throughput),
+        ("steady", "Steady", |p| p.steady_throughput),
+    ];
+    for (key, label, rate) in precondition_rates {
+        rows.push(MetricRow {
+            category: "disk",
+            key: format!("disk_{}_write_mibs", key),
+            label: format!("Disk {} Write (MiB/s)", label),
+            unit: Unit::MebibytesPerSec,
+            values: disk
+                .iter()
+                .filter_map(|r| r.precondition.as_ref())
+                .map(rate)
+                .collect(),
+        });
+    }
+
+    // Sequential passes through the OS cache, when the first run measured them
+    if disk.first().is_some_and(|r| r.cached.is_some()) {
+        type CachedMetric = fn(&DiskResult, &CachedResult) -> f64;
+        let cached_metrics: [(&str, &str, Unit, CachedMetric); 5] = [
+            (
+                "disk_cached_write_mibs",
+                "Disk Cached Write (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, c| c.write_throughput,
+            ),
+            (
+                "disk_cached_read_mibs",
+                "Disk Cached Read (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, c| c.read_throughput,
+            ),
+            (
+                "disk_cached_combined_mibs",
+                "Disk Cached Avg (MiB/s)",
+                Unit::MebibytesPerSec,
+                |_, c| c.combined_throughput,
+            ),
+            (
+                "disk_cache_read_speedup",
+                "Disk Cache Read Speedup (x)",
+                Unit::Ratio,
+                |r, c| c.read_speedup(r),
+            ),
+            (
+                "disk_cache_speedup",
+                "Disk Cache Speedup (x)",
+                Unit::Ratio,
+                |r, c| c.speedup(r),
+            ),
+        ];
+        for (key, label, unit, metric) in cached_metrics {
+            rows.push(MetricRow {
+                category: "disk",
+                key: key.to_string(),
+                label: label.to_string(),
+                unit,
+                values: disk
+                    .iter()
+                    .filter_map(|r| r.cached.as_ref().map(|c| metric(r, c)))
+                    .collect(),
+            });
+        }
+    }
+
+    // Per-block completion latency of the main sequential passes; runs from reports
+    // written before it was recorded are omitted
+    type Percentile = fn(&HistogramSummary) -> f64;
+    let percentiles: [(&str, &str, Percentile); 5] = [
+        ("p50", "P50", |l| l.p50_us),
+        ("p90", "P90", |l| l.p90_us),
+        ("p99", "P99", |l| l.p99_us),
+        ("p999", "P99.9", |l| l.p999_us),
+        ("max", "Max", |l| l.max_us),
+    ];
+    type Direction = fn(&DiskResult) -> &HistogramSummary;
+    let directions: [(&str, &str, Direction); 2] = [
+        ("write", "Write", |r| &r.write_latency),
+        ("read", "Read", |r| &r.read_latency),
+    ];
+    for (direction, direction_label, latency) in directions {
+        for (key, label, percentile) in percentiles {
+            rows.push(MetricRow {
+                category: "disk",
+                key: format!("disk_{}_latency_{}_us", direction, key),
+                label: format!("Disk {} Latency {} (us)", direction_label, label),
+                unit: Unit::Microseconds,
+                values: disk
+                    .iter()
+                    .map(latency)
+                    .filter(|l| l.samples > 0)
+                    .map(percentile)
+                    .collect(),
+            });
+        }
+    }
+
+    // Filesystem metadata operations; runs from reports written before they were measured
+    // are omitted
+    type MetadataRate = fn(&MetadataResult) -> f64;
+    let metadata_rates: [(&str, &str, MetadataRate); 4] = [
+        ("create", "Create", |m| m.create_ops_per_sec),
+        ("stat", "Stat", |m| m.stat_ops_per_sec),
+        ("rename", "Rename", |m| m.rename_ops_per_sec),
+        ("delete", "Delete", |m| m.delete_ops_per_sec),
+    ];
+    for (key, label, rate) in metadata_rates {
+        rows.push(MetricRow {
+            category: "disk",
+            key: format!("disk_metadata_{}_ops_per_sec", key),
+            label: format!("Disk Metadata {} (ops/sec)", label),
+            unit: Unit::OpsPerSec,
+            values: disk
+                .iter()
+                .map(|r| &r.metadata)
+                .filter(|m| m.create_ops_per_sec > 0.0)
+                .map(rate)
+                .collect(),
+        });
+    }
+
+    // Small-file writes; runs from reports written before they were measured are omitted
+    type SmallFilesRate = fn(&SmallFilesResult) -> f64;
+    let small_files_rates: [(&str, &str, Unit, SmallFilesRate); 4] = [
+        ("files_per_sec", "(files/sec)", Unit::FilesPerSec, |s| {
+            s.files_per_sec
+        }),
+        ("mibs", "(MiB/s)", Unit::MebibytesPerSec, |s| s.throughput),
+        (
+            "synced_files_per_sec",
+            "Synced (files/sec)",
+            Unit::FilesPerSec,
+            |s| s.synced_files_per_sec,
+        ),
+        (
+            "synced_mibs",
+            "Synced (MiB/s)",
+            Unit::MebibytesPerSec,
+            |s| s.synced_throughput,
+        ),
+    ];
+    for (key, label, unit, rate) in small_files_rates {
+        rows.push(MetricRow {
+            category: "disk",
+            key: format!("disk_small_{}", key),
+            label: format!("Disk Small Files {}", label),
+            unit,
+            values: disk
+                .iter()
+                .map(|r| &r.small_files)
+                .filter(|s| s.files_per_sec > 0.0)
+                .map(rate)
+                .collect(),
+        });
+    }
+
+    // Mixed disk workloads, one block of rows per profile of the first run
+    let disk_profiles: Vec<disk::DiskProfile> = disk
+        .first()
+        .map(|r| r.profiles.iter().map(|p| p.profile).collect())
+        .unwrap_or_default();
+    type ProfileValue = fn(&DiskProfileResult) -> Option<f64>;
+    let profile_values: [(&str, &str, Unit, ProfileValue); 4] = [
+        ("mibs", "(MiB/s)", Unit::MebibytesPerSec, |p| {
+            Some(p.throughput)
+        }),
+        ("iops", "(IOPS)", Unit::Iops, |p| Some(p.iops)),
+        ("read_p99_us", "Read P99 (us)", Unit::Microseconds, |p| {
+            (p.read_latency.samples > 0).then_some(p.read_latency.p99_us)
+        }),
+        ("write_p99_us", "Write P99 (us)", Unit::Microseconds, |p| {
+            (p.write_latency.samples > 0).then_some(p.write_latency.p99_us)
+        }),
+    ];
+    for profile in disk_profiles {
+        for (key, label, unit, value) in profile_values {
+            rows.push(MetricRow {
+                category: "disk",
+                key: format!("disk_{}_{}", profile.name(), key),
+                label: format!("Disk {} {}", profile.label(), label),
+                unit,
+                values: disk
+                    .iter()
+                    .filter_map(|r| r.profiles.iter().find(|p| p.profile == profile))
+                    .filter_map(value)
+                    .collect(),
+            });
+        }
+    }
+
+    // Composite scores; runs without a score for a subsystem are omitted
+    let score_row = |key: &str, label: &str, score: fn(&Scores) -> Option<f64>| MetricRow {
+        category: "score",
+        key: key.to_string(),
+        label: label.to_string(),
+        unit: Unit::Score,
+        values: scores.iter().filter_map(score).collect(),
+    };
+    rows.extend([
+        score_row("score_cpu", "Score CPU", |s| s.cpu),
+        score_row("score_memory", "Score Memory", |s| s.memory),
+        score_row("score_disk", "Score Disk", |s| s.disk),
+        score_row("score_overall", "Score Overall", |s| s.overall),
+    ]);
+
+    rows
+}
+
+/// Short block size name used in metric keys and labels, e.g. "4K" or "1M"
+fn block_size_name(block_size: usize) -> String {
+    if block_size >= 1024 * 1024 && block_size.is_multiple_of(1024 * 1024) {
+        format!("{}M", block_size / (1024 * 1024))
+    } else {
+        format!("{}K", block_size / 1024)
+    }
+}
+
+/// Path of a new `output_<timestamp>.<extension>` report in `dir`; reports in the working
+/// directory are named without a directory
+pub fn report_path(dir: &Path, extension: &str) -> PathBuf {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let filename = format!("output_{}.{}", timestamp, extension);
+    if dir == Path::new(".") {
+        PathBuf::from(filename)
+    } else {
+        dir.join(filename)
+    }
+}
+
+/// Layout of the CSV report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvFormat {
+    /// One row per metric, with a column per run followed by the statistics
+    #[default]
+    Wide,
+    /// One row per metric and run (`metric,unit,run,value`), with the statistics in a
+    /// separate file, as pandas and R expect tidy data
+    Long,
+}
+
+impl CsvFormat {
+    pub const ALL: [CsvFormat; 2] = [CsvFormat::Wide, CsvFormat::Long];
+
+    /// Format with the given name, ignoring case (e.g. "wide" or "long")
+    pub fn from_name(name: &str) -> Option<CsvFormat> {
+        CsvFormat::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvFormat::Wide => "wide",
+            CsvFormat::Long => "long",
+        }
+    }
+}
+
+/// Suffix of the statistics file that accompanies a long CSV report
+const STATS_CSV_SUFFIX: &str = "_stats.csv";
+
+/// Write per-run results and statistics to `output_<timestamp>.csv` in `dir`, with byte
+/// rates in `units`
+/// Returns the name of the written file
+pub fn write_csv_report(
+    results: &SuiteResults,
+    _system_info: &SystemInfo,
+    dir: &Path,
+    units: UnitSystem,
+) -> io::Result<String> {
+    let path = report_path(dir, "csv");
+    let mut file = BufWriter::new(File::create(&path)?);
+
+    // Comment line naming the build, which most CSV readers can be told to skip
+    writeln!(file, "# HsBenchMarkSuite {}", build_summary())?;
+
+    // Write header with individual runs and statistics
+    let mut header = vec!["Metric".to_string(), "Unit".to_string()];
+    for i in 1..=results.config.runs {
+        header.push(format!("Run {}", i));
+    }
+    header.extend_from_slice(&[
+        "Mean".to_string(),
+        "StdDev".to_string(),
+        "Min".to_string(),
+        "Max".to_string(),
+        "P50".to_string(),
+        "P95".to_string(),
+        "P99".to_string(),
+        "CV%".to_string(),
+        "GeoMean".to_string(),
+        "HarmonicMean".to_string(),
+    ]);
+    writeln!(file, "{}", header.join(","))?;
+
+    for metric in display_rows(results, units) {
+        // Skip subsystems that were disabled for this run
+        if metric.values.is_empty() {
+            continue;
+        }
+
+        let mut row = vec![metric.label.to_string(), metric.unit.symbol().to_string()];
+        for val in &metric.values {
+            row.push(format!("{:.2}", val));
+        }
+        // Keep statistics columns aligned when some runs failed
+        for _ in metric.values.len()..results.config.runs {
+            row.push(String::new());
+        }
+
+        // Calculate and append statistics
+        if let Some(stats) = Statistics::from_values(&metric.values) {
+            row.push(format!("{:.2}", stats.mean));
+            row.push(format!("{:.2}", stats.std_dev));
+            row.push(format!("{:.2}", stats.min));
+            row.push(format!("{:.2}", stats.max));
+            row.push(format!("{:.2}", stats.p50));
+            row.push(format!("{:.2}", stats.p95));
+            row.push(format!("{:.2}", stats.p99));
+            row.push(format!("{:.2}", stats.coefficient_of_variation));
+            for mean in [stats.geo_mean, stats.harmonic_mean] {
+                row.push(mean.map(|m| format!("{:.2}", m)).unwrap_or_default());
+            }
+        }
+
+        writeln!(file, "{}", row.join(","))?;
+    }
+
+    file.flush()?;
+    Ok(path.display().to_string())
+}
+
+/// Write one row per metric and run to `output_<timestamp>.csv` in `dir`, and the
+/// statistics of every metric to `output_<timestamp>_stats.csv` next to it
+/// Values are written at full precision, byte rates in `units`, and metrics are named by
+/// their JSON keys
+/// Returns the names of the written files
+pub fn write_long_csv_report(
+    results: &SuiteResults,
+    dir: &Path,
+    units: UnitSystem,
+) -> io::Result<(String, String)> {
+    let path = report_path(dir, "csv");
+    let stats_path = path.with_file_name(format!(
+        "{}{}",
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("output"),
+        STATS_CSV_SUFFIX
+    ));
+    let rows: Vec<MetricRow> = display_rows(results, units)
+        .into_iter()
+        .filter(|metric| !metric.values.is_empty())
+        .collect();
+
+    let mut file = BufWriter::new(File::create(&path)?);
+    writeln!(file, "# HsBenchMarkSuite {}", build_summary())?;
+    writeln!(file, "metric,unit,run,value")?;
+    for metric in &rows {
+        for (run, value) in metric.values.iter().enumerate() {
+            writeln!(
+                file,
+                "{},{},{},{}",
+                metric.key,
+                metric.unit.symbol(),
+                run + 1,
+                value
+            )?;
+        }
+    }
+    file.flush()?;
+
+    // Column names match the keys of the JSON statistics
+    let mut file = BufWriter::new(File::create(&stats_path)?);
+    writeln!(file, "# HsBenchMarkSuite {}", build_summary())?;
+    writeln!(
+        file,
+        "metric,unit,count,mean,std_dev,min,max,p50,p95,p99,cv_percent,ci95_low,ci95_high,geo_mean,harmonic_mean"
+    )?;
+    for metric in &rows {
+        let Some(stats) = Statistics::from_values(&metric.values) else {
+            continue;
+        };
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            metric.key,
+            metric.unit.symbol(),
+            stats.count,
+            stats.mean,
+            stats.std_dev,
+            stats.min,
+            stats.max,
+            stats.p50,
+            stats.p95,
+            stats.p99,
+            stats.coefficient_of_variation,
+            stats.ci95_low,
+            stats.ci95_high,
+            optional(stats.geo_mean),
+            optional(stats.harmonic_mean)
+        )?;
+    }
+    file.flush()?;
+
+    Ok((path.display().to_string(), stats_path.display().to_string()))
+}
+
+/// Write system info, configuration, per-run results, and statistics to
+/// `output_<timestamp>.json` in `dir`
+/// Returns the name of the written file
+pub fn write_json_report(
+    results: &SuiteResults,
+    system_info: &SystemInfo,
+    dir: &Path,
+) -> io::Result<String> {
+    let path = report_path(dir, "json");
+    let mut file = BufWriter::new(File::create(&path)?);
+
+    let report = SuiteReport::new(results, system_info);
+    serde_json::to_writer_pretty(&mut file, &report)?;
+    writeln!(file)?;
+    file.flush()?;
+
+    Ok(path.display().to_string())
+}
+
+/// Extensions of the `output_<timestamp>` files written by the report writers
+pub const REPORT_EXTENSIONS: [&str; 4] = ["csv", "json", "html", "md"];
+
+/// Delete all but the `keep` newest `output_<timestamp>` reports of each format in `dir`
+/// Timestamps sort chronologically, so the newest reports have the greatest names
+/// Returns the deleted files
+pub fn rotate_reports(dir: &Path, keep: usize) -> io::Result<Vec<PathBuf>> {
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let is_report = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("output_"));
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            is_report && extension.is_some_and(|ext| REPORT_EXTENSIONS.contains(&ext))
+        })
+        .collect();
+    reports.sort();
+
+    // The statistics files of long CSV reports are kept like a format of their own
+    let is_stats = |path: &PathBuf| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(STATS_CSV_SUFFIX))
+    };
+    let mut removed = Vec::new();
+    for (extension, stats) in REPORT_EXTENSIONS
+        .into_iter()
+        .map(|extension| (extension, false))
+        .chain([("csv", true)])
+    {
+        let of_format: Vec<&PathBuf> = reports
+            .iter()
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some(extension))
+            .filter(|path| is_stats(path) == stats)
+            .collect();
+        let excess = of_format.len().saturating_sub(keep);
+        for path in &of_format[..excess] {
+            std::fs::remove_file(path)?;
+            removed.push((*path).clone());
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryScalingPoint;
+    use crate::timing::{KernelTiming, KernelTimings};
+
+    fn sample_results() -> SuiteResults {
+        SuiteResults {
+            config: SuiteConfig {
+                runs: 2,
+                ..SuiteConfig::default()
+            },
+            cpu: vec![],
+            memory: vec![
+                MemoryResult {
+                    write_throughput: 100.0,
+                    read_throughput: 200.0,
+                    combined_throughput: 133.3,
+                    prefaulted: false,
+                    thread_scaling: vec![],
+                    numa: None,
+                    counters: Default::default(),
+                    stream_copy_gbs: 0.0,
+                    stream_scale_gbs: 0.0,
+                    stream_add_gbs: 0.0,
+                    stream_triad_gbs: 0.0,
+                    triad_scalar_gbs: 0.0,
+                    triad_simd_gbs: 0.0,
+                    write_cached_gbs: 0.0,
+                    write_nt_gbs: 0.0,
+                    page_faults_per_sec: 0.0,
+                    alloc_small_per_sec: 0.0,
+                    alloc_medium_per_sec: 0.0,
+                    alloc_large_per_sec: 0.0,
+                    alloc_small_mt_per_sec: 0.0,
+                    alloc_fragmented_per_sec: 0.0,
+                    footprint: Default::default(),
+                    huge_pages: None,
+                    access_patterns: Vec::new(),
+                    cache_probe: Default::default(),
+                    timings: KernelTimings::from([(
+                        "write_throughput".to_string(),
+                        KernelTiming::new(250.0, 2, 5.0),
+                    )]),
+                },
+                MemoryResult {
+                    write_throughput: 110.0,
+                    read_throughput: 210.0,
+                    combined_throughput: 144.4,
+                    prefaulted: false,
+                    thread_scaling: vec![],
+                    numa: None,
+                    counters: Default::default(),
+                    stream_copy_gbs: 0.0,
+                    stream_scale_gbs: 0.0,
+                    stream_add_gbs: 0.0,
+                    stream_triad_gbs: 0.0,
+                    triad_scalar_gbs: 0.0,
+                    triad_simd_gbs: 0.0,
+                    write_cached_gbs: 0.0,
+                    write_nt_gbs: 0.0,
+                    page_faults_per_sec: 0.0,
+                    alloc_small_per_sec: 0.0,
+                    alloc_medium_per_sec: 0.0,
+                    alloc_large_per_sec: 0.0,
+                    alloc_small_mt_per_sec: 0.0,
+                    alloc_fragmented_per_sec: 0.0,
+                    footprint: Default::default(),
+                    huge_pages: None,
+                    access_patterns: Vec::new(),
+                    cache_probe: Default::default(),
+                    timings: Default::default(),
+                },
+            ],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        }
+    }
+
+    fn sample_system_info() -> SystemInfo {
+        SystemInfo {
+            cpu_brand: "Test \"CPU\"".to_string(),
+            cpu_physical_cores: 4,
+            cpu_logical_cores: 8,
+            total_memory_mb: 16384,
+            os_name: "TestOS".to_string(),
+            os_version: "1.0".to_string(),
+            hostname: "test-host".to_string(),
+            gpu_adapter: None,
+            aes_acceleration: None,
+            simd_features: Vec::new(),
+            simd_level: String::new(),
+            cpu_frequency: Default::default(),
+            cpu_topology: Default::default(),
+            numa_nodes: Vec::new(),
+            power: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_report_groups_metrics_by_category() {
+        let report = SuiteReport::new(&sample_results(), &sample_system_info());
+        let categories: Vec<&String> = report.results.keys().collect();
+        assert_eq!(
+            categories,
+            vec![
+                "cpu",
+                "memory",
+                "concurrency",
+                "disk",
+                "network",
+                "gpu",
+                "score"
+            ]
+        );
+
+        let write = &report.results["memory"]["memory_write_throughput_mibs"];
+        assert_eq!(write.runs, vec![100.0, 110.0]);
+        assert_eq!(write.unit, Some(Unit::MebibytesPerSec));
+        assert!((write.statistics.as_ref().unwrap().mean - 105.0).abs() < 0.01);
+
+        // Disabled subsystems keep their keys with no runs and null statistics
+        let primes = &report.results["cpu"]["cpu_primes_per_sec"];
+        assert!(primes.runs.is_empty());
+        assert!(primes.statistics.is_none());
+
+        // Only memory was benchmarked, so it alone determines the overall score
+        assert_eq!(report.results["score"]["score_memory"].runs.len(), 2);
+        assert!(report.results["score"]["score_cpu"].runs.is_empty());
+        assert_eq!(report.scores.overall, report.scores.memory);
+    }
+
+    #[test]
+    fn test_report_energy_rows() {
+        let mut results = sample_results();
+        let cpu = CpuResult {
+            timings: KernelTimings::from([(
+                "primes_per_sec".to_string(),
+                KernelTiming::new(1000.0, 1, 1.0),
+            )]),
+            energy: Some(EnergyResult {
+                joules: 100.0,
+                seconds: 4.0,
+                kernel_watts: [("primes_per_sec".to_string(), 20.0)].into_iter().collect(),
+            }),
+            ..CpuResult::default()
+        };
+        results.cpu = vec![cpu.clone(), cpu];
+        let report = SuiteReport::new(&results, &sample_system_info());
+        let rows = &report.results["cpu"];
+        assert_eq!(rows["cpu_energy_joules"].runs, vec![100.0, 100.0]);
+        assert_eq!(rows["cpu_average_watts"].runs, vec![25.0, 25.0]);
+        assert_eq!(rows["cpu_primes_per_sec_per_watt"].runs, vec![50.0, 50.0]);
+
+        results.cpu = vec![CpuResult::default()];
+        let report = SuiteReport::new(&results, &sample_system_info());
+        assert!(!report.results["cpu"].contains_key("cpu_energy_joules"));
+    }
+
+    #[test]
+    fn test_report_numa_rows() {
+        let mut results = sample_results();
+        assert!(
+            !SuiteReport::new(&results, &sample_system_info()).results["memory"]
+                .contains_key("memory_numa_latency_penalty")
+        );
+
+        for result in &mut results.memory {
+            result.numa = Some(NumaResult {
+                nodes: 2,
+                local_read_gbs: 12.0,
+                remote_read_gbs: 8.0,
+                local_latency_ns: 90.0,
+                remote_latency_ns: 135.0,
+            });
+        }
+        let report = SuiteReport::new(&results, &sample_system_info());
+        let memory = &report.results["memory"];
+        assert_eq!(memory["memory_numa_remote_read_gbs"].runs, vec![8.0, 8.0]);
+        assert_eq!(memory["memory_numa_bandwidth_penalty"].runs, vec![1.5, 1.5]);
+        assert_eq!(memory["memory_numa_latency_penalty"].runs, vec![1.5, 1.5]);
+    }
+
+    #[test]
+    fn test_report_disk_cache_rows() {
+        let disk: DiskResult = serde_json::from_str(
+            "{\"write_throughput\": 200.0, \"read_throughput\": 400.0, \
+             \"combined_throughput\": 266.0}",
+        )
+        .unwrap();
+        let mut results = sample_results();
+        results.disk = vec![disk.clone(), disk];
+        assert!(
+            !SuiteReport::new(&results, &sample_system_info()).results["disk"]
+                .contains_key("disk_cache_speedup")
+        );
+
+        for result in &mut results.disk {
+            result.cached = Some(CachedResult {
+                write_throughput: 1000.0,
+                read_throughput: 4000.0,
+                combined_throughput: 1596.0,
+            });
+        }
+        let report = SuiteReport::new(&results, &sample_system_info());
+        let disk = &report.results["disk"];
+        assert_eq!(disk["disk_cached_read_mibs"].runs, vec![4000.0, 4000.0]);
+        assert_eq!(disk["disk_cache_read_speedup"].runs, vec![10.0, 10.0]);
+        assert_eq!(disk["disk_cache_speedup"].runs, vec![6.0, 6.0]);
+    }
+
+    #[test]
+    fn test_report_memory_scaling_rows() {
+        let mut results = sample_results();
+        for (run, result) in results.memory.iter_mut().enumerate() {
+            result.thread_scaling = [1, 2, 4]
+                .into_iter()
+                .map(|threads| MemoryScalingPoint {
+                    threads,
+                    write_throughput: 0.0,
+                    read_throughput: 0.0,
+                    combined_throughput: (threads * 100 + run) as f64,
+                })
+                .collect();
+        }
+
+        let report = SuiteReport::new(&results, &sample_system_info());
+        let four = &report.results["memory"]["memory_scaling_4t_mibs"];
+        assert_eq!(four.runs, vec![400.0, 401.0]);
+        // Without a sweep there are no scaling rows at all
+        let report = SuiteReport::new(&sample_results(), &sample_system_info());
+        assert!(!report.results["memory"].contains_key("memory_scaling_1t_mibs"));
+    }
+
+    #[test]
+    fn test_report_concurrency_rows() {
+        let mut results = sample_results();
+        results.concurrency = (0..2)
+            .map(|run| ConcurrencyResult {
+                points: [1, 2]
+                    .into_iter()
+                    .map(|threads| ConcurrencyPoint {
+                        threads,
+                        mutex_uncontended_ops_per_sec: 0.0,
+                        mutex_contended_ops_per_sec: (threads * 1000 + run) as f64,
+                        atomic_ops_per_sec: 0.0,
+                        channel_messages_per_sec: 0.0,
+                    })
+                    .collect(),
+                thread_spawn_us: 0.0,
+                context_switch_us: 0.0,
+                false_sharing: Default::default(),
+                core_latency: None,
+                timings: Default::default(),
+            })
+            .collect();
+
+        let report = SuiteReport::new(&results, &sample_system_info());
+        let contended =
+            &report.results["concurrency"]["concurrency_2t_mutex_contended_ops_per_sec"];
+        assert_eq!(contended.runs, vec![2000.0, 2001.0]);
+        // Two latency and three false-sharing rows plus four primitives at two thread counts
+        assert_eq!(report.results["concurrency"].len(), 13);
+    }
+
+    #[test]
+    fn test_block_size_name() {
+        assert_eq!(block_size_name(4 * 1024), "4K");
+        assert_eq!(block_size_name(64 * 1024), "64K");
+        assert_eq!(block_size_name(1024 * 1024), "1M");
+    }
+
+    #[test]
+    fn test_report_json_roundtrip() {
+        let report = SuiteReport::new(&sample_results(), &sample_system_info());
+        let json = serde_json::to_string(&report).unwrap();
+        let loaded: SuiteReport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.metadata.hostname, "test-host");
+        assert_eq!(loaded.system_info.cpu_brand, "Test \"CPU\"");
+        assert_eq!(loaded.configuration.runs, 2);
+        assert_eq!(loaded.run_results.memory.len(), 2);
+        assert_eq!(loaded.run_results.memory[1].read_throughput, 210.0);
+        // Raw timings are exported so rates can be recomputed from them
+        let write = loaded.run_results.memory[0].timings["write_throughput"];
+        assert_eq!(
+            (write.work, write.elapsed_secs, write.rounds),
+            (500.0, 5.0, 2)
+        );
+        assert_eq!(write.rate(), loaded.run_results.memory[0].write_throughput);
+        assert_eq!(
+            loaded.results["memory"]["memory_read_throughput_mibs"].runs,
+            vec![200.0, 210.0]
+        );
+    }
+
+    #[test]
+    fn test_report_json_keeps_statistics_key_names() {
+        let report = SuiteReport::new(&sample_results(), &sample_system_info());
+        let json = serde_json::to_value(&report).unwrap();
+        let stats = &json["results"]["memory"]["memory_write_throughput_mibs"]["statistics"];
+        for key in [
+            "mean",
+            "std_dev",
+            "min",
+            "max",
+            "p50",
+            "p95",
+            "p99",
+            "cv_percent",
+            "geo_mean",
+            "harmonic_mean",
+        ] {
+            assert!(stats.get(key).is_some(), "missing statistics key {}", key);
+        }
+        assert!(json["metadata"]["timestamp"].is_string());
+        assert_eq!(json["metadata"]["suite_version"], SUITE_VERSION);
+        assert!(json["metadata"]["timer"]["resolution_ns"].as_f64().unwrap() > 0.0);
+        assert_eq!(
+            json["results"]["memory"]["memory_write_throughput_mibs"]["unit"],
+            "MiB/s"
+        );
+        assert_eq!(json["results"]["score"]["score_memory"]["unit"], "");
+    }
+
+    #[test]
+    fn test_report_loads_legacy_format() {
+        // Reports written before serde serialization lack run_results and extra config keys
+        let legacy = r#"{
+            "metadata": {"timestamp": "2026-01-25T14:30:22+00:00", "hostname": "old"},
+            "system_info": {"cpu_brand": "CPU", "cpu_physical_cores": 4, "cpu_logical_cores": 8,
+                "total_memory_mb": 1024, "os_name": "OS", "os_version": "1", "hostname": "old"},
+            "configuration": {"scale": 1.0, "runs": 3, "threads": 4, "block_size": 524288},
+            "results": {"cpu": {"cpu_primes_per_sec": {"runs": [1.0, 2.0, 3.0],
+                "statistics": {"mean": 2.0, "std_dev": 0.82, "min": 1.0, "max": 3.0,
+                "p50": 2.0, "p95": 2.9, "p99": 2.98, "cv_percent": 40.8}}}}
+        }"#;
+        let report: SuiteReport = serde_json::from_str(legacy).unwrap();
+        assert_eq!(report.configuration.runs, 3);
+        assert!(report.configuration.cpu);
+        assert!(report.run_results.cpu.is_empty());
+        assert!(report.metadata.suite_version.is_empty());
+        assert_eq!(report.metadata.git_commit, None);
+        assert_eq!(report.results["cpu"]["cpu_primes_per_sec"].runs.len(), 3);
+        assert_eq!(report.results["cpu"]["cpu_primes_per_sec"].unit, None);
+    }
+
+    #[test]
+    fn test_report_load_renames_mebibyte_metrics() {
+        // Before the rename, MiB/s metrics were named `_mbs`
+        let mut json =
+            serde_json::to_value(SuiteReport::new(&sample_results(), &sample_system_info()))
+                .unwrap();
+        let memory = json["results"]["memory"].as_object_mut().unwrap();
+        let write = memory.remove("memory_write_throughput_mibs").unwrap();
+        memory.insert("memory_write_throughput_mbs".to_string(), write);
+        let path = std::env::temp_dir().join(format!("hsbench_legacy_{}.json", std::process::id()));
+        std::fs::write(&path, json.to_string()).unwrap();
+        let loaded = SuiteReport::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        let write = &loaded.results["memory"]["memory_write_throughput_mibs"];
+        assert_eq!(write.runs, vec![100.0, 110.0]);
+        assert!(!loaded.results["memory"].contains_key("memory_write_throughput_mbs"));
+    }
+
+    #[test]
+    fn test_report_path() {
+        let path = report_path(Path::new("."), "csv");
+        assert_eq!(path.parent(), Some(Path::new("")));
+        let name = path.to_str().unwrap();
+        assert!(
+            name.starts_with("output_") && name.ends_with(".csv"),
+            "{}",
+            name
+        );
+        let path = report_path(Path::new("results"), "json");
+        assert_eq!(path.parent(), Some(Path::new("results")));
+    }
+
+    #[test]
+    fn test_csv_report_names_units() {
+        let dir = std::env::temp_dir().join(format!("hsbench_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = write_csv_report(
+            &sample_results(),
+            &sample_system_info(),
+            &dir,
+            UnitSystem::Si,
+        )
+        .unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[1].starts_with("Metric,Unit,Run 1,Run 2,Mean,"));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("Memory Write (MB/s),MB/s,104.86,115.34,110.10,")));
+        // Scores have no unit
+        assert!(lines.iter().any(|line| line.starts_with("Score Memory,,")));
+    }
+
+    #[test]
+    fn test_long_csv_report() {
+        let dir = std::env::temp_dir().join(format!("hsbench_long_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (path, stats_path) =
+            write_long_csv_report(&sample_results(), &dir, UnitSystem::Binary).unwrap();
+        let values = std::fs::read_to_string(&path).unwrap();
+        let stats = std::fs::read_to_string(&stats_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(stats_path.ends_with("_stats.csv"));
+        let lines: Vec<&str> = values.lines().collect();
+        assert!(lines[0].starts_with("# HsBenchMarkSuite "));
+        assert_eq!(lines[1], "metric,unit,run,value");
+        assert!(lines.contains(&"memory_write_throughput_mibs,MiB/s,1,100"));
+        assert!(lines.contains(&"memory_write_throughput_mibs,MiB/s,2,110"));
+        // Scores have no unit
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("score_memory,,1,")));
+        assert!(stats
+            .lines()
+            .any(|line| line.starts_with("memory_write_throughput_mibs,MiB/s,2,105,")));
+    }
+
+    #[test]
+    fn test_rotate_reports_keeps_newest_of_each_format() {
+        let dir = std::env::temp_dir().join(format!("hsbench_rotate_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "output_20260101_120000.csv",
+            "output_20260102_120000.csv",
+            "output_20260103_120000.csv",
+            "output_20260103_120000_stats.csv",
+            "output_20260101_120000.json",
+            "output_20260101_120000.txt",
+            "notes.csv",
+        ] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+
+        let removed = rotate_reports(&dir, 2).unwrap();
+        assert_eq!(removed, vec![dir.join("output_20260101_120000.csv")]);
+        for kept in [
+            "output_20260102_120000.csv",
+            "output_20260103_120000.csv",
+            "output_20260103_120000_stats.csv",
+            "output_20260101_120000.json",
+            "output_20260101_120000.txt",
+            "notes.csv",
+        ] {
+            assert!(dir.join(kept).exists(), "{} was removed", kept);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,712 @@
+/// Self-contained HTML report
+/// Renders system info, configuration, scores, statistics tables, and per-run bar charts
+/// into a single file with inline CSS and SVG, so it can be shared without other tools
+use crate::concurrency::CoreLatencyMatrix;
+use crate::disk::DiskResult;
+use crate::report::{self, display_rows, report_path, MetricRow};
+use crate::scoring;
+use crate::stats::Statistics;
+use crate::suite::SuiteResults;
+use crate::sysinfo_capture::SystemInfo;
+use crate::timing;
+use crate::units::{Unit, UnitSystem};
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const CHART_WIDTH: f64 = 360.0;
+const CHART_HEIGHT: f64 = 180.0;
+const CHART_MARGIN: f64 = 24.0; // Room for value labels above and run labels below the bars
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; margin: 0; background: #f5f6f8; color: #222; }
+header { background: #1f3b57; color: #fff; padding: 24px 32px; }
+header h1 { margin: 0 0 6px 0; font-size: 1.6em; }
+header p { margin: 0; opacity: 0.8; }
+main { padding: 16px 32px 32px 32px; }
+section { background: #fff; border-radius: 6px; box-shadow: 0 1px 3px rgba(0,0,0,0.1); margin-top: 16px; padding: 16px 20px; }
+h2 { margin-top: 0; font-size: 1.25em; color: #1f3b57; }
+table { border-collapse: collapse; font-size: 0.9em; }
+th, td { padding: 6px 10px; border-bottom: 1px solid #e3e6ea; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+.info td:first-child { color: #666; }
+.warning { background: #fff4e5; border-left: 4px solid #e67e22; }
+.score { font-size: 2.4em; font-weight: bold; color: #1f3b57; }
+.subscores span { display: inline-block; margin-right: 24px; color: #444; }
+.charts { display: flex; flex-wrap: wrap; gap: 16px; margin-top: 16px; }
+figure { margin: 0; }
+figcaption { font-size: 0.85em; font-weight: bold; margin-bottom: 4px; }
+.scroll { overflow-x: auto; }
+.matrix th, .matrix td { padding: 3px 5px; font-size: 0.8em; text-align: center; }
+footer { padding: 0 32px 24px 32px; font-size: 0.8em; color: #777; }
+";
+
+/// Write the HTML report to `output_<timestamp>.html` in `dir`, with byte rates in
+/// `units`
+/// Returns the name of the written file
+pub fn write_html_report(
+    results: &SuiteResults,
+    system_info: &SystemInfo,
+    dir: &Path,
+    units: UnitSystem,
+) -> io::Result<String> {
+    let path = report_path(dir, "html");
+    fs::write(&path, render_html_report(results, system_info, units))?;
+    Ok(path.display().to_string())
+}
+
+/// Render the complete HTML document, with byte rates in `units`
+pub fn render_html_report(
+    results: &SuiteResults,
+    system_info: &SystemInfo,
+    units: UnitSystem,
+) -> String {
+    let config = &results.config;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>Benchmark Report - {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape(&system_info.hostname),
+        STYLE
+    ));
+
+    // Header
+    html.push_str(&format!(
+        "<header>\n<h1>HsBenchMarkSuite Report</h1>\n<p>{} &middot; {} &middot; version {}</p>\n</header>\n<main>\n",
+        escape(&system_info.hostname),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        escape(&report::build_summary())
+    ));
+
+    // System information and configuration
+    html.push_str("<section>\n<h2>System Information</h2>\n<table class=\"info\">\n");
+    let mut info_rows = vec![
+        ("CPU", system_info.cpu_brand.clone()),
+        (
+            "Cores",
+            format!(
+                "{} physical, {} logical",
+                system_info.cpu_physical_cores, system_info.cpu_logical_cores
+            ),
+        ),
+        ("Memory", format!("{} MB", system_info.total_memory_mb)),
+        (
+            "OS",
+            format!("{} {}", system_info.os_name, system_info.os_version),
+        ),
+        ("Timer", timing::calibration().to_string()),
+        ("Scale", config.scale_summary()),
+        ("Runs", config.runs.to_string()),
+        ("Threads", config.threads.to_string()),
+        (
+            "Disk Block Size",
+            format!("{} KB", config.block_size / 1024),
+        ),
+        ("Disk Mode", config.disk_mode.name().to_string()),
+        (
+            "Network",
+            config
+                .network_target
+                .clone()
+                .unwrap_or_else(|| "loopback".to_string()),
+        ),
+    ];
+    if let Some(gpu) = &system_info.gpu_adapter {
+        info_rows.push(("GPU", gpu.clone()));
+    }
+    if !system_info.simd_features.is_empty() {
+        info_rows.push((
+            "SIMD",
+            format!(
+                "{} (kernels use {})",
+                system_info.simd_features.join(" "),
+                system_info.simd_level
+            ),
+        ));
+    }
+    info_rows.push((
+        "AES Acceleration",
+        system_info
+            .aes_acceleration
+            .clone()
+            .unwrap_or_else(|| "none (software)".to_string()),
+    ));
+    if let Some(frequency) = system_info.cpu_frequency.summary() {
+        info_rows.push(("CPU Frequency", frequency));
+    }
+    if let Some(core_types) = system_info.cpu_topology.core_type_summary() {
+        info_rows.push(("Core Types", core_types));
+    }
+    if let Some(caches) = system_info.cpu_topology.cache_summary() {
+        info_rows.push(("Caches", caches));
+    }
+    if let Some(numa) = system_info.numa_summary() {
+        info_rows.push(("NUMA", numa));
+    }
+    if let Some(power) = system_info.power.summary() {
+        info_rows.push(("Power", power));
+    }
+    if let Some(target) = &results.disk_target {
+        info_rows.push(("Disk Path", target.summary()));
+    }
+    if let Some(precondition) = &results.config.disk_precondition {
+        info_rows.push(("Disk Precondition", precondition.summary()));
+    }
+    if let Some(footprint) = results.memory_footprint_summary() {
+        info_rows.push(("Memory Footprint", footprint));
+    }
+    info_rows.push((
+        "Schedule",
+        results.config.schedule.description().to_string(),
+    ));
+    if let Some(cooldown) = results.cooldown_summary() {
+        info_rows.push(("Cooldown", cooldown));
+    }
+    if let Some(verification) = results.verification_summary() {
+        info_rows.push(("Verification", verification));
+    }
+    for (name, value) in &info_rows {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            name,
+            escape(value)
+        ));
+    }
+    html.push_str("</table>\n</section>\n");
+
+    for warning in [
+        results.interruption_warning(),
+        system_info.governor_warning(),
+        system_info.battery_warning(),
+        results.throttling_warning(),
+        results.verification_warning(),
+        results.timeout_warning(),
+        results.direct_io_warning(),
+        results.contamination_warning(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        html.push_str(&format!(
+            "<section class=\"warning\">\n<b>Warning:</b> {}\n</section>\n",
+            escape(&warning)
+        ));
+    }
+
+    // Composite score
+    let scores = scoring::compute_scores(results);
+    if let Some(overall) = scores.overall {
+        html.push_str("<section>\n<h2>Overall Score</h2>\n");
+        html.push_str(&format!("<div class=\"score\">{:.0}</div>\n", overall));
+        html.push_str("<p class=\"subscores\">");
+        for (name, score) in [
+            ("CPU", scores.cpu),
+            ("Memory", scores.memory),
+            ("Disk", scores.disk),
+        ] {
+            if let Some(score) = score {
+                html.push_str(&format!("<span>{}: <b>{:.0}</b></span>", name, score));
+            }
+        }
+        html.push_str(&format!(
+            "</p>\n<p>Reference system = {:.0}; higher is better.</p>\n</section>\n",
+            scoring::REFERENCE_SCORE
+        ));
+    }
+
+    // One section per benchmarked category
+    let rows = display_rows(results, units);
+    let mut categories: Vec<&str> = Vec::new();
+    for row in &rows {
+        if !row.values.is_empty() && !categories.contains(&row.category) {
+            categories.push(row.category);
+        }
+    }
+    for category in categories {
+        let category_rows: Vec<&MetricRow> = rows
+            .iter()
+            .filter(|r| r.category == category && !r.values.is_empty())
+            .collect();
+        render_category(&mut html, category, &category_rows);
+    }
+
+    // Core-to-core latency matrix of the first run that measured one
+    if let Some(matrix) = results
+        .concurrency
+        .iter()
+        .find_map(|r| r.core_latency.as_ref())
+    {
+        render_core_latency(&mut html, matrix);
+    }
+
+    render_write_curves(&mut html, &results.disk, units);
+
+    html.push_str("</main>\n<footer>\n");
+    html.push_str(
+        "DISCLAIMER: Results represent performance on specific synthetic algorithms and do NOT \
+         necessarily equate to actual system capability for real-world workloads.\n",
+    );
+    html.push_str("</footer>\n</body>\n</html>\n");
+    html
+}
+
+fn render_category(html: &mut String, category: &str, rows: &[&MetricRow]) {
+    html.push_str(&format!(
+        "<section>\n<h2>{}</h2>\n",
+        category_title(category)
+    ));
+
+    html.push_str("<table>\n<tr><th>Metric</th><th>Mean</th><th>Std Dev</th><th>Min</th>");
+    html.push_str("<th>Max</th><th>P50</th><th>P95</th><th>P99</th><th>CV%</th>");
+    html.push_str("<th>Geo Mean</th><th>Harmonic Mean</th></tr>\n");
+    for row in rows {
+        if let Some(stats) = Statistics::from_values(&row.values) {
+            html.push_str(&format!("<tr><td>{}</td>", escape(&row.label)));
+            for value in [
+                stats.mean,
+                stats.std_dev,
+                stats.min,
+                stats.max,
+                stats.p50,
+                stats.p95,
+                stats.p99,
+                stats.coefficient_of_variation,
+            ] {
+                html.push_str(&format!("<td>{:.2}</td>", value));
+            }
+            for mean in [stats.geo_mean, stats.harmonic_mean] {
+                match mean {
+                    Some(value) => html.push_str(&format!("<td>{:.2}</td>", value)),
+                    None => html.push_str("<td>-</td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+    }
+    html.push_str("</table>\n<div class=\"charts\">\n");
+
+    for row in rows {
+        html.push_str(&format!(
+            "<figure>\n<figcaption>{}</figcaption>\n{}</figure>\n",
+            escape(&row.label),
+            bar_chart_svg(&row.values)
+        ));
+    }
+    html.push_str("</div>\n</section>\n");
+}
+
+/// Latency between every two cores, shaded from the fastest pair (green) to the slowest
+/// (red), so cores sharing a cache, chiplet, or socket show up as blocks
+fn render_core_latency(html: &mut String, matrix: &CoreLatencyMatrix) {
+    let Some((min, mean, max)) = matrix.summary() else {
+        return;
+    };
+    html.push_str("<section>\n<h2>Core-to-Core Latency</h2>\n");
+    html.push_str(&format!(
+        "<p>One-way cache-line latency in ns between {} cores: min {:.1}, mean {:.1}, max {:.1}.</p>\n",
+        matrix.cores.len(),
+        min,
+        mean,
+        max
+    ));
+    html.push_str("<div class=\"scroll\">\n<table class=\"matrix\">\n<tr><th></th>");
+    for core in &matrix.cores {
+        html.push_str(&format!("<th>{}</th>", core));
+    }
+    html.push_str("</tr>\n");
+    for (i, (core, row)) in matrix.cores.iter().zip(&matrix.latency_ns).enumerate() {
+        html.push_str(&format!("<tr><th>{}</th>", core));
+        for (j, latency) in row.iter().enumerate() {
+            if i == j {
+                html.push_str("<td>-</td>");
+                continue;
+            }
+            let position = if max > min {
+                (latency - min) / (max - min)
+            } else {
+                0.0
+            };
+            html.push_str(&format!(
+                "<td style=\"background: hsl({:.0}, 65%, 82%)\">{:.0}</td>",
+                120.0 * (1.0 - position.clamp(0.0, 1.0)),
+                latency
+            ));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</div>\n</section>\n");
+}
+
+/// Write throughput over every second of each disk run's writing, one line chart per run,
+/// so a write cache filling up or thermal throttling shows as a drop partway through
+fn render_write_curves(html: &mut String, disk: &[DiskResult], units: UnitSystem) {
+    if disk.iter().all(|r| r.write_curve.is_empty()) {
+        return;
+    }
+    html.push_str("<section>\n<h2>Disk Write Throughput over Time</h2>\n");
+    html.push_str(&format!(
+        "<p>Write throughput in {} over each second of sequential writing.</p>\n",
+        Unit::MebibytesPerSec.in_system(units).symbol()
+    ));
+    html.push_str("<div class=\"charts\">\n");
+    for (i, result) in disk.iter().enumerate() {
+        if result.write_curve.is_empty() {
+            continue;
+        }
+        let points: Vec<(f64, f64)> = result
+            .write_curve
+            .iter()
+            .map(|s| {
+                let (value, _) = Unit::MebibytesPerSec.display(s.throughput, units);
+                (s.elapsed_secs, value)
+            })
+            .collect();
+        html.push_str(&format!(
+            "<figure>\n<figcaption>Run {}</figcaption>\n{}</figure>\n",
+            i + 1,
+            line_chart_svg(&points)
+        ));
+    }
+    html.push_str("</div>\n</section>\n");
+}
+
+/// Inline SVG line chart of (seconds, value) points, each holding its value since the previous
+/// point, scaled to the last second and the largest value
+fn line_chart_svg(points: &[(f64, f64)]) -> String {
+    let max_x = points.iter().map(|p| p.0).fold(0.0f64, f64::max);
+    let max_y = points
+        .iter()
+        .map(|p| p.1)
+        .filter(|v| v.is_finite())
+        .fold(0.0f64, f64::max);
+    let plot_width = CHART_WIDTH - 2.0 * CHART_MARGIN;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let x = |secs: f64| {
+        CHART_MARGIN
+            + if max_x > 0.0 {
+                secs / max_x * plot_width
+            } else {
+                0.0
+            }
+    };
+    let y = |value: f64| {
+        let height = if max_y > 0.0 && value.is_finite() && value > 0.0 {
+            value / max_y * plot_height
+        } else {
+            0.0
+        };
+        CHART_HEIGHT - CHART_MARGIN - height
+    };
+
+    let mut svg = format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" xmlns=\"http://www.w3.org/2000/svg\" \
+         font-family=\"sans-serif\" font-size=\"11\">\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    svg.push_str(&format!(
+        "<line x1=\"0\" y1=\"{y}\" x2=\"{w}\" y2=\"{y}\" stroke=\"#999\"/>\n",
+        y = CHART_HEIGHT - CHART_MARGIN,
+        w = CHART_WIDTH
+    ));
+
+    // Start at zero seconds with the first interval's value so a single sample is a line too
+    let mut line = Vec::with_capacity(points.len() + 1);
+    if let Some(&(_, first)) = points.first() {
+        line.push(format!("{:.1},{:.1}", x(0.0), y(first)));
+    }
+    line.extend(
+        points
+            .iter()
+            .map(|&(secs, value)| format!("{:.1},{:.1}", x(secs), y(value))),
+    );
+    svg.push_str(&format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"#3f7cac\" stroke-width=\"2\"/>\n",
+        line.join(" ")
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\">max {}</text>\n",
+        CHART_MARGIN,
+        CHART_MARGIN - 8.0,
+        format_value(max_y)
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" fill=\"#666\">{:.0} s</text>\n",
+        CHART_WIDTH - CHART_MARGIN,
+        CHART_HEIGHT - CHART_MARGIN + 16.0,
+        max_x
+    ));
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Section title of a metric category
+pub(crate) fn category_title(category: &str) -> &str {
+    match category {
+        "cpu" => "CPU",
+        "memory" => "Memory",
+        "concurrency" => "Concurrency",
+        "disk" => "Disk",
+        "network" => "Network",
+        "gpu" => "GPU",
+        "score" => "Scores",
+        other => other,
+    }
+}
+
+/// Inline SVG bar chart with one bar per run, scaled to the largest value
+fn bar_chart_svg(values: &[f64]) -> String {
+    let max = values
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .fold(0.0f64, f64::max);
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let slot = CHART_WIDTH / values.len().max(1) as f64;
+    let bar_width = (slot * 0.6).min(60.0);
+
+    let mut svg = format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" xmlns=\"http://www.w3.org/2000/svg\" \
+         font-family=\"sans-serif\" font-size=\"11\">\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    svg.push_str(&format!(
+        "<line x1=\"0\" y1=\"{y}\" x2=\"{w}\" y2=\"{y}\" stroke=\"#999\"/>\n",
+        y = CHART_HEIGHT - CHART_MARGIN,
+        w = CHART_WIDTH
+    ));
+
+    for (i, value) in values.iter().enumerate() {
+        let height = if max > 0.0 && value.is_finite() && *value > 0.0 {
+            value / max * plot_height
+        } else {
+            0.0
+        };
+        let x = slot * i as f64 + (slot - bar_width) / 2.0;
+        let y = CHART_HEIGHT - CHART_MARGIN - height;
+        let center = x + bar_width / 2.0;
+        svg.push_str(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#3f7cac\"/>\n",
+            x, y, bar_width, height
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            center,
+            y - 4.0,
+            format_value(*value)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"#666\">Run {}</text>\n",
+            center,
+            CHART_HEIGHT - CHART_MARGIN + 16.0,
+            i + 1
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Compact bar label: large values are abbreviated so they fit above narrow bars
+fn format_value(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        format!("{:.2}G", value / 1e9)
+    } else if magnitude >= 1e6 {
+        format!("{:.2}M", value / 1e6)
+    } else if magnitude >= 1e4 {
+        format!("{:.1}k", value / 1e3)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Escape text for inclusion in HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk::ThroughputSample;
+    use crate::suite::SuiteConfig;
+
+    fn sample_results() -> SuiteResults {
+        SuiteResults {
+            config: SuiteConfig {
+                runs: 2,
+                ..SuiteConfig::default()
+            },
+            cpu: vec![],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![
+                DiskResult {
+                    mode: Default::default(),
+                    write_throughput: 400.0,
+                    read_throughput: 500.0,
+                    combined_throughput: 447.2,
+                    io_mode: None,
+                    cache_bypassed: None,
+                    block_size_sweep: vec![],
+                    fsync_ops_per_sec: 0.0,
+                    fsync_latency_p50_us: 0.0,
+                    fsync_latency_p95_us: 0.0,
+                    fsync_latency_p99_us: 0.0,
+                    write_latency: Default::default(),
+                    read_latency: Default::default(),
+                    write_curve: vec![],
+                    precondition: None,
+                    cached: None,
+                    profiles: vec![],
+                    metadata: Default::default(),
+                    small_files: Default::default(),
+                    timings: Default::default(),
+                },
+                DiskResult {
+                    mode: Default::default(),
+                    write_throughput: 420.0,
+                    read_throughput: 510.0,
+                    combined_throughput: 462.8,
+                    io_mode: None,
+                    cache_bypassed: None,
+                    block_size_sweep: vec![],
+                    fsync_ops_per_sec: 0.0,
+                    fsync_latency_p50_us: 0.0,
+                    fsync_latency_p95_us: 0.0,
+                    fsync_latency_p99_us: 0.0,
+                    write_latency: Default::default(),
+                    read_latency: Default::default(),
+                    write_curve: vec![],
+                    precondition: None,
+                    cached: None,
+                    profiles: vec![],
+                    metadata: Default::default(),
+                    small_files: Default::default(),
+                    timings: Default::default(),
+                },
+            ],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        }
+    }
+
+    fn sample_system_info() -> SystemInfo {
+        SystemInfo {
+            cpu_brand: "Test <CPU> & Co".to_string(),
+            cpu_physical_cores: 4,
+            cpu_logical_cores: 8,
+            total_memory_mb: 16384,
+            os_name: "TestOS".to_string(),
+            os_version: "1.0".to_string(),
+            hostname: "test-host".to_string(),
+            gpu_adapter: None,
+            aes_acceleration: None,
+            simd_features: Vec::new(),
+            simd_level: String::new(),
+            cpu_frequency: Default::default(),
+            cpu_topology: Default::default(),
+            numa_nodes: Vec::new(),
+            power: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_html_report_contains_sections() {
+        let html = render_html_report(&sample_results(), &sample_system_info(), UnitSystem::Si);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(html.contains("<h2>Disk</h2>"));
+        assert!(html.contains("Overall Score"));
+        assert!(html.contains(&format!("version {}", report::SUITE_VERSION)));
+        assert!(html.contains("Disk Write (MB/s)"));
+        // Disabled subsystems are left out
+        assert!(!html.contains("<h2>CPU</h2>"));
+        // One bar per run for each of the 3 throughput and 4 fsync disk metrics and 2 scores
+        assert_eq!(html.matches("<rect").count(), 9 * 2);
+    }
+
+    #[test]
+    fn test_core_latency_table() {
+        let matrix = CoreLatencyMatrix {
+            cores: vec![0, 2],
+            latency_ns: vec![vec![0.0, 55.0], vec![55.0, 0.0]],
+        };
+        let mut html = String::new();
+        render_core_latency(&mut html, &matrix);
+        assert!(html.contains("<h2>Core-to-Core Latency</h2>"));
+        assert!(html.contains("<th>2</th>"));
+        // Two measured cells and an unshaded diagonal
+        assert_eq!(html.matches(">55</td>").count(), 2);
+        assert_eq!(html.matches("<td>-</td>").count(), 2);
+    }
+
+    #[test]
+    fn test_write_curve_chart() {
+        let mut results = sample_results();
+        let html = render_html_report(&results, &sample_system_info(), UnitSystem::Si);
+        assert!(!html.contains("Disk Write Throughput over Time"));
+
+        results.disk[1].write_curve = vec![
+            ThroughputSample {
+                elapsed_secs: 1.0,
+                throughput: 400.0,
+            },
+            ThroughputSample {
+                elapsed_secs: 2.0,
+                throughput: 200.0,
+            },
+        ];
+        let html = render_html_report(&results, &sample_system_info(), UnitSystem::Binary);
+        assert!(html.contains("<h2>Disk Write Throughput over Time</h2>"));
+        assert!(html.contains("in MiB/s"));
+        // Only the run that recorded a curve is drawn, starting at zero seconds
+        assert_eq!(html.matches("<polyline").count(), 1);
+        assert!(!html.contains("<figcaption>Run 1</figcaption>"));
+        assert!(html.contains("<polyline points=\"24.0,24.0 180.0,24.0 336.0,90.0\""));
+    }
+
+    #[test]
+    fn test_html_report_escapes_system_info() {
+        let html = render_html_report(&sample_results(), &sample_system_info(), UnitSystem::Binary);
+        assert!(html.contains("Test &lt;CPU&gt; &amp; Co"));
+        assert!(!html.contains("<CPU>"));
+    }
+
+    #[test]
+    fn test_bar_chart_scales_to_largest_value() {
+        let svg = bar_chart_svg(&[50.0, 100.0]);
+        let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+        assert!(svg.contains(&format!("height=\"{:.1}\"", plot_height)));
+        assert!(svg.contains(&format!("height=\"{:.1}\"", plot_height / 2.0)));
+        assert!(svg.contains("Run 2"));
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(12.345), "12.35");
+        assert_eq!(format_value(25_000.0), "25.0k");
+        assert_eq!(format_value(5_000_000.0), "5.00M");
+    }
+}
@@ -0,0 +1,328 @@
+/// GitHub-flavored Markdown report
+/// Renders system info, configuration, scores, and one table per subsystem with the per-run
+/// values and statistics of every metric, ready to paste into issues, pull requests, and wikis
+use crate::report::{self, display_rows, report_path, MetricRow};
+use crate::report_html::category_title;
+use crate::scoring;
+use crate::stats::Statistics;
+use crate::suite::SuiteResults;
+use crate::sysinfo_capture::SystemInfo;
+use crate::timing;
+use crate::units::UnitSystem;
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write the Markdown report to `output_<timestamp>.md` in `dir`, with byte rates in
+/// `units`
+/// Returns the name of the written file
+pub fn write_markdown_report(
+    results: &SuiteResults,
+    system_info: &SystemInfo,
+    dir: &Path,
+    units: UnitSystem,
+) -> io::Result<String> {
+    let path = report_path(dir, "md");
+    fs::write(&path, render_markdown_report(results, system_info, units))?;
+    Ok(path.display().to_string())
+}
+
+/// Render the complete Markdown document, with byte rates in `units`
+pub fn render_markdown_report(
+    results: &SuiteResults,
+    system_info: &SystemInfo,
+    units: UnitSystem,
+) -> String {
+    let config = &results.config;
+    let mut md = String::new();
+
+    md.push_str("# HsBenchMarkSuite Report\n\n");
+    md.push_str(&format!(
+        "{} · {} · version {}\n\n",
+        escape(&system_info.hostname),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        escape(&report::build_summary())
+    ));
+
+    // System information and configuration
+    md.push_str("## System Information\n\n| | |\n|---|---|\n");
+    let mut info_rows = vec![
+        ("CPU", system_info.cpu_brand.clone()),
+        (
+            "Cores",
+            format!(
+                "{} physical, {} logical",
+                system_info.cpu_physical_cores, system_info.cpu_logical_cores
+            ),
+        ),
+        ("Memory", format!("{} MB", system_info.total_memory_mb)),
+        (
+            "OS",
+            format!("{} {}", system_info.os_name, system_info.os_version),
+        ),
+        ("Timer", timing::calibration().to_string()),
+    ];
+    if let Some(gpu) = &system_info.gpu_adapter {
+        info_rows.push(("GPU", gpu.clone()));
+    }
+    if !system_info.simd_features.is_empty() {
+        info_rows.push((
+            "SIMD",
+            format!(
+                "{} (kernels use {})",
+                system_info.simd_features.join(" "),
+                system_info.simd_level
+            ),
+        ));
+    }
+    info_rows.push((
+        "AES Acceleration",
+        system_info
+            .aes_acceleration
+            .clone()
+            .unwrap_or_else(|| "none (software)".to_string()),
+    ));
+    if let Some(frequency) = system_info.cpu_frequency.summary() {
+        info_rows.push(("CPU Frequency", frequency));
+    }
+    if let Some(core_types) = system_info.cpu_topology.core_type_summary() {
+        info_rows.push(("Core Types", core_types));
+    }
+    if let Some(caches) = system_info.cpu_topology.cache_summary() {
+        info_rows.push(("Caches", caches));
+    }
+    if let Some(numa) = system_info.numa_summary() {
+        info_rows.push(("NUMA", numa));
+    }
+    if let Some(power) = system_info.power.summary() {
+        info_rows.push(("Power", power));
+    }
+    info_rows.extend([
+        ("Scale", config.scale_summary()),
+        ("Runs", config.runs.to_string()),
+        ("Threads", config.threads.to_string()),
+        ("Memory Threads", config.memory_threads.to_string()),
+        (
+            "Disk Block Size",
+            format!("{} KB", config.block_size / 1024),
+        ),
+        ("Disk Mode", config.disk_mode.name().to_string()),
+    ]);
+    if let Some(target) = &results.disk_target {
+        info_rows.push(("Disk Path", target.summary()));
+    }
+    if let Some(precondition) = &results.config.disk_precondition {
+        info_rows.push(("Disk Precondition", precondition.summary()));
+    }
+    if let Some(footprint) = results.memory_footprint_summary() {
+        info_rows.push(("Memory Footprint", footprint));
+    }
+    info_rows.push((
+        "Schedule",
+        results.config.schedule.description().to_string(),
+    ));
+    if let Some(cooldown) = results.cooldown_summary() {
+        info_rows.push(("Cooldown", cooldown));
+    }
+    if let Some(verification) = results.verification_summary() {
+        info_rows.push(("Verification", verification));
+    }
+    for (name, value) in &info_rows {
+        md.push_str(&format!("| **{}** | {} |\n", name, escape(value)));
+    }
+    md.push('\n');
+
+    for warning in [
+        results.interruption_warning(),
+        system_info.governor_warning(),
+        system_info.battery_warning(),
+        results.throttling_warning(),
+        results.verification_warning(),
+        results.timeout_warning(),
+        results.direct_io_warning(),
+        results.contamination_warning(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        md.push_str(&format!("> **Warning:** {}\n\n", warning));
+    }
+
+    // Composite score
+    let scores = scoring::compute_scores(results);
+    if let Some(overall) = scores.overall {
+        md.push_str(&format!(
+            "## Score\n\n**Overall: {:.0}** (reference system = {:.0}; higher is better)\n\n",
+            overall,
+            scoring::REFERENCE_SCORE
+        ));
+        md.push_str("| Subsystem | Score |\n|---|---:|\n");
+        for (name, score) in [
+            ("CPU", scores.cpu),
+            ("Memory", scores.memory),
+            ("Disk", scores.disk),
+        ] {
+            if let Some(score) = score {
+                md.push_str(&format!("| {} | {:.0} |\n", name, score));
+            }
+        }
+        md.push('\n');
+    }
+
+    // One section per benchmarked category
+    let rows = display_rows(results, units);
+    let mut categories: Vec<&str> = Vec::new();
+    for row in &rows {
+        if !row.values.is_empty() && !categories.contains(&row.category) {
+            categories.push(row.category);
+        }
+    }
+    for category in categories {
+        let category_rows: Vec<&MetricRow> = rows
+            .iter()
+            .filter(|r| r.category == category && !r.values.is_empty())
+            .collect();
+        render_category(&mut md, category, &category_rows, config.runs);
+    }
+
+    md.push_str(
+        "> DISCLAIMER: Results represent performance on specific synthetic algorithms and do \
+         NOT necessarily equate to actual system capability for real-world workloads.\n",
+    );
+    md
+}
+
+fn render_category(md: &mut String, category: &str, rows: &[&MetricRow], runs: usize) {
+    md.push_str(&format!("## {}\n\n| Metric |", category_title(category)));
+    for run in 1..=runs {
+        md.push_str(&format!(" Run {} |", run));
+    }
+    md.push_str(" Mean | Std Dev | CV% |\n|---|");
+    md.push_str(&"---:|".repeat(runs + 3));
+    md.push('\n');
+
+    for row in rows {
+        let Some(stats) = Statistics::from_values(&row.values) else {
+            continue;
+        };
+        md.push_str(&format!("| {} |", escape(&row.label)));
+        for run in 0..runs {
+            // Runs that failed leave an empty cell so the statistics stay aligned
+            match row.values.get(run) {
+                Some(value) => md.push_str(&format!(" {:.2} |", value)),
+                None => md.push_str(" |"),
+            }
+        }
+        md.push_str(&format!(
+            " {:.2} | {:.2} | {:.2} |\n",
+            stats.mean, stats.std_dev, stats.coefficient_of_variation
+        ));
+    }
+    md.push('\n');
+}
+
+/// Escape text for a table cell: pipes would end the cell and newlines the row
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk::DiskResult;
+    use crate::suite::SuiteConfig;
+
+    fn disk_result(write: f64) -> DiskResult {
+        DiskResult {
+            mode: Default::default(),
+            write_throughput: write,
+            read_throughput: 500.0,
+            combined_throughput: 447.2,
+            io_mode: None,
+            cache_bypassed: None,
+            block_size_sweep: vec![],
+            fsync_ops_per_sec: 0.0,
+            fsync_latency_p50_us: 0.0,
+            fsync_latency_p95_us: 0.0,
+            fsync_latency_p99_us: 0.0,
+            write_latency: Default::default(),
+            read_latency: Default::default(),
+            write_curve: vec![],
+            precondition: None,
+            cached: None,
+            profiles: vec![],
+            metadata: Default::default(),
+            small_files: Default::default(),
+            timings: Default::default(),
+        }
+    }
+
+    fn sample_results() -> SuiteResults {
+        SuiteResults {
+            config: SuiteConfig {
+                runs: 2,
+                ..SuiteConfig::default()
+            },
+            cpu: vec![],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![disk_result(400.0), disk_result(420.0)],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        }
+    }
+
+    fn sample_system_info() -> SystemInfo {
+        SystemInfo {
+            cpu_brand: "Test CPU | 8 cores".to_string(),
+            cpu_physical_cores: 4,
+            cpu_logical_cores: 8,
+            total_memory_mb: 16384,
+            os_name: "TestOS".to_string(),
+            os_version: "1.0".to_string(),
+            hostname: "test-host".to_string(),
+            gpu_adapter: None,
+            aes_acceleration: None,
+            simd_features: Vec::new(),
+            simd_level: String::new(),
+            cpu_frequency: Default::default(),
+            cpu_topology: Default::default(),
+            numa_nodes: Vec::new(),
+            power: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_contains_tables() {
+        let md =
+            render_markdown_report(&sample_results(), &sample_system_info(), UnitSystem::Binary);
+        assert!(md.starts_with("# HsBenchMarkSuite Report"));
+        assert!(md.contains("## Disk\n\n| Metric | Run 1 | Run 2 | Mean | Std Dev | CV% |"));
+        assert!(md.contains("|---|---:|---:|---:|---:|---:|"));
+        assert!(md.contains("| Disk Write (MiB/s) | 400.00 | 420.00 | 410.00 |"));
+        assert!(md.contains("**Overall:"));
+        // Disabled subsystems are left out
+        assert!(!md.contains("## CPU"));
+    }
+
+    #[test]
+    fn test_markdown_report_escapes_table_cells() {
+        let md =
+            render_markdown_report(&sample_results(), &sample_system_info(), UnitSystem::Binary);
+        assert!(md.contains("| **CPU** | Test CPU \\| 8 cores |"));
+    }
+
+    #[test]
+    fn test_missing_runs_leave_empty_cells() {
+        let mut results = sample_results();
+        results.config.runs = 3;
+        let md = render_markdown_report(&results, &sample_system_info(), UnitSystem::Binary);
+        assert!(md.contains("| Disk Write (MiB/s) | 400.00 | 420.00 | | 410.00 |"));
+    }
+}
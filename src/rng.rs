@@ -0,0 +1,120 @@
+/// Small deterministic pseudo-random number generator
+/// Minimal 64-bit linear congruential generator shared by the benchmarks that need
+/// reproducible random input; not suitable for anything security related
+/// Every workload starts from a built-in seed, so all machines run the same inputs; a
+/// process-wide `--seed` (`set_seed`) replaces them with another set that is just as fixed
+use std::sync::Mutex;
+
+/// Seed set with `set_seed`; None keeps the built-in workload seeds
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Mix `seed` into every workload seed for the rest of the process; None restores the
+/// built-in seeds
+pub fn set_seed(seed: Option<u64>) {
+    *SEED.lock().unwrap_or_else(|e| e.into_inner()) = seed;
+}
+
+/// Seed set with `set_seed`, if any
+pub fn seed() -> Option<u64> {
+    *SEED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Seed of a workload whose built-in seed is `base`, under the process-wide seed
+pub fn workload_seed(base: u64) -> u64 {
+    mixed_seed(base, seed())
+}
+
+/// `base` itself without a seed; otherwise `base` combined with a scrambled `seed`, so that
+/// workloads keep distinct streams under the same seed
+/// Never zero with a seed, since the xorshift generators would stay at zero
+fn mixed_seed(base: u64, seed: Option<u64>) -> u64 {
+    let Some(seed) = seed else {
+        return base;
+    };
+    // SplitMix64 finalizer: nearby seeds such as 1 and 2 give unrelated values
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (base ^ z ^ (z >> 31)) | 1
+}
+
+/// 64-bit LCG; the same seed always produces the same sequence
+#[derive(Debug, Clone)]
+pub struct SimpleRng {
+    state: u64,
+}
+
+impl SimpleRng {
+    pub fn new(seed: u64) -> Self {
+        SimpleRng { state: seed | 1 } // force odd seed to avoid degenerate cycles
+    }
+
+    /// Next raw 64-bit value
+    pub fn next_u64(&mut self) -> u64 {
+        // 64-bit LCG parameters (Numerical Recipes style)
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    /// Uniform value in [0, 1), built from the 53 high (best mixed) bits
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplerng_deterministic() {
+        let mut rng1 = SimpleRng::new(42);
+        let mut rng2 = SimpleRng::new(42);
+
+        // Same seed should produce same sequence
+        for _ in 0..10 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_simplerng_different_seeds() {
+        let mut rng1 = SimpleRng::new(1);
+        let mut rng2 = SimpleRng::new(2);
+
+        // Different seeds should produce different sequences
+        let val1 = rng1.next_u64();
+        let val2 = rng2.next_u64();
+        assert_ne!(val1, val2);
+    }
+
+    #[test]
+    fn test_mixed_seed() {
+        // Without a seed the built-in seeds are kept
+        assert_eq!(mixed_seed(0xC0FFEE, None), 0xC0FFEE);
+        // A seed gives every workload a different, reproducible seed
+        let a = mixed_seed(0xC0FFEE, Some(1));
+        assert_eq!(a, mixed_seed(0xC0FFEE, Some(1)));
+        assert_ne!(a, 0xC0FFEE);
+        assert_ne!(a, mixed_seed(0xC0FFEE, Some(2)));
+        assert_ne!(a, mixed_seed(0xBEEF, Some(1)));
+        assert_ne!(mixed_seed(0, Some(0)), 0);
+    }
+
+    #[test]
+    fn test_next_f64_in_unit_interval() {
+        let mut rng = SimpleRng::new(7);
+        let mean = (0..10_000)
+            .map(|_| {
+                let value = rng.next_f64();
+                assert!((0.0..1.0).contains(&value));
+                value
+            })
+            .sum::<f64>()
+            / 10_000.0;
+        assert!((mean - 0.5).abs() < 0.02);
+    }
+}
@@ -0,0 +1,312 @@
+/// Composite scoring of benchmark results
+/// Each metric is normalized against a fixed reference system, so a score of 1000 means
+/// "as fast as the reference" and 2000 means twice as fast. Subscores are the geometric
+/// mean of the normalized metrics of one subsystem; the overall score is the geometric
+/// mean of the available subscores.
+///
+/// Network results are not scored: they depend on the target host and network path
+/// rather than on the machine being benchmarked.
+use crate::cpu::CpuResult;
+use crate::disk::DiskResult;
+use crate::memory::MemoryResult;
+use crate::stats::geometric_mean;
+use crate::suite::SuiteResults;
+use serde::{Deserialize, Serialize};
+
+/// Score of a system that exactly matches the reference values
+pub const REFERENCE_SCORE: f64 = 1000.0;
+
+// Reference system: 4-core / 8-thread desktop CPU with dual-channel DDR4 and a SATA SSD,
+// measured at scale 1.0 with 4 threads. Changing these values invalidates comparisons
+// against scores produced by earlier versions.
+pub const REFERENCE_CPU_PRIMES_PER_SEC: f64 = 5_000_000.0;
+pub const REFERENCE_CPU_MATRIX_GFLOPS_ST: f64 = 2.0;
+pub const REFERENCE_CPU_MATRIX_GFLOPS_MT: f64 = 7.0;
+pub const REFERENCE_CPU_MANDELBROT_PIXELS_PER_SEC: f64 = 50_000_000.0;
+pub const REFERENCE_CPU_FFT_MSAMPLES_PER_SEC: f64 = 30.0;
+pub const REFERENCE_MEMORY_WRITE_MBS: f64 = 10_000.0;
+pub const REFERENCE_MEMORY_READ_MBS: f64 = 15_000.0;
+pub const REFERENCE_DISK_WRITE_MBS: f64 = 450.0;
+pub const REFERENCE_DISK_READ_MBS: f64 = 500.0;
+
+/// Subscores and overall score of one run (or the geometric mean over runs)
+/// Subsystems that were not benchmarked have no score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scores {
+    pub cpu: Option<f64>,
+    pub memory: Option<f64>,
+    pub disk: Option<f64>,
+    pub overall: Option<f64>,
+}
+
+impl Scores {
+    /// Geometric mean of each score over the runs in which it was available
+    /// Scores are ratios to the reference system, so runs are averaged like the metrics
+    /// within a subscore
+    pub fn geo_mean(runs: &[Scores]) -> Scores {
+        fn mean_of(runs: &[Scores], field: impl Fn(&Scores) -> Option<f64>) -> Option<f64> {
+            let values: Vec<f64> = runs.iter().filter_map(field).collect();
+            geometric_mean(&values)
+        }
+
+        Scores {
+            cpu: mean_of(runs, |s| s.cpu),
+            memory: mean_of(runs, |s| s.memory),
+            disk: mean_of(runs, |s| s.disk),
+            overall: mean_of(runs, |s| s.overall),
+        }
+    }
+}
+
+pub fn cpu_score(result: &CpuResult) -> Option<f64> {
+    subscore(&[
+        (result.primes_per_sec, REFERENCE_CPU_PRIMES_PER_SEC),
+        (result.matrix_mult_gflops, REFERENCE_CPU_MATRIX_GFLOPS_ST),
+        (
+            result.parallel_matrix_gflops,
+            REFERENCE_CPU_MATRIX_GFLOPS_MT,
+        ),
+        (
+            result.mandelbrot_pixels_per_sec,
+            REFERENCE_CPU_MANDELBROT_PIXELS_PER_SEC,
+        ),
+        (
+            result.fft_msamples_per_sec,
+            REFERENCE_CPU_FFT_MSAMPLES_PER_SEC,
+        ),
+    ])
+}
+
+pub fn memory_score(result: &MemoryResult) -> Option<f64> {
+    subscore(&[
+        (result.write_throughput, REFERENCE_MEMORY_WRITE_MBS),
+        (result.read_throughput, REFERENCE_MEMORY_READ_MBS),
+    ])
+}
+
+pub fn disk_score(result: &DiskResult) -> Option<f64> {
+    subscore(&[
+        (result.write_throughput, REFERENCE_DISK_WRITE_MBS),
+        (result.read_throughput, REFERENCE_DISK_READ_MBS),
+    ])
+}
+
+/// Scores of every run, in run order
+pub fn run_scores(results: &SuiteResults) -> Vec<Scores> {
+    let runs = results
+        .cpu
+        .len()
+        .max(results.memory.len())
+        .max(results.disk.len());
+
+    (0..runs)
+        .map(|i| {
+            let cpu = results.cpu.get(i).and_then(cpu_score);
+            let memory = results.memory.get(i).and_then(memory_score);
+            let disk = results.disk.get(i).and_then(disk_score);
+            let subscores: Vec<f64> = [cpu, memory, disk].into_iter().flatten().collect();
+            Scores {
+                cpu,
+                memory,
+                disk,
+                overall: geometric_mean(&subscores),
+            }
+        })
+        .collect()
+}
+
+/// Geometric mean scores over all runs
+pub fn compute_scores(results: &SuiteResults) -> Scores {
+    Scores::geo_mean(&run_scores(results))
+}
+
+/// REFERENCE_SCORE times the geometric mean of the measured/reference ratios
+/// Non-positive or non-finite measurements are skipped since they cannot be normalized
+fn subscore(metrics: &[(f64, f64)]) -> Option<f64> {
+    let ratios: Vec<f64> = metrics
+        .iter()
+        .filter(|(value, _)| value.is_finite() && *value > 0.0)
+        .map(|(value, reference)| value / reference)
+        .collect();
+    geometric_mean(&ratios).map(|g| g * REFERENCE_SCORE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suite::SuiteConfig;
+
+    fn reference_cpu() -> CpuResult {
+        CpuResult {
+            primes_per_sec: REFERENCE_CPU_PRIMES_PER_SEC,
+            matrix_mult_gflops: REFERENCE_CPU_MATRIX_GFLOPS_ST,
+            mandelbrot_pixels_per_sec: REFERENCE_CPU_MANDELBROT_PIXELS_PER_SEC,
+            fft_msamples_per_sec: REFERENCE_CPU_FFT_MSAMPLES_PER_SEC,
+            parallel_matrix_gflops: REFERENCE_CPU_MATRIX_GFLOPS_MT,
+            parallel_speedup: REFERENCE_CPU_MATRIX_GFLOPS_MT / REFERENCE_CPU_MATRIX_GFLOPS_ST,
+            compression_mibs: 0.0,
+            decompression_mibs: 0.0,
+            parallel_primes_per_sec: 0.0,
+            primes_speedup: 0.0,
+            parallel_mandelbrot_pixels_per_sec: 0.0,
+            mandelbrot_speedup: 0.0,
+            parallel_fft_msamples_per_sec: 0.0,
+            fft_speedup: 0.0,
+            fft_sizes: vec![],
+            matrix_mult_blocked_gflops: 0.0,
+            matrix_simd: String::new(),
+            sieve_primes_per_sec: 0.0,
+            sieve_mibs: 0.0,
+            integer_giops: 0.0,
+            branch_sorted_melem_per_sec: 0.0,
+            branch_unsorted_melem_per_sec: 0.0,
+            branch_mispredict_ns: 0.0,
+            sort_u64_melem_per_sec: 0.0,
+            sort_string_melem_per_sec: 0.0,
+            raytrace_rays_per_sec: 0.0,
+            parallel_raytrace_rays_per_sec: 0.0,
+            raytrace_speedup: 0.0,
+            monte_carlo_msamples_per_sec: 0.0,
+            parallel_monte_carlo_msamples_per_sec: 0.0,
+            monte_carlo_speedup: 0.0,
+            regex_mibs: 0.0,
+            aes_mibs: 0.0,
+            aes_hardware: false,
+            matrix_scalar_gflops: 0.0,
+            matrix_simd_gflops: 0.0,
+            matrix_f32_gflops: 0.0,
+            gemm_i8_gops: 0.0,
+            mandelbrot_f32_pixels_per_sec: 0.0,
+            nbody_minteractions_per_sec: 0.0,
+            nbody_f32_minteractions_per_sec: 0.0,
+            thermal: Default::default(),
+            energy: None,
+            counters: Default::default(),
+            verification: vec![],
+            timings: Default::default(),
+        }
+    }
+
+    fn memory(factor: f64) -> MemoryResult {
+        MemoryResult {
+            write_throughput: REFERENCE_MEMORY_WRITE_MBS * factor,
+            read_throughput: REFERENCE_MEMORY_READ_MBS * factor,
+            combined_throughput: 0.0,
+            prefaulted: false,
+            thread_scaling: vec![],
+            numa: None,
+            counters: Default::default(),
+            stream_copy_gbs: 0.0,
+            stream_scale_gbs: 0.0,
+            stream_add_gbs: 0.0,
+            stream_triad_gbs: 0.0,
+            triad_scalar_gbs: 0.0,
+            triad_simd_gbs: 0.0,
+            write_cached_gbs: 0.0,
+            write_nt_gbs: 0.0,
+            page_faults_per_sec: 0.0,
+            alloc_small_per_sec: 0.0,
+            alloc_medium_per_sec: 0.0,
+            alloc_large_per_sec: 0.0,
+            alloc_small_mt_per_sec: 0.0,
+            alloc_fragmented_per_sec: 0.0,
+            footprint: Default::default(),
+            huge_pages: None,
+            access_patterns: Vec::new(),
+            cache_probe: Default::default(),
+            timings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_reference_system_scores_1000() {
+        let score = cpu_score(&reference_cpu()).unwrap();
+        assert!((score - REFERENCE_SCORE).abs() < 1e-6);
+        let score = memory_score(&memory(1.0)).unwrap();
+        assert!((score - REFERENCE_SCORE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_score_scales_with_performance() {
+        let score = memory_score(&memory(2.0)).unwrap();
+        assert!((score - 2000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_subscore_skips_invalid_metrics() {
+        let mut cpu = reference_cpu();
+        cpu.fft_msamples_per_sec = 0.0;
+        let score = cpu_score(&cpu).unwrap();
+        assert!((score - REFERENCE_SCORE).abs() < 1e-6);
+        assert!(disk_score(&DiskResult {
+            mode: Default::default(),
+            write_throughput: 0.0,
+            read_throughput: f64::NAN,
+            combined_throughput: 0.0,
+            io_mode: None,
+            cache_bypassed: None,
+            block_size_sweep: vec![],
+            fsync_ops_per_sec: 0.0,
+            fsync_latency_p50_us: 0.0,
+            fsync_latency_p95_us: 0.0,
+            fsync_latency_p99_us: 0.0,
+            write_latency: Default::default(),
+            read_latency: Default::default(),
+            write_curve: vec![],
+            precondition: None,
+            cached: None,
+            profiles: vec![],
+            metadata: Default::default(),
+            small_files: Default::default(),
+            timings: Default::default(),
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn test_overall_is_geometric_mean_of_enabled_subsystems() {
+        let results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![reference_cpu(), reference_cpu()],
+            memory: vec![memory(4.0), memory(2.0)],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        let runs = run_scores(&results);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].disk.is_none());
+        // sqrt(1000 * 4000) = 2000
+        assert!((runs[0].overall.unwrap() - 2000.0).abs() < 1e-6);
+
+        // Runs are combined with the geometric mean: sqrt(4000 * 2000)
+        let mean = compute_scores(&results);
+        assert!((mean.memory.unwrap() - 8_000_000f64.sqrt()).abs() < 1e-6);
+        assert!(mean.disk.is_none());
+    }
+
+    #[test]
+    fn test_no_results_has_no_score() {
+        let results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        assert_eq!(compute_scores(&results), Scores::default());
+    }
+}
@@ -1,8 +1,10 @@
 /// Statistical analysis utilities for benchmark results
+use crate::rng::SimpleRng;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Statistical summary of benchmark results across multiple runs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statistics {
     pub mean: f64,
     pub std_dev: f64,
@@ -11,7 +13,24 @@ pub struct Statistics {
     pub p50: f64, // Median
     pub p95: f64,
     pub p99: f64,
+    #[serde(rename = "cv_percent")]
     pub coefficient_of_variation: f64, // std_dev / mean, expressed as percentage
+    /// Number of values; 0 in reports written before it was recorded
+    #[serde(default)]
+    pub count: usize,
+    /// 95% confidence interval of the mean (Student's t-distribution); equal to the mean
+    /// for a single value
+    #[serde(default)]
+    pub ci95_low: f64,
+    #[serde(default)]
+    pub ci95_high: f64,
+    /// Geometric and harmonic mean; None unless every value is positive
+    /// Throughputs and ratios are better summarized by these than by the arithmetic mean,
+    /// which a single fast outlier pulls up
+    #[serde(default)]
+    pub geo_mean: Option<f64>,
+    #[serde(default)]
+    pub harmonic_mean: Option<f64>,
 }
 
 impl Statistics {
@@ -49,6 +68,8 @@ impl Statistics {
             0.0
         };
 
+        let ci95_margin = ci95_margin(variance, n);
+
         Some(Statistics {
             mean,
             std_dev,
@@ -58,16 +79,308 @@ impl Statistics {
             p95,
             p99,
             coefficient_of_variation,
+            count: n,
+            ci95_low: mean - ci95_margin,
+            ci95_high: mean + ci95_margin,
+            geo_mean: geometric_mean(values),
+            harmonic_mean: harmonic_mean(values),
+        })
+    }
+}
+
+/// Half-width of the 95% confidence interval of the mean of `n` values with population
+/// variance `variance`, from the sample standard deviation (n - 1 denominator)
+fn ci95_margin(variance: f64, n: usize) -> f64 {
+    if n > 1 {
+        let sample_std_dev = (variance * n as f64 / (n - 1) as f64).sqrt();
+        t_critical_95((n - 1) as f64) * sample_std_dev / (n as f64).sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// Statistics updated one value at a time in constant memory, for watch sessions of
+/// thousands of runs
+/// Mean and variance use Welford's algorithm, so they stay exact and numerically stable
+/// however many values are pushed. Percentiles come from a uniform random sample of at most
+/// `reservoir_size` values (Vitter's algorithm R); they are exact until the reservoir fills
+/// and estimates afterwards.
+#[derive(Debug, Clone)]
+pub struct StreamingStats {
+    count: usize,
+    mean: f64,
+    /// Sum of squared differences from the current mean
+    m2: f64,
+    min: f64,
+    max: f64,
+    /// Sums of logarithms and reciprocals for the geometric and harmonic mean; None once a
+    /// value is not positive
+    log_sum: Option<f64>,
+    reciprocal_sum: Option<f64>,
+    reservoir: Vec<f64>,
+    reservoir_size: usize,
+    rng: SimpleRng,
+}
+
+impl Default for StreamingStats {
+    fn default() -> Self {
+        StreamingStats::new()
+    }
+}
+
+impl StreamingStats {
+    /// Values kept for percentiles by `new`, enough for about 10 values beyond the P99
+    pub const DEFAULT_RESERVOIR_SIZE: usize = 1024;
+
+    pub fn new() -> Self {
+        StreamingStats::with_reservoir_size(StreamingStats::DEFAULT_RESERVOIR_SIZE)
+    }
+
+    /// Keep at most `reservoir_size` values (at least 1) for percentiles
+    pub fn with_reservoir_size(reservoir_size: usize) -> Self {
+        StreamingStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            log_sum: Some(0.0),
+            reciprocal_sum: Some(0.0),
+            reservoir: Vec::new(),
+            reservoir_size: reservoir_size.max(1),
+            // Fixed seed: which values are sampled does not depend on --seed
+            rng: SimpleRng::new(0x5eed_57a7),
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        let positive = value > 0.0 && value.is_finite();
+        self.log_sum = self
+            .log_sum
+            .filter(|_| positive)
+            .map(|sum| sum + value.ln());
+        self.reciprocal_sum = self
+            .reciprocal_sum
+            .filter(|_| positive)
+            .map(|sum| sum + 1.0 / value);
+
+        if self.reservoir.len() < self.reservoir_size {
+            self.reservoir.push(value);
+        } else {
+            // Replace a sampled value with probability reservoir_size / count
+            let slot = (self.rng.next_u64() % self.count as u64) as usize;
+            if slot < self.reservoir_size {
+                self.reservoir[slot] = value;
+            }
+        }
+    }
+
+    /// Values pushed so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Population variance, as in `Statistics`
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// Summary of every value pushed so far; None before the first
+    pub fn statistics(&self) -> Option<Statistics> {
+        let variance = self.variance()?;
+        let std_dev = variance.sqrt();
+        let mut sorted = self.reservoir.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let coefficient_of_variation = if self.mean.abs() > f64::EPSILON {
+            (std_dev / self.mean) * 100.0
+        } else {
+            0.0
+        };
+        let ci95_margin = ci95_margin(variance, self.count);
+        let n = self.count as f64;
+        Some(Statistics {
+            mean: self.mean,
+            std_dev,
+            min: self.min,
+            max: self.max,
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            coefficient_of_variation,
+            count: self.count,
+            ci95_low: self.mean - ci95_margin,
+            ci95_high: self.mean + ci95_margin,
+            geo_mean: self.log_sum.map(|sum| (sum / n).exp()),
+            harmonic_mean: self.reciprocal_sum.map(|sum| n / sum),
         })
     }
 }
 
+/// Geometric mean, the n-th root of the product; None if empty or any value is not positive
+/// The right average for ratios and normalized scores
+pub fn geometric_mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() || values.iter().any(|&v| !(v > 0.0 && v.is_finite())) {
+        return None;
+    }
+    let log_sum: f64 = values.iter().map(|v| v.ln()).sum();
+    Some((log_sum / values.len() as f64).exp())
+}
+
+/// Harmonic mean, the reciprocal of the mean reciprocal; None if empty or any value is not
+/// positive
+/// The right average for rates measured over the same amount of work (e.g. MB/s for a
+/// fixed file size), since it equals total work over total time
+pub fn harmonic_mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() || values.iter().any(|&v| !(v > 0.0 && v.is_finite())) {
+        return None;
+    }
+    let reciprocal_sum: f64 = values.iter().map(|v| 1.0 / v).sum();
+    Some(values.len() as f64 / reciprocal_sum)
+}
+
+/// Least-squares line through a series of values taken at positions 0, 1, 2, ...
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearFit {
+    /// Change per position
+    pub slope: f64,
+    /// Fitted value at position 0
+    pub intercept: f64,
+    /// Share of the variance explained by the line (0 to 1); 0 for a constant series, which
+    /// has no trend to explain
+    pub r_squared: f64,
+}
+
+impl LinearFit {
+    /// Fitted value at `position`
+    pub fn at(&self, position: f64) -> f64 {
+        self.intercept + self.slope * position
+    }
+}
+
+/// Ordinary least-squares fit of `values` against their index; None for fewer than 2 values
+pub fn linear_fit(values: &[f64]) -> Option<LinearFit> {
+    let n = values.len();
+    if n < 2 {
+        return None;
+    }
+    let mean_x = (n - 1) as f64 / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n as f64;
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for (i, &y) in values.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        let dy = y - mean_y;
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+    let slope = sxy / sxx;
+    let r_squared = if syy > 0.0 {
+        (sxy * sxy / (sxx * syy)).min(1.0)
+    } else {
+        0.0
+    };
+    Some(LinearFit {
+        slope,
+        intercept: mean_y - slope * mean_x,
+        r_squared,
+    })
+}
+
+/// Values each side of a change point needs, so a single outlier is not taken for a shift
+const MIN_SEGMENT_LEN: usize = 2;
+
+/// A step in the level of a series, as opposed to a gradual drift
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangePoint {
+    /// Index of the first value after the step
+    pub index: usize,
+    pub mean_before: f64,
+    pub mean_after: f64,
+    /// Size of the step in standard deviations of the values around their segment mean;
+    /// infinite when both segments are constant
+    pub score: f64,
+}
+
+/// Most likely single change point of `values`: the split into two segments of at least
+/// `MIN_SEGMENT_LEN` values whose means leave the smallest squared error
+/// None for series too short to split or without any change in level
+pub fn change_point(values: &[f64]) -> Option<ChangePoint> {
+    let n = values.len();
+    if n < 2 * MIN_SEGMENT_LEN {
+        return None;
+    }
+    // Prefix sums of the values and their squares give each segment's error in O(1)
+    let mut sums = vec![(0.0, 0.0); n + 1];
+    for (i, &value) in values.iter().enumerate() {
+        sums[i + 1] = (sums[i].0 + value, sums[i].1 + value * value);
+    }
+    let segment = |from: usize, to: usize| {
+        let len = (to - from) as f64;
+        let sum = sums[to].0 - sums[from].0;
+        let squares = sums[to].1 - sums[from].1;
+        (sum / len, (squares - sum * sum / len).max(0.0))
+    };
+
+    let (index, error) = (MIN_SEGMENT_LEN..=n - MIN_SEGMENT_LEN)
+        .map(|index| (index, segment(0, index).1 + segment(index, n).1))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())?;
+    let (mean_before, mean_after) = (segment(0, index).0, segment(index, n).0);
+    let step = (mean_after - mean_before).abs();
+    if step <= f64::EPSILON * mean_before.abs().max(1.0) {
+        return None;
+    }
+    let std_dev = (error / (n - 2) as f64).sqrt();
+    Some(ChangePoint {
+        index,
+        mean_before,
+        mean_after,
+        score: if std_dev > 0.0 {
+            step / std_dev
+        } else {
+            f64::INFINITY
+        },
+    })
+}
+
+/// Two-sided 95% critical values of Student's t-distribution for 1 to 30 degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Two-sided 95% critical value of Student's t-distribution with `df` degrees of freedom
+/// Fractional degrees of freedom (Welch's t-test) are rounded down, which is conservative;
+/// above 30 the Cornish-Fisher expansion around the normal quantile is used
+pub fn t_critical_95(df: f64) -> f64 {
+    if df.is_nan() || df < 1.0 {
+        return f64::INFINITY;
+    }
+    if df < 31.0 {
+        return T_CRITICAL_95[df as usize - 1];
+    }
+    const Z: f64 = 1.959964;
+    Z + (Z.powi(3) + Z) / (4.0 * df)
+        + (5.0 * Z.powi(5) + 16.0 * Z.powi(3) + 3.0 * Z) / (96.0 * df.powi(2))
+}
+
 impl fmt::Display for Statistics {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Mean: {:.2}, StdDev: {:.2}, Min: {:.2}, Max: {:.2}, P50: {:.2}, P95: {:.2}, P99: {:.2}, CV: {:.2}%",
-            self.mean, self.std_dev, self.min, self.max, self.p50, self.p95, self.p99, self.coefficient_of_variation
+            "Mean: {:.2} (95% CI {:.2}..{:.2}), StdDev: {:.2}, Min: {:.2}, Max: {:.2}, P50: {:.2}, P95: {:.2}, P99: {:.2}, CV: {:.2}%",
+            self.mean, self.ci95_low, self.ci95_high, self.std_dev, self.min, self.max, self.p50, self.p95, self.p99, self.coefficient_of_variation
         )
     }
 }
@@ -98,6 +411,161 @@ fn percentile(sorted_data: &[f64], p: f64) -> f64 {
     }
 }
 
+/// Linear sub-buckets per power of two in `Histogram`: values are kept to within
+/// 1/16 (about 6%) of their magnitude, like an HDR histogram with one significant digit
+const HISTOGRAM_SUB_BUCKET_BITS: u32 = 4;
+const HISTOGRAM_SUB_BUCKETS: usize = 1 << HISTOGRAM_SUB_BUCKET_BITS;
+const HISTOGRAM_BUCKETS: usize =
+    HISTOGRAM_SUB_BUCKETS * (64 - HISTOGRAM_SUB_BUCKET_BITS as usize + 1);
+
+/// Percentiles and non-empty buckets of a `Histogram`, in microseconds
+/// Percentiles are the upper bound of the bucket they fall in (capped at the maximum), so
+/// they overstate the exact value by at most one bucket width (about 6%)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistogramSummary {
+    pub samples: u64,
+    pub min_us: f64,
+    pub p50_us: f64,
+    pub p90_us: f64,
+    pub p99_us: f64,
+    pub p999_us: f64,
+    pub max_us: f64,
+    pub buckets: Vec<HistogramBucket>,
+}
+
+impl HistogramSummary {
+    /// The buckets merged per power of two of their bound, for display: a latency
+    /// distribution spanning 10 us to 10 ms takes 10 rows instead of up to 160
+    pub fn octaves(&self) -> Vec<HistogramBucket> {
+        let mut octaves: Vec<HistogramBucket> = Vec::new();
+        for bucket in &self.buckets {
+            let upper_ns = ((bucket.upper_us * 1000.0).round() as u64)
+                .checked_next_power_of_two()
+                .unwrap_or(u64::MAX);
+            let upper_us = upper_ns as f64 / 1000.0;
+            match octaves.last_mut() {
+                Some(last) if last.upper_us == upper_us => last.count += bucket.count,
+                _ => octaves.push(HistogramBucket {
+                    upper_us,
+                    count: bucket.count,
+                }),
+            }
+        }
+        octaves
+    }
+}
+
+/// Completions with a latency below `upper_us` and at or above the previous bucket's bound
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    pub upper_us: f64,
+    pub count: u64,
+}
+
+/// Log-linear (HDR-style) histogram of latencies in nanoseconds
+/// Each power of two is split into `HISTOGRAM_SUB_BUCKETS` equal buckets, so memory stays
+/// constant however many values are recorded while the relative precision stays fixed
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    samples: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; HISTOGRAM_BUCKETS],
+            samples: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+        }
+    }
+
+    pub fn record(&mut self, latency: std::time::Duration) {
+        let ns = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[Self::bucket(ns)] += 1;
+        self.samples += 1;
+        self.min_ns = self.min_ns.min(ns);
+        self.max_ns = self.max_ns.max(ns);
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Smallest latency in nanoseconds that is at least the `quantile` (0.0 to 1.0) of the
+    /// recorded values, at bucket precision; 0 when nothing was recorded
+    pub fn value_at_quantile(&self, quantile: f64) -> u64 {
+        if self.samples == 0 {
+            return 0;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.samples as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return (Self::upper_bound(index) - 1).clamp(self.min_ns, self.max_ns);
+            }
+        }
+        self.max_ns
+    }
+
+    pub fn summary(&self) -> HistogramSummary {
+        if self.samples == 0 {
+            return HistogramSummary::default();
+        }
+        let us = |ns: u64| ns as f64 / 1000.0;
+        HistogramSummary {
+            samples: self.samples,
+            min_us: us(self.min_ns),
+            p50_us: us(self.value_at_quantile(0.50)),
+            p90_us: us(self.value_at_quantile(0.90)),
+            p99_us: us(self.value_at_quantile(0.99)),
+            p999_us: us(self.value_at_quantile(0.999)),
+            max_us: us(self.max_ns),
+            buckets: self
+                .counts
+                .iter()
+                .enumerate()
+                .filter(|(_, &count)| count > 0)
+                .map(|(index, &count)| HistogramBucket {
+                    upper_us: us(Self::upper_bound(index)),
+                    count,
+                })
+                .collect(),
+        }
+    }
+
+    /// Values below `HISTOGRAM_SUB_BUCKETS` get a bucket each; above that, the bucket is
+    /// the power of two followed by the next `HISTOGRAM_SUB_BUCKET_BITS` bits
+    fn bucket(ns: u64) -> usize {
+        if ns < HISTOGRAM_SUB_BUCKETS as u64 {
+            return ns as usize;
+        }
+        let shift = 63 - ns.leading_zeros() - HISTOGRAM_SUB_BUCKET_BITS;
+        let sub_bucket = (ns >> shift) as usize - HISTOGRAM_SUB_BUCKETS;
+        HISTOGRAM_SUB_BUCKETS * (shift as usize + 1) + sub_bucket
+    }
+
+    /// Exclusive upper bound of bucket `index` in nanoseconds
+    fn upper_bound(index: usize) -> u64 {
+        if index < HISTOGRAM_SUB_BUCKETS {
+            return index as u64 + 1;
+        }
+        let shift = (index / HISTOGRAM_SUB_BUCKETS - 1) as u32;
+        let sub_bucket = (index % HISTOGRAM_SUB_BUCKETS) as u64;
+        (HISTOGRAM_SUB_BUCKETS as u64 + sub_bucket + 1).saturating_mul(1 << shift)
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,4 +710,179 @@ mod tests {
         // Should not panic or produce NaN
         assert!(stats.coefficient_of_variation.is_finite());
     }
+
+    #[test]
+    fn test_streaming_stats_match_batch_statistics() {
+        let values: Vec<f64> = (1..=200).map(|x| (x as f64 * 7.3) % 50.0 + 1.0).collect();
+        let mut streaming = StreamingStats::new();
+        assert!(streaming.statistics().is_none());
+        values.iter().for_each(|&value| streaming.push(value));
+
+        // Below the reservoir size every statistic is exact
+        let batch = Statistics::from_values(&values).unwrap();
+        let stream = streaming.statistics().unwrap();
+        assert_eq!(stream.count, 200);
+        for (a, b) in [
+            (stream.mean, batch.mean),
+            (stream.std_dev, batch.std_dev),
+            (stream.min, batch.min),
+            (stream.max, batch.max),
+            (stream.p50, batch.p50),
+            (stream.p99, batch.p99),
+            (stream.ci95_high, batch.ci95_high),
+            (stream.geo_mean.unwrap(), batch.geo_mean.unwrap()),
+            (stream.harmonic_mean.unwrap(), batch.harmonic_mean.unwrap()),
+        ] {
+            assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+        }
+
+        streaming.push(-1.0);
+        let stream = streaming.statistics().unwrap();
+        assert_eq!((stream.geo_mean, stream.harmonic_mean), (None, None));
+    }
+
+    #[test]
+    fn test_streaming_stats_bound_memory() {
+        let mut streaming = StreamingStats::with_reservoir_size(100);
+        for i in 0..100_000 {
+            streaming.push(1e9 + (i % 1000) as f64);
+        }
+        assert_eq!(streaming.reservoir.len(), 100);
+        let stats = streaming.statistics().unwrap();
+        assert_eq!(stats.count, 100_000);
+        // Welford stays exact despite the large offset: values are uniform over 0..1000
+        assert!((stats.mean - (1e9 + 499.5)).abs() < 1e-3);
+        assert!((stats.std_dev - 288.675).abs() < 1e-2);
+        assert_eq!((stats.min, stats.max), (1e9, 1e9 + 999.0));
+        // The sampled median lands near the true one
+        assert!((stats.p50 - (1e9 + 499.5)).abs() < 150.0, "{}", stats.p50);
+    }
+
+    #[test]
+    fn test_linear_fit() {
+        let fit = linear_fit(&[10.0, 12.0, 14.0, 16.0]).unwrap();
+        assert!((fit.slope - 2.0).abs() < 1e-12);
+        assert!((fit.intercept - 10.0).abs() < 1e-12);
+        assert!((fit.r_squared - 1.0).abs() < 1e-12);
+        assert!((fit.at(4.0) - 18.0).abs() < 1e-12);
+
+        // Noise around a flat line explains little
+        let fit = linear_fit(&[10.0, 12.0, 9.0, 11.0, 10.0, 12.0, 9.0, 11.0]).unwrap();
+        assert!(fit.r_squared < 0.1, "{}", fit.r_squared);
+        assert_eq!(linear_fit(&[5.0, 5.0, 5.0]).unwrap().r_squared, 0.0);
+        assert!(linear_fit(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_change_point() {
+        let values = [100.0, 101.0, 99.0, 100.0, 90.0, 91.0, 89.0, 90.0];
+        let point = change_point(&values).unwrap();
+        assert_eq!(point.index, 4);
+        assert!((point.mean_before - 100.0).abs() < 1e-9);
+        assert!((point.mean_after - 90.0).abs() < 1e-9);
+        assert!(point.score > 10.0, "{}", point.score);
+
+        // A lone outlier at the end is not a segment of its own
+        let point = change_point(&[100.0, 101.0, 99.0, 100.0, 101.0, 80.0]).unwrap();
+        assert_ne!(point.index, 5);
+        assert!(point.score < 3.0, "{}", point.score);
+
+        assert!(change_point(&[5.0; 6]).is_none());
+        assert!(change_point(&[1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn test_confidence_interval_uses_t_distribution() {
+        // Sample std dev 1.0, n = 3: margin = 4.303 * 1 / sqrt(3)
+        let stats = Statistics::from_values(&[9.0, 10.0, 11.0]).unwrap();
+        assert_eq!(stats.count, 3);
+        assert!((stats.ci95_low - (10.0 - 2.4843)).abs() < 1e-3);
+        assert!((stats.ci95_high - (10.0 + 2.4843)).abs() < 1e-3);
+
+        let single = Statistics::from_values(&[42.0]).unwrap();
+        assert_eq!((single.ci95_low, single.ci95_high), (42.0, 42.0));
+    }
+
+    #[test]
+    fn test_t_critical_95() {
+        assert_eq!(t_critical_95(1.0), 12.706);
+        assert_eq!(t_critical_95(4.7), 2.776);
+        assert!((t_critical_95(31.0) - 2.0395).abs() < 1e-3);
+        assert!((t_critical_95(120.0) - 1.9799).abs() < 1e-3);
+        assert!((t_critical_95(1e9) - 1.96).abs() < 1e-3);
+        assert_eq!(t_critical_95(0.5), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_geometric_and_harmonic_mean() {
+        let stats = Statistics::from_values(&[1.0, 4.0, 16.0]).unwrap();
+        assert!((stats.mean - 7.0).abs() < 1e-9);
+        assert!((stats.geo_mean.unwrap() - 4.0).abs() < 1e-9);
+        // 3 / (1 + 1/4 + 1/16) = 48 / 21
+        assert!((stats.harmonic_mean.unwrap() - 48.0 / 21.0).abs() < 1e-9);
+
+        // Harmonic <= geometric <= arithmetic
+        assert!(stats.harmonic_mean.unwrap() <= stats.geo_mean.unwrap());
+        assert!(stats.geo_mean.unwrap() <= stats.mean);
+
+        // Only defined for positive values
+        let stats = Statistics::from_values(&[-1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(stats.geo_mean, None);
+        assert_eq!(stats.harmonic_mean, None);
+        assert_eq!(geometric_mean(&[]), None);
+        assert_eq!(harmonic_mean(&[5.0]), Some(5.0));
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        // Small values are exact, larger ones keep 4 significant bits
+        for ns in [0, 1, 15, 16, 31, 32, 33, 1000, 123_456_789, u64::MAX] {
+            let index = Histogram::bucket(ns);
+            assert!(index < HISTOGRAM_BUCKETS);
+            assert!(ns < Histogram::upper_bound(index) || ns == u64::MAX);
+            if index > 0 {
+                assert!(ns >= Histogram::upper_bound(index - 1), "{}", ns);
+            }
+        }
+        assert_eq!(Histogram::bucket(32), Histogram::bucket(33));
+        assert_ne!(Histogram::bucket(33), Histogram::bucket(34));
+    }
+
+    #[test]
+    fn test_histogram_octaves() {
+        let mut histogram = Histogram::new();
+        for us in [100, 101, 120, 127, 200, 10_000] {
+            histogram.record(std::time::Duration::from_micros(us));
+        }
+        let octaves = histogram.summary().octaves();
+        let bounds: Vec<(f64, u64)> = octaves.iter().map(|b| (b.upper_us, b.count)).collect();
+        // 2^17 ns, 2^18 ns, and 2^24 ns
+        assert_eq!(bounds, vec![(131.072, 4), (262.144, 1), (16777.216, 1)]);
+        assert!(HistogramSummary::default().octaves().is_empty());
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = Histogram::new();
+        assert_eq!(histogram.summary(), HistogramSummary::default());
+
+        // 990 fast completions of 100 us and a tail of 10 slow ones of 10 ms
+        for _ in 0..990 {
+            histogram.record(std::time::Duration::from_micros(100));
+        }
+        for _ in 0..10 {
+            histogram.record(std::time::Duration::from_millis(10));
+        }
+        let summary = histogram.summary();
+        assert_eq!(summary.samples, 1000);
+        assert_eq!(summary.min_us, 100.0);
+        assert_eq!(summary.max_us, 10_000.0);
+        // Within one bucket (1/16) of the exact value
+        for p in [summary.p50_us, summary.p90_us, summary.p99_us] {
+            assert!((100.0..100.0 * 17.0 / 16.0).contains(&p), "{}", p);
+        }
+        assert_eq!(summary.p999_us, 10_000.0);
+        assert_eq!(summary.buckets.len(), 2);
+        assert_eq!(summary.buckets.iter().map(|b| b.count).sum::<u64>(), 1000);
+    }
 }
@@ -0,0 +1,378 @@
+/// Combined stress test for burn-in
+/// Runs CPU, memory, and disk loads at the same time for a fixed duration. Every load checks
+/// its own output while it runs: the CPU workers compare each result with a reference
+/// computed before the test, the memory workers read back the patterns they wrote, and the
+/// disk worker reads back the file it wrote. A machine that computes, stores, or writes
+/// wrong results under sustained heat and power draw fails, the way an unstable overclock
+/// fails a torture test; throttling is reported alongside the verdict
+use crate::cpu;
+use crate::disk;
+use crate::interrupt;
+use crate::rng::{self, SimpleRng};
+use crate::thermal::{ThermalMonitor, ThermalResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Stress test duration unless configured otherwise
+pub const DEFAULT_STRESS_DURATION: Duration = Duration::from_secs(600);
+
+/// Time between two status reports while the test runs
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Rows and columns of the matrices each CPU worker multiplies per pass
+const CPU_MATRIX_SIZE: usize = 128;
+/// Numbers each CPU worker sieves per pass; the prime count below it is known
+const CPU_SIEVE_LIMIT: usize = 1_000_000;
+const CPU_SEED: u64 = 0x5742_E550;
+/// Memory all memory workers together fill and verify per pass, at scale 1
+const BASE_MEMORY_BYTES: usize = 512 * 1024 * 1024;
+/// Size of the file the disk worker writes and reads back per pass, at scale 1
+const BASE_DISK_FILE_BYTES: usize = 256 * 1024 * 1024;
+const DISK_FILE_PREFIX: &str = ".bench_stress_"; // Followed by <pid>, inside the disk directory
+/// Pause after a disk pass failed with an I/O error, so a dead disk does not spin the worker
+const DISK_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// What to stress and for how long
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    pub duration: Duration,
+    /// Scale of the memory and disk working sets
+    pub scale: f64,
+    /// CPU worker threads; 0 disables the CPU load
+    pub threads: usize,
+    /// Memory worker threads; 0 disables the memory load
+    pub memory_threads: usize,
+    /// Directory the disk load writes its file to; None disables the disk load
+    pub disk_dir: Option<PathBuf>,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            duration: DEFAULT_STRESS_DURATION,
+            scale: 1.0,
+            threads: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            memory_threads: 2,
+            disk_dir: Some(PathBuf::from(".")),
+        }
+    }
+}
+
+/// Work done and errors found by one load
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StressLoad {
+    /// "CPU", "Memory", or "Disk"
+    pub name: String,
+    /// Completed passes, over all workers of the load
+    pub passes: u64,
+    /// Passes whose output differed from the reference or that failed with an I/O error
+    pub errors: u64,
+    /// Description of the first error
+    pub first_error: Option<String>,
+}
+
+/// Outcome of a stress test
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StressResult {
+    pub elapsed_secs: f64,
+    pub loads: Vec<StressLoad>,
+    pub thermal: ThermalResult,
+    /// Whether the test was interrupted before the configured duration
+    pub interrupted: bool,
+}
+
+impl StressResult {
+    /// Whether every load ran without an error
+    pub fn passed(&self) -> bool {
+        self.loads.iter().all(|load| load.errors == 0)
+    }
+
+    /// "PASS" or "FAIL"
+    pub fn verdict(&self) -> &'static str {
+        if self.passed() {
+            "PASS"
+        } else {
+            "FAIL"
+        }
+    }
+
+    /// Loads that found errors, e.g. "Memory: 2 of 340 passes failed (...)"
+    pub fn failures(&self) -> Vec<String> {
+        self.loads
+            .iter()
+            .filter(|load| load.errors > 0)
+            .map(|load| {
+                format!(
+                    "{}: {} of {} passes failed ({})",
+                    load.name,
+                    load.errors,
+                    load.passes,
+                    load.first_error.as_deref().unwrap_or("no details")
+                )
+            })
+            .collect()
+    }
+}
+
+/// Pass and error counts of one load, shared by its workers
+struct LoadCounters {
+    name: &'static str,
+    passes: AtomicU64,
+    errors: AtomicU64,
+    first_error: Mutex<Option<String>>,
+}
+
+impl LoadCounters {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            passes: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            first_error: Mutex::new(None),
+        }
+    }
+
+    /// Count a pass, failed with `error` if there is one
+    fn pass(&self, error: Option<String>) {
+        self.passes.fetch_add(1, Ordering::Relaxed);
+        if let Some(error) = error {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            let mut first = self.first_error.lock().unwrap_or_else(|e| e.into_inner());
+            first.get_or_insert(error);
+        }
+    }
+
+    fn snapshot(&self) -> StressLoad {
+        StressLoad {
+            name: self.name.to_string(),
+            passes: self.passes.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            first_error: self
+                .first_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+}
+
+/// Run the enabled loads together for `config.duration`, or until interrupted
+/// `status` is called every `STATUS_INTERVAL` with the elapsed time and the loads so far
+pub fn run_stress(
+    config: &StressConfig,
+    mut status: impl FnMut(Duration, &[StressLoad]),
+) -> StressResult {
+    let cpu = LoadCounters::new("CPU");
+    let memory = LoadCounters::new("Memory");
+    let disk = LoadCounters::new("Disk");
+    let stop = AtomicBool::new(false);
+    let reference = cpu_checksum();
+    let memory_words =
+        ((BASE_MEMORY_BYTES as f64 * config.scale) as usize / 8) / config.memory_threads.max(1);
+    let disk_file_size = ((BASE_DISK_FILE_BYTES as f64 * config.scale) as usize)
+        .div_ceil(disk::STRESS_BLOCK_SIZE)
+        .max(1)
+        * disk::STRESS_BLOCK_SIZE;
+
+    let monitor = ThermalMonitor::start();
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..config.threads {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    cpu.pass(check_cpu_pass(reference));
+                }
+            });
+        }
+        for worker in 0..config.memory_threads {
+            let (memory, stop) = (&memory, &stop);
+            scope.spawn(move || {
+                let mut buffer = vec![0u64; memory_words.max(1)];
+                let mut pass = (worker as u64) << 32;
+                while !stop.load(Ordering::Relaxed) {
+                    memory.pass(check_memory_pass(&mut buffer, pass));
+                    pass += 1;
+                }
+            });
+        }
+        if let Some(dir) = &config.disk_dir {
+            let path = dir.join(format!("{}{}", DISK_FILE_PREFIX, std::process::id()));
+            let (disk, stop) = (&disk, &stop);
+            scope.spawn(move || {
+                let mut pass = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let error = match disk::stress_disk_pass(&path, disk_file_size, pass) {
+                        Ok(0) => None,
+                        Ok(blocks) => Some(format!(
+                            "{} blocks read back differently in pass {}",
+                            blocks, pass
+                        )),
+                        Err(e) => {
+                            std::thread::sleep(DISK_ERROR_BACKOFF);
+                            Some(format!("I/O error in pass {}: {}", pass, e))
+                        }
+                    };
+                    disk.pass(error);
+                    pass += 1;
+                }
+                let _ = std::fs::remove_file(&path);
+            });
+        }
+
+        loop {
+            let remaining = config.duration.saturating_sub(start.elapsed());
+            if remaining.is_zero() || !interrupt::sleep(remaining.min(STATUS_INTERVAL)) {
+                break;
+            }
+            status(
+                start.elapsed(),
+                &enabled_loads(config, [&cpu, &memory, &disk]),
+            );
+        }
+        stop.store(true, Ordering::Relaxed);
+    });
+
+    StressResult {
+        elapsed_secs: start.elapsed().as_secs_f64(),
+        loads: enabled_loads(config, [&cpu, &memory, &disk]),
+        thermal: monitor.finish(),
+        interrupted: interrupt::interrupted(),
+    }
+}
+
+/// Snapshots of the loads `config` enables
+fn enabled_loads(
+    config: &StressConfig,
+    [cpu, memory, disk]: [&LoadCounters; 3],
+) -> Vec<StressLoad> {
+    [
+        (cpu, config.threads > 0),
+        (memory, config.memory_threads > 0),
+        (disk, config.disk_dir.is_some()),
+    ]
+    .into_iter()
+    .filter(|&(_, enabled)| enabled)
+    .map(|(load, _)| load.snapshot())
+    .collect()
+}
+
+/// Bits of a product of two seeded matrices, folded into one checksum
+/// The same inputs through the same code give the same bits on a healthy CPU
+fn cpu_checksum() -> u64 {
+    let n = CPU_MATRIX_SIZE;
+    let mut rng = SimpleRng::new(rng::workload_seed(CPU_SEED));
+    let a: Vec<f64> = (0..n * n).map(|_| rng.next_f64() - 0.5).collect();
+    let b: Vec<f64> = (0..n * n).map(|_| rng.next_f64() - 0.5).collect();
+    let mut checksum = 0u64;
+    for i in 0..n {
+        let mut row = vec![0.0; n];
+        for k in 0..n {
+            let a_ik = a[i * n + k];
+            for (c, &b_kj) in row.iter_mut().zip(&b[k * n..(k + 1) * n]) {
+                *c += a_ik * b_kj;
+            }
+        }
+        for c in row {
+            checksum = checksum.rotate_left(7) ^ c.to_bits();
+        }
+    }
+    std::hint::black_box(checksum)
+}
+
+/// One CPU pass: the matrix checksum and a prime count, compared with their references
+/// Returns: the first mismatch, if any
+fn check_cpu_pass(reference: u64) -> Option<String> {
+    let checksum = cpu_checksum();
+    if checksum != reference {
+        return Some(format!(
+            "matrix checksum {:#018x} differs from {:#018x}",
+            checksum, reference
+        ));
+    }
+    let mut is_composite = vec![false; CPU_SIEVE_LIMIT];
+    let count = std::hint::black_box(cpu::sieve_of_eratosthenes(&mut is_composite));
+    let (bound, expected) = cpu::known_prime_count(CPU_SIEVE_LIMIT as u64);
+    (count != expected).then(|| {
+        format!(
+            "counted {} primes below {}, expected {}",
+            count, bound, expected
+        )
+    })
+}
+
+/// Word `index` of the pattern of pass `pass`; differs between neighboring words and passes
+pub(crate) fn pattern_word(pass: u64, index: u64) -> u64 {
+    // SplitMix64 finalizer over the pass and position
+    let mut z = ((pass << 40) ^ index).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// One memory pass: fill `buffer` with the pattern of `pass`, then read it back
+/// Returns: how many words differed, if any did
+fn check_memory_pass(buffer: &mut [u64], pass: u64) -> Option<String> {
+    for (index, word) in buffer.iter_mut().enumerate() {
+        *word = pattern_word(pass, index as u64);
+    }
+    let buffer = std::hint::black_box(&*buffer);
+    let mismatches = buffer
+        .iter()
+        .enumerate()
+        .filter(|&(index, &word)| word != pattern_word(pass, index as u64))
+        .count();
+    (mismatches > 0).then(|| format!("{} words read back differently", mismatches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loads_pass_on_a_healthy_machine() {
+        let reference = cpu_checksum();
+        assert_eq!(cpu_checksum(), reference);
+        assert_eq!(check_cpu_pass(reference), None);
+        assert!(check_cpu_pass(reference ^ 1)
+            .unwrap()
+            .contains("matrix checksum"));
+
+        let mut buffer = vec![0u64; 4096];
+        assert_eq!(check_memory_pass(&mut buffer, 3), None);
+        assert_ne!(pattern_word(3, 0), pattern_word(4, 0));
+        assert_ne!(pattern_word(3, 0), pattern_word(3, 1));
+    }
+
+    #[test]
+    fn test_stress_verdict() {
+        let config = StressConfig {
+            duration: Duration::from_millis(300),
+            scale: 0.01,
+            threads: 1,
+            memory_threads: 1,
+            disk_dir: Some(std::env::temp_dir()),
+        };
+        let result = run_stress(&config, |_, _| {});
+        let names: Vec<&str> = result.loads.iter().map(|load| load.name.as_str()).collect();
+        assert_eq!(names, vec!["CPU", "Memory", "Disk"]);
+        assert!(
+            result.loads.iter().all(|load| load.passes > 0),
+            "{:?}",
+            result
+        );
+        assert!(result.passed(), "{:?}", result.failures());
+        assert_eq!(result.verdict(), "PASS");
+        assert!(!std::env::temp_dir()
+            .join(format!("{}{}", DISK_FILE_PREFIX, std::process::id()))
+            .exists());
+
+        let mut failed = result.clone();
+        failed.loads[1].errors = 1;
+        failed.loads[1].first_error = Some("3 words read back differently".to_string());
+        assert_eq!(failed.verdict(), "FAIL");
+        assert!(failed.failures()[0].starts_with("Memory: 1 of"));
+    }
+}
@@ -0,0 +1,2490 @@
+/// Benchmark suite orchestration
+/// Runs the CPU, memory, concurrency, disk, network, and GPU benchmarks for a configured number
+/// of runs and collects the per-run results for reporting
+use crate::affinity;
+use crate::benchmark::Verification;
+use crate::concurrency::{self, ConcurrencyResult};
+use crate::cpu::{self, CpuResult};
+use crate::disk::{self, DiskMode, DiskProfile, DiskResult, DiskTarget, IoMode, Precondition};
+use crate::error::BenchError;
+use crate::gpu::{self, GpuResult};
+use crate::interrupt;
+use crate::load::{self, BackgroundLoad, LoadMonitor};
+use crate::memory::{self, AccessPattern, MemoryFootprint, MemoryResult};
+use crate::network::{self, NetworkResult};
+use crate::progress::Progress;
+use crate::rng;
+use crate::stats::Statistics;
+use crate::sysinfo_capture;
+use crate::timing::{self, KernelTimings};
+use crate::watchdog::Watchdog;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default step time limit per unit of scale, far above what a healthy machine needs
+pub const STEP_TIMEOUT_SECS_PER_SCALE: f64 = 600.0;
+
+/// Shortest default step time limit, for small scales
+pub const MIN_STEP_TIMEOUT_SECS: f64 = 120.0;
+
+/// Timed passes of `min_time` the default step time limit allows for; the disk block-size
+/// sweep repeats a write and a read pass per block size within one step
+const MIN_TIME_STEP_PASSES: f64 = 20.0;
+
+/// Benchmark subsystems executed by the suite, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Cpu,
+    Memory,
+    Concurrency,
+    Disk,
+    Network,
+    Gpu,
+}
+
+impl Phase {
+    /// Every phase, in execution order
+    pub const ALL: [Phase; 6] = [
+        Phase::Cpu,
+        Phase::Memory,
+        Phase::Concurrency,
+        Phase::Disk,
+        Phase::Network,
+        Phase::Gpu,
+    ];
+
+    /// Phase with the given name, ignoring case (e.g. "cpu" or "Memory")
+    pub fn from_name(name: &str) -> Option<Phase> {
+        Phase::ALL
+            .into_iter()
+            .find(|phase| phase.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Human-readable name used in console output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Cpu => "CPU",
+            Phase::Memory => "Memory",
+            Phase::Concurrency => "Concurrency",
+            Phase::Disk => "Disk",
+            Phase::Network => "Network",
+            Phase::Gpu => "GPU",
+        }
+    }
+}
+
+/// How the phases of a run are scheduled relative to each other
+/// The rates of a parallel run include the contention between the phases, so they are
+/// not comparable with serial or isolated runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Schedule {
+    /// One phase after the other (default)
+    #[default]
+    Serial,
+    /// The CPU, memory, and disk phases at the same time, as a combined stress load; the
+    /// other phases follow one after the other
+    Parallel,
+    /// One phase after the other with an idle gap of at least `ISOLATION_GAP_SECS` before
+    /// every phase but the first, so no phase inherits the heat or the background I/O of the
+    /// previous one
+    Isolated,
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 3] = [Schedule::Serial, Schedule::Parallel, Schedule::Isolated];
+
+    /// Schedule with the given name, ignoring case (e.g. "serial" or "parallel")
+    pub fn from_name(name: &str) -> Option<Schedule> {
+        Schedule::ALL
+            .into_iter()
+            .find(|schedule| schedule.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Name with what it means for the results, e.g. for the report header
+    pub fn description(&self) -> &'static str {
+        match self {
+            Schedule::Serial => "serial (one phase at a time)",
+            Schedule::Parallel => "parallel (CPU, memory, and disk at the same time)",
+            Schedule::Isolated => "isolated (idle gap before each phase)",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schedule::Serial => "serial",
+            Schedule::Parallel => "parallel",
+            Schedule::Isolated => "isolated",
+        }
+    }
+}
+
+/// Shortest idle gap between the phases of an isolated run
+pub const ISOLATION_GAP_SECS: f64 = 10.0;
+
+/// Resolved configuration of a suite run
+/// Missing fields take their default values when deserialized from older reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuiteConfig {
+    pub scale: f64,
+    /// Scales of the CPU, memory, and disk benchmarks; None uses `scale`
+    pub cpu_scale: Option<f64>,
+    pub memory_scale: Option<f64>,
+    pub disk_scale: Option<f64>,
+    /// Target seconds per kernel pass; the CPU, memory, and disk scales without an
+    /// override are then calibrated for this machine before the first run
+    pub auto_scale: Option<f64>,
+    /// Repeat each CPU, memory, and disk kernel until this many seconds have passed;
+    /// None runs every scaled workload once
+    pub min_time: Option<f64>,
+    /// Untimed warmup passes before the CPU, memory, disk, and network kernels; 0 skips
+    /// warmup, e.g. for cold-cache studies
+    pub warmup_runs: usize,
+    /// Seed mixed into the random inputs of every workload (see `rng::set_seed`); None uses
+    /// the built-in seeds
+    pub seed: Option<u64>,
+    pub runs: usize,
+    pub threads: usize,
+    /// Threads streaming memory in the memory benchmark
+    pub memory_threads: usize,
+    /// Also sweep 1, 2, 4, ... `memory_threads` threads to record the bandwidth curve
+    pub memory_scaling: bool,
+    /// Also compare local and remote NUMA node memory on multi-node systems
+    pub memory_numa: bool,
+    /// Read patterns whose bandwidth the memory benchmark also measures; none by default
+    pub memory_patterns: Vec<AccessPattern>,
+    /// Also repeat the memory write and read passes with buffers on huge pages
+    pub memory_huge_pages: bool,
+    /// Run the memory write and read passes on buffers faulted in before timing
+    pub memory_prefault: bool,
+    /// Also measure the cache-line latency between every two cores
+    pub core_latency: bool,
+    /// Pin CPU, memory, and concurrency benchmark threads to cores
+    pub pin_cores: bool,
+    /// Cores to pin to, in worker order; empty uses every core available to the process
+    pub core_list: Vec<usize>,
+    pub block_size: usize,
+    /// Directory the disk benchmark writes its temporary files to; None uses the current directory
+    pub disk_path: Option<PathBuf>,
+    /// How the disk benchmark's sequential passes access the file
+    pub disk_mode: DiskMode,
+    /// Dataset written before the disk benchmark's sequential passes to measure an SSD's
+    /// steady state; None writes the test file on its own
+    pub disk_precondition: Option<Precondition>,
+    /// Repeat the disk benchmark's sequential passes through the OS cache for comparison
+    pub disk_cache_compare: bool,
+    /// Mixed read/write workloads run after the standard disk passes
+    pub disk_profiles: Vec<DiskProfile>,
+    /// Seconds to idle before every phase but the first, so thermals recover between
+    /// phases and runs; 0 runs them back to back
+    pub cooldown: f64,
+    /// Whether the phases run one after the other, partly at the same time, or isolated
+    /// by idle gaps
+    pub schedule: Schedule,
+    /// Seconds every benchmark step may run before the watchdog cuts it short and marks it
+    /// as timed out; None derives the limit from the scales (see `step_timeout`), 0 disables it
+    pub timeout: Option<f64>,
+    pub cpu: bool,
+    /// Registry kernels to run by name (see `benchmark::registry`); empty runs them all
+    pub kernels: Vec<String>,
+    /// Check the output of the CPU kernels that have a check against a reference after
+    /// they were timed (see `Benchmark::verify`)
+    pub verify: bool,
+    pub memory: bool,
+    /// Run the thread synchronization benchmark with 1, 2, 4, ... `threads` threads
+    pub concurrency: bool,
+    pub disk: bool,
+    pub network: bool,
+    /// Remote `host:port` running `--network-server`; None benchmarks loopback
+    pub network_target: Option<String>,
+    /// Run the GPU benchmark; defaults to enabled when built with the `gpu` feature
+    pub gpu: bool,
+    /// Share of the total CPU capacity other processes may use before a run is contaminated
+    pub load_threshold_percent: f64,
+    /// Leave contaminated runs out of the results and statistics
+    pub exclude_contaminated: bool,
+}
+
+impl Default for SuiteConfig {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            cpu_scale: None,
+            memory_scale: None,
+            disk_scale: None,
+            auto_scale: None,
+            min_time: None,
+            warmup_runs: timing::DEFAULT_WARMUP_RUNS,
+            seed: None,
+            runs: 3,
+            threads: 4,
+            memory_threads: memory::DEFAULT_MEMORY_THREADS,
+            memory_scaling: false,
+            memory_numa: false,
+            memory_patterns: Vec::new(),
+            memory_huge_pages: false,
+            memory_prefault: false,
+            core_latency: false,
+            pin_cores: false,
+            core_list: Vec::new(),
+            block_size: disk::DEFAULT_BLOCK_SIZE,
+            disk_path: None,
+            disk_mode: DiskMode::Direct,
+            disk_precondition: None,
+            disk_cache_compare: false,
+            disk_profiles: Vec::new(),
+            cooldown: 0.0,
+            schedule: Schedule::Serial,
+            timeout: None,
+            cpu: true,
+            kernels: Vec::new(),
+            verify: false,
+            memory: true,
+            concurrency: true,
+            disk: true,
+            network: true,
+            network_target: None,
+            gpu: cfg!(feature = "gpu"),
+            load_threshold_percent: load::DEFAULT_LOAD_THRESHOLD_PERCENT,
+            exclude_contaminated: false,
+        }
+    }
+}
+
+impl SuiteConfig {
+    /// Scale of `phase`: its override where one is set, otherwise the global scale
+    pub fn scale_for(&self, phase: Phase) -> f64 {
+        let scale = match phase {
+            Phase::Cpu => self.cpu_scale,
+            Phase::Memory => self.memory_scale,
+            Phase::Disk => self.disk_scale,
+            Phase::Concurrency | Phase::Network | Phase::Gpu => None,
+        };
+        scale.unwrap_or(self.scale)
+    }
+
+    /// Seconds to idle before every phase but the first: the cooldown, at least
+    /// `ISOLATION_GAP_SECS` with the isolated schedule
+    pub fn phase_gap(&self) -> f64 {
+        match self.schedule {
+            Schedule::Isolated => self.cooldown.max(ISOLATION_GAP_SECS),
+            Schedule::Serial | Schedule::Parallel => self.cooldown,
+        }
+    }
+
+    /// Time limit of every benchmark step: the configured `timeout`, or by default
+    /// `STEP_TIMEOUT_SECS_PER_SCALE` per unit of the largest scale plus room for the
+    /// repetitions of `min_time`, at least `MIN_STEP_TIMEOUT_SECS`
+    /// Returns: None when the timeout is disabled
+    pub fn step_timeout(&self) -> Option<Duration> {
+        let secs = match self.timeout {
+            Some(secs) => secs,
+            None => {
+                let scale = [Phase::Cpu, Phase::Memory, Phase::Disk]
+                    .into_iter()
+                    .map(|phase| self.scale_for(phase))
+                    .fold(self.scale, f64::max);
+                let repeated = self.min_time.unwrap_or(0.0) * MIN_TIME_STEP_PASSES;
+                (STEP_TIMEOUT_SECS_PER_SCALE * scale + repeated).max(MIN_STEP_TIMEOUT_SECS)
+            }
+        };
+        (secs > 0.0 && secs.is_finite()).then(|| Duration::from_secs_f64(secs))
+    }
+
+    /// Global scale followed by the overrides, e.g. "0.5 (CPU 2, Disk 0.1)"
+    pub fn scale_summary(&self) -> String {
+        let overrides: Vec<String> = [
+            (Phase::Cpu, self.cpu_scale),
+            (Phase::Memory, self.memory_scale),
+            (Phase::Disk, self.disk_scale),
+        ]
+        .into_iter()
+        .filter_map(|(phase, scale)| Some(format!("{} {}", phase.name(), scale?)))
+        .collect();
+        if overrides.is_empty() {
+            self.scale.to_string()
+        } else {
+            format!("{} ({})", self.scale, overrides.join(", "))
+        }
+    }
+
+    /// Directory used by the disk benchmark
+    pub fn disk_dir(&self) -> &Path {
+        self.disk_path.as_deref().unwrap_or(Path::new("."))
+    }
+
+    /// Cores that benchmark threads are pinned to; empty when pinning is disabled
+    pub fn pinned_cores(&self) -> Vec<usize> {
+        match (self.pin_cores, self.core_list.is_empty()) {
+            (false, _) => Vec::new(),
+            (true, true) => affinity::available_cores(),
+            (true, false) => self.core_list.clone(),
+        }
+    }
+
+    /// Enabled phases of each run, in execution order
+    pub fn phases(&self) -> Vec<Phase> {
+        [
+            (Phase::Cpu, self.cpu),
+            (Phase::Memory, self.memory),
+            (Phase::Concurrency, self.concurrency),
+            (Phase::Disk, self.disk),
+            (Phase::Network, self.network),
+            (Phase::Gpu, self.gpu),
+        ]
+        .into_iter()
+        .filter_map(|(phase, enabled)| enabled.then_some(phase))
+        .collect()
+    }
+}
+
+/// Results of every run, grouped per subsystem
+/// Disabled subsystems have empty result vectors; failed runs of any subsystem are omitted
+pub struct SuiteResults {
+    pub config: SuiteConfig,
+    pub cpu: Vec<CpuResult>,
+    pub memory: Vec<MemoryResult>,
+    pub concurrency: Vec<ConcurrencyResult>,
+    pub disk: Vec<DiskResult>,
+    pub network: Vec<NetworkResult>,
+    pub gpu: Vec<GpuResult>,
+    /// Filesystem the disk benchmark ran on; None when the disk benchmark is disabled
+    pub disk_target: Option<DiskTarget>,
+    /// CPU used by other processes, one entry per run including excluded runs
+    pub background_load: Vec<BackgroundLoad>,
+    /// Idle periods between phases and runs; empty without a cooldown
+    pub cooldowns: Vec<Cooldown>,
+    /// Steps the watchdog cut short after they exceeded the step timeout
+    pub timed_out: Vec<TimedOutStep>,
+    /// Whether the suite was interrupted (`interrupt::interrupted`); the results then end with
+    /// the last phase that completed, and the remaining phases and runs are missing
+    pub interrupted: bool,
+}
+
+impl SuiteResults {
+    /// Number of results of every subsystem
+    fn lengths(&self) -> [usize; 6] {
+        [
+            self.cpu.len(),
+            self.memory.len(),
+            self.concurrency.len(),
+            self.disk.len(),
+            self.network.len(),
+            self.gpu.len(),
+        ]
+    }
+
+    /// Drop the results added since `lengths()` returned `lengths`
+    fn truncate(&mut self, lengths: [usize; 6]) {
+        let [cpu, memory, concurrency, disk, network, gpu] = lengths;
+        self.cpu.truncate(cpu);
+        self.memory.truncate(memory);
+        self.concurrency.truncate(concurrency);
+        self.disk.truncate(disk);
+        self.network.truncate(network);
+        self.gpu.truncate(gpu);
+    }
+
+    /// Warning that the suite was interrupted, if it was
+    pub fn interruption_warning(&self) -> Option<String> {
+        self.interrupted.then(|| {
+            format!(
+                "The suite was interrupted during run {} of {}; the results only include the \
+                 benchmarks that completed before the interruption",
+                self.background_load.len(),
+                self.config.runs
+            )
+        })
+    }
+
+    /// Configured cooldown and the idle time actually spent, e.g. "30 s before each phase
+    /// (9 periods, 270.0 s idle)"; None without a cooldown or isolation gap
+    pub fn cooldown_summary(&self) -> Option<String> {
+        let gap = self.config.phase_gap();
+        (gap > 0.0).then(|| {
+            let idle: f64 = self.cooldowns.iter().map(|cooldown| cooldown.seconds).sum();
+            format!(
+                "{} s before each phase ({} periods, {:.1} s idle)",
+                gap,
+                self.cooldowns.len(),
+                idle
+            )
+        })
+    }
+
+    /// Largest memory benchmark footprint of the runs, e.g. "3.8 GB of 16.0 GB available";
+    /// None without memory results
+    pub fn memory_footprint_summary(&self) -> Option<String> {
+        self.memory
+            .iter()
+            .map(|result| result.footprint)
+            .filter(|footprint| footprint.bytes > 0)
+            .max_by_key(|footprint| footprint.bytes)
+            .map(|footprint| footprint.summary())
+    }
+
+    /// Outcome of the output checks of the runs, e.g. "9 of 9 kernels passed in 3 runs";
+    /// None when the runs were not verified
+    pub fn verification_summary(&self) -> Option<String> {
+        let checks: Vec<&Verification> = self
+            .cpu
+            .iter()
+            .flat_map(|result| &result.verification)
+            .collect();
+        let verified_runs = self
+            .cpu
+            .iter()
+            .filter(|result| !result.verification.is_empty())
+            .count();
+        (verified_runs > 0).then(|| {
+            format!(
+                "{} of {} kernel checks passed in {} run{}",
+                checks.iter().filter(|check| check.passed).count(),
+                checks.len(),
+                verified_runs,
+                if verified_runs == 1 { "" } else { "s" }
+            )
+        })
+    }
+
+    /// Warning listing the kernels whose output differed from the reference, if any
+    pub fn verification_warning(&self) -> Option<String> {
+        let failed: Vec<String> = self
+            .cpu
+            .iter()
+            .enumerate()
+            .flat_map(|(i, result)| {
+                result
+                    .verification
+                    .iter()
+                    .filter(|check| !check.passed)
+                    .map(move |check| {
+                        format!("{} in run {} ({})", check.kernel, i + 1, check.detail)
+                    })
+            })
+            .collect();
+        (!failed.is_empty()).then(|| {
+            format!(
+                "Kernel output differs from the reference: {}; the rates of these kernels \
+                 may not measure the intended work",
+                failed.join(", ")
+            )
+        })
+    }
+
+    /// Warning listing the steps the watchdog cut short, if any
+    pub fn timeout_warning(&self) -> Option<String> {
+        let limit = self.timed_out.first()?.limit_secs;
+        let steps: Vec<String> = self
+            .timed_out
+            .iter()
+            .map(|step| format!("{} {} in run {}", step.phase, step.step, step.run))
+            .collect();
+        Some(format!(
+            "Benchmark steps exceeded the {} s timeout and were cut short: {}; their rates \
+             cover only the rounds completed before the timeout",
+            limit,
+            steps.join(", ")
+        ))
+    }
+
+    /// Warning listing the runs that asked for direct I/O but went through the OS cache
+    /// because the filesystem did not honor it, if any
+    pub fn direct_io_warning(&self) -> Option<String> {
+        let buffered: Vec<String> = self
+            .disk
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| {
+                result.mode == DiskMode::Direct && result.io_mode == Some(IoMode::Buffered)
+            })
+            .map(|(i, _)| format!("run {}", i + 1))
+            .collect();
+        (!buffered.is_empty()).then(|| {
+            let filesystem = self
+                .disk_target
+                .as_ref()
+                .map(|target| format!(" ({})", target.filesystem))
+                .unwrap_or_default();
+            format!(
+                "Direct I/O was requested but the filesystem{} did not honor it in disk {}; \
+                 the sequential passes went through the OS cache and may measure memory \
+                 rather than the disk",
+                filesystem,
+                buffered.join(", ")
+            )
+        })
+    }
+
+    /// Warning listing the runs that other processes disturbed, if any
+    pub fn contamination_warning(&self) -> Option<String> {
+        let contaminated: Vec<String> = self
+            .background_load
+            .iter()
+            .enumerate()
+            .filter(|(_, load)| load.contaminated)
+            .map(|(i, load)| {
+                format!(
+                    "run {} ({:.0}% before, {:.0}% during{})",
+                    i + 1,
+                    load.before_percent,
+                    load.during_percent,
+                    if load.excluded { ", excluded" } else { "" }
+                )
+            })
+            .collect();
+        (!contaminated.is_empty()).then(|| {
+            format!(
+                "Other processes used more than {:.0}% of the CPU in {}; contaminated runs \
+                 measure that contention rather than the system",
+                self.config.load_threshold_percent,
+                contaminated.join(", ")
+            )
+        })
+    }
+
+    /// Warning listing the runs whose CPU benchmark was thermally throttled, if any
+    /// Averages over throttled and unthrottled runs describe neither state
+    pub fn throttling_warning(&self) -> Option<String> {
+        let throttled: Vec<String> = self
+            .cpu
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.thermal.throttled())
+            .filter_map(|(i, result)| {
+                Some(format!(
+                    "run {} ({:.0} MHz sustained, {:.0}% below the {:.0} MHz peak)",
+                    i + 1,
+                    result.thermal.sustained_frequency_mhz?,
+                    result.thermal.frequency_drop_percent()?,
+                    result.thermal.peak_frequency_mhz?
+                ))
+            })
+            .collect();
+        (!throttled.is_empty()).then(|| {
+            format!(
+                "Thermal throttling detected in CPU {}; results of throttled and unthrottled \
+                 runs are not comparable",
+                throttled.join(", ")
+            )
+        })
+    }
+}
+
+/// Benchmark step that exceeded the step timeout
+/// The step stopped after the round in progress, so its rates cover fewer rounds than
+/// `min_time` asked for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedOutStep {
+    pub run: usize,
+    /// Phase and step, e.g. "Disk" and "sequential write"
+    pub phase: String,
+    pub step: String,
+    /// Time limit the step exceeded
+    pub limit_secs: f64,
+}
+
+/// Idle period before a phase, giving the machine time to cool down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cooldown {
+    /// Run of the phase that followed the idle period
+    pub run: usize,
+    /// Phases before and after the idle period, e.g. "CPU" and "Memory"
+    pub after: String,
+    pub before: String,
+    /// Time actually spent idle; shorter than configured if the run was interrupted
+    pub seconds: f64,
+}
+
+/// Predicted duration of one phase of a `--dry-run` plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhasePlan {
+    pub phase: Phase,
+    /// Scale the phase would run at, after `auto_scale` calibration and memory capping
+    pub scale: f64,
+    /// Predicted seconds of the phase in one run; None for the network and GPU phases, which
+    /// are not estimated, and when the calibration pass failed
+    pub estimated_secs: Option<f64>,
+}
+
+/// What a suite run would do, resolved without running the benchmarks
+#[derive(Debug, Clone)]
+pub struct SuitePlan {
+    /// Configuration of the run, with the scales `auto_scale` calibrates filled in
+    pub config: SuiteConfig,
+    /// Enabled phases, in execution order
+    pub phases: Vec<PhasePlan>,
+    /// Buffers of the memory benchmark; None when it is disabled
+    pub memory_footprint: Option<MemoryFootprint>,
+    /// Filesystem of the disk benchmark and the free space its test files need; None when
+    /// the disk benchmark is disabled
+    pub disk_target: Option<DiskTarget>,
+    pub disk_bytes: Option<u64>,
+}
+
+impl SuitePlan {
+    /// Predicted seconds of all runs, including the cooldowns between phases
+    /// Phases without an estimate count as zero, see `is_partial`
+    pub fn estimated_secs(&self) -> f64 {
+        let per_run: f64 = self
+            .phases
+            .iter()
+            .filter_map(|phase| phase.estimated_secs)
+            .sum();
+        let cooldowns = (self.phases.len() * self.config.runs).saturating_sub(1);
+        per_run * self.config.runs as f64 + self.config.phase_gap() * cooldowns as f64
+    }
+
+    /// Whether an enabled phase has no estimate, making `estimated_secs` a lower bound
+    pub fn is_partial(&self) -> bool {
+        self.phases
+            .iter()
+            .any(|phase| phase.estimated_secs.is_none())
+    }
+}
+
+/// Scale of the first `auto_scale` calibration pass; the second runs at twice this scale
+const CALIBRATION_SCALE: f64 = 0.05;
+/// Bounds of a calibrated scale, so that one noisy pass cannot choose an absurd workload
+const MIN_CALIBRATED_SCALE: f64 = 0.01;
+const MAX_CALIBRATED_SCALE: f64 = 50.0;
+
+/// Target seconds per kernel pass of `--auto-scale` without a value
+pub const DEFAULT_AUTO_SCALE_SECS: f64 = 3.0;
+
+/// Median time of one pass (round) over the kernels of a result
+fn median_pass_secs(timings: &KernelTimings) -> Option<f64> {
+    let secs: Vec<f64> = timings
+        .values()
+        .filter(|timing| timing.rounds > 0 && timing.elapsed_secs > 0.0)
+        .map(|timing| timing.elapsed_secs / timing.rounds as f64)
+        .collect();
+    Statistics::from_values(&secs).map(|stats| stats.p50)
+}
+
+/// Exponent k of a pass time modeled as proportional to scale^k, fitted from passes taking
+/// `small_secs` at some scale and `large_secs` at twice that scale (e.g. about 1 for
+/// streaming kernels, higher for the matrix kernels)
+/// Limited to 0.5..3 so that timer noise in tiny passes cannot dominate
+fn scaling_exponent(small_secs: f64, large_secs: f64) -> f64 {
+    let exponent = (large_secs / small_secs).log2();
+    if exponent.is_finite() {
+        exponent.clamp(0.5, 3.0)
+    } else {
+        1.0
+    }
+}
+
+/// Scale at which a pass is predicted to take `target_secs`, from passes taking
+/// `small_secs` at `scale` and `large_secs` at twice `scale`
+pub fn calibrated_scale(scale: f64, small_secs: f64, large_secs: f64, target_secs: f64) -> f64 {
+    let exponent = scaling_exponent(small_secs, large_secs);
+    let calibrated = scale * (target_secs / small_secs).powf(1.0 / exponent);
+    if calibrated.is_finite() {
+        calibrated.clamp(MIN_CALIBRATED_SCALE, MAX_CALIBRATED_SCALE)
+    } else {
+        scale
+    }
+}
+
+/// Seconds a pass is predicted to take at `target_scale`, from passes taking `small_secs` at
+/// `scale` and `large_secs` at twice `scale`
+pub fn predicted_secs(scale: f64, small_secs: f64, large_secs: f64, target_scale: f64) -> f64 {
+    let exponent = scaling_exponent(small_secs, large_secs);
+    small_secs * (target_scale / scale).powf(exponent)
+}
+
+/// Seconds one pass of a phase is predicted to take at `scale`, from its calibration passes
+/// (kernel timings and wall time) at `CALIBRATION_SCALE` and twice that
+/// Each kernel is extrapolated from its own time per round and repeated for at least
+/// `min_time`, like the real run; the setup and warmups around the kernels are extrapolated
+/// from the rest of the wall time
+fn predicted_phase_secs(
+    small: &(KernelTimings, f64),
+    large: &(KernelTimings, f64),
+    scale: f64,
+    min_time: f64,
+) -> f64 {
+    let round_secs =
+        |timing: &timing::KernelTiming| timing.elapsed_secs / timing.rounds.max(1) as f64;
+    let min_secs = min_time.max(timing::MIN_MEASURABLE_SECS);
+    let kernels: f64 = small
+        .0
+        .iter()
+        .filter_map(|(name, timing)| {
+            let predicted = predicted_secs(
+                CALIBRATION_SCALE,
+                round_secs(timing),
+                round_secs(large.0.get(name)?),
+                scale,
+            );
+            Some(predicted.max(min_secs))
+        })
+        .sum();
+    let overhead = |(timings, wall): &(KernelTimings, f64)| {
+        let measured: f64 = timings.values().map(|timing| timing.elapsed_secs).sum();
+        (wall - measured).max(0.0)
+    };
+    kernels + predicted_secs(CALIBRATION_SCALE, overhead(small), overhead(large), scale)
+}
+
+/// One pass of `phase` at `scale` without a minimum time, for calibration and estimates
+/// Returns: the kernel timings and the wall time of the pass in seconds, or None for the
+/// network and GPU phases and when the benchmark fails
+fn calibration_pass(
+    config: &SuiteConfig,
+    phase: Phase,
+    scale: f64,
+    warmup_runs: usize,
+    disk_profiles: &[DiskProfile],
+) -> Option<(KernelTimings, f64)> {
+    let cores = config.pinned_cores();
+    let start = Instant::now();
+    let timings = match phase {
+        Phase::Cpu => {
+            cpu::run_cpu_benchmark_with_progress(
+                scale,
+                config.threads,
+                0.0,
+                warmup_runs,
+                &cores,
+                &config.kernels,
+                false,
+                &mut (),
+            )
+            .ok()?
+            .timings
+        }
+        Phase::Memory => {
+            memory::run_memory_benchmark_with_progress(
+                scale,
+                config.memory_threads,
+                0.0,
+                config.memory_prefault,
+                warmup_runs,
+                &cores,
+                &mut (),
+            )
+            .ok()?
+            .timings
+        }
+        Phase::Concurrency => {
+            concurrency::run_concurrency_benchmark_with_progress(
+                scale,
+                config.threads,
+                0.0,
+                &cores,
+                &mut (),
+            )
+            .ok()?
+            .timings
+        }
+        Phase::Disk => {
+            disk::run_disk_benchmark_with_progress(
+                scale,
+                config.block_size,
+                config.disk_dir(),
+                config.disk_mode,
+                None,
+                disk_profiles,
+                0.0,
+                warmup_runs,
+                &mut (),
+            )
+            .ok()?
+            .timings
+        }
+        Phase::Network | Phase::Gpu => return None,
+    };
+    Some((timings, start.elapsed().as_secs_f64()))
+}
+
+/// Receives progress notifications while the suite is running
+/// All methods have empty default implementations so observers only
+/// override the events they care about
+pub trait SuiteObserver {
+    /// `--auto-scale` measures `phase` to choose its scale
+    fn calibration_started(&mut self, _phase: Phase) {}
+    fn run_started(&mut self, _run: usize, _total_runs: usize) {}
+    /// All phases of a run finished; `load` tells whether other processes disturbed it
+    fn run_finished(&mut self, _run: usize, _load: &BackgroundLoad) {}
+    fn phase_started(&mut self, _phase: Phase) {}
+    /// The suite idles for `duration` before `phase` starts
+    fn cooldown_started(&mut self, _phase: Phase, _duration: Duration) {}
+    /// A step of the running phase started, e.g. "STREAM" during the memory phase
+    fn step_started(&mut self, _phase: Phase, _step: &str) {}
+    /// A step of `phase` exceeded the step timeout `limit` and was cut short
+    fn step_timed_out(&mut self, _phase: Phase, _step: &str, _limit: Duration) {}
+    fn cpu_finished(&mut self, _result: &CpuResult, _elapsed: Duration) {}
+    fn memory_finished(&mut self, _result: &MemoryResult, _elapsed: Duration) {}
+    fn concurrency_finished(&mut self, _result: &ConcurrencyResult, _elapsed: Duration) {}
+    fn disk_finished(&mut self, _result: &DiskResult, _elapsed: Duration) {}
+    fn network_finished(&mut self, _result: &NetworkResult, _elapsed: Duration) {}
+    fn gpu_finished(&mut self, _result: &GpuResult, _elapsed: Duration) {}
+    /// A phase produced no result; nothing is recorded for it in this run
+    fn phase_failed(&mut self, _phase: Phase, _error: &BenchError) {}
+}
+
+/// Observer that ignores all events
+impl SuiteObserver for () {}
+
+/// Forwards the benchmark steps of a phase run on this thread to a callback
+struct StepCallback<F>(F);
+
+impl<F: FnMut(&str)> Progress for StepCallback<F> {
+    fn step(&mut self, name: &str) {
+        (self.0)(name);
+    }
+}
+
+/// Run `phase`, calling `on_step` with each of its steps
+/// With a watchdog, the phase runs on a worker thread that is abandoned once a step stops
+/// returning (see `Watchdog::run`), which fails the phase; without one, it runs on this thread
+/// Returns: the phase's result and the steps that exceeded the step timeout
+fn watch_phase<T: Send + 'static>(
+    watchdog: Option<&Watchdog>,
+    phase: impl FnOnce(&mut dyn Progress) -> Result<T, BenchError> + Send + 'static,
+    on_step: impl FnMut(&str),
+) -> (Result<T, BenchError>, Vec<String>) {
+    match watchdog {
+        Some(watchdog) => {
+            let watched = watchdog.run(phase, on_step);
+            let result = watched.result.unwrap_or_else(|| {
+                let step = watched.timed_out.last().cloned().unwrap_or_default();
+                Err(BenchError::TimedOut(step))
+            });
+            (result, watched.timed_out)
+        }
+        None => (phase(&mut StepCallback(on_step)), Vec::new()),
+    }
+}
+
+/// CPU phase of one run
+fn run_cpu_phase(
+    config: &SuiteConfig,
+    cores: &[usize],
+    progress: &mut dyn Progress,
+) -> Result<CpuResult, BenchError> {
+    cpu::run_cpu_benchmark_with_progress(
+        config.scale_for(Phase::Cpu),
+        config.threads,
+        config.min_time.unwrap_or(0.0),
+        config.warmup_runs,
+        cores,
+        &config.kernels,
+        config.verify,
+        progress,
+    )
+}
+
+/// Memory phase of one run: the bandwidth benchmark followed by the cache probe and the
+/// enabled scaling, access pattern, huge page, and NUMA passes
+fn run_memory_phase(
+    config: &SuiteConfig,
+    cores: &[usize],
+    progress: &mut dyn Progress,
+) -> Result<MemoryResult, BenchError> {
+    let min_time = config.min_time.unwrap_or(0.0);
+    let mut result = memory::run_memory_benchmark_with_progress(
+        config.scale_for(Phase::Memory),
+        config.memory_threads,
+        min_time,
+        config.memory_prefault,
+        config.warmup_runs,
+        cores,
+        progress,
+    )?;
+    progress.step("cache probe");
+    result.cache_probe = {
+        let _pin = affinity::PinGuard::pin(cores);
+        memory::run_cache_probe(&result.footprint)
+    };
+    if config.memory_scaling {
+        progress.step("thread scaling");
+        result.thread_scaling = memory::run_memory_scaling_sweep(
+            result.footprint.scale,
+            config.memory_threads,
+            config.memory_prefault,
+            cores,
+        );
+    }
+    if !config.memory_patterns.is_empty() {
+        progress.step("access patterns");
+        let _pin = affinity::PinGuard::pin(cores);
+        result.access_patterns = memory::run_access_pattern_benchmark(
+            result.footprint.scale,
+            &config.memory_patterns,
+            min_time,
+        );
+    }
+    if config.memory_huge_pages {
+        progress.step("huge pages");
+        result.huge_pages = memory::run_huge_page_benchmark(
+            result.footprint.scale,
+            config.memory_threads,
+            min_time,
+            config.memory_prefault,
+            cores,
+        );
+    }
+    if config.memory_numa {
+        progress.step("NUMA");
+        result.numa = memory::run_numa_benchmark(
+            result.footprint.scale,
+            &sysinfo_capture::detect_numa_nodes(),
+        );
+    }
+    Ok(result)
+}
+
+/// Disk phase of one run
+fn run_disk_phase(
+    config: &SuiteConfig,
+    progress: &mut dyn Progress,
+) -> Result<DiskResult, BenchError> {
+    let mut result = disk::run_disk_benchmark_with_progress(
+        config.scale_for(Phase::Disk),
+        config.block_size,
+        config.disk_dir(),
+        config.disk_mode,
+        config.disk_precondition,
+        &config.disk_profiles,
+        config.min_time.unwrap_or(0.0),
+        config.warmup_runs,
+        progress,
+    )?;
+    if config.disk_cache_compare {
+        progress.step("cached");
+        result.cached = Some(disk::run_cached_disk_benchmark(
+            config.scale_for(Phase::Disk),
+            config.block_size,
+            config.disk_dir(),
+            config.min_time.unwrap_or(0.0),
+        )?);
+    }
+    Ok(result)
+}
+
+/// Run `phase` on its own thread, timing it
+fn spawn_phase<'scope, T: Send + 'scope>(
+    scope: &'scope std::thread::Scope<'scope, '_>,
+    enabled: bool,
+    phase: impl FnOnce() -> T + Send + 'scope,
+) -> Option<std::thread::ScopedJoinHandle<'scope, (T, Duration)>> {
+    enabled.then(|| {
+        scope.spawn(move || {
+            let start = Instant::now();
+            (phase(), start.elapsed())
+        })
+    })
+}
+
+/// Wait for a phase started by `spawn_phase`, passing on its panic
+fn join_phase<T>(
+    handle: Option<std::thread::ScopedJoinHandle<'_, (T, Duration)>>,
+) -> Option<(T, Duration)> {
+    handle.map(|handle| {
+        handle
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Configured benchmark suite, created through `BenchmarkSuite::builder()`
+pub struct BenchmarkSuite {
+    config: SuiteConfig,
+}
+
+impl BenchmarkSuite {
+    pub fn builder() -> BenchmarkSuiteBuilder {
+        BenchmarkSuiteBuilder::default()
+    }
+
+    pub fn config(&self) -> &SuiteConfig {
+        &self.config
+    }
+
+    /// Run all enabled benchmarks without progress reporting
+    pub fn run(&self) -> SuiteResults {
+        self.run_with_observer(&mut ())
+    }
+
+    /// Run all enabled benchmarks, notifying `observer` as each phase completes
+    /// Once `interrupt::interrupted()` is set, the suite stops after the running phase and
+    /// returns the results measured so far with `interrupted` set
+    /// With `auto_scale` set, the CPU, memory, and disk scales are calibrated first, and
+    /// the results carry the calibrated configuration
+    pub fn run_with_observer<O: SuiteObserver + ?Sized>(&self, observer: &mut O) -> SuiteResults {
+        self.apply_seed();
+        let config = &self.calibrated_config(observer);
+        let mut results = SuiteResults {
+            config: config.clone(),
+            cpu: Vec::new(),
+            memory: Vec::new(),
+            concurrency: Vec::new(),
+            disk: Vec::new(),
+            network: Vec::new(),
+            gpu: Vec::new(),
+            disk_target: config.disk.then(|| DiskTarget::describe(config.disk_dir())),
+            background_load: Vec::new(),
+            cooldowns: Vec::new(),
+            timed_out: Vec::new(),
+            interrupted: false,
+        };
+        // Phase that finished last, after which the next phase cools down
+        let mut last_phase = None;
+
+        let min_time = config.min_time.unwrap_or(0.0);
+        let watchdog = config.step_timeout().map(Watchdog::new);
+        // Phases own what they use, since a timed-out phase may outlive this call
+        let cores: Arc<[usize]> = config.pinned_cores().into();
+        let shared = Arc::new(config.clone());
+        // Calibrate the clock now rather than in the middle of the first timed kernel
+        timing::calibration();
+
+        for run in 1..=config.runs {
+            observer.run_started(run, config.runs);
+            let load_monitor = LoadMonitor::start();
+            let kept = results.lengths();
+
+            if config.schedule == Schedule::Parallel {
+                self.run_parallel_phases(
+                    &shared,
+                    run,
+                    &cores,
+                    watchdog.as_ref(),
+                    &mut last_phase,
+                    &mut results,
+                    observer,
+                );
+            } else {
+                if config.cpu
+                    && !interrupt::interrupted()
+                    && self.cool_down(Phase::Cpu, run, &mut last_phase, &mut results, observer)
+                {
+                    observer.phase_started(Phase::Cpu);
+                    let start = Instant::now();
+                    let (config, cores) = (Arc::clone(&shared), Arc::clone(&cores));
+                    let (result, timed_out) = watch_phase(
+                        watchdog.as_ref(),
+                        move |progress| run_cpu_phase(&config, &cores, progress),
+                        |step| observer.step_started(Phase::Cpu, step),
+                    );
+                    match result {
+                        Ok(result) => {
+                            observer.cpu_finished(&result, start.elapsed());
+                            results.cpu.push(result);
+                        }
+                        Err(e) => observer.phase_failed(Phase::Cpu, &e),
+                    }
+                    self.collect_timeouts(
+                        watchdog.as_ref(),
+                        Phase::Cpu,
+                        run,
+                        timed_out,
+                        &mut results,
+                        observer,
+                    );
+                }
+
+                if config.memory
+                    && !interrupt::interrupted()
+                    && self.cool_down(Phase::Memory, run, &mut last_phase, &mut results, observer)
+                {
+                    observer.phase_started(Phase::Memory);
+                    let start = Instant::now();
+                    let (config, cores) = (Arc::clone(&shared), Arc::clone(&cores));
+                    let (result, timed_out) = watch_phase(
+                        watchdog.as_ref(),
+                        move |progress| run_memory_phase(&config, &cores, progress),
+                        |step| observer.step_started(Phase::Memory, step),
+                    );
+                    match result {
+                        Ok(result) => {
+                            observer.memory_finished(&result, start.elapsed());
+                            results.memory.push(result);
+                        }
+                        Err(e) => observer.phase_failed(Phase::Memory, &e),
+                    }
+                    self.collect_timeouts(
+                        watchdog.as_ref(),
+                        Phase::Memory,
+                        run,
+                        timed_out,
+                        &mut results,
+                        observer,
+                    );
+                }
+            }
+
+            if config.concurrency
+                && !interrupt::interrupted()
+                && self.cool_down(
+                    Phase::Concurrency,
+                    run,
+                    &mut last_phase,
+                    &mut results,
+                    observer,
+                )
+            {
+                observer.phase_started(Phase::Concurrency);
+                let start = Instant::now();
+                let (config, cores) = (Arc::clone(&shared), Arc::clone(&cores));
+                let (result, timed_out) = watch_phase(
+                    watchdog.as_ref(),
+                    move |progress| {
+                        let mut result = concurrency::run_concurrency_benchmark_with_progress(
+                            config.scale,
+                            config.threads,
+                            min_time,
+                            &cores,
+                            progress,
+                        )?;
+                        if config.core_latency {
+                            progress.step("core latency");
+                            result.core_latency =
+                                concurrency::run_core_latency_benchmark(config.scale, &cores);
+                        }
+                        Ok(result)
+                    },
+                    |step| observer.step_started(Phase::Concurrency, step),
+                );
+                match result {
+                    Ok(result) => {
+                        observer.concurrency_finished(&result, start.elapsed());
+                        results.concurrency.push(result);
+                    }
+                    Err(e) => observer.phase_failed(Phase::Concurrency, &e),
+                }
+                self.collect_timeouts(
+                    watchdog.as_ref(),
+                    Phase::Concurrency,
+                    run,
+                    timed_out,
+                    &mut results,
+                    observer,
+                );
+            }
+
+            if config.disk
+                && config.schedule != Schedule::Parallel
+                && !interrupt::interrupted()
+                && self.cool_down(Phase::Disk, run, &mut last_phase, &mut results, observer)
+            {
+                observer.phase_started(Phase::Disk);
+                let start = Instant::now();
+                let config = Arc::clone(&shared);
+                let (result, timed_out) = watch_phase(
+                    watchdog.as_ref(),
+                    move |progress| run_disk_phase(&config, progress),
+                    |step| observer.step_started(Phase::Disk, step),
+                );
+                match result {
+                    Ok(result) => {
+                        observer.disk_finished(&result, start.elapsed());
+                        results.disk.push(result);
+                    }
+                    Err(e) => observer.phase_failed(Phase::Disk, &e),
+                }
+                self.collect_timeouts(
+                    watchdog.as_ref(),
+                    Phase::Disk,
+                    run,
+                    timed_out,
+                    &mut results,
+                    observer,
+                );
+            }
+
+            if config.network
+                && !interrupt::interrupted()
+                && self.cool_down(Phase::Network, run, &mut last_phase, &mut results, observer)
+            {
+                observer.phase_started(Phase::Network);
+                let start = Instant::now();
+                let config = Arc::clone(&shared);
+                let (result, timed_out) = watch_phase(
+                    watchdog.as_ref(),
+                    move |progress| {
+                        network::run_network_benchmark_with_progress(
+                            config.scale,
+                            config.network_target.as_deref(),
+                            config.warmup_runs,
+                            progress,
+                        )
+                    },
+                    |step| observer.step_started(Phase::Network, step),
+                );
+                match result {
+                    Ok(result) => {
+                        observer.network_finished(&result, start.elapsed());
+                        results.network.push(result);
+                    }
+                    Err(e) => observer.phase_failed(Phase::Network, &e),
+                }
+                self.collect_timeouts(
+                    watchdog.as_ref(),
+                    Phase::Network,
+                    run,
+                    timed_out,
+                    &mut results,
+                    observer,
+                );
+            }
+
+            if config.gpu
+                && !interrupt::interrupted()
+                && self.cool_down(Phase::Gpu, run, &mut last_phase, &mut results, observer)
+            {
+                observer.phase_started(Phase::Gpu);
+                let start = Instant::now();
+                let scale = config.scale;
+                let (result, timed_out) = watch_phase(
+                    watchdog.as_ref(),
+                    move |progress| gpu::run_gpu_benchmark_with_progress(scale, progress),
+                    |step| observer.step_started(Phase::Gpu, step),
+                );
+                match result {
+                    Ok(result) => {
+                        observer.gpu_finished(&result, start.elapsed());
+                        results.gpu.push(result);
+                    }
+                    Err(e) => observer.phase_failed(Phase::Gpu, &e),
+                }
+                self.collect_timeouts(
+                    watchdog.as_ref(),
+                    Phase::Gpu,
+                    run,
+                    timed_out,
+                    &mut results,
+                    observer,
+                );
+            }
+
+            let mut load = load_monitor.finish(config.load_threshold_percent);
+            if load.contaminated && config.exclude_contaminated {
+                results.truncate(kept);
+                load.excluded = true;
+            }
+            observer.run_finished(run, &load);
+            results.background_load.push(load);
+            if interrupt::interrupted() {
+                results.interrupted = true;
+                break;
+            }
+        }
+
+        results
+    }
+
+    /// Resolve what a run would do without running it: the scales (calibrated first with
+    /// `auto_scale` set), the memory and disk footprint, and the duration of each phase,
+    /// predicted from two quick passes at `CALIBRATION_SCALE` and twice that
+    pub fn plan<O: SuiteObserver + ?Sized>(&self, observer: &mut O) -> SuitePlan {
+        self.apply_seed();
+        let config = self.calibrated_config(observer);
+        let memory_footprint = config.memory.then(|| {
+            MemoryFootprint::plan(
+                config.scale_for(Phase::Memory),
+                config.memory_threads,
+                sysinfo_capture::available_memory_bytes(),
+            )
+        });
+        let min_time = config.min_time.unwrap_or(0.0);
+
+        let phases = config
+            .phases()
+            .into_iter()
+            .map(|phase| {
+                let scale = match (phase, &memory_footprint) {
+                    (Phase::Memory, Some(footprint)) => footprint.scale,
+                    _ => config.scale_for(phase),
+                };
+                let pass = |scale: f64| {
+                    calibration_pass(
+                        &config,
+                        phase,
+                        scale,
+                        config.warmup_runs,
+                        &config.disk_profiles,
+                    )
+                };
+                let estimated_secs = pass(CALIBRATION_SCALE).and_then(|small| {
+                    let large = pass(CALIBRATION_SCALE * 2.0)?;
+                    Some(predicted_phase_secs(&small, &large, scale, min_time))
+                });
+                PhasePlan {
+                    phase,
+                    scale,
+                    estimated_secs,
+                }
+            })
+            .collect();
+
+        SuitePlan {
+            memory_footprint,
+            disk_target: config.disk.then(|| DiskTarget::describe(config.disk_dir())),
+            disk_bytes: config.disk.then(|| {
+                disk::required_space(config.scale_for(Phase::Disk), config.disk_precondition)
+            }),
+            phases,
+            config,
+        }
+    }
+
+    /// Make the configured seed the process-wide workload seed; without one, a seed set
+    /// directly with `rng::set_seed` is left alone
+    fn apply_seed(&self) {
+        if self.config.seed.is_some() {
+            rng::set_seed(self.config.seed);
+        }
+    }
+
+    /// The configuration with the scales `auto_scale` calibrates filled in: each enabled
+    /// CPU, memory, and disk benchmark without its own scale runs once at
+    /// `CALIBRATION_SCALE` and once at twice that, and gets the scale at which the median
+    /// kernel pass is predicted to take `auto_scale` seconds
+    /// A benchmark that fails to calibrate keeps the global scale
+    fn calibrated_config<O: SuiteObserver + ?Sized>(&self, observer: &mut O) -> SuiteConfig {
+        let mut config = self.config.clone();
+        let Some(target_secs) = config.auto_scale else {
+            return config;
+        };
+        let phases = [
+            (Phase::Cpu, config.cpu && config.cpu_scale.is_none()),
+            (
+                Phase::Memory,
+                config.memory && config.memory_scale.is_none(),
+            ),
+            (Phase::Disk, config.disk && config.disk_scale.is_none()),
+        ];
+        for (phase, calibrate) in phases {
+            if !calibrate || interrupt::interrupted() {
+                continue;
+            }
+            observer.calibration_started(phase);
+            let pass_secs = |scale: f64| -> Option<f64> {
+                let (timings, _) = calibration_pass(&config, phase, scale, 0, &[])?;
+                median_pass_secs(&timings)
+            };
+            let Some(small) = pass_secs(CALIBRATION_SCALE) else {
+                continue;
+            };
+            let Some(large) = pass_secs(CALIBRATION_SCALE * 2.0) else {
+                continue;
+            };
+            let mut scale = calibrated_scale(CALIBRATION_SCALE, small, large, target_secs);
+            // A disk test file larger than the free space would fail the whole phase
+            while phase == Phase::Disk
+                && scale > MIN_CALIBRATED_SCALE
+                && disk::preflight(config.disk_dir(), scale, config.disk_precondition).is_err()
+            {
+                scale = (scale / 2.0).max(MIN_CALIBRATED_SCALE);
+            }
+            let scale = Some(scale);
+            match phase {
+                Phase::Cpu => config.cpu_scale = scale,
+                Phase::Memory => config.memory_scale = scale,
+                _ => config.disk_scale = scale,
+            }
+        }
+        config
+    }
+
+    /// Run the enabled CPU, memory, and disk phases of `run` at the same time, as one
+    /// combined load, and report their results in phase order once all have finished
+    /// The phases report no steps while they run; each has its steps timed on its own
+    #[allow(clippy::too_many_arguments)]
+    fn run_parallel_phases<O: SuiteObserver + ?Sized>(
+        &self,
+        config: &Arc<SuiteConfig>,
+        run: usize,
+        cores: &Arc<[usize]>,
+        watchdog: Option<&Watchdog>,
+        last_phase: &mut Option<Phase>,
+        results: &mut SuiteResults,
+        observer: &mut O,
+    ) {
+        let phases: Vec<Phase> = [
+            (Phase::Cpu, config.cpu),
+            (Phase::Memory, config.memory),
+            (Phase::Disk, config.disk),
+        ]
+        .into_iter()
+        .filter_map(|(phase, enabled)| enabled.then_some(phase))
+        .collect();
+        let Some(&first) = phases.first() else {
+            return;
+        };
+        if interrupt::interrupted() || !self.cool_down(first, run, last_phase, results, observer) {
+            return;
+        }
+        for &phase in &phases {
+            observer.phase_started(phase);
+        }
+        let (cpu, memory, disk) = std::thread::scope(|scope| {
+            let cpu = spawn_phase(scope, config.cpu, || {
+                let (config, cores) = (Arc::clone(config), Arc::clone(cores));
+                watch_phase(
+                    watchdog,
+                    move |progress| run_cpu_phase(&config, &cores, progress),
+                    |_| {},
+                )
+            });
+            let memory = spawn_phase(scope, config.memory, || {
+                let (config, cores) = (Arc::clone(config), Arc::clone(cores));
+                watch_phase(
+                    watchdog,
+                    move |progress| run_memory_phase(&config, &cores, progress),
+                    |_| {},
+                )
+            });
+            let disk = spawn_phase(scope, config.disk, || {
+                let config = Arc::clone(config);
+                watch_phase(
+                    watchdog,
+                    move |progress| run_disk_phase(&config, progress),
+                    |_| {},
+                )
+            });
+            (join_phase(cpu), join_phase(memory), join_phase(disk))
+        });
+        *last_phase = phases.last().copied();
+
+        if let Some(((result, timed_out), elapsed)) = cpu {
+            match result {
+                Ok(result) => {
+                    observer.cpu_finished(&result, elapsed);
+                    results.cpu.push(result);
+                }
+                Err(e) => observer.phase_failed(Phase::Cpu, &e),
+            }
+            self.collect_timeouts(watchdog, Phase::Cpu, run, timed_out, results, observer);
+        }
+        if let Some(((result, timed_out), elapsed)) = memory {
+            match result {
+                Ok(result) => {
+                    observer.memory_finished(&result, elapsed);
+                    results.memory.push(result);
+                }
+                Err(e) => observer.phase_failed(Phase::Memory, &e),
+            }
+            self.collect_timeouts(watchdog, Phase::Memory, run, timed_out, results, observer);
+        }
+        if let Some(((result, timed_out), elapsed)) = disk {
+            match result {
+                Ok(result) => {
+                    observer.disk_finished(&result, elapsed);
+                    results.disk.push(result);
+                }
+                Err(e) => observer.phase_failed(Phase::Disk, &e),
+            }
+            self.collect_timeouts(watchdog, Phase::Disk, run, timed_out, results, observer);
+        }
+    }
+
+    /// Record and report the `timed_out` steps of `phase`
+    fn collect_timeouts<O: SuiteObserver + ?Sized>(
+        &self,
+        watchdog: Option<&Watchdog>,
+        phase: Phase,
+        run: usize,
+        timed_out: Vec<String>,
+        results: &mut SuiteResults,
+        observer: &mut O,
+    ) {
+        let Some(watchdog) = watchdog else {
+            return;
+        };
+        for step in timed_out {
+            observer.step_timed_out(phase, &step, watchdog.limit());
+            results.timed_out.push(TimedOutStep {
+                run,
+                phase: phase.name().to_string(),
+                step,
+                limit_secs: watchdog.limit().as_secs_f64(),
+            });
+        }
+    }
+
+    /// Idle for the configured cooldown before `phase` unless it is the first phase of
+    /// the suite, recording the idle period
+    /// Returns: whether `phase` should still run, i.e. the cooldown was not interrupted
+    fn cool_down<O: SuiteObserver + ?Sized>(
+        &self,
+        phase: Phase,
+        run: usize,
+        last_phase: &mut Option<Phase>,
+        results: &mut SuiteResults,
+        observer: &mut O,
+    ) -> bool {
+        let previous = last_phase.replace(phase);
+        let gap = self.config.phase_gap();
+        let Some(previous) = previous.filter(|_| gap > 0.0) else {
+            return true;
+        };
+        let duration = Duration::from_secs_f64(gap);
+        observer.cooldown_started(phase, duration);
+        let start = Instant::now();
+        let rested = interrupt::sleep(duration);
+        results.cooldowns.push(Cooldown {
+            run,
+            after: previous.name().to_string(),
+            before: phase.name().to_string(),
+            seconds: start.elapsed().as_secs_f64(),
+        });
+        rested
+    }
+}
+
+/// Builder for `BenchmarkSuite`
+/// Invalid values are replaced with the defaults when `build()` is called
+#[derive(Default)]
+pub struct BenchmarkSuiteBuilder {
+    config: SuiteConfig,
+}
+
+impl BenchmarkSuiteBuilder {
+    /// Scale factor for benchmark intensity (default: 1.0)
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.config.scale = scale;
+        self
+    }
+
+    /// Scale of the CPU benchmark, overriding `scale` (default: `scale`)
+    pub fn cpu_scale(mut self, scale: f64) -> Self {
+        self.config.cpu_scale = Some(scale);
+        self
+    }
+
+    /// Calibrate the CPU, memory, and disk scales before the first run so that the median
+    /// kernel pass takes about `target_secs` on this machine (default: off)
+    /// Scales set explicitly with `cpu_scale`, `memory_scale`, or `disk_scale` are kept
+    pub fn auto_scale(mut self, target_secs: f64) -> Self {
+        self.config.auto_scale = Some(target_secs);
+        self
+    }
+
+    /// Scale of the memory benchmark, overriding `scale` (default: `scale`)
+    pub fn memory_scale(mut self, scale: f64) -> Self {
+        self.config.memory_scale = Some(scale);
+        self
+    }
+
+    /// Scale of the disk benchmark, overriding `scale` (default: `scale`)
+    pub fn disk_scale(mut self, scale: f64) -> Self {
+        self.config.disk_scale = Some(scale);
+        self
+    }
+
+    /// Repeat each CPU, memory, and disk kernel for at least `seconds` instead of running
+    /// the scaled workload once, so runtime no longer depends on how fast the machine is
+    pub fn min_time(mut self, seconds: f64) -> Self {
+        self.config.min_time = Some(seconds);
+        self
+    }
+
+    /// Untimed warmup passes before the CPU, memory, disk, and network kernels (default: 1)
+    /// 0 skips warmup for cold-cache studies; more passes give CPUs that ramp their clocks
+    /// slowly time to reach full speed
+    pub fn warmup_runs(mut self, runs: usize) -> Self {
+        self.config.warmup_runs = runs;
+        self
+    }
+
+    /// Seed the random inputs of every workload (default: the built-in seeds)
+    /// The seed is process-wide: running the suite passes it to `rng::set_seed`
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    /// Number of times each benchmark is run (default: 3)
+    pub fn runs(mut self, runs: usize) -> Self {
+        self.config.runs = runs;
+        self
+    }
+
+    /// Number of threads for the parallel CPU benchmarks and the largest concurrency
+    /// benchmark point (default: 4)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads;
+        self
+    }
+
+    /// Number of threads for the memory benchmark (default: 8)
+    pub fn memory_threads(mut self, threads: usize) -> Self {
+        self.config.memory_threads = threads;
+        self
+    }
+
+    /// Sweep the memory benchmark over 1, 2, 4, ... `memory_threads` threads (default: disabled)
+    pub fn memory_scaling(mut self, enabled: bool) -> Self {
+        self.config.memory_scaling = enabled;
+        self
+    }
+
+    /// Compare memory bandwidth and latency on the local and a remote NUMA node; does
+    /// nothing on single-node systems (default: disabled)
+    pub fn memory_numa(mut self, enabled: bool) -> Self {
+        self.config.memory_numa = enabled;
+        self
+    }
+
+    /// Also measure single-thread read bandwidth with these access patterns, e.g.
+    /// strided or random reads (default: none)
+    pub fn memory_patterns(mut self, patterns: Vec<AccessPattern>) -> Self {
+        self.config.memory_patterns = patterns;
+        self
+    }
+
+    /// Compare memory bandwidth with buffers on huge pages against regular pages; does
+    /// nothing where the OS provides no huge pages (default: disabled)
+    pub fn memory_huge_pages(mut self, enabled: bool) -> Self {
+        self.config.memory_huge_pages = enabled;
+        self
+    }
+
+    /// Fault the memory buffers in before the write and read passes are timed and reuse
+    /// them, so the passes measure resident bandwidth; otherwise every pass allocates
+    /// fresh buffers and also pays for their page faults (default: disabled)
+    pub fn memory_prefault(mut self, enabled: bool) -> Self {
+        self.config.memory_prefault = enabled;
+        self
+    }
+
+    /// Measure the one-way cache-line latency between every two cores (the pinned cores,
+    /// or all available ones) as part of the concurrency benchmark; needs thread pinning
+    /// (default: disabled)
+    pub fn core_latency(mut self, enabled: bool) -> Self {
+        self.config.core_latency = enabled;
+        self
+    }
+
+    /// Pin CPU, memory, and concurrency benchmark threads to cores (default: disabled)
+    pub fn pin_cores(mut self, enabled: bool) -> Self {
+        self.config.pin_cores = enabled;
+        self
+    }
+
+    /// Pin to these cores, in worker order; implies `pin_cores(true)`
+    pub fn core_list(mut self, cores: Vec<usize>) -> Self {
+        self.config.pin_cores = true;
+        self.config.core_list = cores;
+        self
+    }
+
+    /// Disk benchmark block size in bytes (default: 512 KB)
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.config.block_size = block_size;
+        self
+    }
+
+    /// Directory for the disk benchmark's temporary files (default: current directory)
+    pub fn disk_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.disk_path = Some(path.into());
+        self
+    }
+
+    /// Disk benchmark access mode (default: direct I/O)
+    pub fn disk_mode(mut self, mode: DiskMode) -> Self {
+        self.config.disk_mode = mode;
+        self
+    }
+
+    /// Write a dataset of `multiple` times the test file size before the sequential disk
+    /// passes and run at least `passes` write passes after it, reporting burst and
+    /// steady-state write throughput (default: off)
+    pub fn disk_precondition(mut self, multiple: f64, passes: usize) -> Self {
+        self.config.disk_precondition = Some(Precondition { multiple, passes });
+        self
+    }
+
+    /// Repeat the sequential disk passes through the OS cache and report the cached
+    /// throughput and its speedup over the uncached passes (default: off)
+    pub fn disk_cache_compare(mut self, enabled: bool) -> Self {
+        self.config.disk_cache_compare = enabled;
+        self
+    }
+
+    /// Mixed disk workloads to run, in order (default: none)
+    pub fn disk_profiles(mut self, profiles: Vec<DiskProfile>) -> Self {
+        self.config.disk_profiles = profiles;
+        self
+    }
+
+    /// Idle for `seconds` before every phase but the first so that thermally limited
+    /// machines recover between phases and runs (default: 0, back to back)
+    pub fn cooldown(mut self, seconds: f64) -> Self {
+        self.config.cooldown = seconds;
+        self
+    }
+
+    /// Run the phases one after the other, the CPU, memory, and disk phases at the same
+    /// time, or isolated by idle gaps (default: `Schedule::Serial`)
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.config.schedule = schedule;
+        self
+    }
+
+    /// Seconds every benchmark step may run before it is cut short and marked as timed
+    /// out; 0 disables the watchdog (default: derived from the scales, see
+    /// `SuiteConfig::step_timeout`)
+    pub fn timeout(mut self, seconds: f64) -> Self {
+        self.config.timeout = Some(seconds);
+        self
+    }
+
+    /// Enable or disable the CPU benchmark (default: enabled)
+    pub fn cpu(mut self, enabled: bool) -> Self {
+        self.config.cpu = enabled;
+        self
+    }
+
+    /// Run only the registry kernels with these names, e.g. `["fft", "parallel_fft"]`
+    /// (default: every kernel of the enabled phases)
+    pub fn kernels(mut self, names: Vec<String>) -> Self {
+        self.config.kernels = names;
+        self
+    }
+
+    /// Check the output of the CPU kernels against a reference after timing them, e.g. the
+    /// corners of a matrix product or an FFT round trip (default: disabled)
+    pub fn verify(mut self, enabled: bool) -> Self {
+        self.config.verify = enabled;
+        self
+    }
+
+    /// Enable or disable the memory benchmark (default: enabled)
+    pub fn memory(mut self, enabled: bool) -> Self {
+        self.config.memory = enabled;
+        self
+    }
+
+    /// Enable or disable the concurrency benchmark (default: enabled)
+    pub fn concurrency(mut self, enabled: bool) -> Self {
+        self.config.concurrency = enabled;
+        self
+    }
+
+    /// Enable or disable the disk benchmark (default: enabled)
+    pub fn disk(mut self, enabled: bool) -> Self {
+        self.config.disk = enabled;
+        self
+    }
+
+    /// Enable or disable the network benchmark (default: enabled)
+    pub fn network(mut self, enabled: bool) -> Self {
+        self.config.network = enabled;
+        self
+    }
+
+    /// Benchmark a remote `host:port` running `--network-server` instead of loopback
+    pub fn network_target(mut self, target: impl Into<String>) -> Self {
+        self.config.network_target = Some(target.into());
+        self
+    }
+
+    /// Enable or disable the GPU benchmark (default: enabled with the `gpu` feature)
+    /// Without the feature, or without a usable GPU, every GPU run fails and is skipped
+    pub fn gpu(mut self, enabled: bool) -> Self {
+        self.config.gpu = enabled;
+        self
+    }
+
+    /// Share of the total CPU capacity, in percent, other processes may use before a run is
+    /// marked as contaminated
+    pub fn load_threshold(mut self, percent: f64) -> Self {
+        self.config.load_threshold_percent = percent;
+        self
+    }
+
+    /// Leave contaminated runs out of the results and statistics
+    pub fn exclude_contaminated(mut self, enabled: bool) -> Self {
+        self.config.exclude_contaminated = enabled;
+        self
+    }
+
+    pub fn build(self) -> BenchmarkSuite {
+        let defaults = SuiteConfig::default();
+        let mut config = self.config;
+
+        if config.scale <= 0.0 || !config.scale.is_finite() {
+            config.scale = defaults.scale;
+        }
+        for scale in [
+            &mut config.cpu_scale,
+            &mut config.memory_scale,
+            &mut config.disk_scale,
+            &mut config.auto_scale,
+        ] {
+            if scale.is_some_and(|scale| scale <= 0.0 || !scale.is_finite()) {
+                *scale = None;
+            }
+        }
+        if config
+            .min_time
+            .is_some_and(|seconds| seconds <= 0.0 || !seconds.is_finite())
+        {
+            config.min_time = defaults.min_time;
+        }
+        if config.runs == 0 {
+            config.runs = defaults.runs;
+        }
+        if config.threads == 0 {
+            config.threads = defaults.threads;
+        }
+        if config.memory_threads == 0 {
+            config.memory_threads = defaults.memory_threads;
+        }
+        if config.block_size == 0 {
+            config.block_size = defaults.block_size;
+        }
+        if config.load_threshold_percent < 0.0 || !config.load_threshold_percent.is_finite() {
+            config.load_threshold_percent = defaults.load_threshold_percent;
+        }
+        if config.cooldown < 0.0 || !config.cooldown.is_finite() {
+            config.cooldown = defaults.cooldown;
+        }
+        if config
+            .timeout
+            .is_some_and(|secs| secs < 0.0 || !secs.is_finite())
+        {
+            config.timeout = defaults.timeout;
+        }
+
+        BenchmarkSuite { config }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thermal::{ThermalResult, ThermalSample};
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        runs: Vec<usize>,
+        phases: Vec<Phase>,
+        steps: Vec<(Phase, String)>,
+        cpu_results: usize,
+        failures: Vec<Phase>,
+    }
+
+    impl SuiteObserver for RecordingObserver {
+        fn run_started(&mut self, run: usize, _total_runs: usize) {
+            self.runs.push(run);
+        }
+
+        fn phase_started(&mut self, phase: Phase) {
+            self.phases.push(phase);
+        }
+
+        fn step_started(&mut self, phase: Phase, step: &str) {
+            self.steps.push((phase, step.to_string()));
+        }
+
+        fn cpu_finished(&mut self, _result: &CpuResult, _elapsed: Duration) {
+            self.cpu_results += 1;
+        }
+
+        fn phase_failed(&mut self, phase: Phase, _error: &BenchError) {
+            self.failures.push(phase);
+        }
+    }
+
+    #[test]
+    fn test_throttling_warning_lists_throttled_runs() {
+        let at = |frequency_mhz| ThermalSample {
+            frequency_mhz: Some(frequency_mhz),
+            temperature_c: None,
+        };
+        let cpu = |late_mhz| CpuResult {
+            thermal: ThermalResult::from_samples(
+                at(4000.0),
+                &[at(4000.0), at(late_mhz), at(late_mhz)],
+                at(late_mhz),
+            ),
+            ..CpuResult::default()
+        };
+        let mut results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        assert!(results.throttling_warning().is_none());
+
+        results.cpu = vec![cpu(3950.0), cpu(3000.0)];
+        let warning = results.throttling_warning().unwrap();
+        assert!(
+            warning.contains("run 2 (3000 MHz sustained, 25% below the 4000 MHz peak)"),
+            "{}",
+            warning
+        );
+        assert!(!warning.contains("run 1"));
+
+        assert!(results.interruption_warning().is_none());
+        results.config.runs = 3;
+        results.background_load = vec![BackgroundLoad::default(); 2];
+        results.interrupted = true;
+        let warning = results.interruption_warning().unwrap();
+        assert!(warning.contains("during run 2 of 3"), "{}", warning);
+
+        assert!(results.timeout_warning().is_none());
+        results.timed_out = vec![TimedOutStep {
+            run: 2,
+            phase: "Disk".to_string(),
+            step: "sequential write".to_string(),
+            limit_secs: 600.0,
+        }];
+        let warning = results.timeout_warning().unwrap();
+        assert!(
+            warning.contains("600 s timeout and were cut short: Disk sequential write in run 2"),
+            "{}",
+            warning
+        );
+    }
+
+    #[test]
+    fn test_verification_failures_are_reported() {
+        let check = |kernel: &str, passed| Verification {
+            kernel: kernel.to_string(),
+            passed,
+            detail: "detail".to_string(),
+        };
+        let mut results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![CpuResult::default(), CpuResult::default()],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        assert!(results.verification_summary().is_none());
+        assert!(results.verification_warning().is_none());
+
+        results.cpu[0].verification = vec![check("fft", true), check("sieve", true)];
+        results.cpu[1].verification = vec![check("fft", false), check("sieve", true)];
+        assert_eq!(
+            results.verification_summary().unwrap(),
+            "3 of 4 kernel checks passed in 2 runs"
+        );
+        let warning = results.verification_warning().unwrap();
+        assert!(warning.contains("fft in run 2 (detail)"), "{}", warning);
+        assert!(!warning.contains("sieve"));
+    }
+
+    #[test]
+    fn test_unhonored_direct_io_is_reported() {
+        // Reports written before the effective I/O was recorded leave it out
+        let disk = |io_mode: &str| -> DiskResult {
+            serde_json::from_str(&format!(
+                "{{\"write_throughput\": 1.0, \"read_throughput\": 1.0, \
+                 \"combined_throughput\": 1.0{}}}",
+                io_mode
+            ))
+            .unwrap()
+        };
+        let mut results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![disk(""), disk(", \"io_mode\": \"direct\"")],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        assert_eq!(results.disk[0].io_mode, None);
+        assert!(results.direct_io_warning().is_none());
+
+        let mut mapped = disk(", \"io_mode\": \"buffered\"");
+        mapped.mode = DiskMode::Mmap;
+        results.disk.push(mapped);
+        assert!(results.direct_io_warning().is_none());
+
+        results.disk.push(disk(", \"io_mode\": \"buffered\""));
+        let warning = results.direct_io_warning().unwrap();
+        assert!(
+            warning.contains("did not honor it in disk run 4"),
+            "{}",
+            warning
+        );
+    }
+
+    #[test]
+    fn test_contaminated_runs_are_reported_and_truncated() {
+        let load = |during_percent, contaminated, excluded| BackgroundLoad {
+            before_percent: 1.0,
+            during_percent,
+            contaminated,
+            excluded,
+            ..BackgroundLoad::default()
+        };
+        let mut results = SuiteResults {
+            config: SuiteConfig::default(),
+            cpu: vec![CpuResult::default()],
+            memory: vec![],
+            concurrency: vec![],
+            disk: vec![],
+            network: vec![],
+            gpu: vec![],
+            disk_target: None,
+            background_load: vec![load(2.0, false, false)],
+            cooldowns: vec![],
+            timed_out: vec![],
+            interrupted: false,
+        };
+        assert!(results.contamination_warning().is_none());
+
+        let kept = results.lengths();
+        results.cpu.push(CpuResult::default());
+        results.truncate(kept);
+        assert_eq!(results.cpu.len(), 1);
+
+        results.background_load.push(load(35.0, true, true));
+        let warning = results.contamination_warning().unwrap();
+        assert!(warning.contains("more than 10%"), "{}", warning);
+        assert!(
+            warning.contains("run 2 (1% before, 35% during, excluded)"),
+            "{}",
+            warning
+        );
+    }
+
+    #[test]
+    fn test_phase_from_name() {
+        assert_eq!(Phase::from_name("cpu"), Some(Phase::Cpu));
+        assert_eq!(Phase::from_name("Concurrency"), Some(Phase::Concurrency));
+        assert_eq!(Phase::from_name("GPU"), Some(Phase::Gpu));
+        assert_eq!(Phase::from_name("tape"), None);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let suite = BenchmarkSuite::builder().build();
+        let config = suite.config();
+        assert_eq!(config.scale, 1.0);
+        assert!(config.min_time.is_none());
+        assert_eq!(config.warmup_runs, 1);
+        assert_eq!(config.runs, 3);
+        assert_eq!(config.threads, 4);
+        assert_eq!(config.memory_threads, 8);
+        assert!(!config.memory_scaling);
+        assert!(!config.memory_numa);
+        assert!(!config.memory_prefault);
+        assert!(config.memory_patterns.is_empty());
+        assert!(!config.memory_huge_pages);
+        assert!(!config.disk_cache_compare);
+        assert!(!config.core_latency);
+        assert!(!config.pin_cores && config.pinned_cores().is_empty());
+        assert_eq!(config.block_size, 512 * 1024);
+        assert!(config.cpu && config.memory && config.concurrency);
+        assert!(config.kernels.is_empty());
+        assert_eq!(config.scale_for(Phase::Disk), 1.0);
+        assert_eq!(config.scale_summary(), "1");
+        assert!(config.auto_scale.is_none());
+        assert!(config.disk && config.network);
+        assert!(config.network_target.is_none());
+        assert!(config.disk_path.is_none());
+        assert_eq!(config.gpu, cfg!(feature = "gpu"));
+        assert_eq!(config.disk_dir(), Path::new("."));
+        assert_eq!(config.load_threshold_percent, 10.0);
+        assert!(!config.exclude_contaminated);
+        assert_eq!(config.cooldown, 0.0);
+        assert!(config.timeout.is_none());
+    }
+
+    #[test]
+    fn test_step_timeout() {
+        let config = |builder: BenchmarkSuiteBuilder| builder.build().config().clone();
+        let secs = |config: SuiteConfig| config.step_timeout().map(|limit| limit.as_secs_f64());
+        assert_eq!(secs(config(BenchmarkSuite::builder())), Some(600.0));
+        assert_eq!(
+            secs(config(BenchmarkSuite::builder().scale(0.01))),
+            Some(120.0)
+        );
+        assert_eq!(
+            secs(config(
+                BenchmarkSuite::builder()
+                    .scale(0.5)
+                    .disk_scale(2.0)
+                    .min_time(1.0)
+            )),
+            Some(1220.0)
+        );
+        assert_eq!(
+            secs(config(BenchmarkSuite::builder().timeout(30.0))),
+            Some(30.0)
+        );
+        assert_eq!(secs(config(BenchmarkSuite::builder().timeout(0.0))), None);
+    }
+
+    #[test]
+    fn test_stuck_phase_fails_with_its_timed_out_step() {
+        let watchdog = Watchdog::new(Duration::from_millis(20));
+        let (_release, blocked) = std::sync::mpsc::channel::<()>();
+        let mut steps = Vec::new();
+        let (result, timed_out) = watch_phase(
+            Some(&watchdog),
+            move |progress| -> Result<(), BenchError> {
+                progress.step("sequential write");
+                let _ = blocked.recv();
+                Ok(())
+            },
+            |step| steps.push(step.to_string()),
+        );
+        assert!(matches!(result, Err(BenchError::TimedOut(step)) if step == "sequential write"));
+        assert_eq!(timed_out, vec!["sequential write".to_string()]);
+        assert_eq!(steps, ["sequential write"]);
+
+        // Without a watchdog the phase runs on this thread, untimed
+        let caller = std::thread::current().id();
+        let (result, timed_out) = watch_phase(
+            None,
+            |progress| {
+                progress.step("fsync");
+                Ok(std::thread::current().id())
+            },
+            |_| {},
+        );
+        assert_eq!(result.unwrap(), caller);
+        assert!(timed_out.is_empty());
+    }
+
+    #[test]
+    fn test_builder_replaces_invalid_values() {
+        let suite = BenchmarkSuite::builder()
+            .scale(-2.0)
+            .min_time(-1.0)
+            .runs(0)
+            .threads(0)
+            .memory_threads(0)
+            .block_size(0)
+            .load_threshold(f64::NAN)
+            .cooldown(-1.0)
+            .timeout(f64::INFINITY)
+            .build();
+        let config = suite.config();
+        assert_eq!(config.scale, 1.0);
+        assert!(config.min_time.is_none());
+        assert_eq!(config.runs, 3);
+        assert_eq!(config.threads, 4);
+        assert_eq!(config.memory_threads, 8);
+        assert_eq!(config.block_size, 512 * 1024);
+        assert_eq!(config.load_threshold_percent, 10.0);
+        assert_eq!(config.cooldown, 0.0);
+        // A valid count is kept as given
+        assert_eq!(BenchmarkSuite::builder().runs(1).build().config().runs, 1);
+    }
+
+    #[test]
+    fn test_per_phase_scales() {
+        let suite = BenchmarkSuite::builder()
+            .scale(0.5)
+            .cpu_scale(2.0)
+            .disk_scale(0.1)
+            .memory_scale(-1.0)
+            .build();
+        let config = suite.config();
+        assert_eq!(config.scale_for(Phase::Cpu), 2.0);
+        assert_eq!(config.scale_for(Phase::Memory), 0.5);
+        assert_eq!(config.scale_for(Phase::Disk), 0.1);
+        assert_eq!(config.scale_for(Phase::Network), 0.5);
+        assert_eq!(config.scale_summary(), "0.5 (CPU 2, Disk 0.1)");
+    }
+
+    #[test]
+    fn test_calibrated_scale() {
+        // Linear kernel: 0.1 s at scale 0.05, 0.2 s at 0.1
+        assert!((calibrated_scale(0.05, 0.1, 0.2, 2.0) - 1.0).abs() < 1e-9);
+        // Cubic kernel: 8x the time for 2x the scale
+        assert!((calibrated_scale(0.05, 0.001, 0.008, 1.0) - 0.5).abs() < 1e-9);
+        // Noise that makes the larger pass faster falls back to the flattest fit
+        assert!((calibrated_scale(0.05, 0.1, 0.05, 0.4) - 0.8).abs() < 1e-9);
+        assert_eq!(
+            calibrated_scale(0.05, 1e-9, 2e-9, 1000.0),
+            MAX_CALIBRATED_SCALE
+        );
+        assert_eq!(calibrated_scale(0.05, 0.0, 0.0, 1.0), 0.05);
+
+        // The same fits predict the time at a given scale
+        assert!((predicted_secs(0.05, 0.1, 0.2, 1.0) - 2.0).abs() < 1e-9);
+        assert!((predicted_secs(0.05, 0.001, 0.008, 0.5) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_suite_plan() {
+        let suite = BenchmarkSuite::builder()
+            .scale(0.1)
+            .runs(2)
+            .cooldown(0.5)
+            .kernels(vec!["integer".to_string()])
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .gpu(false)
+            .build();
+        let plan = suite.plan(&mut ());
+
+        let phases: Vec<Phase> = plan.phases.iter().map(|phase| phase.phase).collect();
+        assert_eq!(phases, vec![Phase::Cpu, Phase::Network]);
+        let cpu_secs = plan.phases[0].estimated_secs.unwrap();
+        assert!(cpu_secs > 0.0);
+        assert_eq!(plan.phases[0].scale, 0.1);
+        // The network phase is not estimated
+        assert!(plan.phases[1].estimated_secs.is_none());
+        assert!(plan.is_partial());
+        // Two runs of the CPU phase and three cooldowns between the four phases
+        assert!((plan.estimated_secs() - (2.0 * cpu_secs + 1.5)).abs() < 1e-9);
+        assert!(plan.memory_footprint.is_none() && plan.disk_bytes.is_none());
+    }
+
+    #[test]
+    fn test_suite_auto_scale() {
+        let suite = BenchmarkSuite::builder()
+            .runs(1)
+            .auto_scale(0.001)
+            .kernels(vec!["integer".to_string()])
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .network(false)
+            .gpu(false)
+            .build();
+        let results = suite.run();
+
+        let scale = results.config.cpu_scale.unwrap();
+        assert!((MIN_CALIBRATED_SCALE..=MAX_CALIBRATED_SCALE).contains(&scale));
+        assert!(results.config.memory_scale.is_none());
+        assert_eq!(results.cpu.len(), 1);
+        assert!(suite.config().cpu_scale.is_none());
+    }
+
+    #[test]
+    fn test_pinned_cores() {
+        let suite = BenchmarkSuite::builder().pin_cores(true).build();
+        assert_eq!(suite.config().pinned_cores(), affinity::available_cores());
+
+        let suite = BenchmarkSuite::builder().core_list(vec![0, 2, 4]).build();
+        assert!(suite.config().pin_cores);
+        assert_eq!(suite.config().pinned_cores(), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_suite_runs_only_enabled_phases() {
+        // Use lightweight scale for CI/testing
+        let suite = BenchmarkSuite::builder()
+            .scale(0.1)
+            .runs(2)
+            .threads(2)
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .network(false)
+            .gpu(false)
+            .build();
+        assert_eq!(suite.config().phases(), vec![Phase::Cpu]);
+
+        let mut observer = RecordingObserver::default();
+        let results = suite.run_with_observer(&mut observer);
+
+        assert_eq!(results.cpu.len(), 2);
+        assert!(results.memory.is_empty());
+        assert!(results.concurrency.is_empty());
+        assert!(results.disk.is_empty());
+        assert!(results.network.is_empty());
+        assert!(results.disk_target.is_none());
+        assert_eq!(observer.runs, vec![1, 2]);
+        assert_eq!(observer.phases, vec![Phase::Cpu, Phase::Cpu]);
+        assert_eq!(observer.cpu_results, 2);
+        // Warmup plus twenty-seven timed benchmarks per run
+        assert_eq!(observer.steps.len(), 56);
+        assert_eq!(observer.steps[0], (Phase::Cpu, "warmup".to_string()));
+    }
+
+    #[test]
+    fn test_suite_parallel_schedule() {
+        let suite = BenchmarkSuite::builder()
+            .scale(0.05)
+            .runs(1)
+            .schedule(Schedule::Parallel)
+            .kernels(vec!["integer".to_string()])
+            .concurrency(false)
+            .disk(false)
+            .network(false)
+            .gpu(false)
+            .build();
+        let mut observer = RecordingObserver::default();
+        let results = suite.run_with_observer(&mut observer);
+
+        assert_eq!(results.cpu.len(), 1);
+        assert_eq!(results.memory.len(), 1);
+        assert_eq!(results.config.schedule, Schedule::Parallel);
+        assert_eq!(observer.phases, vec![Phase::Cpu, Phase::Memory]);
+        assert_eq!(observer.cpu_results, 1);
+        // Concurrent phases report no steps
+        assert!(observer.steps.is_empty());
+    }
+
+    #[test]
+    fn test_isolated_schedule_gap() {
+        let config = |builder: BenchmarkSuiteBuilder| builder.build().config().clone();
+        assert_eq!(config(BenchmarkSuite::builder()).phase_gap(), 0.0);
+        let isolated = BenchmarkSuite::builder().schedule(Schedule::Isolated);
+        assert_eq!(config(isolated).phase_gap(), ISOLATION_GAP_SECS);
+        let isolated = BenchmarkSuite::builder()
+            .schedule(Schedule::Isolated)
+            .cooldown(30.0);
+        assert_eq!(config(isolated).phase_gap(), 30.0);
+        assert_eq!(Schedule::from_name("Parallel"), Some(Schedule::Parallel));
+        assert_eq!(Schedule::from_name("concurrent"), None);
+    }
+
+    #[test]
+    fn test_suite_without_warmup() {
+        let suite = BenchmarkSuite::builder()
+            .scale(0.05)
+            .runs(1)
+            .warmup_runs(0)
+            .cpu(false)
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .gpu(false)
+            .build();
+        let mut observer = RecordingObserver::default();
+        let results = suite.run_with_observer(&mut observer);
+
+        assert_eq!(results.network.len(), 1);
+        assert!(!observer.steps.is_empty());
+        assert!(observer.steps.iter().all(|(_, step)| step != "warmup"));
+    }
+
+    #[test]
+    fn test_suite_cooldown_between_runs() {
+        let suite = BenchmarkSuite::builder()
+            .scale(0.05)
+            .runs(2)
+            .cooldown(0.05)
+            .cpu(false)
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .gpu(false)
+            .build();
+        let results = suite.run();
+
+        assert_eq!(results.network.len(), 2);
+        assert_eq!(results.cooldowns.len(), 1);
+        let cooldown = &results.cooldowns[0];
+        assert_eq!(cooldown.run, 2);
+        assert_eq!(
+            (cooldown.after.as_str(), cooldown.before.as_str()),
+            ("Network", "Network")
+        );
+        assert!(cooldown.seconds >= 0.05);
+        assert!(results.cooldown_summary().unwrap().contains("1 periods"));
+    }
+
+    #[test]
+    fn test_suite_memory_scaling() {
+        // Use lightweight scale for CI/testing
+        let suite = BenchmarkSuite::builder()
+            .scale(0.05)
+            .runs(1)
+            .memory_threads(2)
+            .memory_scaling(true)
+            .cpu(false)
+            .concurrency(false)
+            .disk(false)
+            .network(false)
+            .gpu(false)
+            .build();
+
+        let results = suite.run();
+        let threads: Vec<usize> = results.memory[0]
+            .thread_scaling
+            .iter()
+            .map(|p| p.threads)
+            .collect();
+        assert_eq!(threads, vec![1, 2]);
+        // The cache probe runs with every memory phase, within its footprint
+        let memory = &results.memory[0];
+        assert!(!memory.cache_probe.points.is_empty());
+        assert!(memory
+            .cache_probe
+            .points
+            .iter()
+            .all(|p| p.size_bytes as u64 <= memory.footprint.bytes));
+    }
+
+    #[test]
+    fn test_suite_concurrency_phase() {
+        // Use lightweight scale for CI/testing
+        let suite = BenchmarkSuite::builder()
+            .scale(0.01)
+            .runs(1)
+            .threads(2)
+            .cpu(false)
+            .memory(false)
+            .disk(false)
+            .network(false)
+            .gpu(false)
+            .build();
+        assert_eq!(suite.config().phases(), vec![Phase::Concurrency]);
+
+        let mut observer = RecordingObserver::default();
+        let results = suite.run_with_observer(&mut observer);
+
+        let threads: Vec<usize> = results.concurrency[0]
+            .points
+            .iter()
+            .map(|p| p.threads)
+            .collect();
+        assert_eq!(threads, vec![1, 2]);
+        assert_eq!(observer.steps.len(), 7);
+    }
+
+    #[test]
+    fn test_suite_reports_network_failure() {
+        let suite = BenchmarkSuite::builder()
+            .runs(1)
+            .cpu(false)
+            .memory(false)
+            .concurrency(false)
+            .disk(false)
+            .gpu(false)
+            .network_target("not a host")
+            .build();
+
+        let mut observer = RecordingObserver::default();
+        let results = suite.run_with_observer(&mut observer);
+
+        assert!(results.network.is_empty());
+        assert_eq!(observer.failures, vec![Phase::Network]);
+        assert_eq!(observer.phases, vec![Phase::Network]);
+    }
+}
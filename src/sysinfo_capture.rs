@@ -1,7 +1,9 @@
 /// System information capture for benchmark context
-use sysinfo::System;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sysinfo::{DiskKind, Disks, System};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub cpu_brand: String,
     pub cpu_physical_cores: usize,
@@ -10,6 +12,829 @@ pub struct SystemInfo {
     pub os_name: String,
     pub os_version: String,
     pub hostname: String,
+    /// GPU adapter used by the GPU benchmark; None without the `gpu` feature or a usable GPU
+    #[serde(default)]
+    pub gpu_adapter: Option<String>,
+    /// Hardware AES instructions used by the CPU AES benchmark (e.g. "aes-ni"); None means
+    /// the portable software implementation was measured
+    #[serde(default)]
+    pub aes_acceleration: Option<String>,
+    /// Vector instruction set extensions detected at runtime (e.g. "sse4.2", "avx2", "neon")
+    #[serde(default)]
+    pub simd_features: Vec<String>,
+    /// Instruction set used by the explicit SIMD kernels (see `SimdLevel`)
+    #[serde(default)]
+    pub simd_level: String,
+    /// Clock limits and frequency scaling settings of the CPU
+    #[serde(default)]
+    pub cpu_frequency: CpuFrequencyInfo,
+    /// Cache hierarchy and hybrid core types of the CPU
+    #[serde(default)]
+    pub cpu_topology: CpuTopology,
+    /// NUMA nodes with their CPUs and memory; empty where the OS does not expose them
+    #[serde(default)]
+    pub numa_nodes: Vec<NumaNode>,
+    /// Power source, battery charge, and OS power plan
+    #[serde(default)]
+    pub power: PowerInfo,
+}
+
+/// Clock limits and frequency scaling settings of the CPU
+/// Each field is None where the OS does not expose it (e.g. most virtual machines)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuFrequencyInfo {
+    /// Nominal frequency the CPU is rated for
+    pub base_mhz: Option<u64>,
+    /// Highest frequency the CPU may reach, including turbo/boost clocks
+    pub max_mhz: Option<u64>,
+    /// cpufreq scaling governor of CPU 0 on Linux, e.g. "performance" or "powersave"
+    pub governor: Option<String>,
+    /// Whether turbo/boost clocks are enabled
+    pub turbo_enabled: Option<bool>,
+}
+
+impl CpuFrequencyInfo {
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Self {
+        Self::from_sysfs(Path::new("/sys/devices/system/cpu"))
+    }
+
+    /// Base and maximum frequency from sysctl; governors and turbo switches are not exposed
+    /// Apple Silicon reports neither frequency
+    #[cfg(target_os = "macos")]
+    pub fn capture() -> Self {
+        let mhz = |name: &str| sysctl_u64(name).map(|hz| hz / 1_000_000);
+        Self {
+            base_mhz: mhz("hw.cpufrequency"),
+            max_mhz: mhz("hw.cpufrequency_max"),
+            ..Self::default()
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    /// Read the cpufreq files under `root` (normally /sys/devices/system/cpu)
+    /// Frequencies are given in kHz; turbo is reported by intel_pstate as `no_turbo` and by
+    /// acpi-cpufreq and amd-pstate as `cpufreq/boost`
+    pub fn from_sysfs(root: &Path) -> Self {
+        let read = |file: &str| {
+            std::fs::read_to_string(root.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        let mhz = |file: &str| read(file)?.parse::<u64>().ok().map(|khz| khz / 1000);
+        let turbo_enabled = match read("intel_pstate/no_turbo").as_deref() {
+            Some("0") => Some(true),
+            Some("1") => Some(false),
+            _ => match read("cpufreq/boost").as_deref() {
+                Some("1") => Some(true),
+                Some("0") => Some(false),
+                _ => None,
+            },
+        };
+        Self {
+            base_mhz: mhz("cpu0/cpufreq/base_frequency"),
+            max_mhz: mhz("cpu0/cpufreq/cpuinfo_max_freq"),
+            governor: read("cpu0/cpufreq/scaling_governor").filter(|g| !g.is_empty()),
+            turbo_enabled,
+        }
+    }
+
+    /// Known settings, e.g. "base 2100 MHz, max 4700 MHz, governor powersave, turbo on";
+    /// None when nothing is known
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(base) = self.base_mhz {
+            parts.push(format!("base {} MHz", base));
+        }
+        if let Some(max) = self.max_mhz {
+            parts.push(format!("max {} MHz", max));
+        }
+        if let Some(governor) = &self.governor {
+            parts.push(format!("governor {}", governor));
+        }
+        if let Some(turbo) = self.turbo_enabled {
+            parts.push(format!("turbo {}", if turbo { "on" } else { "off" }));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Power source, battery charge, and OS power plan
+/// Each field is None where it is unknown (e.g. desktops without a battery report no
+/// battery charge)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerInfo {
+    /// Whether the machine runs from its battery rather than AC power
+    pub on_battery: Option<bool>,
+    /// Battery charge in percent
+    pub battery_percent: Option<u8>,
+    /// Active power plan or profile, e.g. "balanced" or "power saver"
+    pub power_plan: Option<String>,
+}
+
+impl PowerInfo {
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Self {
+        Self::from_sysfs(Path::new("/sys"))
+    }
+
+    /// Power source and charge from `pmset -g batt`, and Low Power Mode from `pmset -g`
+    #[cfg(target_os = "macos")]
+    pub fn capture() -> Self {
+        let pmset = |args: &[&str]| {
+            std::process::Command::new("pmset")
+                .args(args)
+                .output()
+                .ok()
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+                .unwrap_or_default()
+        };
+        Self::from_pmset(&pmset(&["-g", "batt"]), &pmset(&["-g"]))
+    }
+
+    #[cfg(windows)]
+    pub fn capture() -> Self {
+        windows_power::capture()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    /// Read the power supplies under `root` (normally /sys) from `class/power_supply` and
+    /// the ACPI platform profile from `firmware/acpi/platform_profile`
+    /// With an AC adapter ("Mains" or "USB" supply) the adapter's `online` state decides;
+    /// otherwise a discharging battery means the machine is on battery
+    pub fn from_sysfs(root: &Path) -> Self {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        let mut adapters_online = Vec::new();
+        let mut batteries = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(root.join("class/power_supply")) {
+            let mut supplies: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+            supplies.sort();
+            for supply in supplies {
+                match read(&supply.join("type")).as_deref() {
+                    Some("Mains") | Some("USB") => {
+                        adapters_online.push(read(&supply.join("online")).as_deref() == Some("1"))
+                    }
+                    Some("Battery") => batteries.push((
+                        read(&supply.join("capacity")).and_then(|c| c.parse::<u8>().ok()),
+                        read(&supply.join("status")),
+                    )),
+                    _ => {}
+                }
+            }
+        }
+
+        let on_battery = if !adapters_online.is_empty() && !batteries.is_empty() {
+            Some(!adapters_online.contains(&true))
+        } else if let Some((_, status)) = batteries.first() {
+            Some(status.as_deref() == Some("Discharging"))
+        } else if !adapters_online.is_empty() {
+            Some(false)
+        } else {
+            None
+        };
+        Self {
+            on_battery,
+            battery_percent: batteries.first().and_then(|(capacity, _)| *capacity),
+            power_plan: read(&root.join("firmware/acpi/platform_profile"))
+                .filter(|profile| !profile.is_empty()),
+        }
+    }
+
+    /// Parse `pmset -g batt` ("Now drawing from 'Battery Power'" and "...\t85%; discharging")
+    /// and `pmset -g` (" lowpowermode 1")
+    pub fn from_pmset(battery: &str, settings: &str) -> Self {
+        let on_battery = if battery.contains("'Battery Power'") {
+            Some(true)
+        } else if battery.contains("'AC Power'") {
+            Some(false)
+        } else {
+            None
+        };
+        let battery_percent = battery
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("%;")?.parse::<u8>().ok());
+        let power_plan = settings.lines().find_map(|line| {
+            let mut words = line.split_whitespace();
+            (words.next()? == "lowpowermode").then(|| {
+                match words.next() {
+                    Some("1") => "low power",
+                    _ => "automatic",
+                }
+                .to_string()
+            })
+        });
+        Self {
+            on_battery,
+            battery_percent,
+            power_plan,
+        }
+    }
+
+    /// Known settings, e.g. "battery (85%), plan balanced"; None when nothing is known
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        let source = match self.on_battery {
+            Some(true) => Some("battery"),
+            Some(false) => Some("AC power"),
+            None => None,
+        };
+        match (source, self.battery_percent) {
+            (Some(source), Some(percent)) => parts.push(format!("{} ({}%)", source, percent)),
+            (Some(source), None) => parts.push(source.to_string()),
+            (None, Some(percent)) => parts.push(format!("battery {}%", percent)),
+            (None, None) => {}
+        }
+        if let Some(plan) = &self.power_plan {
+            parts.push(format!("plan {}", plan));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Power status from GetSystemPowerStatus and the active power scheme from
+/// PowerGetActiveScheme
+#[cfg(windows)]
+mod windows_power {
+    use super::PowerInfo;
+    use std::ffi::c_void;
+
+    // Mirrors the Windows structure; not every field is read
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    // Mirrors the Windows structure; not every field is read
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Guid {
+        data1: u32,
+        data2: u16,
+        data3: u16,
+        data4: [u8; 8],
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+        fn LocalFree(memory: *mut c_void) -> *mut c_void;
+    }
+
+    #[link(name = "powrprof")]
+    extern "system" {
+        fn PowerGetActiveScheme(user_root: *mut c_void, scheme: *mut *mut Guid) -> u32;
+    }
+
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
+
+    pub fn capture() -> PowerInfo {
+        let mut status = SystemPowerStatus::default();
+        let has_status = unsafe { GetSystemPowerStatus(&mut status) } != 0;
+        let has_battery = has_status && status.battery_flag & NO_SYSTEM_BATTERY == 0;
+        PowerInfo {
+            on_battery: match status.ac_line_status {
+                _ if !has_status => None,
+                0 => Some(true),
+                1 => Some(false),
+                _ => None,
+            },
+            battery_percent: (has_battery && status.battery_life_percent != UNKNOWN)
+                .then_some(status.battery_life_percent),
+            power_plan: active_scheme(),
+        }
+    }
+
+    /// Name of the active built-in power scheme; "custom" for user-created schemes
+    fn active_scheme() -> Option<String> {
+        let mut scheme: *mut Guid = std::ptr::null_mut();
+        // SAFETY: on success the scheme GUID is allocated by the OS and freed with LocalFree
+        let data1 = unsafe {
+            if PowerGetActiveScheme(std::ptr::null_mut(), &mut scheme) != 0 || scheme.is_null() {
+                return None;
+            }
+            let data1 = (*scheme).data1;
+            LocalFree(scheme.cast());
+            data1
+        };
+        Some(
+            match data1 {
+                0x381b4222 => "balanced",
+                0x8c5e7fda => "high performance",
+                0xa1841308 => "power saver",
+                0xe9a42b02 => "ultimate performance",
+                _ => "custom",
+            }
+            .to_string(),
+        )
+    }
+}
+
+/// Integer sysctl value; None when the name is unknown or the value is zero
+#[cfg(target_os = "macos")]
+fn sysctl_u64(name: &str) -> Option<u64> {
+    let name = std::ffi::CString::new(name).ok()?;
+    // Some values are 32-bit; the zeroed upper half keeps them correct on little-endian
+    let mut value = 0u64;
+    let mut size = std::mem::size_of::<u64>();
+    let status = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            (&mut value as *mut u64).cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (status == 0 && value > 0).then_some(value)
+}
+
+/// Kind of data a cache holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    Data,
+    Instruction,
+    Unified,
+}
+
+/// One level of the cache hierarchy, e.g. the L1 data caches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub level: u8,
+    pub kind: CacheKind,
+    /// Size of one cache instance
+    pub size_kb: u64,
+    /// Logical CPUs sharing each instance in Linux cpulist form, e.g. ["0-1", "2-3"];
+    /// empty where the OS does not expose the topology
+    #[serde(default)]
+    pub shared_cpu_lists: Vec<String>,
+}
+
+impl CacheInfo {
+    /// Short name such as "L1d", "L1i" or "L3"
+    pub fn label(&self) -> String {
+        match self.kind {
+            CacheKind::Data => format!("L{}d", self.level),
+            CacheKind::Instruction => format!("L{}i", self.level),
+            CacheKind::Unified => format!("L{}", self.level),
+        }
+    }
+
+    /// Logical CPUs sharing one instance, when known
+    pub fn cpus_per_instance(&self) -> Option<usize> {
+        self.shared_cpu_lists.first().map(|list| cpu_list_len(list))
+    }
+
+    /// e.g. "L2 2 MB x8 (shared by 2 CPUs)"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} {}",
+            self.label(),
+            crate::memory::format_size(self.size_kb as usize * 1024)
+        );
+        if self.shared_cpu_lists.len() > 1 {
+            summary.push_str(&format!(" x{}", self.shared_cpu_lists.len()));
+        }
+        if let Some(cpus) = self.cpus_per_instance().filter(|&cpus| cpus > 1) {
+            summary.push_str(&format!(" (shared by {} CPUs)", cpus));
+        }
+        summary
+    }
+}
+
+/// Logical CPUs of each core type on a hybrid CPU (e.g. Intel P-cores and E-cores or Apple
+/// performance and efficiency cores)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HybridCores {
+    pub performance_cpus: usize,
+    pub efficiency_cpus: usize,
+}
+
+/// Cache hierarchy and core types of the CPU, used to relate memory benchmark cliffs to
+/// the actual cache sizes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuTopology {
+    /// Caches from L1 outwards; empty where the OS does not expose them
+    pub caches: Vec<CacheInfo>,
+    /// Core types of a hybrid CPU; None for CPUs with a single core type
+    pub hybrid: Option<HybridCores>,
+}
+
+impl CpuTopology {
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Self {
+        Self::from_sysfs(Path::new("/sys/devices"))
+    }
+
+    /// Cache sizes and performance levels from sysctl; the sharing of caches between cores
+    /// is not exposed
+    #[cfg(target_os = "macos")]
+    pub fn capture() -> Self {
+        let caches = [
+            (1, CacheKind::Data, "hw.l1dcachesize"),
+            (1, CacheKind::Instruction, "hw.l1icachesize"),
+            (2, CacheKind::Unified, "hw.l2cachesize"),
+            (3, CacheKind::Unified, "hw.l3cachesize"),
+        ]
+        .into_iter()
+        .filter_map(|(level, kind, name)| {
+            Some(CacheInfo {
+                level,
+                kind,
+                size_kb: sysctl_u64(name)? / 1024,
+                shared_cpu_lists: Vec::new(),
+            })
+        })
+        .collect();
+        let hybrid = (sysctl_u64("hw.nperflevels") == Some(2)).then(|| HybridCores {
+            performance_cpus: sysctl_u64("hw.perflevel0.logicalcpu").unwrap_or(0) as usize,
+            efficiency_cpus: sysctl_u64("hw.perflevel1.logicalcpu").unwrap_or(0) as usize,
+        });
+        Self { caches, hybrid }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    /// Read the cache and core type files under `root` (normally /sys/devices)
+    /// Caches come from `system/cpu/cpuN/cache/indexM`; Intel hybrid CPUs list their P-core
+    /// and E-core CPUs in `cpu_core/cpus` and `cpu_atom/cpus`
+    pub fn from_sysfs(root: &Path) -> Self {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+
+        let cpu_root = root.join("system/cpu");
+        let mut cpus: Vec<(usize, std::path::PathBuf)> = std::fs::read_dir(&cpu_root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let name = entry.file_name();
+                        let id = name.to_str()?.strip_prefix("cpu")?.parse().ok()?;
+                        Some((id, entry.path()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        cpus.sort();
+
+        let mut caches: Vec<CacheInfo> = Vec::new();
+        for (_, cpu) in &cpus {
+            let Ok(entries) = std::fs::read_dir(cpu.join("cache")) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let index = entry.path();
+                let level = read(&index.join("level")).and_then(|level| level.parse().ok());
+                let kind = match read(&index.join("type")).as_deref() {
+                    Some("Data") => Some(CacheKind::Data),
+                    Some("Instruction") => Some(CacheKind::Instruction),
+                    Some("Unified") => Some(CacheKind::Unified),
+                    _ => None,
+                };
+                let size_kb = read(&index.join("size")).and_then(|size| parse_cache_size(&size));
+                let (Some(level), Some(kind), Some(size_kb)) = (level, kind, size_kb) else {
+                    continue;
+                };
+                let shared = read(&index.join("shared_cpu_list")).filter(|list| !list.is_empty());
+                let cache = match caches
+                    .iter_mut()
+                    .position(|c| c.level == level && c.kind == kind)
+                {
+                    Some(position) => &mut caches[position],
+                    None => {
+                        caches.push(CacheInfo {
+                            level,
+                            kind,
+                            size_kb,
+                            shared_cpu_lists: Vec::new(),
+                        });
+                        caches.last_mut().unwrap()
+                    }
+                };
+                if let Some(shared) = shared {
+                    if !cache.shared_cpu_lists.contains(&shared) {
+                        cache.shared_cpu_lists.push(shared);
+                    }
+                }
+            }
+        }
+        caches.sort_by_key(|cache| (cache.level, cache.kind));
+
+        let core_cpus = |pmu: &str| read(&root.join(pmu).join("cpus")).map(|l| cpu_list_len(&l));
+        let hybrid = match (core_cpus("cpu_core"), core_cpus("cpu_atom")) {
+            (Some(performance_cpus), Some(efficiency_cpus)) => Some(HybridCores {
+                performance_cpus,
+                efficiency_cpus,
+            }),
+            _ => None,
+        };
+
+        Self { caches, hybrid }
+    }
+
+    /// Known caches, e.g. "L1d 48 KB x8, L1i 32 KB x8, L2 2 MB x8, L3 30 MB (shared by 16
+    /// CPUs)"; None when nothing is known
+    pub fn cache_summary(&self) -> Option<String> {
+        (!self.caches.is_empty()).then(|| {
+            self.caches
+                .iter()
+                .map(CacheInfo::summary)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+
+    /// e.g. "8 performance + 16 efficiency logical CPUs"; None for non-hybrid CPUs
+    pub fn core_type_summary(&self) -> Option<String> {
+        self.hybrid.map(|hybrid| {
+            format!(
+                "{} performance + {} efficiency logical CPUs",
+                hybrid.performance_cpus, hybrid.efficiency_cpus
+            )
+        })
+    }
+}
+
+/// A NUMA node: a group of CPUs and the memory attached to them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub id: usize,
+    /// Logical CPUs of the node in Linux cpulist form, e.g. "0-15,32-47"; empty for
+    /// memory-only nodes
+    pub cpu_list: String,
+    pub memory_mb: u64,
+}
+
+impl NumaNode {
+    /// Logical CPUs of the node, in ascending order
+    pub fn cpus(&self) -> Vec<usize> {
+        parse_cpu_list(&self.cpu_list)
+    }
+}
+
+/// NUMA nodes of this system; empty where the OS does not expose them
+#[cfg(target_os = "linux")]
+pub fn detect_numa_nodes() -> Vec<NumaNode> {
+    numa_nodes_from_sysfs(Path::new("/sys/devices"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect_numa_nodes() -> Vec<NumaNode> {
+    Vec::new()
+}
+
+/// Read the NUMA nodes under `root` (normally /sys/devices) from `system/node/nodeN`,
+/// ordered by node id
+pub fn numa_nodes_from_sysfs(root: &Path) -> Vec<NumaNode> {
+    let Ok(entries) = std::fs::read_dir(root.join("system/node")) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<NumaNode> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let id = name.to_str()?.strip_prefix("node")?.parse().ok()?;
+            let read = |file: &str| std::fs::read_to_string(entry.path().join(file)).ok();
+            // meminfo lines look like "Node 0 MemTotal:       32768 kB"
+            let memory_mb = read("meminfo")
+                .and_then(|meminfo| {
+                    let line = meminfo.lines().find(|line| line.contains("MemTotal:"))?;
+                    line.split_whitespace().rev().nth(1)?.parse::<u64>().ok()
+                })
+                .map_or(0, |kb| kb / 1024);
+            Some(NumaNode {
+                id,
+                cpu_list: read("cpulist").unwrap_or_default().trim().to_string(),
+                memory_mb,
+            })
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+/// Parse a sysfs cache size such as "48K" or "30M" into KB
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let (digits, multiplier) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 1),
+        b'M' => (&size[..size.len() - 1], 1024),
+        b'G' => (&size[..size.len() - 1], 1024 * 1024),
+        _ => (size, 1),
+    };
+    digits.parse::<u64>().ok().map(|value| value * multiplier)
+}
+
+/// CPUs of a Linux cpulist such as "0-3,8-11"; malformed ranges are skipped
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .flat_map(|range| {
+            let range = range.trim();
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            match (start.parse::<usize>(), end.parse::<usize>()) {
+                (Ok(start), Ok(end)) => start..end + 1,
+                _ => 0..0,
+            }
+        })
+        .collect()
+}
+
+/// Number of CPUs in a Linux cpulist
+fn cpu_list_len(list: &str) -> usize {
+    parse_cpu_list(list).len()
+}
+
+/// Widest vector instruction set the explicit SIMD benchmark kernels can use on this CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimdLevel {
+    Scalar,
+    Sse2,
+    Avx2Fma,
+    Avx512,
+    Neon,
+}
+
+impl SimdLevel {
+    /// Detect the widest supported level at runtime
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx512f") {
+                return SimdLevel::Avx512;
+            }
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                return SimdLevel::Avx2Fma;
+            }
+            if is_x86_feature_detected!("sse2") {
+                return SimdLevel::Sse2;
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                return SimdLevel::Neon;
+            }
+        }
+        SimdLevel::Scalar
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SimdLevel::Scalar => "scalar",
+            SimdLevel::Sse2 => "sse2",
+            SimdLevel::Avx2Fma => "avx2+fma",
+            SimdLevel::Avx512 => "avx512f",
+            SimdLevel::Neon => "neon",
+        }
+    }
+}
+
+/// Vector instruction set extensions supported by this CPU, detected at runtime
+pub fn detect_simd_features() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut features = Vec::new();
+    #[cfg(target_arch = "x86_64")]
+    {
+        macro_rules! detect {
+            ($($feature:tt),*) => {
+                $(if is_x86_feature_detected!($feature) {
+                    features.push($feature.to_string());
+                })*
+            };
+        }
+        detect!(
+            "sse2", "sse3", "ssse3", "sse4.1", "sse4.2", "avx", "avx2", "fma", "avx512f",
+            "avx512bw", "avx512vl"
+        );
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        macro_rules! detect {
+            ($($feature:tt),*) => {
+                $(if std::arch::is_aarch64_feature_detected!($feature) {
+                    features.push($feature.to_string());
+                })*
+            };
+        }
+        detect!("neon", "sve", "sve2");
+    }
+    features
+}
+
+/// Type of a storage device, as far as the OS reports it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    /// Rotational disk
+    Hdd,
+    /// Non-rotational device (SSD, NVMe)
+    Ssd,
+    #[default]
+    Unknown,
+}
+
+impl StorageKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StorageKind::Hdd => "HDD",
+            StorageKind::Ssd => "SSD",
+            StorageKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// Disk listed by the OS
+#[derive(Debug, Clone)]
+pub struct DiskDevice {
+    pub name: String,
+    pub filesystem: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub kind: StorageKind,
+}
+
+/// Disk holding `path`: the listed disk with the longest mount point containing it
+/// None when no listed disk contains the path, e.g. for overlay and network filesystems
+/// that the OS does not report as disks
+pub fn disk_for_path(path: &Path) -> Option<DiskDevice> {
+    let disks = Disks::new_with_refreshed_list();
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())?;
+    Some(DiskDevice {
+        name: disk.name().to_string_lossy().into_owned(),
+        filesystem: disk.file_system().to_string_lossy().into_owned(),
+        mount_point: disk.mount_point().display().to_string(),
+        total_bytes: disk.total_space(),
+        free_bytes: disk.available_space(),
+        kind: match disk.kind() {
+            DiskKind::HDD => StorageKind::Hdd,
+            DiskKind::SSD => StorageKind::Ssd,
+            DiskKind::Unknown(_) => StorageKind::Unknown,
+        },
+    })
+}
+
+/// Whether the block device `device` (e.g. "/dev/sda1") is rotational, from sysfs
+/// Partitions report the queue of the disk they belong to
+#[cfg(target_os = "linux")]
+pub fn block_device_kind(device: &str) -> StorageKind {
+    let Some(name) = device.strip_prefix("/dev/") else {
+        return StorageKind::Unknown;
+    };
+    let block = Path::new("/sys/class/block").join(name);
+    [
+        block.join("queue/rotational"),
+        block.join("../queue/rotational"),
+    ]
+    .iter()
+    .find_map(|path| std::fs::read_to_string(path).ok())
+    .map_or(StorageKind::Unknown, |rotational| match rotational.trim() {
+        "1" => StorageKind::Hdd,
+        "0" => StorageKind::Ssd,
+        _ => StorageKind::Unknown,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn block_device_kind(_device: &str) -> StorageKind {
+    StorageKind::Unknown
+}
+
+/// Memory that can be allocated without swapping, in bytes; None where the OS does not
+/// report it
+pub fn available_memory_bytes() -> Option<u64> {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    Some(sys.available_memory()).filter(|&bytes| bytes > 0)
 }
 
 impl SystemInfo {
@@ -40,20 +865,54 @@ impl SystemInfo {
             os_name,
             os_version,
             hostname,
+            gpu_adapter: crate::gpu::adapter_name(),
+            aes_acceleration: crate::cpu::aes_acceleration().map(str::to_string),
+            simd_features: detect_simd_features(),
+            simd_level: SimdLevel::detect().name().to_string(),
+            cpu_frequency: CpuFrequencyInfo::capture(),
+            cpu_topology: CpuTopology::capture(),
+            numa_nodes: detect_numa_nodes(),
+            power: PowerInfo::capture(),
         }
     }
 
-    /// Display formatted system information
-    pub fn display(&self) {
-        println!("=== System Information ===");
-        println!("CPU: {}", self.cpu_brand);
-        println!(
-            "Cores: {} physical, {} logical",
-            self.cpu_physical_cores, self.cpu_logical_cores
-        );
-        println!("Memory: {} MB", self.total_memory_mb);
-        println!("OS: {} {}", self.os_name, self.os_version);
-        println!("Hostname: {}\n", self.hostname);
+    /// NUMA layout of multi-node systems, e.g. "2 nodes (node 0: CPUs 0-15, 65536 MB;
+    /// node 1: CPUs 16-31, 65536 MB)"; None for single-node systems
+    pub fn numa_summary(&self) -> Option<String> {
+        (self.numa_nodes.len() > 1).then(|| {
+            let nodes: Vec<String> = self
+                .numa_nodes
+                .iter()
+                .map(|node| {
+                    let cpus = if node.cpu_list.is_empty() {
+                        "no CPUs"
+                    } else {
+                        &node.cpu_list
+                    };
+                    format!("node {}: CPUs {}, {} MB", node.id, cpus, node.memory_mb)
+                })
+                .collect();
+            format!("{} nodes ({})", self.numa_nodes.len(), nodes.join("; "))
+        })
+    }
+
+    /// Warning that the CPU frequency governor holds the clocks down, if it does
+    pub fn governor_warning(&self) -> Option<String> {
+        (self.cpu_frequency.governor.as_deref() == Some("powersave")).then(|| {
+            "The CPU frequency governor is 'powersave', which favors low clocks and can \
+             understate CPU results; use the 'performance' governor (e.g. `cpupower \
+             frequency-set -g performance`) for comparable runs"
+                .to_string()
+        })
+    }
+
+    /// Warning that the machine runs on battery power, if it does
+    pub fn battery_warning(&self) -> Option<String> {
+        (self.power.on_battery == Some(true)).then(|| {
+            "The machine is running on battery power, where laptops lower clocks and power \
+             limits; connect AC power for results comparable with other runs"
+                .to_string()
+        })
     }
 }
 
@@ -72,6 +931,247 @@ mod tests {
         assert!(!info.os_name.is_empty());
     }
 
+    #[test]
+    fn test_system_info_reports_aes_acceleration() {
+        assert_eq!(
+            SystemInfo::capture().aes_acceleration.as_deref(),
+            crate::cpu::aes_acceleration()
+        );
+    }
+
+    #[test]
+    fn test_cpu_frequency_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("hsbench_cpufreq_{}", std::process::id()));
+        let write = |file: &str, value: &str| {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        write("cpu0/cpufreq/base_frequency", "2100000\n");
+        write("cpu0/cpufreq/cpuinfo_max_freq", "4700000\n");
+        write("cpu0/cpufreq/scaling_governor", "powersave\n");
+        write("intel_pstate/no_turbo", "0\n");
+        let info = CpuFrequencyInfo::from_sysfs(&root);
+        write("intel_pstate/no_turbo", "");
+        write("cpufreq/boost", "0\n");
+        let boost_off = CpuFrequencyInfo::from_sysfs(&root).turbo_enabled;
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(
+            info,
+            CpuFrequencyInfo {
+                base_mhz: Some(2100),
+                max_mhz: Some(4700),
+                governor: Some("powersave".to_string()),
+                turbo_enabled: Some(true),
+            }
+        );
+        assert_eq!(
+            info.summary().as_deref(),
+            Some("base 2100 MHz, max 4700 MHz, governor powersave, turbo on")
+        );
+        assert_eq!(boost_off, Some(false));
+        assert_eq!(
+            CpuFrequencyInfo::from_sysfs(Path::new("/nonexistent")),
+            CpuFrequencyInfo::default()
+        );
+        assert!(CpuFrequencyInfo::default().summary().is_none());
+    }
+
+    #[test]
+    fn test_cpu_topology_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("hsbench_topology_{}", std::process::id()));
+        let write = |file: &str, value: &str| {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        let cache =
+            |cpu: usize, index: usize, level: &str, kind: &str, size: &str, shared: &str| {
+                let dir = format!("system/cpu/cpu{}/cache/index{}", cpu, index);
+                write(&format!("{}/level", dir), level);
+                write(&format!("{}/type", dir), kind);
+                write(&format!("{}/size", dir), size);
+                write(&format!("{}/shared_cpu_list", dir), shared);
+            };
+        for cpu in 0..4 {
+            let pair = if cpu < 2 { "0-1" } else { "2-3" };
+            cache(cpu, 0, "1\n", "Data\n", "48K\n", &format!("{}\n", cpu));
+            cache(
+                cpu,
+                1,
+                "1\n",
+                "Instruction\n",
+                "32K\n",
+                &format!("{}\n", cpu),
+            );
+            cache(
+                cpu,
+                2,
+                "2\n",
+                "Unified\n",
+                "2048K\n",
+                &format!("{}\n", pair),
+            );
+            cache(cpu, 3, "3\n", "Unified\n", "30M\n", "0-3\n");
+        }
+        write("system/cpu/cpufreq/policy0", "");
+        write("cpu_core/cpus", "0-1\n");
+        write("cpu_atom/cpus", "2-3\n");
+        let topology = CpuTopology::from_sysfs(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let labels: Vec<String> = topology.caches.iter().map(CacheInfo::label).collect();
+        assert_eq!(labels, ["L1d", "L1i", "L2", "L3"]);
+        assert_eq!(topology.caches[2].shared_cpu_lists, ["0-1", "2-3"]);
+        assert_eq!(topology.caches[3].size_kb, 30 * 1024);
+        assert_eq!(
+            topology.cache_summary().as_deref(),
+            Some(
+                "L1d 48 KB x4, L1i 32 KB x4, L2 2 MB x2 (shared by 2 CPUs), \
+                 L3 30 MB (shared by 4 CPUs)"
+            )
+        );
+        assert_eq!(
+            topology.core_type_summary().as_deref(),
+            Some("2 performance + 2 efficiency logical CPUs")
+        );
+        assert_eq!(
+            CpuTopology::from_sysfs(Path::new("/nonexistent")),
+            CpuTopology::default()
+        );
+        assert_eq!(cpu_list_len("0-3,8-11,15"), 9);
+        assert_eq!(parse_cpu_list("0-2,5"), [0, 1, 2, 5]);
+        assert!(parse_cpu_list("").is_empty());
+        assert_eq!(parse_cache_size("1G"), Some(1024 * 1024));
+    }
+
+    #[test]
+    fn test_numa_nodes_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("hsbench_numa_{}", std::process::id()));
+        let write = |file: &str, value: &str| {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        for (node, cpus) in [(1, "16-31\n"), (0, "0-15\n")] {
+            write(&format!("system/node/node{}/cpulist", node), cpus);
+            write(
+                &format!("system/node/node{}/meminfo", node),
+                &format!(
+                    "Node {} MemTotal:       67108864 kB\nNode {} MemFree:  1024 kB\n",
+                    node, node
+                ),
+            );
+        }
+        write("system/node/online", "0-1\n");
+        let nodes = numa_nodes_from_sysfs(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].id, 0);
+        assert_eq!(nodes[1].cpus().len(), 16);
+        assert_eq!(nodes[1].memory_mb, 65536);
+        assert!(numa_nodes_from_sysfs(Path::new("/nonexistent")).is_empty());
+
+        let mut info = SystemInfo::capture();
+        info.numa_nodes = nodes;
+        assert_eq!(
+            info.numa_summary().as_deref(),
+            Some("2 nodes (node 0: CPUs 0-15, 65536 MB; node 1: CPUs 16-31, 65536 MB)")
+        );
+        info.numa_nodes.truncate(1);
+        assert!(info.numa_summary().is_none());
+    }
+
+    #[test]
+    fn test_power_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("hsbench_power_{}", std::process::id()));
+        let write = |file: &str, value: &str| {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        write("class/power_supply/BAT0/type", "Battery\n");
+        write("class/power_supply/BAT0/capacity", "85\n");
+        write("class/power_supply/BAT0/status", "Discharging\n");
+        let battery_only = PowerInfo::from_sysfs(&root);
+        write("class/power_supply/AC/type", "Mains\n");
+        write("class/power_supply/AC/online", "1\n");
+        write("firmware/acpi/platform_profile", "balanced\n");
+        let plugged_in = PowerInfo::from_sysfs(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(battery_only.on_battery, Some(true));
+        assert_eq!(battery_only.summary().as_deref(), Some("battery (85%)"));
+        assert_eq!(
+            plugged_in,
+            PowerInfo {
+                on_battery: Some(false),
+                battery_percent: Some(85),
+                power_plan: Some("balanced".to_string()),
+            }
+        );
+        assert_eq!(
+            plugged_in.summary().as_deref(),
+            Some("AC power (85%), plan balanced")
+        );
+        assert_eq!(
+            PowerInfo::from_sysfs(Path::new("/nonexistent")),
+            PowerInfo::default()
+        );
+        assert!(PowerInfo::default().summary().is_none());
+
+        let mut info = SystemInfo::capture();
+        info.power = battery_only;
+        assert!(info.battery_warning().is_some());
+        info.power = plugged_in;
+        assert!(info.battery_warning().is_none());
+    }
+
+    #[test]
+    fn test_power_from_pmset() {
+        let battery = "Now drawing from 'Battery Power'\n \
+                       -InternalBattery-0 (id=1234)\t85%; discharging; 4:10 remaining present: true\n";
+        let settings = "System-wide power settings:\nCurrently in use:\n lowpowermode         1\n";
+        assert_eq!(
+            PowerInfo::from_pmset(battery, settings),
+            PowerInfo {
+                on_battery: Some(true),
+                battery_percent: Some(85),
+                power_plan: Some("low power".to_string()),
+            }
+        );
+        let desktop = PowerInfo::from_pmset("Now drawing from 'AC Power'\n", "");
+        assert_eq!(desktop.summary().as_deref(), Some("AC power"));
+    }
+
+    #[test]
+    fn test_disk_for_path() {
+        let root = if cfg!(windows) { "C:\\" } else { "/" };
+        if let Some(disk) = disk_for_path(Path::new(root)) {
+            assert!(Path::new(root).starts_with(&disk.mount_point));
+            assert!(disk.free_bytes <= disk.total_bytes);
+        }
+        assert_eq!(block_device_kind("tmpfs"), StorageKind::Unknown);
+        assert_eq!(StorageKind::Ssd.name(), "SSD");
+    }
+
+    #[test]
+    fn test_simd_detection_is_consistent() {
+        let features = detect_simd_features();
+        let level = SimdLevel::detect();
+        let has = |feature: &str| features.iter().any(|f| f == feature);
+        match level {
+            SimdLevel::Avx512 => assert!(has("avx512f")),
+            SimdLevel::Avx2Fma => assert!(has("avx2") && has("fma")),
+            SimdLevel::Sse2 => assert!(has("sse2")),
+            SimdLevel::Neon => assert!(has("neon")),
+            SimdLevel::Scalar => assert!(!has("sse2") && !has("neon")),
+        }
+        assert_eq!(SystemInfo::capture().simd_level, level.name());
+    }
+
     #[test]
     fn test_system_info_fields() {
         let info = SystemInfo::capture();
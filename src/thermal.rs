@@ -0,0 +1,229 @@
+/// Thermal throttling detection
+/// Samples the average CPU frequency and the hottest CPU temperature sensor in a background
+/// thread while a benchmark runs; a sustained frequency well below the peak means the CPU
+/// slowed down under load, so runs measured before and after throttling are not comparable
+/// Readings the OS does not expose (e.g. temperatures in most virtual machines) are None
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use sysinfo::{Components, CpuRefreshKind, RefreshKind, System};
+
+/// Time between two samples while a benchmark runs
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sustained frequency drop below the peak, in percent, that is reported as throttling
+pub const THROTTLE_DROP_PERCENT: f64 = 10.0;
+
+/// Labels of temperature sensors that measure the CPU (Linux hwmon, macOS, and Windows names)
+const CPU_SENSOR_LABELS: [&str; 7] = [
+    "cpu", "package", "core", "tctl", "tdie", "k10temp", "coretemp",
+];
+
+/// One reading of the CPU frequency and temperature
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalSample {
+    /// Average current frequency over all logical CPUs
+    pub frequency_mhz: Option<f64>,
+    /// Hottest CPU temperature sensor
+    pub temperature_c: Option<f64>,
+}
+
+/// Frequency and temperature of the CPU over one benchmark
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalResult {
+    pub before: ThermalSample,
+    pub after: ThermalSample,
+    /// Highest frequency sampled while the benchmark ran
+    pub peak_frequency_mhz: Option<f64>,
+    /// Median frequency over the second half of the benchmark
+    pub sustained_frequency_mhz: Option<f64>,
+    pub max_temperature_c: Option<f64>,
+    /// Samples taken while the benchmark ran
+    pub samples: usize,
+}
+
+impl ThermalResult {
+    /// Summarize the samples taken before, while, and after a benchmark ran
+    pub fn from_samples(
+        before: ThermalSample,
+        during: &[ThermalSample],
+        after: ThermalSample,
+    ) -> Self {
+        let all: Vec<ThermalSample> = std::iter::once(before)
+            .chain(during.iter().copied())
+            .chain(std::iter::once(after))
+            .collect();
+        let max = |values: &mut dyn Iterator<Item = f64>| values.reduce(f64::max);
+
+        // The sample after the benchmark still reflects the clock reached under load
+        let mut late: Vec<f64> = during[during.len() / 2..]
+            .iter()
+            .chain(std::iter::once(&after))
+            .filter_map(|s| s.frequency_mhz)
+            .collect();
+        late.sort_by(f64::total_cmp);
+
+        Self {
+            before,
+            after,
+            peak_frequency_mhz: max(&mut all.iter().filter_map(|s| s.frequency_mhz)),
+            sustained_frequency_mhz: late.get(late.len() / 2).copied(),
+            max_temperature_c: max(&mut all.iter().filter_map(|s| s.temperature_c)),
+            samples: during.len(),
+        }
+    }
+
+    /// Percentage by which the sustained frequency fell below the peak
+    pub fn frequency_drop_percent(&self) -> Option<f64> {
+        let peak = self.peak_frequency_mhz?;
+        let sustained = self.sustained_frequency_mhz?;
+        (peak > 0.0).then(|| (peak - sustained) / peak * 100.0)
+    }
+
+    /// True when the sustained frequency dropped more than `THROTTLE_DROP_PERCENT` below
+    /// the peak
+    pub fn throttled(&self) -> bool {
+        self.frequency_drop_percent()
+            .is_some_and(|drop| drop > THROTTLE_DROP_PERCENT)
+    }
+}
+
+/// Reads the CPU frequency and temperature sensors
+pub struct ThermalSensors {
+    system: System,
+    components: Components,
+}
+
+impl ThermalSensors {
+    pub fn new() -> Self {
+        Self {
+            system: System::new_with_specifics(
+                RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing().with_frequency()),
+            ),
+            components: Components::new_with_refreshed_list(),
+        }
+    }
+
+    pub fn sample(&mut self) -> ThermalSample {
+        self.system.refresh_cpu_frequency();
+        let frequencies: Vec<f64> = self
+            .system
+            .cpus()
+            .iter()
+            .map(|cpu| cpu.frequency() as f64)
+            .filter(|&mhz| mhz > 0.0)
+            .collect();
+
+        self.components.refresh(false);
+        let temperature_c = self
+            .components
+            .iter()
+            .filter(|component| {
+                let label = component.label().to_lowercase();
+                CPU_SENSOR_LABELS.iter().any(|name| label.contains(name))
+            })
+            .filter_map(|component| component.temperature())
+            .map(f64::from)
+            .filter(|celsius| celsius.is_finite() && *celsius > 0.0)
+            .reduce(f64::max);
+
+        ThermalSample {
+            frequency_mhz: (!frequencies.is_empty())
+                .then(|| frequencies.iter().sum::<f64>() / frequencies.len() as f64),
+            temperature_c,
+        }
+    }
+}
+
+impl Default for ThermalSensors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Samples the sensors every `SAMPLE_INTERVAL` in a background thread until `finish()`
+pub struct ThermalMonitor {
+    before: ThermalSample,
+    stop: mpsc::Sender<()>,
+    sampler: JoinHandle<(ThermalSensors, Vec<ThermalSample>)>,
+}
+
+impl ThermalMonitor {
+    /// Take the sample before the benchmark and start sampling in the background
+    pub fn start() -> Self {
+        let mut sensors = ThermalSensors::new();
+        let before = sensors.sample();
+        let (stop, stopped) = mpsc::channel();
+        let sampler = thread::spawn(move || {
+            let mut samples = Vec::new();
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(SAMPLE_INTERVAL) {
+                samples.push(sensors.sample());
+            }
+            (sensors, samples)
+        });
+        Self {
+            before,
+            stop,
+            sampler,
+        }
+    }
+
+    /// Stop sampling and take the sample after the benchmark
+    pub fn finish(self) -> ThermalResult {
+        // A failed send means the sampler already stopped; join reports why
+        let _ = self.stop.send(());
+        let (mut sensors, during) = self.sampler.join().expect("thermal sampler panicked");
+        ThermalResult::from_samples(self.before, &during, sensors.sample())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(frequency_mhz: f64) -> ThermalSample {
+        ThermalSample {
+            frequency_mhz: Some(frequency_mhz),
+            temperature_c: None,
+        }
+    }
+
+    #[test]
+    fn test_steady_frequency_is_not_throttled() {
+        let during = [at(3000.0), at(3010.0), at(2990.0), at(3000.0)];
+        let result = ThermalResult::from_samples(at(1200.0), &during, at(3000.0));
+        assert_eq!(result.peak_frequency_mhz, Some(3010.0));
+        assert_eq!(result.sustained_frequency_mhz, Some(3000.0));
+        assert_eq!(result.samples, 4);
+        assert!(!result.throttled());
+    }
+
+    #[test]
+    fn test_sustained_frequency_drop_is_throttling() {
+        let during = [at(4000.0), at(3900.0), at(3000.0), at(2900.0), at(2950.0)];
+        let result = ThermalResult::from_samples(at(4000.0), &during, at(2900.0));
+        assert_eq!(result.sustained_frequency_mhz, Some(2950.0));
+        let drop = result.frequency_drop_percent().unwrap();
+        assert!((drop - 26.25).abs() < 1e-9, "{}", drop);
+        assert!(result.throttled());
+    }
+
+    #[test]
+    fn test_missing_readings() {
+        let none = ThermalSample::default();
+        let result = ThermalResult::from_samples(none, &[], none);
+        assert_eq!(result.peak_frequency_mhz, None);
+        assert_eq!(result.max_temperature_c, None);
+        assert_eq!(result.frequency_drop_percent(), None);
+        assert!(!result.throttled());
+    }
+
+    #[test]
+    fn test_monitor_samples_in_background() {
+        let monitor = ThermalMonitor::start();
+        thread::sleep(SAMPLE_INTERVAL * 2 + SAMPLE_INTERVAL / 2);
+        let result = monitor.finish();
+        assert!(result.samples >= 1, "{:?}", result);
+    }
+}
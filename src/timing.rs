@@ -0,0 +1,470 @@
+/// Repetition helpers for duration-based benchmarking
+/// Kernels are repeated until a minimum wall time has passed, so that a rate can be
+/// measured reliably regardless of how fast the machine runs one round
+/// Every reported rate is kept together with the raw measurement it was computed from
+/// Repetition stops early once the watchdog flags the running step as timed out
+/// The cost of reading the clock is calibrated once per process and subtracted from
+/// individually timed rounds and operations, where it would otherwise inflate latencies
+use crate::error::BenchError;
+use crate::stats::{Histogram, StreamingStats};
+use crate::watchdog;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Shortest measurement the auto-round CPU kernels accept when no minimum time is set
+pub const MIN_MEASURABLE_SECS: f64 = 0.01;
+
+/// Untimed warmup passes before the timed kernels of the CPU, memory, disk, and network
+/// benchmarks, unless configured otherwise
+pub const DEFAULT_WARMUP_RUNS: usize = 1;
+
+/// Share of the benchmark scale each warmup pass runs at
+pub const WARMUP_SCALE: f64 = 0.1;
+
+/// Clock reads timed per calibration batch, so one batch lasts well above the resolution
+const CALIBRATION_READS: usize = 100;
+
+/// Calibration batches; their median is the overhead, robust against interrupted batches
+const CALIBRATION_BATCHES: usize = 200;
+
+/// Cost and granularity of the monotonic clock (`Instant`) on this machine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimerCalibration {
+    /// Time one measurement adds to what it measures: reading the clock at the start and
+    /// again at the end, in nanoseconds
+    pub overhead_ns: f64,
+    /// Smallest step between two clock reads that differ, in nanoseconds; durations near it
+    /// cannot be told apart
+    pub resolution_ns: f64,
+}
+
+impl TimerCalibration {
+    fn overhead(&self) -> Duration {
+        Duration::from_nanos(self.overhead_ns as u64)
+    }
+}
+
+impl fmt::Display for TimerCalibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0} ns overhead, {:.0} ns resolution",
+            self.overhead_ns, self.resolution_ns
+        )
+    }
+}
+
+/// Calibration of the clock, measured on first use and then kept for the process
+/// The suite calls it before the first timed kernel, so the calibration does not run
+/// between the timed rounds of a benchmark
+pub fn calibration() -> TimerCalibration {
+    static CALIBRATION: OnceLock<TimerCalibration> = OnceLock::new();
+    *CALIBRATION.get_or_init(calibrate)
+}
+
+fn calibrate() -> TimerCalibration {
+    let mut batches: Vec<f64> = (0..CALIBRATION_BATCHES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..CALIBRATION_READS {
+                std::hint::black_box(std::hint::black_box(Instant::now()).elapsed());
+            }
+            start.elapsed().as_nanos() as f64 / CALIBRATION_READS as f64
+        })
+        .collect();
+    batches.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let overhead_ns = batches[batches.len() / 2];
+
+    let mut resolution = Duration::MAX;
+    for _ in 0..CALIBRATION_BATCHES {
+        let first = Instant::now();
+        let mut next = Instant::now();
+        while next == first {
+            next = Instant::now();
+        }
+        resolution = resolution.min(next - first);
+    }
+
+    TimerCalibration {
+        overhead_ns,
+        resolution_ns: resolution.as_nanos() as f64,
+    }
+}
+
+/// Time since `start` without the calibrated cost of the measurement itself, for timing
+/// single operations such as one disk block or one network round trip
+pub fn measured_since(start: Instant) -> Duration {
+    start.elapsed().saturating_sub(calibration().overhead())
+}
+
+/// Floating-point rate per thread no current core comes near (about 160 GFLOPS in f64 and
+/// 320 in f32 with two AVX-512 FMA units at 5 GHz); a higher rate means the work was
+/// optimized away
+pub const PLAUSIBLE_GFLOPS_PER_THREAD: f64 = 1000.0;
+
+/// Bandwidth per thread no current core comes near, even from L1 (two 64-byte loads and
+/// a store per cycle at 5 GHz is under 1 TB/s); a higher rate means the work was optimized away
+pub const PLAUSIBLE_GBS_PER_THREAD: f64 = 2000.0;
+
+/// Raw measurement behind one reported rate
+/// The rate is `work / elapsed_secs`, so it can be recomputed from the exported values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct KernelTiming {
+    /// Work done over all rounds, in the numerator unit of the rate (e.g. primes, GFLOP,
+    /// MB, million elements)
+    pub work: f64,
+    /// Wall time of all rounds in seconds, excluding untimed setup
+    pub elapsed_secs: f64,
+    /// Times the kernel was repeated (rounds or passes)
+    pub rounds: u64,
+    /// Distribution of the individual round times; None for kernels timed in batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_spread: Option<RoundSpread>,
+}
+
+impl KernelTiming {
+    /// Measurement of `rounds` repetitions doing `work_per_round` each
+    pub fn new(work_per_round: f64, rounds: u64, elapsed_secs: f64) -> Self {
+        Self {
+            work: work_per_round * rounds as f64,
+            elapsed_secs,
+            rounds,
+            round_spread: None,
+        }
+    }
+
+    pub fn with_round_spread(mut self, spread: RoundSpread) -> Self {
+        self.round_spread = Some(spread);
+        self
+    }
+
+    /// Work per second
+    pub fn rate(&self) -> f64 {
+        self.work / self.elapsed_secs.max(1e-9)
+    }
+
+    /// Seconds per unit of work, for latencies; 0 when no work was done
+    pub fn secs_per_unit(&self) -> f64 {
+        if self.work > 0.0 {
+            self.elapsed_secs / self.work
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Distribution of the round times within one measurement, which the rate averages away:
+/// a CV of a few percent is normal, a P99 far above the P50 means some rounds were
+/// interrupted, e.g. by another process, a frequency drop, or a page-fault storm
+/// Percentiles are at `Histogram` precision (about 6%)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoundSpread {
+    pub min_us: f64,
+    pub p50_us: f64,
+    pub p99_us: f64,
+    pub max_us: f64,
+    /// Coefficient of variation of the round times, in percent
+    pub cv_percent: f64,
+}
+
+/// Raw measurements of one benchmark run, keyed by the name of the rate they produced
+pub type KernelTimings = IndexMap<String, KernelTiming>;
+
+/// Run `kernel` repeatedly until at least `min_secs` seconds have passed (and at least once),
+/// or the step times out
+/// The clock is read between batches whose size is chosen from the rate measured so far,
+/// so fast kernels are not dominated by timer overhead and the target is not overshot; the
+/// calibrated cost of those reads is subtracted from the elapsed time
+/// Returns: (rounds executed, elapsed seconds)
+pub fn repeat_for(min_secs: f64, mut kernel: impl FnMut()) -> (u64, f64) {
+    let overhead_secs = calibration().overhead_ns / 1e9;
+    let start = Instant::now();
+    let mut rounds = 0u64;
+    let mut batch = 1u64;
+    let mut batches = 0u64;
+    loop {
+        for _ in 0..batch {
+            kernel();
+            rounds += 1;
+            if watchdog::expired() {
+                break;
+            }
+        }
+        batches += 1;
+        let elapsed = subtract_overhead(start.elapsed().as_secs_f64(), batches, overhead_secs);
+        if elapsed >= min_secs || watchdog::expired() {
+            return (rounds, elapsed);
+        }
+        // Aim for the target, but never more than double the rounds on an early estimate
+        let per_round = elapsed / rounds as f64;
+        let remaining_rounds = if per_round > 0.0 {
+            ((min_secs - elapsed) / per_round).ceil() as u64
+        } else {
+            rounds
+        };
+        batch = remaining_rounds.clamp(1, rounds);
+    }
+}
+
+/// `repeat_for` that times every round on its own and also returns their distribution
+/// The calibrated cost of reading the clock is subtracted from every round, but it still
+/// adds noise of its own, so this is meant for kernels whose rounds take microseconds or more
+/// Returns: (rounds executed, elapsed seconds, round time distribution)
+pub fn repeat_sampled(min_secs: f64, mut kernel: impl FnMut()) -> (u64, f64, RoundSpread) {
+    let overhead_secs = calibration().overhead_ns / 1e9;
+    let start = Instant::now();
+    let mut histogram = Histogram::new();
+    let mut round_secs = StreamingStats::new();
+    loop {
+        let round_start = Instant::now();
+        kernel();
+        let round = measured_since(round_start);
+        histogram.record(round);
+        round_secs.push(round.as_secs_f64());
+        let elapsed = subtract_overhead(
+            start.elapsed().as_secs_f64(),
+            histogram.samples(),
+            overhead_secs,
+        );
+        if elapsed >= min_secs || watchdog::expired() {
+            let summary = histogram.summary();
+            let spread = RoundSpread {
+                min_us: summary.min_us,
+                p50_us: summary.p50_us,
+                p99_us: summary.p99_us,
+                max_us: summary.max_us,
+                cv_percent: round_secs
+                    .statistics()
+                    .map_or(0.0, |s| s.coefficient_of_variation),
+            };
+            return (histogram.samples(), elapsed, spread);
+        }
+    }
+}
+
+/// `elapsed` seconds without the cost of `reads` clock measurements, never below a tenth
+/// of it, should the calibration overestimate the cost
+fn subtract_overhead(elapsed: f64, reads: u64, overhead_secs: f64) -> f64 {
+    (elapsed - reads as f64 * overhead_secs).max(elapsed / 10.0)
+}
+
+/// Run `pass` repeatedly until the times it reports add up to at least `min_secs`
+/// (and at least once); for passes that time themselves and exclude their setup
+/// Returns: (passes executed, sum of the reported seconds)
+pub fn repeat_passes(min_secs: f64, mut pass: impl FnMut() -> f64) -> (u64, f64) {
+    let Ok(result) = try_repeat_passes(min_secs, || Ok::<f64, std::convert::Infallible>(pass()));
+    result
+}
+
+/// Fail on the first timing that completed no rounds or measured an invalid duration, which
+/// is what a kernel that could not run reports; its rate would describe work that never happened
+pub fn check_timings(subsystem: &str, timings: &KernelTimings) -> Result<(), BenchError> {
+    let invalid = timings.iter().find(|(_, timing)| {
+        timing.rounds == 0 || !timing.elapsed_secs.is_finite() || timing.elapsed_secs < 0.0
+    });
+    match invalid {
+        Some((name, _)) => Err(BenchError::InvalidResult(format!(
+            "{} {} completed no measurement",
+            subsystem, name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Fail on the first rate above the ceiling `ceiling` returns for its name (None for rates
+/// without one): no hardware reaches it, so the kernel's work was optimized away, e.g. after
+/// an optimization-level or LTO change, and the rate describes work that never happened
+pub fn check_plausible(
+    subsystem: &str,
+    timings: &KernelTimings,
+    ceiling: impl Fn(&str) -> Option<f64>,
+) -> Result<(), BenchError> {
+    for (name, timing) in timings {
+        if let Some(ceiling) = ceiling(name) {
+            if timing.rate() > ceiling {
+                return Err(BenchError::InvalidResult(format!(
+                    "{} {} of {:.0} is above the plausible {:.0}; its work was likely optimized away",
+                    subsystem,
+                    name,
+                    timing.rate(),
+                    ceiling
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `repeat_passes` for passes that can fail; stops at the first error and returns it
+pub fn try_repeat_passes<E>(
+    min_secs: f64,
+    pass: impl FnMut() -> Result<f64, E>,
+) -> Result<(u64, f64), E> {
+    try_repeat_passes_min(1, min_secs, pass)
+}
+
+/// `try_repeat_passes` that also runs at least `min_passes` passes, unless the watchdog
+/// cuts it short
+pub fn try_repeat_passes_min<E>(
+    min_passes: u64,
+    min_secs: f64,
+    mut pass: impl FnMut() -> Result<f64, E>,
+) -> Result<(u64, f64), E> {
+    let mut passes = 0u64;
+    let mut total = 0.0;
+    loop {
+        total += pass()?;
+        passes += 1;
+        if (passes >= min_passes && total >= min_secs) || watchdog::expired() {
+            return Ok((passes, total));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_for_runs_at_least_once() {
+        let mut calls = 0;
+        let (rounds, elapsed) = repeat_for(0.0, || calls += 1);
+        assert_eq!((rounds, calls), (1, 1));
+        assert!(elapsed >= 0.0);
+    }
+
+    #[test]
+    fn test_repeat_for_reaches_min_time() {
+        let mut calls = 0u64;
+        let (rounds, elapsed) = repeat_for(0.02, || {
+            calls += 1;
+            std::hint::black_box((0..1000u64).sum::<u64>());
+        });
+        assert_eq!(rounds, calls);
+        assert!(elapsed >= 0.02);
+        // Batches are sized from the measured rate, so the target is not grossly overshot
+        assert!(elapsed < 1.0, "overshot the target: {elapsed}s");
+    }
+
+    #[test]
+    fn test_kernel_timing_rate() {
+        let timing = KernelTiming::new(2.5, 4, 0.5);
+        assert_eq!(timing.work, 10.0);
+        assert_eq!(timing.rate(), 20.0);
+        // A zero-length measurement does not divide by zero
+        assert!(KernelTiming::new(1.0, 1, 0.0).rate().is_finite());
+        assert_eq!(timing.secs_per_unit(), 0.05);
+        assert_eq!(KernelTiming::default().secs_per_unit(), 0.0);
+    }
+
+    #[test]
+    fn test_timer_calibration() {
+        let calibration = calibration();
+        // Reading the clock takes time but far less than a millisecond, and the clock
+        // resolves steps finer than a millisecond on every supported platform
+        assert!(calibration.overhead_ns > 0.0);
+        assert!(calibration.overhead_ns < 1e6, "{:?}", calibration);
+        assert!(calibration.resolution_ns > 0.0);
+        assert!(calibration.resolution_ns < 1e6, "{:?}", calibration);
+        assert_eq!(calibration, super::calibration());
+
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(measured_since(start) >= Duration::from_millis(2) - calibration.overhead());
+        assert_eq!(subtract_overhead(1.0, 10, 0.01), 0.9);
+        assert_eq!(subtract_overhead(1.0, 1000, 0.01), 0.1);
+    }
+
+    #[test]
+    fn test_repeat_sampled_times_every_round() {
+        let mut calls = 0;
+        let (rounds, elapsed, spread) = repeat_sampled(0.02, || {
+            calls += 1;
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        });
+        assert_eq!(rounds, calls);
+        assert!(elapsed >= 0.02);
+        assert!(spread.min_us >= 1000.0);
+        assert!(spread.min_us <= spread.p50_us && spread.p50_us <= spread.p99_us);
+        assert!(spread.p99_us <= spread.max_us);
+        assert!(spread.cv_percent >= 0.0);
+
+        // serde_json may parse a float one ulp off, so round-trip whole microseconds
+        let spread = RoundSpread {
+            min_us: spread.min_us.round(),
+            p50_us: spread.p50_us.round(),
+            p99_us: spread.p99_us.round(),
+            max_us: spread.max_us.round(),
+            cv_percent: spread.cv_percent.round(),
+        };
+        let timing = KernelTiming::new(1.0, rounds, 0.5).with_round_spread(spread);
+        let json = serde_json::to_string(&timing).unwrap();
+        assert_eq!(serde_json::from_str::<KernelTiming>(&json).unwrap(), timing);
+        // Batched timings export no spread
+        let json = serde_json::to_string(&KernelTiming::new(1.0, 1, 1.0)).unwrap();
+        assert!(!json.contains("round_spread"));
+    }
+
+    #[test]
+    fn test_repeat_passes_sums_reported_times() {
+        let (passes, total) = repeat_passes(1.0, || 0.3);
+        assert_eq!(passes, 4);
+        assert!((total - 1.2).abs() < 1e-9);
+        assert_eq!(repeat_passes(0.0, || 0.3).0, 1);
+
+        let mut calls = 0;
+        let failed = try_repeat_passes(1.0, || {
+            calls += 1;
+            if calls < 3 {
+                Ok(0.3)
+            } else {
+                Err("disk full")
+            }
+        });
+        assert_eq!(failed, Err("disk full"));
+        assert_eq!(calls, 3);
+
+        let passes = try_repeat_passes_min(4, 0.5, || Ok::<f64, ()>(0.25));
+        assert_eq!(passes, Ok((4, 1.0)));
+    }
+
+    #[test]
+    fn test_check_timings_rejects_empty_measurements() {
+        let mut timings = KernelTimings::new();
+        timings.insert("write_mibs".to_string(), KernelTiming::new(64.0, 2, 0.1));
+        assert!(check_timings("disk", &timings).is_ok());
+
+        timings.insert("fsync_ops_per_sec".to_string(), KernelTiming::default());
+        let error = check_timings("disk", &timings).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid result: disk fsync_ops_per_sec completed no measurement"
+        );
+
+        timings.insert(
+            "fsync_ops_per_sec".to_string(),
+            KernelTiming::new(1.0, 1, f64::NAN),
+        );
+        assert!(check_timings("disk", &timings).is_err());
+    }
+
+    #[test]
+    fn test_check_plausible_rejects_impossible_rates() {
+        let mut timings = KernelTimings::new();
+        timings.insert("matrix_gflops".to_string(), KernelTiming::new(2.0, 10, 1.0));
+        timings.insert(
+            "primes_per_sec".to_string(),
+            KernelTiming::new(1e12, 1, 1.0),
+        );
+        let ceiling = |name: &str| name.ends_with("gflops").then_some(100.0);
+        assert!(check_plausible("cpu", &timings, ceiling).is_ok());
+
+        // A multiplication that took no time at all
+        timings.insert("matrix_gflops".to_string(), KernelTiming::new(2.0, 10, 0.0));
+        let error = check_plausible("cpu", &timings, ceiling).unwrap_err();
+        assert!(error.to_string().contains("cpu matrix_gflops"), "{}", error);
+    }
+}
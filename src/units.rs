@@ -0,0 +1,353 @@
+/// Units of the reported metrics
+/// Every metric carries a `Unit` instead of spelling its unit in a label or print string, so
+/// the JSON and CSV reports name units explicitly and the console converts values the same
+/// way everywhere. Units of the same `Quantity` convert into each other by their scale, e.g.
+/// MB/s to MiB/s or GFLOPS to MFLOPS; the remaining units count something specific (primes,
+/// pixels, files) and are shown as measured.
+/// Byte rates are measured in MiB/s (the MB/s-named rates of the memory, disk, network, and
+/// CPU kernels) or GB/s (bandwidths, as published STREAM results are); `UnitSystem` picks
+/// whether they are shown with decimal or binary prefixes.
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Bytes in a mebibyte, the unit the MB/s-named rates are measured in
+pub const MEBIBYTE: f64 = 1024.0 * 1024.0;
+
+/// Prefixes byte rates are shown with (`--units`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// Decimal prefixes: MB/s (10^6 bytes/s) and GB/s (10^9 bytes/s)
+    #[default]
+    Si,
+    /// Binary prefixes: MiB/s (2^20 bytes/s) and GiB/s (2^30 bytes/s)
+    Binary,
+}
+
+impl UnitSystem {
+    pub const ALL: [UnitSystem; 2] = [UnitSystem::Si, UnitSystem::Binary];
+
+    /// Case-insensitive lookup by name, e.g. "binary"
+    pub fn from_name(name: &str) -> Option<Self> {
+        UnitSystem::ALL
+            .into_iter()
+            .find(|system| system.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitSystem::Si => "si",
+            UnitSystem::Binary => "binary",
+        }
+    }
+}
+
+/// What a convertible unit measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantity {
+    /// Bytes per second
+    ByteRate,
+    /// Floating-point operations per second
+    FloatOps,
+    /// Operations, allocations, or messages per second
+    Events,
+    /// Seconds
+    Duration,
+    /// Hertz
+    Frequency,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    MegabytesPerSec,
+    MebibytesPerSec,
+    GigabytesPerSec,
+    GibibytesPerSec,
+    Mflops,
+    Gflops,
+    GflopsPerWatt,
+    /// Billions of integer operations per second
+    Giops,
+    /// Billions of i8 multiply-accumulate operations per second
+    Gops,
+    OpsPerSec,
+    AllocsPerSec,
+    MessagesPerSec,
+    /// Millions of operations (or allocations) per second
+    MillionsPerSec,
+    MillionMessagesPerSec,
+    Iops,
+    PrimesPerSec,
+    PixelsPerSec,
+    RaysPerSec,
+    PacketsPerSec,
+    FilesPerSec,
+    /// Pages faulted in per second
+    PagesPerSec,
+    MelemPerSec,
+    MsamplesPerSec,
+    MinteractionsPerSec,
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Megahertz,
+    Gigahertz,
+    Celsius,
+    Joules,
+    Watts,
+    /// A kernel's rate per watt of package power, in the kernel's unit per watt
+    PerWatt,
+    /// Ratio of two rates, e.g. a speedup
+    Ratio,
+    /// Composite score relative to the reference system; has no symbol
+    Score,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 36] = [
+        Unit::MegabytesPerSec,
+        Unit::MebibytesPerSec,
+        Unit::GigabytesPerSec,
+        Unit::GibibytesPerSec,
+        Unit::Mflops,
+        Unit::Gflops,
+        Unit::GflopsPerWatt,
+        Unit::Giops,
+        Unit::Gops,
+        Unit::OpsPerSec,
+        Unit::AllocsPerSec,
+        Unit::MessagesPerSec,
+        Unit::MillionsPerSec,
+        Unit::MillionMessagesPerSec,
+        Unit::Iops,
+        Unit::PrimesPerSec,
+        Unit::PixelsPerSec,
+        Unit::RaysPerSec,
+        Unit::PacketsPerSec,
+        Unit::FilesPerSec,
+        Unit::PagesPerSec,
+        Unit::MelemPerSec,
+        Unit::MsamplesPerSec,
+        Unit::MinteractionsPerSec,
+        Unit::Nanoseconds,
+        Unit::Microseconds,
+        Unit::Milliseconds,
+        Unit::Seconds,
+        Unit::Megahertz,
+        Unit::Gigahertz,
+        Unit::Celsius,
+        Unit::Joules,
+        Unit::Watts,
+        Unit::PerWatt,
+        Unit::Ratio,
+        Unit::Score,
+    ];
+
+    /// Unit with the given symbol, e.g. "MB/s"
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        Unit::ALL.into_iter().find(|unit| unit.symbol() == symbol)
+    }
+
+    /// Symbol written after values and in reports, e.g. "MB/s"; empty for scores
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::MegabytesPerSec => "MB/s",
+            Unit::MebibytesPerSec => "MiB/s",
+            Unit::GigabytesPerSec => "GB/s",
+            Unit::GibibytesPerSec => "GiB/s",
+            Unit::Mflops => "MFLOPS",
+            Unit::Gflops => "GFLOPS",
+            Unit::GflopsPerWatt => "GFLOPS/W",
+            Unit::Giops => "GIOPS",
+            Unit::Gops => "GOPS",
+            Unit::OpsPerSec => "ops/sec",
+            Unit::AllocsPerSec => "allocs/sec",
+            Unit::MessagesPerSec => "msgs/sec",
+            Unit::MillionsPerSec => "M/s",
+            Unit::MillionMessagesPerSec => "M msgs/s",
+            Unit::Iops => "IOPS",
+            Unit::PrimesPerSec => "primes/sec",
+            Unit::PixelsPerSec => "pixels/sec",
+            Unit::RaysPerSec => "rays/sec",
+            Unit::PacketsPerSec => "packets/sec",
+            Unit::FilesPerSec => "files/sec",
+            Unit::PagesPerSec => "pages/sec",
+            Unit::MelemPerSec => "Melem/s",
+            Unit::MsamplesPerSec => "Msamples/sec",
+            Unit::MinteractionsPerSec => "Minteractions/sec",
+            Unit::Nanoseconds => "ns",
+            Unit::Microseconds => "us",
+            Unit::Milliseconds => "ms",
+            Unit::Seconds => "s",
+            Unit::Megahertz => "MHz",
+            Unit::Gigahertz => "GHz",
+            Unit::Celsius => "C",
+            Unit::Joules => "J",
+            Unit::Watts => "W",
+            Unit::PerWatt => "/W",
+            Unit::Ratio => "x",
+            Unit::Score => "",
+        }
+    }
+
+    /// Quantity the unit measures and the size of one unit in the quantity's base unit
+    /// (bytes/s, FLOPS, events/s, seconds, Hz); None for units that convert into no other
+    fn scale(&self) -> Option<(Quantity, f64)> {
+        let scale = match self {
+            Unit::MegabytesPerSec => (Quantity::ByteRate, 1e6),
+            Unit::MebibytesPerSec => (Quantity::ByteRate, MEBIBYTE),
+            Unit::GigabytesPerSec => (Quantity::ByteRate, 1e9),
+            Unit::GibibytesPerSec => (Quantity::ByteRate, MEBIBYTE * 1024.0),
+            Unit::Mflops => (Quantity::FloatOps, 1e6),
+            Unit::Gflops => (Quantity::FloatOps, 1e9),
+            Unit::OpsPerSec | Unit::AllocsPerSec | Unit::MessagesPerSec => (Quantity::Events, 1.0),
+            Unit::MillionsPerSec | Unit::MillionMessagesPerSec => (Quantity::Events, 1e6),
+            Unit::Nanoseconds => (Quantity::Duration, 1e-9),
+            Unit::Microseconds => (Quantity::Duration, 1e-6),
+            Unit::Milliseconds => (Quantity::Duration, 1e-3),
+            Unit::Seconds => (Quantity::Duration, 1.0),
+            Unit::Megahertz => (Quantity::Frequency, 1e6),
+            Unit::Gigahertz => (Quantity::Frequency, 1e9),
+            _ => return None,
+        };
+        Some(scale)
+    }
+
+    /// Quantity the unit measures; None for units that convert into no other
+    pub fn quantity(&self) -> Option<Quantity> {
+        self.scale().map(|(quantity, _)| quantity)
+    }
+
+    /// `value` in this unit expressed in `to`; None when the units measure different
+    /// quantities
+    pub fn convert(&self, value: f64, to: Unit) -> Option<f64> {
+        if *self == to {
+            return Some(value);
+        }
+        let (quantity, from_scale) = self.scale()?;
+        let (to_quantity, to_scale) = to.scale()?;
+        (quantity == to_quantity).then(|| value * from_scale / to_scale)
+    }
+
+    /// The unit byte rates in this unit are shown in under `system`, e.g. MB/s for MiB/s
+    /// under `UnitSystem::Si`; other units are returned unchanged
+    pub fn in_system(&self, system: UnitSystem) -> Unit {
+        match (self, system) {
+            (Unit::MebibytesPerSec, UnitSystem::Si) => Unit::MegabytesPerSec,
+            (Unit::GibibytesPerSec, UnitSystem::Si) => Unit::GigabytesPerSec,
+            (Unit::MegabytesPerSec, UnitSystem::Binary) => Unit::MebibytesPerSec,
+            (Unit::GigabytesPerSec, UnitSystem::Binary) => Unit::GibibytesPerSec,
+            _ => *self,
+        }
+    }
+
+    /// `value` in this unit shown under `system`, and the unit it is shown in
+    pub fn display(&self, value: f64, system: UnitSystem) -> (f64, Unit) {
+        let unit = self.in_system(system);
+        (self.convert(value, unit).unwrap_or(value), unit)
+    }
+
+    /// `name` followed by the symbol in parentheses, e.g. "Memory Write (MB/s)"; the name
+    /// alone for scores
+    pub fn label(&self, name: &str) -> String {
+        if self.symbol().is_empty() {
+            name.to_string()
+        } else {
+            format!("{} ({})", name, self.symbol())
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.symbol())
+    }
+}
+
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = String::deserialize(deserializer)?;
+        Unit::from_symbol(&symbol)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown unit '{}'", symbol)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_are_unique_and_roundtrip() {
+        for unit in Unit::ALL {
+            assert_eq!(Unit::from_symbol(unit.symbol()), Some(unit));
+            let json = serde_json::to_string(&unit).unwrap();
+            assert_eq!(serde_json::from_str::<Unit>(&json).unwrap(), unit);
+        }
+        assert!(serde_json::from_str::<Unit>("\"furlongs\"").is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mibs = Unit::MegabytesPerSec
+            .convert(1000.0, Unit::MebibytesPerSec)
+            .unwrap();
+        assert!((mibs - 953.674).abs() < 1e-3);
+        assert_eq!(Unit::Gflops.convert(2.5, Unit::Mflops), Some(2500.0));
+        assert_eq!(
+            Unit::GigabytesPerSec.convert(1.5, Unit::MegabytesPerSec),
+            Some(1500.0)
+        );
+        assert_eq!(
+            Unit::OpsPerSec.convert(2e6, Unit::MillionsPerSec),
+            Some(2.0)
+        );
+        assert_eq!(
+            Unit::Microseconds.convert(1.5, Unit::Nanoseconds),
+            Some(1500.0)
+        );
+        assert_eq!(
+            Unit::PrimesPerSec.convert(1.0, Unit::PrimesPerSec),
+            Some(1.0)
+        );
+        // Different quantities, or units that count different things, do not convert
+        assert_eq!(Unit::Gflops.convert(1.0, Unit::GigabytesPerSec), None);
+        assert_eq!(Unit::PixelsPerSec.convert(1.0, Unit::RaysPerSec), None);
+    }
+
+    #[test]
+    fn test_unit_systems() {
+        assert_eq!(UnitSystem::from_name("Binary"), Some(UnitSystem::Binary));
+        assert_eq!(UnitSystem::from_name("iec"), None);
+        assert_eq!(UnitSystem::default(), UnitSystem::Si);
+
+        let (value, unit) = Unit::MebibytesPerSec.display(1000.0, UnitSystem::Si);
+        assert_eq!(unit, Unit::MegabytesPerSec);
+        assert!((value - 1048.576).abs() < 1e-9);
+        assert_eq!(
+            Unit::GigabytesPerSec.display(2.0, UnitSystem::Si),
+            (2.0, Unit::GigabytesPerSec)
+        );
+        let (value, unit) = Unit::GigabytesPerSec.display(2.0, UnitSystem::Binary);
+        assert_eq!(unit, Unit::GibibytesPerSec);
+        assert!((value - 1.8626).abs() < 1e-4);
+        assert_eq!(
+            Unit::Gflops.display(2.0, UnitSystem::Binary),
+            (2.0, Unit::Gflops)
+        );
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(
+            Unit::MegabytesPerSec.label("Disk Write"),
+            "Disk Write (MB/s)"
+        );
+        assert_eq!(Unit::Score.label("Score CPU"), "Score CPU");
+    }
+}
@@ -0,0 +1,355 @@
+/// Upload of JSON reports to a results server
+/// POSTs a report as `application/json` to a user-specified HTTP endpoint, so a fleet of
+/// machines can collect its results in one place. Connection errors and 408, 429, and 5xx
+/// responses are retried with exponential backoff; other responses are final. Requests use
+/// plain HTTP/1.1 over the standard library, so HTTPS endpoints need a TLS-terminating proxy.
+/// The bearer token would cross the network in clear text, so it is only sent to loopback
+/// hosts, e.g. a proxy running on the same machine.
+use crate::interrupt;
+use crate::report::{self, SuiteReport};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{IpAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Environment variable holding the token sent as `Authorization: Bearer <token>`; kept out
+/// of the command line and configuration files so it does not end up in shell history
+pub const TOKEN_ENV: &str = "HSBENCH_UPLOAD_TOKEN";
+
+/// Attempts per upload, including the first
+pub const MAX_ATTEMPTS: usize = 4;
+
+/// Wait before the first retry; doubled before every further retry
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Limit for connecting and for every read and write of a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Successful upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Upload {
+    /// HTTP status of the accepted request, e.g. 201
+    pub status: u16,
+    pub attempts: usize,
+}
+
+/// Endpoint of an `http://host[:port]/path` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Endpoint {
+    /// Host as written in the URL, e.g. "results.local" or "[::1]"
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> io::Result<Self> {
+        let invalid = |message: &str| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", message, url))
+        };
+        if url.starts_with("https://") {
+            return Err(invalid(
+                "HTTPS is not supported, use an http:// endpoint or a TLS-terminating proxy",
+            ));
+        }
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| invalid("upload URL must start with http://"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, "/"),
+        };
+        // The port follows the last colon, unless that colon is inside an IPv6 literal
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => (
+                host,
+                port.parse()
+                    .map_err(|_| invalid("invalid port in upload URL"))?,
+            ),
+            _ => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid("upload URL has no host"));
+        }
+        Ok(Endpoint {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// Whether the host is this machine: "localhost" or a loopback address literal; names
+    /// are not resolved, as a name that resolves to loopback today may not tomorrow
+    fn is_loopback(&self) -> bool {
+        let host = self.host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .parse::<IpAddr>()
+                .is_ok_and(|address| address.is_loopback())
+    }
+
+    /// Host header value; the port is left out when it is the default
+    fn host_header(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Check that `url` is a valid upload endpoint and, if a `token` is given, that sending it
+/// over plain HTTP keeps it on this machine
+pub fn check_endpoint(url: &str, token: Option<&str>) -> io::Result<()> {
+    let endpoint = Endpoint::parse(url)?;
+    if token.is_some() && !endpoint.is_loopback() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is not sent over plain HTTP to {}, a host other than this machine; \
+                 upload through a TLS-terminating proxy on localhost instead",
+                TOKEN_ENV, endpoint.host
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Why a single attempt failed, and whether another attempt may succeed
+struct AttemptError {
+    error: io::Error,
+    retryable: bool,
+}
+
+/// POST `report` as JSON to `url`, authenticated with `token` if given
+/// `on_retry` is called before each retry with the failed attempt's number, its error, and
+/// the wait before the next attempt
+/// Returns: the accepted request's status and the attempts it took; the last error after
+/// `MAX_ATTEMPTS` failures, on a final (e.g. 401) response, or when interrupted; an
+/// `InvalidInput` error without connecting when `check_endpoint` rejects the URL
+pub fn upload_report(
+    report: &SuiteReport,
+    url: &str,
+    token: Option<&str>,
+    on_retry: impl FnMut(usize, &io::Error, Duration),
+) -> io::Result<Upload> {
+    let body = serde_json::to_vec(report)?;
+    post_with_retry(url, &body, token, INITIAL_BACKOFF, on_retry)
+}
+
+fn post_with_retry(
+    url: &str,
+    body: &[u8],
+    token: Option<&str>,
+    initial_backoff: Duration,
+    mut on_retry: impl FnMut(usize, &io::Error, Duration),
+) -> io::Result<Upload> {
+    check_endpoint(url, token)?;
+    let endpoint = Endpoint::parse(url)?;
+    let mut backoff = initial_backoff;
+    for attempt in 1.. {
+        match post(&endpoint, body, token) {
+            Ok(status) => {
+                return Ok(Upload {
+                    status,
+                    attempts: attempt,
+                })
+            }
+            Err(failure) => {
+                if !failure.retryable || attempt >= MAX_ATTEMPTS || interrupt::interrupted() {
+                    return Err(failure.error);
+                }
+                on_retry(attempt, &failure.error, backoff);
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    }
+    unreachable!("the retry loop returns")
+}
+
+/// Send one request and read the response status
+fn post(endpoint: &Endpoint, body: &[u8], token: Option<&str>) -> Result<u16, AttemptError> {
+    let retryable = |error: io::Error| AttemptError {
+        error,
+        retryable: true,
+    };
+    let host = endpoint.host.trim_start_matches('[').trim_end_matches(']');
+    let address = (host, endpoint.port)
+        .to_socket_addrs()
+        .map_err(retryable)?
+        .next()
+        .ok_or_else(|| retryable(io::Error::other(format!("no address for {}", host))))?;
+    let mut stream = TcpStream::connect_timeout(&address, REQUEST_TIMEOUT).map_err(retryable)?;
+    stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(REQUEST_TIMEOUT)))
+        .map_err(retryable)?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: HsBenchMarkSuite/{}\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        endpoint.path,
+        endpoint.host_header(),
+        report::SUITE_VERSION,
+        body.len()
+    );
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|()| stream.write_all(body))
+        .and_then(|()| stream.flush())
+        .map_err(retryable)?;
+
+    // Only the status line matters; the rest of the response is ignored
+    let mut status_line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut status_line)
+        .map_err(retryable)?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| {
+            retryable(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid HTTP response: {:?}", status_line.trim_end()),
+            ))
+        })?;
+    match status {
+        200..=299 => Ok(status),
+        _ => Err(AttemptError {
+            error: io::Error::other(format!("server responded {}", status_line.trim_end())),
+            retryable: status == 408 || status == 429 || status >= 500,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_endpoint_parse() {
+        let endpoint = Endpoint::parse("http://results.local:8080/api/runs").unwrap();
+        assert_eq!(endpoint.host, "results.local");
+        assert_eq!(endpoint.port, 8080);
+        assert_eq!(endpoint.path, "/api/runs");
+        assert_eq!(endpoint.host_header(), "results.local:8080");
+
+        let endpoint = Endpoint::parse("http://[::1]").unwrap();
+        assert_eq!((endpoint.host.as_str(), endpoint.port), ("[::1]", 80));
+        assert_eq!(endpoint.path, "/");
+
+        assert!(Endpoint::parse("https://results.local/").is_err());
+        assert!(Endpoint::parse("results.local/api").is_err());
+        assert!(Endpoint::parse("http://results.local:http/").is_err());
+    }
+
+    #[test]
+    fn test_token_is_only_sent_to_loopback_hosts() {
+        for url in [
+            "http://localhost:8080/runs",
+            "http://127.0.0.1/runs",
+            "http://[::1]:8080/",
+        ] {
+            assert!(check_endpoint(url, Some("secret")).is_ok(), "{}", url);
+        }
+        let error = post_with_retry(
+            "http://results.lab:8080/runs",
+            b"{}",
+            Some("secret"),
+            Duration::from_millis(1),
+            |_, _, _| panic!("a refused token must not be retried"),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(error.to_string().contains(TOKEN_ENV), "{}", error);
+        // Without a token, any host may be used
+        assert!(check_endpoint("http://results.lab:8080/runs", None).is_ok());
+    }
+
+    /// Serve one connection per status in `statuses`, returning the requests received
+    fn serve(statuses: &[u16]) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/runs", listener.local_addr().unwrap());
+        let statuses = statuses.to_vec();
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // Read until the header and the body of the Content-Length are complete
+                loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length: usize = text
+                            .lines()
+                            .find_map(|line| line.strip_prefix("Content-Length: "))
+                            .map_or(0, |length| length.trim().parse().unwrap());
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                requests.push(String::from_utf8_lossy(&request).to_string());
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Length: 0\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_upload_retries_server_errors() {
+        let (url, server) = serve(&[503, 201]);
+        let mut retries = Vec::new();
+        let upload = post_with_retry(
+            &url,
+            b"{\"results\":{}}",
+            Some("secret"),
+            Duration::from_millis(1),
+            |attempt, _, _| retries.push(attempt),
+        )
+        .unwrap();
+        assert_eq!(
+            upload,
+            Upload {
+                status: 201,
+                attempts: 2
+            }
+        );
+        assert_eq!(retries, vec![1]);
+
+        let requests = server.join().unwrap();
+        assert!(requests[1].starts_with("POST /runs HTTP/1.1\r\n"));
+        assert!(requests[1].contains("Authorization: Bearer secret\r\n"));
+        assert!(requests[1].ends_with("\r\n\r\n{\"results\":{}}"));
+    }
+
+    #[test]
+    fn test_upload_does_not_retry_client_errors() {
+        let (url, server) = serve(&[401]);
+        let error = post_with_retry(&url, b"{}", None, Duration::from_millis(1), |_, _, _| {
+            panic!("401 must not be retried")
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("401"), "{}", error);
+        assert!(!server.join().unwrap()[0].contains("Authorization"));
+    }
+}
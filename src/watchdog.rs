@@ -0,0 +1,220 @@
+/// Per-step time limit of a benchmark run
+/// `Watchdog::run` runs a phase on a worker thread and waits for each of its steps with a
+/// timeout. Once a step runs past the limit, it is recorded as timed out and the phase's
+/// expired flag is set; the repetition helpers in `timing` check the flag on the worker
+/// thread, so the step stops after the round in progress and the phase carries on. A round
+/// that still has not returned after a grace period (e.g. a write blocked in the kernel)
+/// is not waited for: the worker is abandoned, left to finish or stay blocked on its own,
+/// and the phase yields no result.
+use crate::progress::Progress;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Shortest time a timed-out step is given to finish its round before it is abandoned
+const MIN_GRACE: Duration = Duration::from_secs(1);
+
+thread_local! {
+    /// Expired flag of the watched phase running on this thread
+    static EXPIRED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Whether the step running on this thread has exceeded its time limit; always false
+/// outside a phase run by `Watchdog::run`
+pub fn expired() -> bool {
+    EXPIRED.with(|expired| {
+        expired
+            .borrow()
+            .as_ref()
+            .is_some_and(|expired| expired.load(Ordering::SeqCst))
+    })
+}
+
+/// Outcome of a phase run by `Watchdog::run`
+#[derive(Debug)]
+pub struct Watched<T> {
+    /// The phase's result; None when it was abandoned
+    pub result: Option<T>,
+    /// Steps that exceeded the limit, in order
+    pub timed_out: Vec<String>,
+}
+
+/// Time limit enforced on every step of the phases run through `run`
+#[derive(Debug, Clone, Copy)]
+pub struct Watchdog {
+    limit: Duration,
+}
+
+/// Message from a watched phase's worker thread
+enum Message<T> {
+    Step(String),
+    Done(T),
+}
+
+/// Progress of a watched phase, forwarded to the thread waiting for it
+struct StepSender<T> {
+    sender: mpsc::Sender<Message<T>>,
+    expired: Arc<AtomicBool>,
+}
+
+impl<T> Progress for StepSender<T> {
+    /// Clear the expired flag here, so the new step never sees the previous one's timeout
+    fn step(&mut self, name: &str) {
+        self.expired.store(false, Ordering::SeqCst);
+        let _ = self.sender.send(Message::Step(name.to_string()));
+    }
+}
+
+impl Watchdog {
+    /// Watchdog that allows every step `limit` before it times out
+    pub fn new(limit: Duration) -> Self {
+        Watchdog { limit }
+    }
+
+    /// Time each step is allowed
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// Time a timed-out step is given to finish its round before the phase is abandoned
+    pub fn grace(&self) -> Duration {
+        (self.limit / 4).max(MIN_GRACE)
+    }
+
+    /// Run `phase` on a worker thread, timing its steps from a first step named "setup";
+    /// `on_step` is called on this thread with the name of every step the phase reports
+    /// Several phases may run at the same time, each with its own expired flag
+    /// A panic in `phase` is passed on
+    pub fn run<T: Send + 'static>(
+        &self,
+        phase: impl FnOnce(&mut dyn Progress) -> T + Send + 'static,
+        mut on_step: impl FnMut(&str),
+    ) -> Watched<T> {
+        let expired = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let worker = {
+            let expired = Arc::clone(&expired);
+            std::thread::Builder::new()
+                .name("benchmark phase".to_string())
+                .spawn(move || {
+                    EXPIRED.with(|flag| *flag.borrow_mut() = Some(Arc::clone(&expired)));
+                    let result = phase(&mut StepSender {
+                        sender: sender.clone(),
+                        expired,
+                    });
+                    let _ = sender.send(Message::Done(result));
+                })
+                .expect("failed to spawn a benchmark phase thread")
+        };
+
+        let mut step = "setup".to_string();
+        let mut deadline = Instant::now() + self.limit;
+        let mut timed_out = Vec::new();
+        loop {
+            match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Message::Step(name)) => {
+                    on_step(&name);
+                    step = name;
+                    deadline = Instant::now() + self.limit;
+                }
+                Ok(Message::Done(result)) => {
+                    return Watched {
+                        result: Some(result),
+                        timed_out,
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) if !expired.swap(true, Ordering::SeqCst) => {
+                    timed_out.push(step.clone());
+                    deadline = Instant::now() + self.grace();
+                }
+                // Still in the same round after the grace period: abandon the worker
+                Err(RecvTimeoutError::Timeout) => {
+                    return Watched {
+                        result: None,
+                        timed_out,
+                    }
+                }
+                // The worker only hangs up without a result when it panicked
+                Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                    Err(panic) => std::panic::resume_unwind(panic),
+                    Ok(()) => unreachable!("a finished phase sends its result"),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sleep in short rounds until the watchdog flags the step, like the `timing` helpers
+    fn wait_until_expired() {
+        let start = Instant::now();
+        while !expired() && start.elapsed() < Duration::from_secs(10) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_watchdog_flags_only_steps_past_the_limit() {
+        let watchdog = Watchdog::new(Duration::from_millis(50));
+        let mut steps = Vec::new();
+        let watched = watchdog.run(
+            |progress| {
+                progress.step("fast");
+                progress.step("slow");
+                wait_until_expired();
+                let slow_expired = expired();
+                progress.step("next");
+                (slow_expired, expired())
+            },
+            |step| steps.push(step.to_string()),
+        );
+        assert_eq!(watched.result, Some((true, false)));
+        assert_eq!(watched.timed_out, vec!["slow".to_string()]);
+        assert_eq!(steps, ["fast", "slow", "next"]);
+        // The flag belongs to the watched phase, not to this thread
+        assert!(!expired());
+    }
+
+    #[test]
+    fn test_watchdog_abandons_a_step_that_never_returns() {
+        let watchdog = Watchdog::new(Duration::from_millis(20));
+        let (_release, blocked) = mpsc::channel::<()>();
+        let start = Instant::now();
+        let watched = watchdog.run(
+            move |progress| {
+                progress.step("stuck");
+                // Blocks until the test ends, ignoring the expired flag
+                let _ = blocked.recv();
+            },
+            |_| {},
+        );
+        assert!(watched.result.is_none());
+        assert_eq!(watched.timed_out, vec!["stuck".to_string()]);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_concurrent_phases_are_timed_independently() {
+        let watchdog = Watchdog::new(Duration::from_millis(50));
+        let (slow, fast) = std::thread::scope(|scope| {
+            let slow = scope.spawn(|| {
+                watchdog.run(
+                    |progress| {
+                        progress.step("slow");
+                        wait_until_expired();
+                    },
+                    |_| {},
+                )
+            });
+            let fast = scope.spawn(|| watchdog.run(|progress| progress.step("fast"), |_| {}));
+            (slow.join().unwrap(), fast.join().unwrap())
+        });
+        assert_eq!(slow.timed_out, vec!["slow".to_string()]);
+        assert!(fast.timed_out.is_empty() && fast.result.is_some());
+    }
+}